@@ -0,0 +1,58 @@
+//! Compares buffered reads and memory-mapped input on the samples directory, repeated
+//! `JUMPLIST_BENCH_ROUNDS` times (100 by default).
+//!
+//! `cargo bench --features mmap --bench mmap`
+
+use jumplist_parser::{JumplistParser, ParseOptions};
+use std::time::{Duration, Instant};
+
+fn run(
+    name: &str,
+    paths: &[String],
+    rounds: usize,
+    parse: impl Fn(&str) -> Option<JumplistParser>,
+) -> Duration {
+    let start = Instant::now();
+    let mut entries = 0;
+    for _ in 0..rounds {
+        for path in paths {
+            if let Some(parsed) = parse(path) {
+                entries += parsed.entry_count();
+            }
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<10} {:>5} files {:>7} entries {:>10.2?} ({:.2?}/file)",
+        name,
+        paths.len() * rounds,
+        entries,
+        elapsed,
+        elapsed / (paths.len() * rounds).max(1) as u32
+    );
+    elapsed
+}
+
+fn main() {
+    let rounds = std::env::var("JUMPLIST_BENCH_ROUNDS")
+        .ok()
+        .and_then(|rounds| rounds.parse().ok())
+        .unwrap_or(100);
+    let paths: Vec<String> = glob::glob("samples/**/*Destinations-ms")
+        .unwrap()
+        .flatten()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    let options = ParseOptions::default();
+
+    let buffered = run("buffered", &paths, rounds, |path| {
+        JumplistParser::from_path_with_options(path, &options).ok()
+    });
+    let mapped = run("mmap", &paths, rounds, |path| {
+        JumplistParser::from_path_mmap(path, &options).ok()
+    });
+    println!(
+        "mmap / buffered: {:.2}",
+        mapped.as_secs_f64() / buffered.as_secs_f64()
+    );
+}
@@ -0,0 +1,81 @@
+//! Parsing benchmarks on the committed samples, plus a Quick Access sized `DestList`
+//! (10000 entries, about 3 MB) written with the builder.
+//!
+//! `cargo bench --bench parse`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use jumplist_parser::{
+    builder::{AutomaticEntry, AutomaticJumplistBuilder},
+    destlist::{DestList, DestListHeader, DestListVersion},
+    JumplistParser, ParseOptions,
+};
+use std::io::Cursor;
+
+const DESTLIST: &str = "samples/other/DestList";
+const AUTOMATIC: &str = "samples/other/5f7b5f1e01b83767.automaticDestinations-ms";
+const CUSTOM: &str = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+
+fn read(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|e| panic!("Can't read '{}': {}", path, e))
+}
+
+fn large_destlist() -> Vec<u8> {
+    let mut builder = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus);
+    for number in 1..=10_000 {
+        let path = format!(
+            r"C:\Users\user\Documents\Projects\{:05}\report.docx",
+            number
+        );
+        builder = builder.entry(AutomaticEntry {
+            // The LNK streams are not part of the `DestList`
+            lnk: None,
+            ..AutomaticEntry::new(number, &path)
+        });
+    }
+    builder.destlist_bytes()
+}
+
+fn destlist(c: &mut Criterion) {
+    let destlist = read(DESTLIST);
+    c.bench_function("destlist_header", |b| {
+        b.iter(|| DestListHeader::from_buffer(black_box(&destlist)).unwrap())
+    });
+    c.bench_function("destlist_stream", |b| {
+        b.iter(|| DestList::parse_stream(&mut Cursor::new(black_box(&destlist))).unwrap())
+    });
+    let large = large_destlist();
+    c.bench_function("destlist_stream_10000_entries", |b| {
+        b.iter(|| DestList::parse_stream(&mut Cursor::new(black_box(&large))).unwrap())
+    });
+}
+
+fn jumplists(c: &mut Criterion) {
+    let automatic = read(AUTOMATIC);
+    let name = Some("5f7b5f1e01b83767.automaticDestinations-ms");
+    let skip_lnks = ParseOptions {
+        skip_lnks: true,
+        ..Default::default()
+    };
+    c.bench_function("automatic_without_lnks", |b| {
+        b.iter(|| {
+            JumplistParser::from_bytes_with_options(black_box(&automatic), name, &skip_lnks)
+                .unwrap()
+        })
+    });
+    c.bench_function("automatic_with_lnks", |b| {
+        b.iter(|| JumplistParser::from_bytes(black_box(&automatic), name).unwrap())
+    });
+    let custom = read(CUSTOM);
+    c.bench_function("custom", |b| {
+        b.iter(|| {
+            JumplistParser::from_bytes(
+                black_box(&custom),
+                Some("5d696d521de238c3.customDestinations-ms"),
+            )
+            .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, destlist, jumplists);
+criterion_main!(benches);
@@ -0,0 +1,16 @@
+#![no_main]
+
+use jumplist_parser::JumplistParser;
+use libfuzzer_sys::fuzz_target;
+
+// The same input as both formats, the file name picks the parser
+const NAMES: [&str; 2] = [
+    "0123456789abcdef.automaticDestinations-ms",
+    "0123456789abcdef.customDestinations-ms",
+];
+
+fuzz_target!(|data: &[u8]| {
+    for name in NAMES {
+        let _ = JumplistParser::from_bytes(data, Some(name));
+    }
+});
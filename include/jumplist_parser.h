@@ -0,0 +1,62 @@
+#ifndef JUMPLIST_PARSER_H
+#define JUMPLIST_PARSER_H
+
+/* Generated with cbindgen, don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Success.
+#define JUMPLIST_OK 0
+
+// A required pointer is null or a string isn't valid UTF-8.
+#define JUMPLIST_ERR_ARGUMENT -1
+
+// The Jumplist can't be read or parsed.
+#define JUMPLIST_ERR_PARSE -2
+
+// The parsed Jumplist can't be serialized.
+#define JUMPLIST_ERR_SERIALIZE -3
+
+// The parser panicked.
+#define JUMPLIST_ERR_PANIC -4
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parse the Jumplist file at `path`, the type is detected from the file extension as
+// in [`JumplistParser::from_path`].
+//
+// # Safety
+// `path` must be a valid NUL-terminated string. `out_json` must be a valid pointer,
+// `out_err` must be a valid pointer or null.
+int32_t jumplist_parse_path(const char *path, char **out_json, char **out_err);
+
+// Parse a Jumplist held in memory. `name_hint` is the original file name, it gives the
+// Jumplist type and the AppID. When it's null, the type is detected from the content.
+//
+// # Safety
+// `data` must point to `len` readable bytes. `name_hint` must be a valid NUL-terminated
+// string or null. `out_json` must be a valid pointer, `out_err` a valid pointer or null.
+int32_t jumplist_parse_buffer(const uint8_t *data,
+                              size_t len,
+                              const char *name_hint,
+                              char **out_json,
+                              char **out_err);
+
+// Release a string returned by this library. Null pointers are ignored.
+//
+// # Safety
+// `s` must be null or a string returned through `out_json` or `out_err` that hasn't
+// been released yet.
+void jumplist_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JUMPLIST_PARSER_H */
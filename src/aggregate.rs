@@ -0,0 +1,405 @@
+//! Correlation of targets across multiple Jumplists.
+//!
+//! The same document usually shows up in several Jumplists (the application that opened
+//! it, Quick Access, ...). [`TargetActivity`] groups the entries of many parsed Jumplists
+//! by target path and gives a first seen / last seen window per target. [`Summary`] counts
+//! the entries of every application by type of the volume of their targets, and by
+//! [`FileCategory`] of their targets.
+
+use crate::{
+    filetype::FileCategory,
+    lnk_info::{DriveType, LinkInfo},
+    target::canonicalize_target_path,
+    timestamp::Timestamp,
+    JumplistParser,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Columns of [`TargetActivity::csv_row`], in order.
+pub const AGGREGATE_CSV_COLUMNS: &[&str] = &[
+    "target_path",
+    "app_ids",
+    "app_names",
+    "first_seen",
+    "last_seen",
+    "access_count",
+    "pinned",
+    "hostnames",
+    "entry_count",
+];
+
+/// Options for [`TargetActivity::from_parsers_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct AggregateOptions {
+    /// Group targets by their [`canonicalize_target_path`] form, so that e.g. `c:\dir\`,
+    /// `%USERPROFILE%\dir` and `C:\Users\<user>\dir` are the same target.
+    pub canonicalize_paths: bool,
+}
+
+/// An application whose Jumplist references a target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AppReference {
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+}
+
+/// Activity of a single target across Jumplists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TargetActivity {
+    /// Path of the target, as first seen (or canonicalized). Grouping ignores case.
+    pub target_path: String,
+    /// Applications whose Jumplists reference the target.
+    pub apps: Vec<AppReference>,
+    /// Earliest `DestList` interaction time.
+    pub first_seen: Option<Timestamp>,
+    /// Latest `DestList` interaction time.
+    pub last_seen: Option<Timestamp>,
+    /// Sum of the `DestList` access counts, `None` when no entry recorded one.
+    pub access_count: Option<u32>,
+    /// Whether the target was pinned in any Jumplist.
+    pub pinned: bool,
+    /// Hostnames recorded for the target, sorted.
+    pub hostnames: Vec<String>,
+    /// Number of Jumplist entries referencing the target.
+    pub entry_count: usize,
+}
+
+impl TargetActivity {
+    /// Group the entries of `parsers` by target path, in order of first appearance.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{aggregate::TargetActivity, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let activity = TargetActivity::from_parsers(&[parsed]);
+    /// assert_eq!(activity.len(), 24);
+    /// assert!(activity.iter().all(|a| a.first_seen.is_some() && a.entry_count == 1));
+    /// ```
+    pub fn from_parsers(parsers: &[JumplistParser]) -> Vec<Self> {
+        Self::from_parsers_with_options(parsers, &AggregateOptions::default())
+    }
+
+    /// Same as [`TargetActivity::from_parsers`] but with explicit [`AggregateOptions`].
+    pub fn from_parsers_with_options(
+        parsers: &[JumplistParser],
+        options: &AggregateOptions,
+    ) -> Vec<Self> {
+        let mut activities: Vec<TargetActivity> = vec![];
+        let mut hostnames: Vec<BTreeSet<String>> = vec![];
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for parsed in parsers {
+            for entry in parsed.entries() {
+                let path = match entry.target_path() {
+                    Some(path) if !path.is_empty() => path,
+                    _ => continue,
+                };
+                let path = match options.canonicalize_paths {
+                    true => canonicalize_target_path(&path, parsed.user.as_deref()),
+                    false => path,
+                };
+                let i = *index.entry(path.to_lowercase()).or_insert_with(|| {
+                    activities.push(TargetActivity {
+                        target_path: path.clone(),
+                        apps: vec![],
+                        first_seen: None,
+                        last_seen: None,
+                        access_count: None,
+                        pinned: false,
+                        hostnames: vec![],
+                        entry_count: 0,
+                    });
+                    hostnames.push(BTreeSet::new());
+                    activities.len() - 1
+                });
+
+                let activity = &mut activities[i];
+                let app = AppReference {
+                    app_id: parsed.app_id.clone(),
+                    app_name: parsed.app_name.clone(),
+                };
+                if !activity.apps.contains(&app) {
+                    activity.apps.push(app);
+                }
+                if let Some(mtime) = entry.interaction_time().filter(|t| !t.is_zero()) {
+                    if activity
+                        .first_seen
+                        .is_none_or(|t| mtime.filetime < t.filetime)
+                    {
+                        activity.first_seen = Some(*mtime);
+                    }
+                    if activity
+                        .last_seen
+                        .is_none_or(|t| mtime.filetime > t.filetime)
+                    {
+                        activity.last_seen = Some(*mtime);
+                    }
+                }
+                if let Some(count) = entry.access_count() {
+                    activity.access_count = Some(
+                        activity
+                            .access_count
+                            .unwrap_or_default()
+                            .saturating_add(count),
+                    );
+                }
+                activity.pinned |= entry.pinned().unwrap_or(false);
+                if let Some(hostname) = entry.hostname() {
+                    hostnames[i].insert(hostname);
+                }
+                activity.entry_count += 1;
+            }
+        }
+
+        for (activity, hostnames) in activities.iter_mut().zip(hostnames) {
+            activity.hostnames = hostnames.into_iter().collect();
+        }
+        activities
+    }
+
+    /// The values of [`AGGREGATE_CSV_COLUMNS`], lists are joined with `; `.
+    pub fn csv_row(&self) -> Vec<String> {
+        let join = |values: Vec<&str>| values.join("; ");
+        AGGREGATE_CSV_COLUMNS
+            .iter()
+            .map(|column| match *column {
+                "target_path" => self.target_path.clone(),
+                "app_ids" => join(
+                    self.apps
+                        .iter()
+                        .map(|a| a.app_id.as_deref().unwrap_or_default())
+                        .collect(),
+                ),
+                "app_names" => join(
+                    self.apps
+                        .iter()
+                        .map(|a| a.app_name.as_deref().unwrap_or_default())
+                        .collect(),
+                ),
+                "first_seen" => self.first_seen.map(|t| t.to_string()).unwrap_or_default(),
+                "last_seen" => self.last_seen.map(|t| t.to_string()).unwrap_or_default(),
+                "access_count" => self.access_count.map(|c| c.to_string()).unwrap_or_default(),
+                "pinned" => self.pinned.to_string(),
+                "hostnames" => join(self.hostnames.iter().map(|h| h.as_str()).collect()),
+                "entry_count" => self.entry_count.to_string(),
+                _ => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Columns of [`AppSummary::csv_row`], in order. The lists of volume serial numbers and UNC
+/// servers are only counted, the format versions and the categories are joined with `; `.
+pub const SUMMARY_CSV_COLUMNS: &[&str] = &[
+    "app_id",
+    "app_name",
+    "files",
+    "entries",
+    "fixed",
+    "removable",
+    "remote",
+    "cdrom",
+    "other",
+    "unknown",
+    "volume_serials",
+    "unc_servers",
+    "format_versions",
+    "categories",
+];
+
+/// Entries by type of the volume their target was on, from the LNK `LinkInfo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VolumeCounts {
+    pub fixed: usize,
+    pub removable: usize,
+    /// Network drives and shares.
+    pub remote: usize,
+    pub cdrom: usize,
+    /// RAM disks and the other drive types.
+    pub other: usize,
+    /// Entries without a `LinkInfo`, or without a drive type nor a network share in it.
+    pub unknown: usize,
+}
+
+impl VolumeCounts {
+    /// Count an entry whose LNK has `link_info`.
+    pub fn add(&mut self, link_info: Option<&LinkInfo>) {
+        let drive_type = link_info.and_then(|l| l.drive_type);
+        let count = match drive_type {
+            Some(DriveType::Fixed) => &mut self.fixed,
+            Some(DriveType::Removable) => &mut self.removable,
+            Some(DriveType::Remote) => &mut self.remote,
+            Some(DriveType::Cdrom) => &mut self.cdrom,
+            Some(_) => &mut self.other,
+            None if link_info.is_some_and(|l| l.network_share.is_some()) => &mut self.remote,
+            None => &mut self.unknown,
+        };
+        *count += 1;
+    }
+}
+
+/// Summary of the Jumplists of one application.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AppSummary {
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    /// Number of Jumplist files of the application.
+    pub files: usize,
+    /// Number of entries in these files.
+    pub entries: usize,
+    pub volumes: VolumeCounts,
+    /// Distinct serial numbers of the volumes of the targets, e.g. `A4C2-11F0`, sorted.
+    pub volume_serials: Vec<String>,
+    /// Distinct servers of the network shares of the targets (`server` of
+    /// `\\server\share`), sorted.
+    pub unc_servers: Vec<String>,
+    /// Distinct format versions of the files, as `type:version` (e.g. `automatic:4`,
+    /// `custom:2`), sorted. See [`JumplistParser::format_version`].
+    #[serde(default)]
+    pub format_versions: Vec<String>,
+    /// Entries by category of their target, see [`JumplistParser::target_categories`]. The
+    /// categories without entries are left out.
+    #[serde(default)]
+    pub categories: BTreeMap<FileCategory, usize>,
+}
+
+impl AppSummary {
+    /// The values of [`SUMMARY_CSV_COLUMNS`].
+    pub fn csv_row(&self) -> Vec<String> {
+        SUMMARY_CSV_COLUMNS
+            .iter()
+            .map(|column| match *column {
+                "app_id" => self.app_id.clone().unwrap_or_default(),
+                "app_name" => self.app_name.clone().unwrap_or_default(),
+                "files" => self.files.to_string(),
+                "entries" => self.entries.to_string(),
+                "fixed" => self.volumes.fixed.to_string(),
+                "removable" => self.volumes.removable.to_string(),
+                "remote" => self.volumes.remote.to_string(),
+                "cdrom" => self.volumes.cdrom.to_string(),
+                "other" => self.volumes.other.to_string(),
+                "unknown" => self.volumes.unknown.to_string(),
+                "volume_serials" => self.volume_serials.len().to_string(),
+                "unc_servers" => self.unc_servers.len().to_string(),
+                "format_versions" => self.format_versions.join("; "),
+                "categories" => self
+                    .categories
+                    .iter()
+                    .map(|(category, count)| format!("{}:{}", category, count))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                _ => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Per application summary of many parsed Jumplists: where the targets of their entries
+/// were (fixed, removable, network or optical volumes), to spot the applications used on
+/// removable media or shares.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Summary {
+    /// One summary per AppID, sorted by AppID.
+    pub apps: Vec<AppSummary>,
+}
+
+impl Summary {
+    /// The summary of `parsers`.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{aggregate::Summary, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let summary = Summary::from_parsers(&[parsed]);
+    /// let app = &summary.apps[0];
+    /// assert_eq!(app.entries, 24);
+    /// println!("{} entries on removable media", app.volumes.removable);
+    /// ```
+    pub fn from_parsers(parsers: &[JumplistParser]) -> Self {
+        let mut summary = Summary::default();
+        for parsed in parsers {
+            summary.add(parsed);
+        }
+        summary
+    }
+
+    /// Add the entries of `parsed` to the summary of its application.
+    pub fn add(&mut self, parsed: &JumplistParser) {
+        let position = self
+            .apps
+            .binary_search_by(|app| app.app_id.cmp(&parsed.app_id));
+        let index = match position {
+            Ok(index) => index,
+            Err(index) => {
+                self.apps.insert(
+                    index,
+                    AppSummary {
+                        app_id: parsed.app_id.clone(),
+                        app_name: parsed.app_name.clone(),
+                        files: 0,
+                        entries: 0,
+                        volumes: VolumeCounts::default(),
+                        volume_serials: vec![],
+                        unc_servers: vec![],
+                        format_versions: vec![],
+                        categories: BTreeMap::new(),
+                    },
+                );
+                index
+            }
+        };
+        let app = &mut self.apps[index];
+        let mut volume_serials: BTreeSet<String> = app.volume_serials.drain(..).collect();
+        let mut unc_servers: BTreeSet<String> = app.unc_servers.drain(..).collect();
+        app.files += 1;
+        if let Some(version) = parsed.format_version() {
+            let version = format!("{}:{}", parsed.r#type, version);
+            if let Err(index) = app.format_versions.binary_search(&version) {
+                app.format_versions.insert(index, version);
+            }
+        }
+        for category in parsed.target_categories() {
+            *app.categories.entry(category).or_default() += 1;
+        }
+        for entry in parsed.entries() {
+            let link_info = entry.link_info();
+            app.entries += 1;
+            app.volumes.add(link_info);
+            if let Some(serial) = link_info.and_then(|l| l.volume_serial_number()) {
+                volume_serials.insert(serial);
+            }
+            let server = link_info
+                .and_then(|l| l.network_share.as_deref())
+                .and_then(unc_server);
+            if let Some(server) = server {
+                unc_servers.insert(server);
+            }
+        }
+        app.volume_serials = volume_serials.into_iter().collect();
+        app.unc_servers = unc_servers.into_iter().collect();
+    }
+}
+
+/// The server of a `\\server\share` path, lowercase.
+fn unc_server(share: &str) -> Option<String> {
+    share
+        .strip_prefix(r"\\")?
+        .split('\\')
+        .next()
+        .filter(|server| !server.is_empty())
+        .map(|server| server.to_lowercase())
+}
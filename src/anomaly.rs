@@ -0,0 +1,203 @@
+//! Detection of signs of tampering and timestomping (see [`ParseOptions::detect_anomalies`](crate::ParseOptions::detect_anomalies)).
+//!
+//! None of the checks proves anything on its own, e.g. copying a file sets a creation time
+//! later than its modification time. They point at the entries worth a closer look.
+
+use crate::{
+    timestamp::{Timestamp, TimestampFormat},
+    JumplistParser,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+
+/// Number of deleted entries (see [`DestListHeader::deleted_entry_estimate`](crate::destlist::DestListHeader::deleted_entry_estimate))
+/// above which an [`AnomalyKind::EntryNumberGap`] is reported.
+pub const ENTRY_NUMBER_GAP_THRESHOLD: u32 = 100;
+
+/// What an [`Anomaly`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Several `DestList` entries have the same entry number.
+    DuplicateEntryNumber,
+    /// A `DestList` entry has no LNK stream.
+    MissingLnkStream,
+    /// A LNK stream is numbered after the last entry number of the `DestList` header.
+    UnexpectedLnkStream,
+    /// A `DestList` interaction time is later than the modification time of the Jumplist file.
+    InteractionAfterFileModified,
+    /// The LNK target creation time is later than its modification time.
+    CreatedAfterModified,
+    /// A `DestList` interaction time is the FILETIME epoch (`1601-01-01`).
+    EpochTimestamp,
+    /// Many more entry numbers were issued than there are entries, see [`ENTRY_NUMBER_GAP_THRESHOLD`].
+    EntryNumberGap,
+    /// A hostname contains non-printable characters.
+    NonPrintableHostname,
+}
+
+/// A sign of tampering found in a parsed Jumplist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    /// Position of the entry in [`JumplistParser::entries`], `None` for anomalies of the
+    /// whole file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Entry number of the `DestList` entry or LNK stream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_number: Option<u32>,
+    /// Human readable description.
+    pub description: String,
+}
+
+/// `ts` as ISO 8601, whatever its [`TimestampFormat`].
+fn iso(ts: &Timestamp) -> String {
+    Timestamp::new(ts.filetime, TimestampFormat::Iso).to_string()
+}
+
+/// The modification time of `file`, when the filesystem records one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn file_mtime(file: &File) -> Option<Timestamp> {
+    let modified = file.metadata().and_then(|m| m.modified()).ok()?;
+    Some(Timestamp::from_system_time(
+        modified,
+        TimestampFormat::default(),
+    ))
+}
+
+impl JumplistParser {
+    /// Run the checks of [`ParseOptions::detect_anomalies`](crate::ParseOptions::detect_anomalies)
+    /// on the parsed entries. `file_mtime` is the modification time of the Jumplist file,
+    /// the interaction times are only compared to it when it's known.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// for anomaly in parsed.detect_anomalies(None) {
+    ///     println!("{:?}: {}", anomaly.kind, anomaly.description);
+    /// }
+    /// ```
+    pub fn detect_anomalies(&self, file_mtime: Option<&Timestamp>) -> Vec<Anomaly> {
+        let mut anomalies = vec![];
+        let mut add = |kind, index, entry_number, description| {
+            anomalies.push(Anomaly {
+                kind,
+                index,
+                entry_number,
+                description,
+            })
+        };
+
+        if let Some(destlist) = self.destlist() {
+            let gap = destlist.header.deleted_entry_estimate();
+            if gap > ENTRY_NUMBER_GAP_THRESHOLD {
+                add(
+                    AnomalyKind::EntryNumberGap,
+                    None,
+                    None,
+                    format!(
+                        "The last entry number is {} but there are {} entries",
+                        destlist.header.last_entry_number, destlist.header.number_of_entries
+                    ),
+                );
+            }
+            for number in &destlist.unexpected_lnk_streams {
+                add(
+                    AnomalyKind::UnexpectedLnkStream,
+                    None,
+                    Some(*number),
+                    format!(
+                        "The LNK stream '{:x}' is numbered after the last entry number {:x}",
+                        number, destlist.header.last_entry_number
+                    ),
+                );
+            }
+        }
+
+        for (index, entry) in self.entries().enumerate() {
+            let number = entry.entry_number();
+            if let Some(destlist) = self.destlist() {
+                let number = number.unwrap_or_default();
+                if destlist.duplicate_entry_numbers.contains(&number) {
+                    add(
+                        AnomalyKind::DuplicateEntryNumber,
+                        Some(index),
+                        Some(number),
+                        format!("The entry number {:x} is used by another entry", number),
+                    );
+                }
+                if destlist.missing_lnk_streams.contains(&number) {
+                    add(
+                        AnomalyKind::MissingLnkStream,
+                        Some(index),
+                        Some(number),
+                        format!("There is no LNK stream for the entry {:x}", number),
+                    );
+                }
+            }
+            if let Some(interaction) = entry.interaction_time() {
+                if interaction.is_zero() {
+                    add(
+                        AnomalyKind::EpochTimestamp,
+                        Some(index),
+                        number,
+                        "The interaction time is the FILETIME epoch".to_string(),
+                    );
+                }
+                if let Some(file_mtime) = file_mtime {
+                    if interaction.filetime > file_mtime.filetime {
+                        add(
+                            AnomalyKind::InteractionAfterFileModified,
+                            Some(index),
+                            number,
+                            format!(
+                                "The interaction time {} is later than the file modification time {}",
+                                iso(interaction),
+                                iso(file_mtime)
+                            ),
+                        );
+                    }
+                }
+            }
+            if let Some(header) = entry.lnk_header() {
+                if !header.write_time.is_zero()
+                    && header.creation_time.filetime > header.write_time.filetime
+                {
+                    add(
+                        AnomalyKind::CreatedAfterModified,
+                        Some(index),
+                        number,
+                        format!(
+                            "The target was created at {}, after its modification at {}",
+                            iso(&header.creation_time),
+                            iso(&header.write_time)
+                        ),
+                    );
+                }
+            }
+            if let Some(hostname) = entry.hostname() {
+                if hostname
+                    .chars()
+                    .any(|c| c.is_control() || c == char::REPLACEMENT_CHARACTER)
+                {
+                    add(
+                        AnomalyKind::NonPrintableHostname,
+                        Some(index),
+                        number,
+                        format!("The hostname {:?} has non-printable characters", hostname),
+                    );
+                }
+            }
+        }
+        anomalies
+    }
+}
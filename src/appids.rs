@@ -734,3 +734,45 @@ pub static APPID_TO_NAME: phf::Map<&'static str, &'static str> = phf_map! {
     // Custom AppIDs
     "a0c14af241d40144" => "Foxit PDF Reader"
 };
+
+/// The AppID in a Jumplist file name, and whether it looks like a real AppID.
+///
+/// Collected files are often renamed (`HOST_user_9b9cdc69c1c24e2b.automaticDestinations-ms`,
+/// `9b9cdc69c1c24e2b (1).automaticDestinations-ms`), so the AppID is the first token of
+/// 16 hex digits of the stem, lowercased. Without such a token, the stem is returned as is.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::appids::app_id_from_file_name;
+///
+/// assert_eq!(
+///     app_id_from_file_name("PC01_john_9B9CDC69C1C24E2B (1).automaticDestinations-ms"),
+///     ("9b9cdc69c1c24e2b".to_string(), true)
+/// );
+/// assert_eq!(
+///     app_id_from_file_name("notes.customDestinations-ms"),
+///     ("notes".to_string(), false)
+/// );
+/// ```
+pub fn app_id_from_file_name(file_name: &str) -> (String, bool) {
+    let stem = match crate::JumplistParser::split_extension(file_name) {
+        Some((stem, _, _)) => stem,
+        None => file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem),
+    };
+    let token = stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|token| token.len() == 16 && token.chars().all(|c| c.is_ascii_hexdigit()));
+    match token {
+        Some(token) => (token.to_ascii_lowercase(), true),
+        None => (stem.to_string(), false),
+    }
+}
+
+/// The application name of an AppID, whatever its case.
+pub fn app_name(app_id: &str) -> Option<&'static str> {
+    APPID_TO_NAME
+        .get(app_id.to_ascii_lowercase().as_str())
+        .copied()
+}
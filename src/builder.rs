@@ -0,0 +1,432 @@
+//! Synthesizing Jumplist files, to generate test fixtures without collecting real samples.
+//!
+//! [`AutomaticJumplistBuilder`] writes a compound file with a `DestList` stream and the
+//! numbered LNK streams, [`CustomJumplistBuilder`] a custom Jumplist with categories of
+//! every type. The LNK entries are given as raw bytes, [`lnk_bytes`] builds a minimal one.
+//! Nothing is validated, so that inconsistent files (duplicate entry numbers, wrong
+//! counts, missing streams, ...) can be written on purpose.
+
+use crate::{
+    custom_destinations::{CategoryId, CATEGORY_FOOTER},
+    destlist::DestListVersion,
+    errors::JumplistParserError,
+    guid::Guid,
+    lnk_info::{
+        HAS_LINK_INFO, IS_UNICODE, LNK_CLSID, LNK_HEADER_SIZE, VOLUME_ID_AND_LOCAL_BASE_PATH,
+    },
+};
+use std::io::{Cursor, Write};
+
+/// `FILE_ATTRIBUTE_ARCHIVE`, the attributes of the target of [`lnk_bytes`].
+const FILE_ATTRIBUTE_ARCHIVE: u32 = 0x20;
+/// `DRIVE_FIXED`, the drive type of the target of [`lnk_bytes`].
+const DRIVE_FIXED: u32 = 3;
+
+/// An entry of an [`AutomaticJumplistBuilder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomaticEntry {
+    pub entry_number: u32,
+    pub path: String,
+    /// `path_size` of the entry, the number of UTF-16 code units of `path` when `None`.
+    /// Some writers count bytes instead.
+    pub path_size: Option<u16>,
+    /// Last interaction time, as a FILETIME.
+    pub mtime: u64,
+    /// Position in the pinned list, `None` for an unpinned entry.
+    pub pin_position: Option<u32>,
+    /// Written as a floating point number by version 1 `DestList` streams.
+    pub access_count: u32,
+    /// NetBIOS name, truncated to 16 bytes.
+    pub hostname: String,
+    pub volume_droid: Guid,
+    pub file_droid: Guid,
+    pub volume_birth_droid: Guid,
+    pub file_birth_droid: Guid,
+    /// Bytes of the LNK stream named after the entry number, no stream when `None`.
+    pub lnk: Option<Vec<u8>>,
+}
+
+impl AutomaticEntry {
+    /// An unpinned entry with a LNK stream to `path`, see [`lnk_bytes`].
+    pub fn new(entry_number: u32, path: &str) -> Self {
+        AutomaticEntry {
+            entry_number,
+            path: path.to_string(),
+            lnk: Some(lnk_bytes(path)),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for AutomaticEntry {
+    fn default() -> Self {
+        AutomaticEntry {
+            entry_number: 1,
+            path: String::new(),
+            path_size: None,
+            mtime: 0,
+            pin_position: None,
+            access_count: 1,
+            hostname: String::new(),
+            volume_droid: Guid::default(),
+            file_droid: Guid::default(),
+            volume_birth_droid: Guid::default(),
+            file_birth_droid: Guid::default(),
+            lnk: None,
+        }
+    }
+}
+
+/// Writes automatic Jumplists (`.automaticDestinations-ms`).
+///
+/// # Example
+/// ```
+/// use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+/// use jumplist_parser::{destlist::DestListVersion, JumplistParser};
+///
+/// let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+///     .entry(AutomaticEntry::new(1, r"C:\Users\user\Desktop\report.docx"))
+///     .entry(AutomaticEntry {
+///         pin_position: Some(0),
+///         ..AutomaticEntry::new(2, r"C:\Users\user\Desktop\notes.txt")
+///     })
+///     .to_bytes()
+///     .unwrap();
+/// let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+/// assert_eq!(parsed.entry_count(), 2);
+/// assert!(parsed.warnings().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomaticJumplistBuilder {
+    pub version: DestListVersion,
+    pub entries: Vec<AutomaticEntry>,
+    /// Number of entries declared by the `DestList` header, the number of entries when `None`.
+    pub declared_entries: Option<u32>,
+    /// Last entry number of the `DestList` header, the highest entry number when `None`.
+    pub last_entry_number: Option<u32>,
+    pub last_revision_number: u64,
+    /// Streams written as is after the LNK streams, e.g. an LNK stream without an entry. A
+    /// name with `/` is a path, its storages are created, e.g. `Storage/DestList`.
+    pub streams: Vec<(String, Vec<u8>)>,
+    /// Don't write the `DestList` stream.
+    pub omit_destlist: bool,
+}
+
+impl AutomaticJumplistBuilder {
+    pub fn new(version: impl Into<DestListVersion>) -> Self {
+        AutomaticJumplistBuilder {
+            version: version.into(),
+            entries: vec![],
+            declared_entries: None,
+            last_entry_number: None,
+            last_revision_number: 0,
+            streams: vec![],
+            omit_destlist: false,
+        }
+    }
+
+    /// Add an entry, the entries are written in order.
+    pub fn entry(mut self, entry: AutomaticEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Add a stream named `name`.
+    pub fn stream(mut self, name: &str, data: Vec<u8>) -> Self {
+        self.streams.push((name.to_string(), data));
+        self
+    }
+
+    /// The bytes of the `DestList` stream.
+    pub fn destlist_bytes(&self) -> Vec<u8> {
+        let pinned = self.entries.iter().filter(|e| e.pin_position.is_some());
+        let last_entry_number = self.entries.iter().map(|e| e.entry_number).max();
+        let mut data = vec![];
+        data.extend_from_slice(&self.version.as_u32().to_le_bytes());
+        let declared = self.declared_entries.unwrap_or(self.entries.len() as u32);
+        data.extend_from_slice(&declared.to_le_bytes());
+        data.extend_from_slice(&(pinned.count() as u32).to_le_bytes());
+        data.extend_from_slice(&0f32.to_le_bytes());
+        let last_entry_number = self.last_entry_number.or(last_entry_number);
+        data.extend_from_slice(&last_entry_number.unwrap_or_default().to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&self.last_revision_number.to_le_bytes());
+
+        for entry in &self.entries {
+            // Checksum
+            data.extend_from_slice(&[0; 8]);
+            for guid in [
+                entry.volume_droid,
+                entry.file_droid,
+                entry.volume_birth_droid,
+                entry.file_birth_droid,
+            ] {
+                data.extend_from_slice(&guid.0);
+            }
+            let mut hostname = [0; 16];
+            let name = entry.hostname.as_bytes();
+            let len = name.len().min(hostname.len());
+            hostname[..len].copy_from_slice(&name[..len]);
+            data.extend_from_slice(&hostname);
+            data.extend_from_slice(&entry.entry_number.to_le_bytes());
+            data.extend_from_slice(&[0; 4]);
+            match self.version.has_extended_entries() {
+                true => data.extend_from_slice(&[0; 4]),
+                // Version 1 access counts are floating point numbers
+                false => data.extend_from_slice(&(entry.access_count as f32).to_le_bytes()),
+            }
+            data.extend_from_slice(&entry.mtime.to_le_bytes());
+            let pin = entry.pin_position.unwrap_or(0xFFFF_FFFF);
+            data.extend_from_slice(&pin.to_le_bytes());
+            if self.version.has_extended_entries() {
+                data.extend_from_slice(&[0; 4]);
+                data.extend_from_slice(&entry.access_count.to_le_bytes());
+                data.extend_from_slice(&[0; 8]);
+            }
+            let path: Vec<u16> = entry.path.encode_utf16().collect();
+            let path_size = entry.path_size.unwrap_or(path.len() as u16);
+            data.extend_from_slice(&path_size.to_le_bytes());
+            path.iter()
+                .for_each(|unit| data.extend_from_slice(&unit.to_le_bytes()));
+            if self.version.has_extended_entries() {
+                data.extend_from_slice(&[0; 4]);
+            }
+        }
+        data
+    }
+
+    /// The bytes of the compound file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JumplistParserError> {
+        let write_error = |e: std::io::Error| {
+            JumplistParserError::General(
+                format!("Can't write the compound file, ERROR: {}", e),
+                line!(),
+                file!().to_string(),
+            )
+        };
+        let mut compound = cfb::CompoundFile::create(Cursor::new(vec![])).map_err(write_error)?;
+        let destlist =
+            (!self.omit_destlist).then(|| ("DestList".to_string(), self.destlist_bytes()));
+        let lnks = self.entries.iter().filter_map(|entry| {
+            let lnk = entry.lnk.clone()?;
+            Some((format!("{:x}", entry.entry_number), lnk))
+        });
+        let streams = destlist
+            .into_iter()
+            .chain(lnks)
+            .chain(self.streams.iter().cloned());
+        for (name, data) in streams {
+            if let Some((storage, _)) = name.rsplit_once('/') {
+                compound.create_storage_all(storage).map_err(write_error)?;
+            }
+            compound
+                .create_stream(&name)
+                .and_then(|mut stream| stream.write_all(&data))
+                .map_err(write_error)?;
+        }
+        compound.flush().map_err(write_error)?;
+        Ok(compound.into_inner().into_inner())
+    }
+
+    /// Write the compound file to `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to(&self, path: &str) -> Result<(), JumplistParserError> {
+        write_file(path, &self.to_bytes()?)
+    }
+}
+
+/// A category of a [`CustomJumplistBuilder`], the entries are raw LNK bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomCategory {
+    /// A category named by the application.
+    Custom { name: String, lnks: Vec<Vec<u8>> },
+    /// A known category, only its ID is stored.
+    Known(CategoryId),
+    /// The tasks of the application.
+    Task(Vec<Vec<u8>>),
+}
+
+/// Writes custom Jumplists (`.customDestinations-ms`).
+///
+/// # Example
+/// ```
+/// use jumplist_parser::builder::{lnk_bytes, CustomCategory, CustomJumplistBuilder};
+/// use jumplist_parser::{custom_destinations::CategoryId, JumplistParser};
+///
+/// let data = CustomJumplistBuilder::new()
+///     .category(CustomCategory::Known(CategoryId::Recent))
+///     .category(CustomCategory::Task(vec![lnk_bytes(r"C:\Windows\notepad.exe")]))
+///     .to_bytes();
+/// let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+/// assert_eq!(parsed.entry_count(), 1);
+/// assert_eq!(parsed.entries().next().unwrap().category(), Some("tasks"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomJumplistBuilder {
+    /// Format version of the header, `2` in every known file.
+    pub version: u32,
+    /// Number of categories declared by the header, the number of categories when `None`.
+    pub declared_categories: Option<u32>,
+    /// Entries of the pinned items block written before the first category, no block when
+    /// `None`.
+    pub pinned: Option<Vec<Vec<u8>>>,
+    pub categories: Vec<CustomCategory>,
+}
+
+impl Default for CustomJumplistBuilder {
+    fn default() -> Self {
+        CustomJumplistBuilder {
+            version: 2,
+            declared_categories: None,
+            pinned: None,
+            categories: vec![],
+        }
+    }
+}
+
+impl CustomJumplistBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a category, the categories are written in order.
+    pub fn category(mut self, category: CustomCategory) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    /// Write a pinned items block with `lnks`.
+    pub fn pinned(mut self, lnks: Vec<Vec<u8>>) -> Self {
+        self.pinned = Some(lnks);
+        self
+    }
+
+    /// The bytes of the file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        fn write_entries(data: &mut Vec<u8>, lnks: &[Vec<u8>]) {
+            data.extend_from_slice(&(lnks.len() as u32).to_le_bytes());
+            for lnk in lnks {
+                data.extend_from_slice(&LNK_CLSID);
+                data.extend_from_slice(lnk);
+            }
+        }
+
+        let mut data = vec![];
+        data.extend_from_slice(&self.version.to_le_bytes());
+        let declared = self
+            .declared_categories
+            .unwrap_or(self.categories.len() as u32);
+        data.extend_from_slice(&declared.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        if let Some(lnks) = &self.pinned {
+            write_entries(&mut data, lnks);
+            data.extend_from_slice(&CATEGORY_FOOTER.to_le_bytes());
+        }
+        for category in &self.categories {
+            match category {
+                CustomCategory::Custom { name, lnks } => {
+                    data.extend_from_slice(&0u32.to_le_bytes());
+                    let name: Vec<u16> = name.encode_utf16().collect();
+                    data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                    name.iter()
+                        .for_each(|unit| data.extend_from_slice(&unit.to_le_bytes()));
+                    write_entries(&mut data, lnks);
+                }
+                CustomCategory::Known(id) => {
+                    data.extend_from_slice(&1u32.to_le_bytes());
+                    let id = match id {
+                        CategoryId::Frequent => 1,
+                        CategoryId::Recent => 2,
+                        CategoryId::Pinned => 3,
+                        CategoryId::None => -1,
+                        CategoryId::Unknown(id) => *id,
+                    };
+                    data.extend_from_slice(&id.to_le_bytes());
+                }
+                CustomCategory::Task(lnks) => {
+                    data.extend_from_slice(&2u32.to_le_bytes());
+                    write_entries(&mut data, lnks);
+                }
+            }
+            data.extend_from_slice(&CATEGORY_FOOTER.to_le_bytes());
+        }
+        data
+    }
+
+    /// Write the file to `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to(&self, path: &str) -> Result<(), JumplistParserError> {
+        write_file(path, &self.to_bytes())
+    }
+}
+
+/// A minimal LNK to `target_path`, a file on a fixed drive: a header and a `LinkInfo` with
+/// the path as its local base path. Characters outside of ASCII are written as `?`.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{builder::lnk_bytes, lnk_info::LnkDetails, timestamp::TimestampFormat};
+///
+/// let lnk = lnk_bytes(r"C:\Windows\notepad.exe");
+/// let details = LnkDetails::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+/// assert_eq!(
+///     details.link_info.unwrap().local_base_path.as_deref(),
+///     Some(r"C:\Windows\notepad.exe")
+/// );
+/// ```
+pub fn lnk_bytes(target_path: &str) -> Vec<u8> {
+    let mut lnk = vec![0; LNK_HEADER_SIZE];
+    lnk[..4].copy_from_slice(&(LNK_HEADER_SIZE as u32).to_le_bytes());
+    lnk[4..20].copy_from_slice(&LNK_CLSID);
+    lnk[0x14..0x18].copy_from_slice(&(HAS_LINK_INFO | IS_UNICODE).to_le_bytes());
+    lnk[0x18..0x1C].copy_from_slice(&FILE_ATTRIBUTE_ARCHIVE.to_le_bytes());
+
+    // VolumeID with an empty label
+    let mut volume_id = vec![];
+    for value in [0x11u32, DRIVE_FIXED, 0, 0x10] {
+        volume_id.extend_from_slice(&value.to_le_bytes());
+    }
+    volume_id.push(0);
+    let mut local_base_path: Vec<u8> = target_path
+        .chars()
+        .map(|c| match c.is_ascii() {
+            true => c as u8,
+            false => b'?',
+        })
+        .collect();
+    local_base_path.push(0);
+
+    let header_size = 0x1Cu32;
+    let volume_id_offset = header_size;
+    let local_base_path_offset = volume_id_offset + volume_id.len() as u32;
+    let common_path_suffix_offset = local_base_path_offset + local_base_path.len() as u32;
+    let size = common_path_suffix_offset + 1;
+    for value in [
+        size,
+        header_size,
+        VOLUME_ID_AND_LOCAL_BASE_PATH,
+        volume_id_offset,
+        local_base_path_offset,
+        0,
+        common_path_suffix_offset,
+    ] {
+        lnk.extend_from_slice(&value.to_le_bytes());
+    }
+    lnk.extend_from_slice(&volume_id);
+    lnk.extend_from_slice(&local_base_path);
+    // Empty common path suffix, then the terminal block of the extra data
+    lnk.push(0);
+    lnk.extend_from_slice(&[0; 4]);
+    lnk
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_file(path: &str, data: &[u8]) -> Result<(), JumplistParserError> {
+    std::fs::write(path, data).map_err(|e| {
+        JumplistParserError::General(
+            format!("Can't write the file '{}', ERROR: {}", path, e),
+            line!(),
+            file!().to_string(),
+        )
+    })
+}
@@ -0,0 +1,133 @@
+//! Metadata of the compound file (CFB) container of automatic Jumplists.
+//!
+//! An automatic Jumplist holds a `DestList` stream and one stream per LNK entry, named
+//! after the entry number in hex. Any other stream is unexpected and worth a look.
+
+use crate::{
+    destlist::is_destlist_stream,
+    property_store::PropertyStore,
+    timestamp::{Timestamp, TimestampFormat},
+    utils::to_hex,
+    ParseOptions,
+};
+use cfb::CompoundFile;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+
+/// A stream of the compound file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StreamInfo {
+    pub name: String,
+    pub size: u64,
+    /// Creation time, rarely set by the writers of Jumplists.
+    pub created: Timestamp,
+    /// Modification time, rarely set by the writers of Jumplists.
+    pub modified: Timestamp,
+}
+
+impl StreamInfo {
+    /// Whether the stream is named like an LNK stream (an entry number in hex).
+    pub fn is_numbered(&self) -> bool {
+        !self.name.is_empty() && self.name.chars().all(|c| c.is_ascii_hexdigit())
+    }
+}
+
+/// Metadata of the compound file container.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CfbInfo {
+    /// CLSID of the root storage.
+    pub root_clsid: String,
+    /// Every stream of the compound file, in directory order.
+    pub streams: Vec<StreamInfo>,
+    /// Number of streams named after an entry number (LNK streams).
+    pub numbered_streams: usize,
+    /// Number of other streams, normally only `DestList`.
+    pub other_streams: usize,
+}
+
+impl CfbInfo {
+    /// Collect the metadata of an opened compound file.
+    pub fn from_compound_file<F>(parser: &CompoundFile<F>, ts_format: TimestampFormat) -> Self {
+        let streams: Vec<StreamInfo> = parser
+            .walk()
+            .filter(|entry| entry.is_stream())
+            .map(|entry| StreamInfo {
+                name: entry.name().to_string(),
+                size: entry.len(),
+                created: Timestamp::from_system_time(entry.created(), ts_format),
+                modified: Timestamp::from_system_time(entry.modified(), ts_format),
+            })
+            .collect();
+        let numbered_streams = streams.iter().filter(|s| s.is_numbered()).count();
+
+        Self {
+            root_clsid: parser.root_entry().clsid().to_string().to_uppercase(),
+            other_streams: streams.len() - numbered_streams,
+            numbered_streams,
+            streams,
+        }
+    }
+
+    /// Streams that are neither `DestList` nor an LNK stream.
+    pub fn unexpected_streams(&self) -> impl Iterator<Item = &StreamInfo> {
+        self.streams
+            .iter()
+            .filter(|s| !s.is_numbered() && !s.name.eq_ignore_ascii_case("DestList"))
+    }
+}
+
+/// A stream that is neither `DestList` nor an LNK stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExtraStream {
+    pub name: String,
+    /// Path of the stream within the compound file, e.g. `/DestListPropertyStore`.
+    pub path: String,
+    pub size: u64,
+    /// The first bytes of the stream as hex, only set with
+    /// [`ParseOptions::extra_stream_preview`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+    /// The decoded values, when the stream is a serialized property store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_store: Option<PropertyStore>,
+}
+
+impl ExtraStream {
+    /// Collect the streams of a compound file that are neither `DestList` nor an LNK
+    /// stream. Streams that can't be read are listed without their contents.
+    pub fn from_compound_file<F: Read + Seek>(
+        parser: &mut CompoundFile<F>,
+        options: &ParseOptions,
+    ) -> Vec<Self> {
+        let entries: Vec<cfb::Entry> = parser
+            .walk()
+            .filter(|entry| entry.is_stream() && !is_destlist_stream(entry))
+            .filter(|entry| {
+                let name = entry.name();
+                name.is_empty() || !name.chars().all(|c| c.is_ascii_hexdigit())
+            })
+            .collect();
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut data = vec![];
+                if let Ok(stream) = parser.open_stream(entry.path()) {
+                    let _ = stream.take(entry.len()).read_to_end(&mut data);
+                }
+                let preview_len = data.len().min(options.extra_stream_preview);
+                Self {
+                    name: entry.name().to_string(),
+                    path: entry.path().to_string_lossy().to_string(),
+                    size: entry.len(),
+                    preview: (options.extra_stream_preview > 0)
+                        .then(|| to_hex(&data[..preview_len])),
+                    property_store: PropertyStore::from_buffer(&data, options.ts_format),
+                }
+            })
+            .collect()
+    }
+}
@@ -4,41 +4,80 @@
 
 use byteorder::{LittleEndian, ReadBytesExt};
 use lnk_parser::LNKParser;
-use serde::{Serialize, Serializer};
-use winparsingtools::structs::Guid;
-use std::collections::HashMap;
-use std::io::{Read, Seek, SeekFrom};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::str::FromStr;
+
+use winparsingtools::traits::Normalize;
 
-use crate::Flaten;
 use crate::errors::JumplistParserError;
-use winparsingtools::{traits::Normalize, utils::read_utf16_string};
+use crate::guid::Guid;
+use crate::lnk_info::{
+    check_lnk_header, skip_lnk, Lnk, LnkDetails, LnkHeader, TargetPathSource, LNK_CLSID,
+    LNK_HEADER_SIZE,
+};
+use crate::options::{LimitTracker, ParseLimits, ProgressEvent};
+use crate::target::TargetInfo;
+use crate::utils::{check_fits, clean_hostname, read_utf16_units, remaining_len, to_hex};
+use crate::{
+    known_folders::resolve_known_folder_path, output::sort_keys, Flatten, NormalizedEntry,
+    ParseOptions,
+};
+
+/// Minimum size of an entry: the LNK GUID followed by an LNK header.
+const MIN_ENTRY_SIZE: u64 = 16 + 0x4C;
+/// First and largest read sizes when looking for the next entry after a corrupt one.
+const RESYNC_CHUNK_SIZE: u64 = 512;
+const MAX_RESYNC_CHUNK_SIZE: u64 = 64 * 1024;
+/// Footer of the categories.
+pub(crate) const CATEGORY_FOOTER: u32 = 0xBABF_FBAB;
+/// Format version of every known custom Jumplist.
+pub const KNOWN_VERSION: u32 = 2;
+/// Largest plausible format version, a larger one means the file isn't a custom Jumplist.
+pub const MAX_PLAUSIBLE_VERSION: u32 = 1000;
 
 /// Category types used in CustomDestinations.
 /// - `Custom`: User-defined or application-defined category.
 /// - `Known`: Special categories like "Recent" or "Frequent".
 /// - `Task`: Represents shortcut tasks like creating new project.
-#[derive(Debug, Serialize, PartialEq)]
+/// - `Pinned`: Not stored as a category type, holds the pinned items block that some files
+///   have before their first category.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
-pub enum CatagoryType {
+pub enum CategoryType {
     Custom = 0x00,
     Known = 0x01,
     Task = 0x02,
+    Pinned = 0x03,
 }
 
+/// The former, misspelled name of [`CategoryType`].
+#[deprecated(note = "renamed to `CategoryType`")]
+pub type CatagoryType = CategoryType;
+
 /// Represents the file header of a `.customDestinations-ms` file.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CustomDestinationsHeader {
-    /// File format version
+    /// File format version, [`KNOWN_VERSION`] in every known file.
     pub version: u32,
     /// Number of categories
     pub num_of_cat: u32,
-    /// Unknown field, seen as 0x0 always this might be a reserved.
-    #[serde(skip_serializing)]
-    pub unkonwn: u32,
+    /// Reserved field of unknown meaning, usually 0. Named `unkonwn` and not serialized
+    /// before.
+    #[serde(default, alias = "unkonwn")]
+    pub reserved: u32,
+    /// Bytes of unknown meaning, only set with [`ParseOptions::capture_unknown_bytes`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unknown_fields: Option<BTreeMap<String, String>>,
 }
 
 impl CustomDestinationsHeader {
     /// Parses the header from a file path.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_path(path: &str) -> Result<Self, JumplistParserError> {
         let mut file = std::fs::File::open(path).map_err(|e| {
             JumplistParserError::General(e.to_string(), line!(), file!().to_string())
@@ -47,67 +86,376 @@ impl CustomDestinationsHeader {
     }
     /// Parses the header from a given reader.
     pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<Self, JumplistParserError> {
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Same as [`CustomDestinationsHeader::from_reader`] but with explicit [`ParseOptions`].
+    ///
+    /// Fails on a version that isn't [plausible](CustomDestinationsHeader::is_plausible_version).
+    pub fn from_reader_with_options<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
         let version = reader.read_u32::<LittleEndian>().map_err(|e| {
             JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
         })?;
+        if !Self::is_plausible_version(version) {
+            return Err(JumplistParserError::FileStructure(
+                format!(
+                    "The format version {} is not plausible, the file is not a custom Jumplist",
+                    version
+                ),
+                line!(),
+                file!().to_string(),
+            ));
+        }
         let num_of_cat = reader.read_u32::<LittleEndian>().map_err(|e| {
             JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
         })?;
-        let unkonwn = reader.read_u32::<LittleEndian>().map_err(|e| {
+        let reserved = reader.read_u32::<LittleEndian>().map_err(|e| {
             JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
         })?;
 
+        let unknown_fields = options.capture_unknown_bytes.then(|| {
+            let mut fields = BTreeMap::new();
+            fields.insert(
+                "header_unknown_0x08_4".to_string(),
+                to_hex(&reserved.to_le_bytes()),
+            );
+            fields
+        });
+
         Ok(CustomDestinationsHeader {
             version,
             num_of_cat,
-            unkonwn,
+            reserved,
+            unknown_fields,
         })
     }
+
+    /// Whether `version` can be the format version of a custom Jumplist: from 1 to
+    /// [`MAX_PLAUSIBLE_VERSION`].
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::custom_destinations::CustomDestinationsHeader;
+    ///
+    /// assert!(CustomDestinationsHeader::is_plausible_version(2));
+    /// assert!(!CustomDestinationsHeader::is_plausible_version(0x4F4C_4548));
+    /// ```
+    pub fn is_plausible_version(version: u32) -> bool {
+        (1..=MAX_PLAUSIBLE_VERSION).contains(&version)
+    }
+
+    /// Whether the version is [`KNOWN_VERSION`]. Other versions are parsed with its layout,
+    /// no file with a different layout is known.
+    pub fn is_known_version(&self) -> bool {
+        self.version == KNOWN_VERSION
+    }
 }
 
-/// IDs of categories. either `Frequent` or `Recent`.
-#[derive(Debug)]
+/// IDs of known categories (`KNOWNDESTCATEGORY`).
+///
+/// Serialized as `frequent`, `recent`, `pinned`, `none` or `unknown(0x0000000A)` for
+/// unknown IDs (the 32-bit two's complement value).
+#[derive(Debug, Clone, PartialEq)]
 #[repr(i32)]
-pub enum CategoryID {
+pub enum CategoryId {
     Frequent = 0x01,
     Recent = 0x02,
+    /// Pinned destinations. Not stored as a known category ID, given to the pinned items
+    /// block that some files have before their first category.
+    Pinned = 0x03,
     None = -1,
     /// Unknown or unrecognized category ID.
     Unknown(i32),
 }
 
-impl Serialize for CategoryID {
+/// The former name of [`CategoryId`].
+#[deprecated(note = "renamed to `CategoryId`")]
+pub type CategoryID = CategoryId;
+
+impl From<i32> for CategoryId {
+    /// The ID of a known category as stored in the file.
+    fn from(id: i32) -> Self {
+        match id {
+            1 => CategoryId::Frequent,
+            2 => CategoryId::Recent,
+            -1 => CategoryId::None,
+            x => CategoryId::Unknown(x),
+        }
+    }
+}
+
+impl Display for CategoryId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CategoryId::Frequent => write!(f, "frequent"),
+            CategoryId::Recent => write!(f, "recent"),
+            CategoryId::Pinned => write!(f, "pinned"),
+            CategoryId::None => write!(f, "none"),
+            CategoryId::Unknown(val) => write!(f, "unknown(0x{:08X})", val),
+        }
+    }
+}
+
+impl FromStr for CategoryId {
+    type Err = String;
+
+    /// Parses the [`Display`] form back. The bare hex IDs written by older versions
+    /// (e.g. `0005`) are accepted too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid category ID '{}'", s);
+        match s {
+            "frequent" => Ok(CategoryId::Frequent),
+            "recent" => Ok(CategoryId::Recent),
+            "pinned" => Ok(CategoryId::Pinned),
+            "none" => Ok(CategoryId::None),
+            _ => {
+                let hex = match s.strip_prefix("unknown(0x") {
+                    Some(rest) => rest.strip_suffix(')').ok_or_else(invalid)?,
+                    None => s,
+                };
+                u32::from_str_radix(hex, 16)
+                    .map(|val| CategoryId::Unknown(val as i32))
+                    .map_err(|_| invalid())
+            }
+        }
+    }
+}
+
+impl Serialize for CategoryId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match self {
-            CategoryID::Frequent => serializer.serialize_str("frequent"),
-            CategoryID::Recent => serializer.serialize_str("recent"),
-            CategoryID::None => serializer.serialize_str("none"),
-            CategoryID::Unknown(val) => serializer.serialize_str(&format!("{:04X}", val)),
-        }
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CategoryId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
     }
 }
 
 /// Represents a category inside a CustomDestinations file.
 /// A category groups one or more LNK entries or Shellitems.
-#[derive(Debug, Serialize)]
-pub struct Catagory {
-    /// Type of the category (`Custom`, `Known` or `Task`).
-    pub r#type: CatagoryType,
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Category {
+    /// Offset of the category within the file.
+    #[serde(default)]
+    pub file_offset: u64,
+    /// Type of the category (`Custom`, `Known`, `Task` or `Pinned`).
+    pub r#type: CategoryType,
     /// Name of the category (only for `Custom`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// Number of LNK entries or Shellitems.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_of_entries: Option<u32>,
-    /// Known category ID (used only when `type` is `Known`).
+    /// Known category ID (only for `Known` and `Pinned`).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<CategoryID>,
+    pub id: Option<CategoryId>,
     /// Parsed LNK entries.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub entries: Option<Vec<LNKParser>>,
+    pub entries: Option<Vec<CustomDestinationsEntry>>,
+    /// Bytes of unknown meaning, only set with [`ParseOptions::capture_unknown_bytes`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unknown_fields: Option<BTreeMap<String, String>>,
+    /// Whether the category is synthetic and holds the LNK entries recovered by
+    /// [`CustomDestinations::carve_from_reader`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub recovered: bool,
+}
+
+impl Category {
+    /// Whether the entries of the category count as pinned: the tasks, which are pinned
+    /// by the application, and the pinned items block.
+    pub fn is_pinned(&self) -> bool {
+        matches!(self.r#type, CategoryType::Task | CategoryType::Pinned)
+            || self.id == Some(CategoryId::Pinned)
+    }
+
+    /// Number of parsed entries, which may differ from [`Category::declared_entry_count`]
+    /// for a damaged file or after [`JumplistParser::retain_entries`](crate::JumplistParser::retain_entries).
+    pub fn entry_count(&self) -> usize {
+        self.entries.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Number of entries declared by the category, `None` for the categories without a
+    /// count (custom categories declare one, known categories don't).
+    pub fn declared_entry_count(&self) -> Option<u32> {
+        self.num_of_entries
+    }
+
+    /// The parsed entries, in file order.
+    pub fn iter_entries(&self) -> impl Iterator<Item = &CustomDestinationsEntry> {
+        self.entries.iter().flatten()
+    }
+}
+
+/// The former, misspelled name of [`Category`].
+#[deprecated(note = "use Category")]
+pub type Catagory = Category;
+
+/// Represents a single LNK entry inside a category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schema",
+    schemars(
+        description = "A single LNK entry inside a category, the LNK fields are flattened in."
+    )
+)]
+pub struct CustomDestinationsEntry {
+    /// Parsed LNK entry, always `None` when deserialized.
+    #[serde(flatten, skip_deserializing)]
+    #[cfg_attr(feature = "schema", schemars(skip))]
+    pub lnk: Option<Lnk>,
+    /// Offset of the entry (its LNK GUID) within the file.
+    #[serde(default)]
+    pub file_offset: u64,
+    /// Size of the entry in bytes, the LNK GUID included.
+    #[serde(default)]
+    pub entry_size: u64,
+    /// Classification of the LNK target path.
+    #[serde(flatten)]
+    pub target: TargetInfo,
+    /// Raw bytes of the LNK entry, only kept when [`ParseOptions::keep_raw_lnk`] is set.
+    #[serde(skip)]
+    pub raw_lnk: Option<Vec<u8>>,
+    /// Header of the LNK entry, read from the raw LNK bytes.
+    #[serde(skip)]
+    pub lnk_header: Option<LnkHeader>,
+    /// MFT entry number of the LNK target, from the shell items of the LNK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_mft_entry: Option<u64>,
+    /// MFT sequence number of the LNK target, from the shell items of the LNK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_mft_sequence: Option<u16>,
+    /// Structures of the LNK entry that `lnk_parser` doesn't expose, read from the raw LNK bytes.
+    #[serde(skip)]
+    pub lnk_details: Option<LnkDetails>,
+    /// Deterministic ID of the entry, see [`fingerprint`](crate::fingerprint).
+    #[serde(default)]
+    pub entry_fingerprint: String,
+}
+
+impl CustomDestinationsEntry {
+    /// File attributes of the LNK target, from the LNK header.
+    pub fn file_attributes(&self) -> Option<u32> {
+        self.lnk_header.as_ref().map(|h| h.file_attributes)
+    }
+
+    /// Best path of the target, see [`LnkDetails::target_path`]. `None` when the LNK records
+    /// no path.
+    pub fn best_target_path(&self) -> Option<&str> {
+        LnkDetails::best_target_path(self.lnk_details.as_ref(), None).map(|(p, _)| p)
+    }
+
+    /// Where [`CustomDestinationsEntry::best_target_path`] comes from, never
+    /// [`TargetPathSource::Destlist`].
+    pub fn target_path_source(&self) -> Option<TargetPathSource> {
+        LnkDetails::best_target_path(self.lnk_details.as_ref(), None).map(|(_, s)| s)
+    }
+
+    /// An entry whose LNK was skipped, see [`CustomDestinations::scan_structure`].
+    fn skipped(file_offset: u64, entry_size: u64) -> Self {
+        CustomDestinationsEntry {
+            lnk: None,
+            file_offset,
+            entry_size,
+            target: TargetInfo::from_path("", None),
+            raw_lnk: None,
+            lnk_header: None,
+            target_mft_entry: None,
+            target_mft_sequence: None,
+            lnk_details: None,
+            entry_fingerprint: String::new(),
+        }
+    }
+
+    /// Parse the LNK entry at the current position of `reader`, `file_offset` is the
+    /// start of the entry (its GUID, or the LNK itself for carved entries).
+    pub(crate) fn from_lnk_reader<R: Read + Seek>(
+        reader: &mut R,
+        file_offset: u64,
+        options: &ParseOptions,
+        tracker: &mut LimitTracker,
+    ) -> Result<Self, JumplistParserError> {
+        let mut limited = tracker.start_lnk(reader)?;
+        let entry = Self::from_limited_reader(&mut limited, file_offset, options);
+        tracker.end_lnk(limited)?;
+        entry
+    }
+
+    fn from_limited_reader<R: Read + Seek>(
+        reader: &mut R,
+        file_offset: u64,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let start = reader.stream_position().map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        let mut lnk_header = [0; LNK_HEADER_SIZE];
+        reader.read_exact(&mut lnk_header).map_err(|e| {
+            JumplistParserError::LnkEntry(e.to_string(), line!(), file!().to_string())
+        })?;
+        // `lnk_parser` accepts LNKs with a corrupt header, they are dropped here instead
+        check_lnk_header(&lnk_header)?;
+        let lnk_header = LnkHeader::from_buffer(&lnk_header, options.ts_format);
+        reader.seek(SeekFrom::Start(start)).map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        let lnk = LNKParser::from_reader(reader).map_err(|e| {
+            JumplistParserError::LnkEntry(e.to_string(), line!(), file!().to_string())
+        })?;
+
+        let end = reader.stream_position().map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        let mut buffer = vec![0; (end - start) as usize];
+        reader.seek(SeekFrom::Start(start)).map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        reader.read_exact(&mut buffer).map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        let lnk_details = LnkDetails::from_buffer(&buffer, options.ts_format);
+        let mft_reference = lnk_details.as_ref().and_then(|d| d.target_mft_reference);
+        let raw_lnk = match options.keep_raw_lnk {
+            true => Some(buffer),
+            false => None,
+        };
+
+        let target = TargetInfo::from_path(
+            lnk.normalize()
+                .get("target_full_path")
+                .map(|s| s.as_str())
+                .unwrap_or_default(),
+            lnk_header.as_ref().map(|h| h.file_attributes),
+        );
+
+        Ok(Self {
+            lnk: Some(Lnk::new(lnk)),
+            file_offset,
+            entry_size: end - file_offset,
+            target,
+            raw_lnk,
+            lnk_header,
+            target_mft_entry: mft_reference.map(|r| r.entry),
+            target_mft_sequence: mft_reference.map(|r| r.sequence),
+            lnk_details,
+            entry_fingerprint: String::new(),
+        })
+    }
 }
 
 /// Represents the entire parsed CustomDestinations jumplist file.
@@ -121,16 +469,29 @@ pub struct Catagory {
 ///     println!("{:#?}", cd);
 /// }
 /// ```
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schema",
+    schemars(description = "Represents the entire parsed CustomDestinations jumplist file.")
+)]
 pub struct CustomDestinations {
     /// File header with metadata.
     pub header: CustomDestinationsHeader,
     /// All parsed categories and their LNK entries.
-    pub entries: Vec<Catagory>,
+    pub entries: Vec<Category>,
+    /// Inconsistencies found while parsing (e.g. fewer categories than declared in the header).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Whether parsing stopped before the end of the file (truncated or corrupt data), the
+    /// categories parsed until then are kept.
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 impl CustomDestinations {
     /// Parse a CustomDestinations file from a path on disk.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_path(path: &str) -> Result<Self, JumplistParserError> {
         let mut file = std::fs::File::open(path).map_err(|e| {
             JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
@@ -138,15 +499,118 @@ impl CustomDestinations {
         Self::from_reader(&mut file)
     }
 
+    /// Parse a CustomDestinations file from a raw byte buffer.
+    pub fn from_buffer(buf: &[u8]) -> Result<Self, JumplistParserError> {
+        Self::from_reader(&mut Cursor::new(buf))
+    }
+
     /// Parse a CustomDestinations file from a reader.
     pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<Self, JumplistParserError> {
-        let header = CustomDestinationsHeader::from_reader(reader)?;
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Read the layout of a CustomDestinations file: the header and the type, name, counts
+    /// and byte range of every category. The LNK entries are not parsed, only their sizes
+    /// are read to find the category boundaries, which makes it much faster than
+    /// [`CustomDestinations::from_reader`] for inventories of many files.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::custom_destinations::CustomDestinations;
+    /// use std::fs::File;
+    ///
+    /// let mut file = File::open(
+    ///     "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let outline = CustomDestinations::scan_structure(&mut file).unwrap();
+    /// assert_eq!(outline.categories.len(), 3);
+    /// assert_eq!(outline.total_entries(), 6);
+    /// ```
+    pub fn scan_structure<R: Read + Seek>(
+        reader: &mut R,
+    ) -> Result<CustomDestinationsOutline, JumplistParserError> {
+        Self::scan_structure_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Same as [`CustomDestinations::scan_structure`] but with explicit [`ParseOptions`].
+    pub fn scan_structure_with_options<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> Result<CustomDestinationsOutline, JumplistParserError> {
+        let parsed = Self::parse(reader, options, true)?;
+        let end = reader.stream_position().map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        // A category ends where the next one starts, the last one where parsing stopped
+        let ends: Vec<u64> = parsed
+            .entries
+            .iter()
+            .skip(1)
+            .map(|c| c.file_offset)
+            .chain([end])
+            .collect();
+        let categories = parsed
+            .entries
+            .iter()
+            .zip(ends)
+            .map(|(category, end)| CategoryOutline {
+                file_offset: category.file_offset,
+                size: end.saturating_sub(category.file_offset),
+                r#type: category.r#type.clone(),
+                name: category.name.clone(),
+                id: category.id.clone(),
+                declared_entry_count: category.declared_entry_count(),
+                entry_count: category.entry_count(),
+            })
+            .collect();
+        Ok(CustomDestinationsOutline {
+            header: parsed.header,
+            categories,
+            warnings: parsed.warnings,
+            truncated: parsed.truncated,
+        })
+    }
+
+    /// Same as [`CustomDestinations::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        Self::parse(reader, options, false)
+    }
+
+    /// Parse the file, with `skip_lnks` the LNK entries are only delimited: they have no
+    /// LNK data.
+    fn parse<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+        skip_lnks: bool,
+    ) -> Result<Self, JumplistParserError> {
+        let header = CustomDestinationsHeader::from_reader_with_options(reader, options)?;
+        options.report(ProgressEvent::HeaderParsed {
+            declared: header.num_of_cat,
+        });
+        let mut warnings = vec![];
+        if !header.is_known_version() {
+            warnings.push(format!(
+                "Unknown format version {}, the file was parsed with the layout of version {}",
+                header.version, KNOWN_VERSION
+            ));
+        }
         let mut categories = Vec::new();
+        let mut tracker = LimitTracker::new(options.limits);
     
         fn read_guid_and_validate<R: Read + Seek>(
             reader: &mut R,
             category: &str,
-        ) -> Result<LNKParser, JumplistParserError> {
+            options: &ParseOptions,
+            tracker: &mut LimitTracker,
+            skip_lnks: bool,
+        ) -> Result<CustomDestinationsEntry, JumplistParserError> {
+            let file_offset = reader.stream_position().map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
             let mut guid_data = [0; 16];
             reader.read_exact(&mut guid_data).map_err(|e| {
                 JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
@@ -164,31 +628,155 @@ impl CustomDestinations {
                 ));
             }
     
-            LNKParser::from_reader(reader).map_err(|e| {
-                JumplistParserError::LnkEntry(e.to_string(), line!(), file!().to_string())
-            })
+            if skip_lnks {
+                let mut limited = tracker.start_lnk(reader)?;
+                let lnk_size = skip_lnk(&mut limited);
+                tracker.end_lnk(limited)?;
+                return Ok(CustomDestinationsEntry::skipped(
+                    file_offset,
+                    guid_data.len() as u64 + lnk_size?,
+                ));
+            }
+            CustomDestinationsEntry::from_lnk_reader(reader, file_offset, options, tracker)
         }
     
+        /// Parse `count` entries. An entry that can't be parsed is dropped and parsing resumes
+        /// at the next LNK CLSID, or the category footer. `truncated` is set when neither
+        /// follows.
+        #[allow(clippy::too_many_arguments)]
         fn parse_lnk_entries<R: Read + Seek>(
             reader: &mut R,
             count: u32,
             category: &str,
-        ) -> Result<Vec<LNKParser>, JumplistParserError> {
-            let mut entries = Vec::with_capacity(count as usize);
+            options: &ParseOptions,
+            warnings: &mut Vec<String>,
+            truncated: &mut bool,
+            tracker: &mut LimitTracker,
+            skip_lnks: bool,
+        ) -> Result<Vec<CustomDestinationsEntry>, JumplistParserError> {
+            // Categories can be registered without ever being populated
+            if count == 0 {
+                return Ok(vec![]);
+            }
+            let capacity = remaining_len(reader)? / MIN_ENTRY_SIZE;
+            let mut entries = Vec::with_capacity((count as u64).min(capacity) as usize);
             for _ in 0..count {
-                entries.push(read_guid_and_validate(reader, category)?);
+                let start = reader.stream_position().map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })?;
+                let error =
+                    match read_guid_and_validate(reader, category, options, tracker, skip_lnks) {
+                        Ok(entry) => {
+                            tracker.add_entries(1)?;
+                            options.report_entries(tracker.entries());
+                            entries.push(entry);
+                            continue;
+                        }
+                        Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                        Err(e) => e,
+                    };
+                match find_resync_offset(reader, start + 1)? {
+                    Some((offset, is_footer)) => {
+                        warnings.push(format!(
+                            "Dropped the {} entry at offset {}: {}",
+                            category, start, error
+                        ));
+                        reader.seek(SeekFrom::Start(offset)).map_err(|e| {
+                            JumplistParserError::FileStructure(
+                                e.to_string(),
+                                line!(),
+                                file!().to_string(),
+                            )
+                        })?;
+                        if is_footer {
+                            break;
+                        }
+                    }
+                    None => {
+                        warnings.push(format!(
+                            "Parsing stopped at the {} entry at offset {}: {}",
+                            category, start, error
+                        ));
+                        *truncated = true;
+                        break;
+                    }
+                }
             }
             Ok(entries)
         }
-    
-        for _ in 0..header.num_of_cat {
+
+        /// Offset of the next LNK CLSID or category footer from `from`, and whether it is the
+        /// footer.
+        ///
+        /// The file is read in growing chunks, a resync costs the distance to the next match
+        /// rather than the rest of the file.
+        fn find_resync_offset<R: Read + Seek>(
+            reader: &mut R,
+            from: u64,
+        ) -> Result<Option<(u64, bool)>, JumplistParserError> {
+            let to_error = |e: std::io::Error| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            };
+            // The LNK header starts with its size and the same CLSID
+            let mut entry_start = LNK_CLSID.to_vec();
+            entry_start.extend_from_slice(&(LNK_HEADER_SIZE as u32).to_le_bytes());
+            entry_start.extend_from_slice(&LNK_CLSID);
+            let footer = CATEGORY_FOOTER.to_le_bytes();
+
+            reader.seek(SeekFrom::Start(from)).map_err(to_error)?;
+            let mut window = vec![];
+            let mut window_start = from;
+            let mut chunk_size = RESYNC_CHUNK_SIZE;
+            loop {
+                let read = reader
+                    .by_ref()
+                    .take(chunk_size)
+                    .read_to_end(&mut window)
+                    .map_err(to_error)?;
+                let at_end = (read as u64) < chunk_size;
+                // Before the end, the last bytes may be the start of a match
+                let searched = match at_end {
+                    true => window.len(),
+                    false => window.len().saturating_sub(entry_start.len() - 1),
+                };
+                let found = (0..searched).find_map(|i| {
+                    match (
+                        window[i..].starts_with(&entry_start),
+                        window[i..].starts_with(&footer),
+                    ) {
+                        (true, _) => Some((window_start + i as u64, false)),
+                        (_, true) => Some((window_start + i as u64, true)),
+                        _ => None,
+                    }
+                });
+                if found.is_some() || at_end {
+                    return Ok(found);
+                }
+                window.drain(..searched);
+                window_start += searched as u64;
+                chunk_size = (chunk_size * 2).min(MAX_RESYNC_CHUNK_SIZE);
+            }
+        }
+
+        /// Parse a category, its entries and its footer.
+        fn parse_category<R: Read + Seek>(
+            reader: &mut R,
+            options: &ParseOptions,
+            warnings: &mut Vec<String>,
+            truncated: &mut bool,
+            tracker: &mut LimitTracker,
+            skip_lnks: bool,
+        ) -> Result<Category, JumplistParserError> {
+            let file_offset = reader.stream_position().map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
             let r#type = match reader.read_u32::<LittleEndian>() {
-                Ok(0x00) => CatagoryType::Custom,
-                Ok(0x01) => CatagoryType::Known,
-                Ok(0x02) => CatagoryType::Task,
+                Ok(0x00) => CategoryType::Custom,
+                Ok(0x01) => CategoryType::Known,
+                Ok(0x02) => CategoryType::Task,
                 Ok(x) => {
                     return Err(JumplistParserError::FileStructure(
-                        format!("CatagoryType unknown '{}'", x),
+                        format!("CategoryType unknown '{}'", x),
                         line!(),
                         file!().to_string(),
                     ))
@@ -201,97 +789,515 @@ impl CustomDestinations {
                     ))
                 }
             };
+            let read_count = |reader: &mut R| {
+                reader.read_u32::<LittleEndian>().map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })
+            };
     
-            match r#type {
-                CatagoryType::Custom => {
-                    let name_len = reader.read_u16::<LittleEndian>().ok().unwrap();
-                    let name = read_utf16_string(reader, Some(name_len as usize)).ok();
-                    let num_of_entries = reader.read_u32::<LittleEndian>().ok();
-                    let entries = parse_lnk_entries(reader, num_of_entries.unwrap(), "Custom")?;
+            let mut category = match r#type {
+                // Only built from the pinned items block
+                CategoryType::Pinned => unreachable!(),
+                CategoryType::Custom => {
+                    let name_len = reader.read_u16::<LittleEndian>().map_err(|e| {
+                        JumplistParserError::FileStructure(
+                            e.to_string(),
+                            line!(),
+                            file!().to_string(),
+                        )
+                    })?;
+                    check_fits(reader, "name_len", name_len as u64, 2)?;
+                    ParseLimits::check(
+                        "The 'name_len'",
+                        name_len as u64,
+                        options.limits.max_string_length,
+                    )?;
+                    // `name_len` counts UTF-16 code units, not characters
+                    let name = read_utf16_units(reader, name_len as usize).map_err(|e| {
+                        JumplistParserError::FileStructure(
+                            e.to_string(),
+                            line!(),
+                            file!().to_string(),
+                        )
+                    })?;
+                    let num_of_entries = read_count(reader)?;
+                    let entries = parse_lnk_entries(
+                        reader,
+                        num_of_entries,
+                        "Custom",
+                        options,
+                        warnings,
+                        truncated,
+                        tracker,
+                        skip_lnks,
+                    )?;
     
-                    categories.push(Catagory {
+                    Category {
+                        file_offset,
                         r#type,
-                        name,
-                        num_of_entries,
+                        name: Some(name),
+                        num_of_entries: Some(num_of_entries),
                         entries: Some(entries),
                         id: None,
-                    });
+                        unknown_fields: None,
+                        recovered: false,
+                    }
                 }
-                CatagoryType::Known => {
-                    let id = match reader.read_i32::<LittleEndian>().map_err(|e| {
-                        JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
-                    })? {
-                        1 => CategoryID::Frequent,
-                        2 => CategoryID::Recent,
-                        -1 => CategoryID::None,
-                        x => CategoryID::Unknown(x),
-                    };
+                CategoryType::Known => {
+                    let id = reader.read_i32::<LittleEndian>().map_err(|e| {
+                        JumplistParserError::FileStructure(
+                            e.to_string(),
+                            line!(),
+                            file!().to_string(),
+                        )
+                    })?;
+                    let id = CategoryId::from(id);
     
-                    categories.push(Catagory {
+                    Category {
+                        file_offset,
                         r#type,
                         name: None,
                         num_of_entries: None,
                         id: Some(id),
                         entries: None,
-                    });
+                        unknown_fields: None,
+                        recovered: false,
+                    }
                 }
-                CatagoryType::Task => {
-                    let num_of_entries = reader.read_u32::<LittleEndian>().ok();
-                    let entries = parse_lnk_entries(reader, num_of_entries.unwrap(), "Task")?;
+                CategoryType::Task => {
+                    let num_of_entries = read_count(reader)?;
+                    let entries = parse_lnk_entries(
+                        reader,
+                        num_of_entries,
+                        "Task",
+                        options,
+                        warnings,
+                        truncated,
+                        tracker,
+                        skip_lnks,
+                    )?;
     
-                    categories.push(Catagory {
+                    Category {
+                        file_offset,
                         r#type,
                         name: None,
-                        num_of_entries,
+                        num_of_entries: Some(num_of_entries),
                         entries: Some(entries),
                         id: None,
-                    });
+                        unknown_fields: None,
+                        recovered: false,
+                    }
                 }
+            };
+            if *truncated {
+                return Ok(category);
             }
     
             // Skip footer
-            reader.seek(SeekFrom::Current(4)).map_err(|e| {
+            if remaining_len(reader)? < 4 {
+                warnings.push(format!(
+                    "The category at offset {} has no footer, the file is truncated",
+                    file_offset
+                ));
+                *truncated = true;
+                return Ok(category);
+            }
+            let footer = reader.read_u32::<LittleEndian>().map_err(|e| {
                 JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
             })?;
+            if footer != CATEGORY_FOOTER {
+                warnings.push(format!(
+                    "The category at offset {} ends with 0x{:08X} instead of the footer",
+                    file_offset, footer
+                ));
+            }
+            category.unknown_fields = options.capture_unknown_bytes.then(|| {
+                let mut fields = BTreeMap::new();
+                fields.insert(
+                    "category_footer_4".to_string(),
+                    to_hex(&footer.to_le_bytes()),
+                );
+                fields
+            });
+            Ok(category)
         }
     
+        let mut truncated = false;
+    
+        // Some files have a count-prefixed block of pinned LNK entries before the first
+        // category, it is recognized by the LNK CLSID following the count.
+        let file_offset = reader.stream_position().map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        let mut peek = [0; 20];
+        let has_pinned_block = match reader.read_exact(&mut peek) {
+            Ok(_) => peek[4..] == LNK_CLSID,
+            Err(_) => false,
+        };
+        reader.seek(SeekFrom::Start(file_offset)).map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        if has_pinned_block {
+            let num_of_entries = reader.read_u32::<LittleEndian>().map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
+            let entries = parse_lnk_entries(
+                reader,
+                num_of_entries,
+                "Pinned",
+                options,
+                &mut warnings,
+                &mut truncated,
+                &mut tracker,
+                skip_lnks,
+            )?;
+            // The block may or may not end with a category footer
+            let mut unknown_fields = options.capture_unknown_bytes.then(BTreeMap::new);
+            let footer_offset = reader.stream_position().map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
+            match reader.read_u32::<LittleEndian>() {
+                Ok(CATEGORY_FOOTER) => {
+                    if let Some(fields) = unknown_fields.as_mut() {
+                        fields.insert(
+                            "category_footer_4".to_string(),
+                            to_hex(&CATEGORY_FOOTER.to_le_bytes()),
+                        );
+                    }
+                }
+                _ => {
+                    reader.seek(SeekFrom::Start(footer_offset)).map_err(|e| {
+                        JumplistParserError::FileStructure(
+                            e.to_string(),
+                            line!(),
+                            file!().to_string(),
+                        )
+                    })?;
+                }
+            }
+            categories.push(Category {
+                file_offset,
+                r#type: CategoryType::Pinned,
+                name: None,
+                num_of_entries: Some(num_of_entries),
+                entries: Some(entries),
+                id: Some(CategoryId::Pinned),
+                unknown_fields,
+                recovered: false,
+            });
+        }
+
+        // A truncated or corrupt category ends the parsing, the categories before it are kept
+        for index in 0..header.num_of_cat {
+            if truncated {
+                break;
+            }
+            ParseLimits::check(
+                "The number of categories",
+                index as u64 + 1,
+                options.limits.max_categories,
+            )?;
+            let file_offset = reader.stream_position().map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
+            match parse_category(
+                reader,
+                options,
+                &mut warnings,
+                &mut truncated,
+                &mut tracker,
+                skip_lnks,
+            ) {
+                Ok(category) => categories.push(category),
+                Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                Err(e) => {
+                    warnings.push(format!(
+                        "Parsing stopped at the category at offset {}: {}",
+                        file_offset, e
+                    ));
+                    truncated = true;
+                }
+            }
+        }
+    
+        let num_of_cat = count_categories(&categories);
+        if num_of_cat != header.num_of_cat as usize {
+            warnings.push(format!(
+                "The header declares {} categories but {} were parsed",
+                header.num_of_cat, num_of_cat
+            ));
+        }
+        for (index, category) in categories.iter().enumerate() {
+            if let Some(declared) = category.declared_entry_count() {
+                if declared as usize != category.entry_count() {
+                    warnings.push(format!(
+                        "Category {} declares {} entries but {} were parsed",
+                        index,
+                        declared,
+                        category.entry_count()
+                    ));
+                }
+            }
+        }
+
+        options.check_warnings(&warnings)?;
         Ok(Self {
             entries: categories,
             header,
+            warnings,
+            truncated,
         })
     }
-    
-}
 
-impl Flaten for CustomDestinations {
-    /// Normalizes all LNK entries within the CustomDestinations file
-    /// into a vector of `key` and `value` maps by exteracting the most important fields.
+    /// Whether the number of parsed categories and entries matches the declared counts.
+    pub fn is_complete(&self) -> bool {
+        !self.truncated
+            && self.entries.iter().all(|c| !c.recovered)
+            && count_categories(&self.entries) == self.header.num_of_cat as usize
+            && self.entries.iter().all(|c| match c.declared_entry_count() {
+                Some(declared) => declared as usize == c.entry_count(),
+                None => true,
+            })
+    }
+
+    /// The parsed categories, in file order.
+    pub fn categories(&self) -> &[Category] {
+        &self.entries
+    }
+
+    /// Number of parsed entries in all the categories.
+    pub fn total_entries(&self) -> usize {
+        self.entries.iter().map(Category::entry_count).sum()
+    }
+
+    /// Every parsed entry with its category, in file order.
     ///
-    /// Fields like `name_string` and `command_line_arguments` are extracted
-    /// to provide meaningful descriptions of the LNK contents.
-    fn flaten(&self) -> Vec<HashMap<String, String>> {
-        let mut results: Vec<HashMap<String, String>> = Vec::new();
-        for entry in &self.entries {
-            if let Some(lnks) = &entry.entries {
-                for lnk in lnks {
-                    let mut lnk_normalized = lnk.normalize();
-                    let name_string = match lnk.get_name_string() {
-                        Some(s) => s.to_string(),
-                        None => String::from(""),
-                    };
+    /// # Example
+    /// ```
+    /// use jumplist_parser::custom_destinations::CustomDestinations;
+    ///
+    /// let custom = CustomDestinations::from_path(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(custom.iter_entries().count(), custom.total_entries());
+    /// for (category, entry) in custom.iter_entries() {
+    ///     println!("{:?} {:?}", category.r#type, entry.best_target_path());
+    /// }
+    /// ```
+    pub fn iter_entries(&self) -> impl Iterator<Item = (&Category, &CustomDestinationsEntry)> {
+        self.entries
+            .iter()
+            .flat_map(|category| category.iter_entries().map(move |entry| (category, entry)))
+    }
 
-                    let command_line_arguments = match lnk.get_command_line_arguments() {
-                        Some(s) => s.to_string(),
-                        None => String::from(""),
-                    };
-                    lnk_normalized.insert("name_string".to_string(), name_string);
-                    lnk_normalized
-                        .insert("command_line_arguments".to_string(), command_line_arguments);
+    /// Recover the LNK entries of a damaged CustomDestinations file by scanning for LNK
+    /// headers (their size, `0x4C`, followed by the shell link CLSID) instead of following
+    /// the file structure.
+    ///
+    /// Every LNK found is put in a single synthetic category marked as `recovered`. The
+    /// header is kept when it can be read.
+    pub fn carve_from_reader<R: Read + Seek>(reader: &mut R) -> Result<Self, JumplistParserError> {
+        Self::carve_from_reader_with_options(reader, &ParseOptions::default())
+    }
 
-                    results.push(lnk_normalized);
+    /// Same as [`CustomDestinations::carve_from_reader`] but with explicit [`ParseOptions`].
+    pub fn carve_from_reader_with_options<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let mut data = vec![];
+        reader
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| reader.read_to_end(&mut data))
+            .map_err(|e| {
+                JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+            })?;
+        let mut cursor = std::io::Cursor::new(&data[..]);
+        let header = CustomDestinationsHeader::from_reader_with_options(&mut cursor, options)
+            .unwrap_or(CustomDestinationsHeader {
+                version: 0,
+                num_of_cat: 0,
+                reserved: 0,
+                unknown_fields: None,
+            });
+
+        let mut signature = (LNK_HEADER_SIZE as u32).to_le_bytes().to_vec();
+        signature.extend_from_slice(&LNK_CLSID);
+        let mut entries = vec![];
+        let mut tracker = LimitTracker::new(options.limits);
+        let mut offset = 0;
+        while let Some(found) = data[offset..]
+            .windows(signature.len())
+            .position(|w| w == &signature[..])
+        {
+            let start = (offset + found) as u64;
+            cursor.set_position(start);
+            match CustomDestinationsEntry::from_lnk_reader(
+                &mut cursor,
+                start,
+                options,
+                &mut tracker,
+            ) {
+                Ok(entry) => {
+                    tracker.add_entries(1)?;
+                    options.report_entries(tracker.entries());
+                    offset = (start + entry.entry_size) as usize;
+                    entries.push(entry);
                 }
+                Err(_) => offset = start as usize + 1,
             }
         }
-        results
+
+        let warnings = vec![format!(
+            "Recovered {} LNK entries by scanning for LNK headers",
+            entries.len()
+        )];
+        Ok(Self {
+            header,
+            entries: vec![Category {
+                file_offset: entries.first().map(|e| e.file_offset).unwrap_or_default(),
+                r#type: CategoryType::Custom,
+                name: None,
+                num_of_entries: Some(entries.len() as u32),
+                id: None,
+                entries: Some(entries),
+                unknown_fields: None,
+                recovered: true,
+            }],
+            warnings,
+            truncated: false,
+        })
+    }
+}
+
+/// The layout of a CustomDestinations file, see [`CustomDestinations::scan_structure`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomDestinationsOutline {
+    /// File header with metadata.
+    pub header: CustomDestinationsHeader,
+    /// The categories, in file order.
+    pub categories: Vec<CategoryOutline>,
+    /// Inconsistencies found while scanning, the same as when parsing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Whether the scan stopped before the end of the file.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl CustomDestinationsOutline {
+    /// Number of entries found in all the categories.
+    pub fn total_entries(&self) -> usize {
+        self.categories.iter().map(|c| c.entry_count).sum()
+    }
+}
+
+/// A category of a [`CustomDestinationsOutline`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryOutline {
+    /// Offset of the category within the file.
+    pub file_offset: u64,
+    /// Size of the category in bytes, its entries and footer included.
+    pub size: u64,
+    /// Type of the category (`Custom`, `Known`, `Task` or `Pinned`).
+    pub r#type: CategoryType,
+    /// Name of the category (only for `Custom`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Known category ID (only for `Known` and `Pinned`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<CategoryId>,
+    /// See [`Category::declared_entry_count`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_entry_count: Option<u32>,
+    /// Number of LNK entries found.
+    pub entry_count: usize,
+}
+
+/// Number of categories counted by the header: the pinned items block isn't one.
+fn count_categories(categories: &[Category]) -> usize {
+    categories
+        .iter()
+        .filter(|c| c.r#type != CategoryType::Pinned)
+        .count()
+}
+
+impl Normalize for CustomDestinationsEntry {
+    /// Normalizes the LNK entry (if present) and returns selected fields.
+    ///
+    /// Fields like `lnk_description` and `lnk_command_line_arguments` are extracted
+    /// to provide meaningful descriptions of the LNK contents.
+    fn normalize(&self) -> HashMap<String, String> {
+        let lnk = match &self.lnk {
+            Some(lnk) => lnk,
+            None => return HashMap::new(),
+        };
+        let mut lnk_normalized = lnk.normalize_target();
+        let name_string = match lnk.get_name_string() {
+            Some(s) => s.to_string(),
+            None => String::from(""),
+        };
+
+        let command_line_arguments = match lnk.get_command_line_arguments() {
+            Some(s) => s.to_string(),
+            None => String::from(""),
+        };
+        lnk_normalized.insert("lnk_description".to_string(), name_string);
+        lnk_normalized.insert(
+            "lnk_command_line_arguments".to_string(),
+            command_line_arguments,
+        );
+        let target_hostname = lnk_normalized
+            .get("target_hostname")
+            .and_then(|h| clean_hostname(h.as_bytes()))
+            .unwrap_or_default();
+        lnk_normalized.insert("target_hostname".to_string(), target_hostname);
+        LnkDetails::normalize_target_path_into(
+            self.lnk_details.as_ref(),
+            None,
+            &mut lnk_normalized,
+        );
+        TargetInfo::normalize_into(&mut lnk_normalized, "", self.file_attributes());
+        if let Some(header) = &self.lnk_header {
+            header.normalize_times_into(&mut lnk_normalized);
+        }
+        LnkHeader::normalize_into(self.lnk_header.as_ref(), &mut lnk_normalized);
+        LnkDetails::normalize_into(self.lnk_details.as_ref(), None, &mut lnk_normalized);
+        let target_full_path_resolved = resolve_known_folder_path(
+            lnk_normalized
+                .get("target_full_path")
+                .map(|s| s.as_str())
+                .unwrap_or_default(),
+        );
+        lnk_normalized.insert(
+            "target_full_path_resolved".to_string(),
+            target_full_path_resolved,
+        );
+        lnk_normalized.insert("entry_offset".to_string(), self.file_offset.to_string());
+        lnk_normalized.insert("entry_size".to_string(), self.entry_size.to_string());
+        lnk_normalized
+    }
+}
+
+impl Flatten for CustomDestinations {
+    /// Normalizes all LNK entries within the CustomDestinations file
+    /// into a vector of `key` and `value` maps by exteracting the most important fields.
+    fn flatten(&self) -> Vec<NormalizedEntry> {
+        self.iter_entries()
+            .map(|(category, entry)| {
+                let mut normalized: NormalizedEntry = entry.normalize().into_iter().collect();
+                normalized.insert(
+                    "entry_category_id".to_string(),
+                    category
+                        .id
+                        .as_ref()
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                );
+                sort_keys(&mut normalized);
+                normalized
+            })
+            .collect()
     }
 }
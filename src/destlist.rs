@@ -2,29 +2,170 @@
 //! These contain metadata about recently or frequently accessed files, including
 //! a reference to LNK entries stored in the same compound file.
 
-use crate::errors::JumplistParserError;
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::{
+    errors::JumplistParserError,
+    guid::{Droid, Guid},
+    known_folders::{may_reference_known_folder, resolve_known_folder_path},
+    lnk_info::{Lnk, LnkDetails, LnkHeader, TargetPathSource},
+    options::{LimitTracker, ParseLimits, ProgressEvent, PROGRESS_INTERVAL},
+    stats::{ParseStats, Timer},
+    target::{paths_differ, TargetInfo},
+    timestamp::{Timestamp, TimestampFormat},
+    utils::{
+        check_fits, clean_hostname, decode_utf16_without_nuls, remaining_len, skip_unknown, to_hex,
+    },
+    ParseOptions,
+};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
+use chrono::Datelike;
 use lnk_parser::LNKParser;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
-    io::{Cursor, Read, Seek, SeekFrom},
-};
-use winparsingtools::{
-    date_time::FileTime,
-    structs::Guid,
-    traits::Normalize,
-    utils::{read_utf16_string, read_utf8_string},
+    collections::{BTreeMap, HashMap},
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    net::IpAddr,
 };
+use winparsingtools::traits::Normalize;
+
+use crate::{output::sort_keys, Flatten, NormalizedEntry};
+
+/// Entry numbers as they appear in the LNK stream names, e.g. `1a, 1b`.
+fn hex_list(numbers: &[u32]) -> String {
+    numbers
+        .iter()
+        .map(|number| format!("{:x}", number))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether `entry` is a `DestList` stream. The name is matched case insensitively, some
+/// third party shells write it as `destlist`.
+pub(crate) fn is_destlist_stream(entry: &cfb::Entry) -> bool {
+    entry.is_stream() && entry.name().eq_ignore_ascii_case("DestList")
+}
+
+/// The `DestList` stream of a compound file, at any depth of the storage tree. The one
+/// closest to the root storage when there are several.
+fn find_destlist(cfb_entries: &[cfb::Entry]) -> Option<&cfb::Entry> {
+    cfb_entries
+        .iter()
+        .filter(|entry| is_destlist_stream(entry))
+        .min_by_key(|entry| entry.path().components().count())
+}
+
+/// Minimum size of a version 1 `DestListEntry` (with an empty path).
+const MIN_ENTRY_SIZE_V1: u64 = 114;
+/// Minimum size of a version 3+ `DestListEntry` (with an empty path).
+const MIN_ENTRY_SIZE: u64 = 134;
+/// Size of the fields before the path of a version 1 `DestListEntry`, `path_size` included.
+const ENTRY_PREFIX_SIZE: usize = 0x72;
+/// Size of the fields before the path of a version 3+ `DestListEntry`, `path_size` included.
+const EXTENDED_ENTRY_PREFIX_SIZE: usize = 0x82;
+
+/// Version of a `DestList` stream, it determines the layout of the entries.
+///
+/// Serialized as the raw version number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "u32", into = "u32")]
+pub enum DestListVersion {
+    /// Windows 7 and 8: entries have a floating point access count, no 16 bytes before the
+    /// path size and no trailing bytes after the path.
+    V1Win7,
+    /// Windows 10 before 1703.
+    V3Win10,
+    /// Windows 10 1703 and later, and Windows 11.
+    V4Win10_1703Plus,
+    /// Recent Windows 11 builds, the entries have the layout of version 4.
+    V6Win11,
+    /// Any other version, e.g. version 2 written by early Windows 10 builds. Its entries are
+    /// parsed with the newest known layout.
+    Unknown(u32),
+}
+
+impl DestListVersion {
+    /// The raw version number.
+    pub fn as_u32(&self) -> u32 {
+        match self {
+            DestListVersion::V1Win7 => 1,
+            DestListVersion::V3Win10 => 3,
+            DestListVersion::V4Win10_1703Plus => 4,
+            DestListVersion::V6Win11 => 6,
+            DestListVersion::Unknown(version) => *version,
+        }
+    }
+
+    /// Whether entries have the 16 bytes (with the access count) before the path size and
+    /// 4 bytes after the path, as all versions after 1 do. Unknown versions are assumed to
+    /// use the newest layout.
+    pub fn has_extended_entries(&self) -> bool {
+        match self {
+            DestListVersion::V1Win7 => false,
+            DestListVersion::V3Win10
+            | DestListVersion::V4Win10_1703Plus
+            | DestListVersion::V6Win11
+            | DestListVersion::Unknown(_) => true,
+        }
+    }
 
-use crate::Flaten;
+    /// Whether the version is one the parser knows the layout of: 1, 3, 4 or 6.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, DestListVersion::Unknown(_))
+    }
+
+    /// Minimum size of an entry (with an empty path).
+    fn min_entry_size(&self) -> u64 {
+        match self.has_extended_entries() {
+            true => MIN_ENTRY_SIZE,
+            false => MIN_ENTRY_SIZE_V1,
+        }
+    }
+}
+
+impl From<u32> for DestListVersion {
+    fn from(version: u32) -> Self {
+        match version {
+            1 => DestListVersion::V1Win7,
+            3 => DestListVersion::V3Win10,
+            4 => DestListVersion::V4Win10_1703Plus,
+            6 => DestListVersion::V6Win11,
+            _ => DestListVersion::Unknown(version),
+        }
+    }
+}
+
+impl From<DestListVersion> for u32 {
+    fn from(version: DestListVersion) -> Self {
+        version.as_u32()
+    }
+}
+
+fn default_version_supported() -> bool {
+    true
+}
 
 /// Represents the header of a `DestList` stream.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DestListHeader {
-    pub version: u32,
+    /// Format version, `1` (Windows 7/8), `3` (Windows 10), `4` (Windows 10 1703+) or `6`
+    /// (Windows 11).
+    #[cfg_attr(feature = "schema", schemars(with = "u32"))]
+    pub version: DestListVersion,
+    /// `false` when `version` is unknown, the entries were then parsed with the newest known
+    /// layout and may be garbled. Always `true` for a missing `DestList` stream.
+    #[serde(default = "default_version_supported")]
+    pub version_supported: bool,
     pub number_of_entries: u32,
     pub number_of_pinned_entries: u32,
+    /// Floating point counter used by the shell when aging entries.
+    pub aging_counter: f32,
+    /// The last entry number issued, entry numbers are never reused.
+    pub last_entry_number: u32,
+    /// Number of add, delete and pin operations performed on the list.
+    pub last_revision_number: u64,
+    /// Bytes of unknown meaning, only set with [`ParseOptions::capture_unknown_bytes`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unknown_fields: Option<BTreeMap<String, String>>,
 }
 
 impl DestListHeader {
@@ -35,13 +176,22 @@ impl DestListHeader {
 
     /// Parse a `DestListHeader` from a readable and seekable stream.
     pub fn from_reader<R: Read + Seek>(r: &mut R) -> Result<Self, JumplistParserError> {
-        let version = r.read_u32::<LittleEndian>().map_err(|_| {
+        Self::from_reader_with_options(r, &ParseOptions::default())
+    }
+
+    /// Same as [`DestListHeader::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        r: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let mut unknown_fields = options.capture_unknown_bytes.then(BTreeMap::new);
+        let version = DestListVersion::from(r.read_u32::<LittleEndian>().map_err(|_| {
             JumplistParserError::DestListHeader(
                 "Can't parse the 'version'".to_string(),
                 line!(),
                 file!().to_string(),
             )
-        })?;
+        })?);
         let number_of_entries = r.read_u32::<LittleEndian>().map_err(|_| {
             JumplistParserError::DestListHeader(
                 "Can't parse the 'number_of_entries'".to_string(),
@@ -56,174 +206,373 @@ impl DestListHeader {
                 file!().to_string(),
             )
         })?;
+        let aging_counter = r.read_f32::<LittleEndian>().map_err(|_| {
+            JumplistParserError::DestListHeader(
+                "Can't parse the 'aging_counter'".to_string(),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+        let last_entry_number = r.read_u32::<LittleEndian>().map_err(|_| {
+            JumplistParserError::DestListHeader(
+                "Can't parse the 'last_entry_number'".to_string(),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
         // Ignore unknown bytes
-        r.seek(SeekFrom::Current(20)).map_err(|_| {
+        skip_unknown(r, 4, "header_unknown_0x14_4", &mut unknown_fields).map_err(|_| {
             JumplistParserError::DestListHeader(
                 "Can't seek after the unknow bytes".to_string(),
                 line!(),
                 file!().to_string(),
             )
         })?;
+        let last_revision_number = r.read_u64::<LittleEndian>().map_err(|_| {
+            JumplistParserError::DestListHeader(
+                "Can't parse the 'last_revision_number'".to_string(),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+
+        check_fits(
+            r,
+            "number_of_entries",
+            number_of_entries as u64,
+            version.min_entry_size(),
+        )?;
 
         Ok(Self {
             version,
+            version_supported: version.is_supported(),
             number_of_entries,
             number_of_pinned_entries,
+            aging_counter,
+            last_entry_number,
+            last_revision_number,
+            unknown_fields,
         })
     }
+
+    /// Header of a missing or empty `DestList` stream.
+    fn empty() -> Self {
+        DestListHeader {
+            version: DestListVersion::Unknown(0),
+            version_supported: true,
+            number_of_entries: 0,
+            number_of_pinned_entries: 0,
+            aging_counter: 0.0,
+            last_entry_number: 0,
+            last_revision_number: 0,
+            unknown_fields: None,
+        }
+    }
+
+    /// Estimated number of entries deleted from the list.
+    ///
+    /// Entry numbers are issued sequentially, so every number up to `last_entry_number`
+    /// that is not among the current entries belonged to a deleted entry.
+    pub fn deleted_entry_estimate(&self) -> u32 {
+        self.last_entry_number
+            .saturating_sub(self.number_of_entries)
+    }
+}
+
+/// Outcome of the lookup of the LNK stream of a [`DestListEntry`], serialized as
+/// `{"status": "parse_failed", "error": "..."}`.
+///
+/// A stream that exists but can't be parsed is forensically interesting: it may have been
+/// wiped or tampered with, while a missing stream is usually a deleted entry.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum LnkStatus {
+    /// The LNK streams were not looked up: a standalone `DestList` stream, or parsed with
+    /// [`ParseOptions::skip_lnks`].
+    #[default]
+    Skipped,
+    /// The LNK stream was parsed.
+    Parsed,
+    /// No LNK stream is named after the entry number.
+    Missing,
+    /// The LNK stream exists but can't be read or parsed, with the error.
+    ParseFailed(String),
+}
+
+impl LnkStatus {
+    /// Snake case name of the status, without the error.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LnkStatus::Skipped => "skipped",
+            LnkStatus::Parsed => "parsed",
+            LnkStatus::Missing => "missing",
+            LnkStatus::ParseFailed(_) => "parse_failed",
+        }
+    }
+}
+
+impl std::fmt::Display for LnkStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 /// Represents a single entry in the DestList stream.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DestListEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entry_id: Option<usize>,
+    /// Offset of the entry within the `DestList` stream.
+    #[serde(default)]
+    pub stream_offset: u64,
+    /// Size of the entry in bytes.
+    #[serde(default)]
+    pub entry_size: u64,
     /// GUID of the volume the file resides on.
-    pub volume_droid: Guid,
+    #[serde(default, skip_serializing_if = "Droid::is_omitted")]
+    pub volume_droid: Droid,
     /// GUID of the file itself.
-    pub file_droid: Guid,
+    #[serde(default, skip_serializing_if = "Droid::is_omitted")]
+    pub file_droid: Droid,
     /// Volume birth GUID.
-    pub volume_birth_droid: Guid,
+    #[serde(default, skip_serializing_if = "Droid::is_omitted")]
+    pub volume_birth_droid: Droid,
     /// File birth GUID.
-    pub file_birth_droid: Guid,
-    /// Hostname where the file was accessed.
-    pub hostname: String,
+    #[serde(default, skip_serializing_if = "Droid::is_omitted")]
+    pub file_birth_droid: Droid,
+    /// NetBIOS name of the machine where the file was accessed, `None` when empty.
+    ///
+    /// Trailing NULs and control characters are removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Whether `hostname` is an IP address rather than a machine name, as recorded for some
+    /// network share accesses.
+    pub hostname_is_ip: bool,
     /// Entry index number that corresponds with the LNK file with the same number in hex in the same compund file.
     pub entry_number: u32,
     /// Last modification time.
-    pub mtime: FileTime,
-    /// Indicates whether the entry is pinned.
-    pub pined: bool,
-    /// UTF-16 path of the file.
+    pub mtime: Timestamp,
+    /// Indicates whether the entry is pinned. Serialized as `pined` before, which is still
+    /// read.
+    #[serde(alias = "pined")]
+    pub pinned: bool,
+    /// Number of times the entry was opened. Version 1 `DestList` streams record it as a
+    /// floating point number, rounded here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_count: Option<u32>,
+    /// UTF-16 path of the file, without NULs. Unpaired surrogates are replaced with U+FFFD.
     pub path: String,
-    /// Parsed LNK entry associated with this entry.
+    /// Size of the path as stored, in bytes: twice `path_size`, or `path_size` for the
+    /// writers that count bytes instead of UTF-16 code units.
+    #[serde(default)]
+    pub path_raw_len: u64,
+    /// The 4 bytes after the path of version 3+ entries, read as a little-endian number.
+    /// Windows 10 1607+ seems to write the entry number again there, or flags. `None` for
+    /// version 1 entries, which don't have them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_value: Option<u32>,
+    /// `path` with known folder GUIDs replaced by their names, only set when `path` references one.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub lnk: Option<LNKParser>,
+    pub path_resolved: Option<String>,
+    /// Classification of `path`.
+    #[serde(flatten)]
+    pub target: TargetInfo,
+    /// Whether `path` and the target path of the LNK name different targets, e.g. a file
+    /// renamed after it was last opened. `false` without a LNK, see
+    /// [`paths_differ`](crate::target::paths_differ).
+    #[serde(default)]
+    pub path_mismatch: bool,
+    /// Parsed LNK entry associated with this entry, always `None` when deserialized.
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<serde_json::Value>"))]
+    pub lnk: Option<Lnk>,
+    /// Whether the LNK stream of the entry was found and parsed.
+    #[serde(default)]
+    pub lnk_status: LnkStatus,
+    /// Raw bytes of the LNK stream, only kept when [`ParseOptions::keep_raw_lnk`] is set.
+    #[serde(skip)]
+    pub raw_lnk: Option<Vec<u8>>,
+    /// Header of the LNK stream, read from the raw LNK bytes.
+    #[serde(skip)]
+    pub lnk_header: Option<LnkHeader>,
+    /// MFT entry number of the LNK target, from the shell items of the LNK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_mft_entry: Option<u64>,
+    /// MFT sequence number of the LNK target, from the shell items of the LNK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_mft_sequence: Option<u16>,
+    /// Structures of the LNK stream that `lnk_parser` doesn't expose, read from the raw LNK bytes.
+    #[serde(skip)]
+    pub lnk_details: Option<LnkDetails>,
+    /// Deterministic ID of the entry, see [`fingerprint`](crate::fingerprint).
+    #[serde(default)]
+    pub entry_fingerprint: String,
+    /// Bytes of unknown meaning, only set with [`ParseOptions::capture_unknown_bytes`].
+    ///
+    /// Keys are named after the offset (or position) and size of the region, e.g.
+    /// `entry_unknown_0x00_8`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unknown_fields: Option<BTreeMap<String, String>>,
 }
 
 impl DestListEntry {
     /// Parses a `DestListEntry` from a buffer.
-    pub fn from_buffer(buf: &[u8], version: u32) -> Result<Self, JumplistParserError> {
+    pub fn from_buffer<V: Into<DestListVersion>>(
+        buf: &[u8],
+        version: V,
+    ) -> Result<Self, JumplistParserError> {
         Self::from_reader(&mut Cursor::new(buf), version)
     }
 
     /// Parses a `DestListEntry` from a readable and seekable stream.
-    pub fn from_reader<R: Read + Seek>(
+    ///
+    /// `version` is the [`DestListVersion`] (or raw version number) of the stream header.
+    pub fn from_reader<R: Read + Seek, V: Into<DestListVersion>>(
         r: &mut R,
-        version: u32,
+        version: V,
     ) -> Result<Self, JumplistParserError> {
-        // Ignore unknown bytes
-        r.seek(SeekFrom::Current(8)).map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't seek after unknown 8 bytes".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        let volume_droid = Guid::from_reader(r).map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'volume_droid'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        let file_droid = Guid::from_reader(r).map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'file_droid'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        let volume_birth_droid = Guid::from_reader(r).map_err(|_| {
+        Self::from_reader_with_options(r, version, &ParseOptions::default())
+    }
+
+    /// Same as [`DestListEntry::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek, V: Into<DestListVersion>>(
+        r: &mut R,
+        version: V,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let version = version.into();
+        let mut unknown_fields = options.capture_unknown_bytes.then(BTreeMap::new);
+        let stream_offset = r.stream_position().map_err(|_| {
             JumplistParserError::DestListEntry(
-                "Can't parse the 'volume_birth_droid'".to_string(),
+                "Can't get the entry offset".to_string(),
                 line!(),
                 file!().to_string(),
             )
         })?;
-        let file_birth_droid = Guid::from_reader(r).map_err(|_| {
+        // The fields before the path are read at once, small reads are slow on CFB streams
+        let prefix_size = match version.has_extended_entries() {
+            true => EXTENDED_ENTRY_PREFIX_SIZE,
+            false => ENTRY_PREFIX_SIZE,
+        };
+        let mut prefix = [0; EXTENDED_ENTRY_PREFIX_SIZE];
+        let prefix = &mut prefix[..prefix_size];
+        r.read_exact(prefix).map_err(|_| {
             JumplistParserError::DestListEntry(
-                "Can't parse the 'file_birth_droid'".to_string(),
+                format!("Can't read the {} bytes before the 'path'", prefix_size),
                 line!(),
                 file!().to_string(),
             )
         })?;
+        let guid_at = |offset: usize| {
+            let mut guid = Guid::default();
+            guid.0.copy_from_slice(&prefix[offset..offset + 16]);
+            Droid::new(guid, options.guid_format)
+        };
+        if let Some(fields) = unknown_fields.as_mut() {
+            let mut unknown = vec![("entry_unknown_0x00_8", 0x00..0x08)];
+            if version.has_extended_entries() {
+                unknown.push(("entry_unknown_0x5c_8", 0x5C..0x64));
+                unknown.push(("entry_unknown_0x70_4", 0x70..0x74));
+                unknown.push(("entry_unknown_0x78_8", 0x78..0x80));
+            }
+            if !version.has_extended_entries() {
+                unknown.push(("entry_unknown_0x5c_4", 0x5C..0x60));
+            }
+            for (key, range) in unknown {
+                fields.insert(key.to_string(), to_hex(&prefix[range]));
+            }
+        }
+        let volume_droid = guid_at(0x08);
+        let file_droid = guid_at(0x18);
+        let volume_birth_droid = guid_at(0x28);
+        let file_birth_droid = guid_at(0x38);
         // The hostname is 16 bytes
-        let hostname = read_utf8_string(r, Some(16)).map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'hostname'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        let entry_number = r.read_u32::<LittleEndian>().map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'entry_number'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        // Ignore unknown bytes
-        r.seek(SeekFrom::Current(8)).map_err(|_| {
+        let hostname = clean_hostname(&prefix[0x48..0x58]);
+        let hostname_is_ip = hostname
+            .as_deref()
+            .map(|h| h.parse::<IpAddr>().is_ok())
+            .unwrap_or(false);
+        let entry_number = LittleEndian::read_u32(&prefix[0x58..]);
+        let mtime = Timestamp::new(
+            LittleEndian::read_u64(&prefix[0x64..]),
+            TimestampFormat::default(),
+        );
+        // The position in the pinned list, the position is ignored and only whether the item
+        // is pinned is returned. Unpinned items are -1 in version 3+, version 1 stores a signed
+        // position where any negative value means unpinned.
+        let pin_status = LittleEndian::read_u32(&prefix[0x6C..]);
+        let pinned = match version.has_extended_entries() {
+            true => pin_status != 0xffffffff,
+            false => (pin_status as i32) >= 0,
+        };
+        // Version 1 has a floating point access count before the interaction time, version
+        // 3+ an integer after the pin status
+        let access_count = match version.has_extended_entries() {
+            true => LittleEndian::read_u32(&prefix[0x74..]),
+            false => {
+                let count = LittleEndian::read_f32(&prefix[0x60..]);
+                match count.is_finite() && count > 0.0 {
+                    true => count.round().min(u32::MAX as f32) as u32,
+                    false => 0,
+                }
+            }
+        };
+        let access_count = Some(access_count);
+        let path_size = LittleEndian::read_u16(&prefix[prefix_size - 2..]);
+        ParseLimits::check(
+            "The 'path_size'",
+            path_size as u64,
+            options.limits.max_string_length,
+        )?;
+        // The path is read as raw bytes, so that its content can't move the reader
+        let path_start = stream_offset + prefix_size as u64;
+        let remaining = remaining_len(r)?;
+        let mut raw_path = vec![0; (path_size as u64 * 2).min(remaining) as usize];
+        r.read_exact(&mut raw_path).map_err(|_| {
             JumplistParserError::DestListEntry(
-                "Can't seek after unknown 8 bytes".to_string(),
+                "Can't parse the 'path'".to_string(),
                 line!(),
                 file!().to_string(),
             )
         })?;
-        let mtime = FileTime::new(r.read_u64::<LittleEndian>().map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'mtime'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?);
-        // Ignore pinned items order and only return true if the item is pinned
-        let pined = match r.read_u32::<LittleEndian>().map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'pined'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })? {
-            0xffffffff => false,
-            _ => true,
-        };
-        if version > 1 {
-            // Ignore unknown bytes
-            r.seek(SeekFrom::Current(16)).map_err(|_| {
+        let path_raw_len = Self::path_raw_len(r, path_start, path_size, &raw_path, version)?;
+        raw_path.truncate(path_raw_len as usize);
+        r.seek(SeekFrom::Start(path_start + path_raw_len))
+            .map_err(|_| {
                 JumplistParserError::DestListEntry(
-                    "Can't seek after unknown 16 bytes".to_string(),
+                    "Can't seek after the 'path'".to_string(),
                     line!(),
                     file!().to_string(),
                 )
             })?;
-        }
-        let path_size = r.read_u16::<LittleEndian>().map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'path_size'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
-        let path = read_utf16_string(r, Some(path_size as usize)).map_err(|_| {
-            JumplistParserError::DestListEntry(
-                "Can't parse the 'path'".to_string(),
-                line!(),
-                file!().to_string(),
-            )
-        })?;
+        let path = decode_utf16_without_nuls(&raw_path);
 
-        if version > 1 {
-            // Ignore unknown bytes
-            r.seek(SeekFrom::Current(4)).map_err(|_| {
+        let trailing_value = match version.has_extended_entries() {
+            true => Self::read_trailing_value(r, &mut unknown_fields).map_err(|_| {
                 JumplistParserError::DestListEntry(
-                    "Can't seek after unknown 4 bytes".to_string(),
+                    "Can't read the 4 bytes after the 'path'".to_string(),
                     line!(),
                     file!().to_string(),
                 )
-            })?;
-        }
+            })?,
+            false => None,
+        };
+
+        let entry_size = r.stream_position().map_err(|_| {
+            JumplistParserError::DestListEntry(
+                "Can't get the entry end offset".to_string(),
+                line!(),
+                file!().to_string(),
+            )
+        })? - stream_offset;
+
+        let path_resolved = Some(&path)
+            .filter(|p| may_reference_known_folder(p))
+            .map(|p| resolve_known_folder_path(p))
+            .filter(|p| p != &path);
+        let target = TargetInfo::from_path(&path, None);
 
         Ok(Self {
             volume_droid,
@@ -231,118 +580,735 @@ impl DestListEntry {
             volume_birth_droid,
             file_birth_droid,
             hostname,
+            hostname_is_ip,
             entry_number,
             mtime,
-            pined,
+            pinned,
+            access_count,
             path,
+            path_raw_len,
+            trailing_value,
+            path_resolved,
+            target,
+            path_mismatch: false,
             lnk: None,
+            lnk_status: LnkStatus::Skipped,
+            raw_lnk: None,
+            lnk_header: None,
+            lnk_details: None,
+            target_mft_entry: None,
+            target_mft_sequence: None,
             entry_id: None,
+            stream_offset,
+            entry_size,
+            unknown_fields,
+            entry_fingerprint: String::new(),
         })
     }
 
-    /// Tries to parse and attach an LNK entry to this DestList entry.
-    fn process_lnk(&mut self, lnk: &[u8]) {
-        self.lnk = match LNKParser::from_buffer(&lnk) {
-            Ok(a) => Some(a),
-            Err(_) => None,
+    /// The 4 bytes after the path of a version 3+ entry, `None` when the stream ends before
+    /// them. The reader is moved after them either way, like before they were read.
+    fn read_trailing_value<R: Read + Seek>(
+        r: &mut R,
+        unknown_fields: &mut Option<BTreeMap<String, String>>,
+    ) -> io::Result<Option<u32>> {
+        let mut bytes = Vec::with_capacity(4);
+        let read = r.by_ref().take(4).read_to_end(&mut bytes)?;
+        r.seek(SeekFrom::Current(4 - read as i64))?;
+        if let Some(fields) = unknown_fields {
+            fields.insert("entry_unknown_post_path_4".to_string(), to_hex(&bytes));
+        }
+        Ok((read == 4).then(|| LittleEndian::read_u32(&bytes)))
+    }
+
+    /// Size in bytes of the path starting at `path_start`, `raw_path` is what was read of
+    /// `path_size` code units.
+    ///
+    /// Some writers count bytes in `path_size`. The byte count is used when the code units
+    /// would overrun the stream, or would swallow the start of the next entry (a NUL code
+    /// unit in their second half) while the byte count ends where a plausible entry starts.
+    fn path_raw_len<R: Read + Seek>(
+        r: &mut R,
+        path_start: u64,
+        path_size: u16,
+        raw_path: &[u8],
+        version: DestListVersion,
+    ) -> Result<u64, JumplistParserError> {
+        let units_len = path_size as u64 * 2;
+        let units_fit = raw_path.len() as u64 == units_len;
+        let second_half = &raw_path[(path_size as usize & !1).min(raw_path.len())..];
+        if units_fit && !second_half.chunks_exact(2).any(|unit| unit == [0, 0]) {
+            return Ok(units_len);
+        }
+        let end = path_start + raw_path.len() as u64 + remaining_len(r)?;
+        let trailer = match version.has_extended_entries() {
+            true => 4,
+            false => 0,
+        };
+        let mut ends_entry = |offset: u64| {
+            offset == end || (offset < end && Self::is_plausible_at(r, offset, version))
+        };
+        let bytes_fit = ends_entry(path_start + path_size as u64 + trailer);
+        match units_fit {
+            true if bytes_fit && !ends_entry(path_start + units_len + trailer) => {
+                Ok(path_size as u64)
+            }
+            true => Ok(units_len),
+            false if bytes_fit => Ok(path_size as u64),
+            false => Err(JumplistParserError::FileStructure(
+                format!(
+                    "The 'path_size' value {} at offset {} doesn't fit in the remaining {} bytes",
+                    path_size,
+                    path_start - 2,
+                    raw_path.len()
+                ),
+                line!(),
+                file!().to_string(),
+            )),
+        }
+    }
+
+    /// Whether the bytes at `offset` look like the start of an entry: a printable hostname,
+    /// a small pin position, an interaction time between 1980 and 2200 (or zero) and a path
+    /// that fits in the stream.
+    fn is_plausible_at<R: Read + Seek>(r: &mut R, offset: u64, version: DestListVersion) -> bool {
+        let prefix_size = match version.has_extended_entries() {
+            true => EXTENDED_ENTRY_PREFIX_SIZE,
+            false => ENTRY_PREFIX_SIZE,
+        };
+        let mut prefix = [0; EXTENDED_ENTRY_PREFIX_SIZE];
+        let prefix = &mut prefix[..prefix_size];
+        if r.seek(SeekFrom::Start(offset)).is_err() || r.read_exact(prefix).is_err() {
+            return false;
+        }
+        let hostname = &prefix[0x48..0x58];
+        let hostname_end = hostname
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(hostname.len());
+        let pin = LittleEndian::read_u32(&prefix[0x6C..]);
+        let mtime = LittleEndian::read_u64(&prefix[0x64..]);
+        let year = Timestamp::new(mtime, TimestampFormat::default())
+            .to_datetime()
+            .map(|t| t.year());
+        let path_size = LittleEndian::read_u16(&prefix[prefix_size - 2..]) as u64;
+        hostname[..hostname_end]
+            .iter()
+            .all(|b| b.is_ascii_graphic() || *b == b' ')
+            && (pin == 0xFFFF_FFFF || pin < 0x1_0000)
+            && (mtime == 0 || year.is_some_and(|year| (1980..2200).contains(&year)))
+            && remaining_len(r).is_ok_and(|remaining| path_size <= remaining)
+    }
+
+    /// File attributes of the LNK target, from the LNK header.
+    pub fn file_attributes(&self) -> Option<u32> {
+        self.lnk_header.as_ref().map(|h| h.file_attributes)
+    }
+
+    /// Path of the LNK target, see [`LnkDetails::target_path`]. `None` without a LNK or when
+    /// the LNK records no path.
+    pub fn lnk_target_path(&self) -> Option<String> {
+        self.lnk_details
+            .as_ref()
+            .and_then(|d| d.target_path.clone())
+    }
+
+    /// Best path of the target: the path of the LNK (see [`LnkDetails::target_path`]), or
+    /// else the `DestList` path. `None` when both are empty.
+    pub fn best_target_path(&self) -> Option<&str> {
+        LnkDetails::best_target_path(self.lnk_details.as_ref(), Some(&self.path)).map(|(p, _)| p)
+    }
+
+    /// Where [`DestListEntry::best_target_path`] comes from.
+    pub fn target_path_source(&self) -> Option<TargetPathSource> {
+        LnkDetails::best_target_path(self.lnk_details.as_ref(), Some(&self.path)).map(|(_, s)| s)
+    }
+
+    /// Tries to parse and attach an LNK entry to this DestList entry, returns the error of
+    /// the LNK parser when it fails.
+    fn process_lnk(&mut self, lnk: Vec<u8>, options: &ParseOptions) -> Result<(), String> {
+        let parsed = match LNKParser::from_buffer(&lnk) {
+            Ok(parsed) => {
+                self.lnk = Some(Lnk::new(parsed));
+                Ok(())
+            }
+            Err(e) => {
+                self.lnk = None;
+                Err(e.to_string())
+            }
+        };
+        self.lnk_header = LnkHeader::from_buffer(&lnk, options.ts_format);
+        self.lnk_details = LnkDetails::from_buffer(&lnk, options.ts_format);
+        let mft_reference = self
+            .lnk_details
+            .as_ref()
+            .and_then(|d| d.target_mft_reference);
+        self.target_mft_entry = mft_reference.map(|r| r.entry);
+        self.target_mft_sequence = mft_reference.map(|r| r.sequence);
+        self.target = TargetInfo::from_path(&self.path, self.file_attributes());
+        self.path_mismatch = self
+            .lnk_target_path()
+            .is_some_and(|target| paths_differ(&self.path, &target));
+        if options.keep_raw_lnk {
+            self.raw_lnk = Some(lnk);
         }
+        parsed
     }
 }
 
+/// Slack ratio above which [`DestList::slack_note`] reports the slack of a stream.
+pub const LARGE_SLACK_RATIO: f64 = 0.25;
+
 /// Represents a parsed `DestList` stream with optional LNK parsing.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DestList {
     pub header: DestListHeader,
+    /// Sorted by entry number, highest (most recently added) first. Entries sharing an
+    /// entry number are in stream order (`stream_offset`), so the order is the same on
+    /// every run.
     pub entries: Vec<DestListEntry>,
+    /// Inconsistencies found while parsing (e.g. fewer entries than declared in the header).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Entry numbers used by more than one entry. Windows never reuses an entry number, so
+    /// this is a sign of a tampered Jumplist.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicate_entry_numbers: Vec<u32>,
+    /// Entry numbers of the entries without a LNK stream in the compound file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_lnk_streams: Vec<u32>,
+    /// LNK streams numbered after [`DestListHeader::last_entry_number`], entry numbers the
+    /// shell never issued.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unexpected_lnk_streams: Vec<u32>,
+    /// Size of the `DestList` stream in bytes.
+    #[serde(default)]
+    pub stream_size: u64,
+    /// Where the entries stop: the end of the last parsed entry, or of the header when
+    /// there is none. See [`DestList::slack_bytes`].
+    #[serde(default)]
+    pub bytes_consumed: u64,
 }
 
 impl DestList {
+    /// Parses a standalone `DestList` stream from a file path, e.g. one exported from a
+    /// Jumplist or carved from unallocated space. LNK entries are not attached.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path(path: &str) -> Result<Self, JumplistParserError> {
+        let mut file = std::fs::File::open(path).map_err(|e| {
+            JumplistParserError::General(e.to_string(), line!(), file!().to_string())
+        })?;
+        Self::parse_stream(&mut file)
+    }
+
+    /// Parses the header and entries of a `DestList` stream, without LNK entries.
+    ///
+    /// Use [`DestList::attach_lnks`] to attach the LNK entries afterwards.
+    pub fn parse_stream<R: Read + Seek>(r: &mut R) -> Result<Self, JumplistParserError> {
+        Self::parse_stream_with_options(r, &ParseOptions::default())
+    }
+
+    /// Same as [`DestList::parse_stream`] but with explicit [`ParseOptions`].
+    pub fn parse_stream_with_options<R: Read + Seek>(
+        r: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let header = DestListHeader::from_reader_with_options(r, options)?;
+        let destlist = Self::parse_entries(r, header, options)?;
+        options.check_warnings(&destlist.warnings)?;
+        Ok(destlist)
+    }
+
+    /// Parses the entries following `header`, until the first one that fails to parse.
+    fn parse_entries<R: Read + Seek>(
+        r: &mut R,
+        header: DestListHeader,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        // Entries are large, growing the vector would copy them several times. The declared
+        // count is only trusted as far as it fits in the stream
+        let entries_start = r.stream_position().map_err(|e| {
+            JumplistParserError::DestList(e.to_string(), line!(), file!().to_string())
+        })?;
+        let remaining = remaining_len(r)?;
+        let stream_size = entries_start + remaining;
+        let capacity = remaining / header.version.min_entry_size();
+        let mut entries: Vec<DestListEntry> =
+            Vec::with_capacity((header.number_of_entries as u64).min(capacity) as usize);
+        let mut tracker = LimitTracker::new(options.limits);
+        options.report(ProgressEvent::HeaderParsed {
+            declared: header.number_of_entries,
+        });
+        loop {
+            let mut entry =
+                match DestListEntry::from_reader_with_options(r, header.version, options) {
+                    Ok(entry) => entry,
+                    Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                    Err(_) => break,
+                };
+            tracker.add_entries(1)?;
+            options.report_entries(tracker.entries());
+            entry.mtime.format = options.ts_format;
+            entries.push(entry);
+        }
+        let bytes_consumed = entries
+            .last()
+            .map(|e| e.stream_offset + e.entry_size)
+            .unwrap_or(entries_start);
+        // Entries sharing a number (tampered files) keep their stream order
+        entries.sort_by(|a, b| {
+            b.entry_number
+                .cmp(&a.entry_number)
+                .then_with(|| a.stream_offset.cmp(&b.stream_offset))
+        });
+
+        let mut warnings = vec![];
+        if !header.version_supported {
+            warnings.push(format!(
+                "Unknown DestList version {}, the entries were parsed with the newest known layout and may be garbled",
+                header.version.as_u32()
+            ));
+        }
+        if entries.len() != header.number_of_entries as usize {
+            warnings.push(format!(
+                "The DestList header declares {} entries but {} were parsed",
+                header.number_of_entries,
+                entries.len()
+            ));
+        }
+        // Entries are sorted, duplicates are next to each other
+        let mut duplicate_entry_numbers: Vec<u32> = entries
+            .windows(2)
+            .filter(|pair| pair[0].entry_number == pair[1].entry_number)
+            .map(|pair| pair[0].entry_number)
+            .collect();
+        duplicate_entry_numbers.sort_unstable();
+        duplicate_entry_numbers.dedup();
+        if !duplicate_entry_numbers.is_empty() {
+            warnings.push(format!(
+                "Entry numbers used by more than one DestList entry: {}",
+                hex_list(&duplicate_entry_numbers)
+            ));
+        }
+        // The trailing value is 0 on the known samples, a number of an entry other than its
+        // own one hints at a tampered or garbled entry
+        let trailing_mismatches: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| match entry.trailing_value {
+                Some(value)
+                    if value != 0
+                        && value <= header.last_entry_number
+                        && value != entry.entry_number =>
+                {
+                    Some(format!("{:x} ({:x})", entry.entry_number, value))
+                }
+                _ => None,
+            })
+            .collect();
+        if !trailing_mismatches.is_empty() {
+            warnings.push(format!(
+                "DestList entries whose trailing value is another entry number: {}",
+                trailing_mismatches.join(", ")
+            ));
+        }
+
+        Ok(Self {
+            header,
+            entries,
+            warnings,
+            duplicate_entry_numbers,
+            missing_lnk_streams: vec![],
+            unexpected_lnk_streams: vec![],
+            stream_size,
+            bytes_consumed,
+        })
+    }
+
+    /// Bytes of the stream after the last parsed entry: deleted entries, or garbage.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let destlist = parsed.destlist().unwrap();
+    /// assert_eq!(destlist.slack_bytes(), destlist.stream_size - destlist.bytes_consumed);
+    /// assert!(destlist.slack_ratio() < 0.25);
+    /// ```
+    pub fn slack_bytes(&self) -> u64 {
+        self.stream_size.saturating_sub(self.bytes_consumed)
+    }
+
+    /// [`DestList::slack_bytes`] as a fraction of [`DestList::stream_size`], from `0.0` to
+    /// `1.0`.
+    pub fn slack_ratio(&self) -> f64 {
+        match self.stream_size {
+            0 => 0.0,
+            size => self.slack_bytes() as f64 / size as f64,
+        }
+    }
+
+    /// A note when more than [`LARGE_SLACK_RATIO`] of the stream is slack, which often
+    /// comes with many deleted entries and makes carving worth running.
+    pub fn slack_note(&self) -> Option<String> {
+        (self.slack_ratio() > LARGE_SLACK_RATIO).then(|| {
+            format!(
+                "{} of the {} bytes of the DestList stream ({:.0}%) follow the last entry, entries were likely deleted",
+                self.slack_bytes(),
+                self.stream_size,
+                self.slack_ratio() * 100.0
+            )
+        })
+    }
+
+    /// Attaches LNK entries to the parsed entries. `resolver` is called with the entry
+    /// number of every entry and returns the bytes of its LNK entry, if any.
+    pub fn attach_lnks(&mut self, resolver: impl FnMut(u32) -> Option<Vec<u8>>) {
+        self.attach_lnks_with_options(resolver, &ParseOptions::default())
+    }
+
+    /// Same as [`DestList::attach_lnks`] but with explicit [`ParseOptions`].
+    pub fn attach_lnks_with_options(
+        &mut self,
+        mut resolver: impl FnMut(u32) -> Option<Vec<u8>>,
+        options: &ParseOptions,
+    ) {
+        let total = self.entries.len();
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            entry.lnk_status = match resolver(entry.entry_number) {
+                Some(lnk) => match entry.process_lnk(lnk, options) {
+                    Ok(_) => LnkStatus::Parsed,
+                    Err(e) => {
+                        self.warnings.push(format!(
+                            "The LNK of entry {:x} can't be parsed: {}",
+                            entry.entry_number, e
+                        ));
+                        LnkStatus::ParseFailed(e)
+                    }
+                },
+                None => LnkStatus::Missing,
+            };
+            let attached = index + 1;
+            if attached.is_multiple_of(PROGRESS_INTERVAL) || attached == total {
+                options.report(ProgressEvent::LnksAttached { attached, total });
+            }
+        }
+    }
+
     /// Parses a DestList stream and associated LNK entries from a CFB compound file.
     pub fn from_reader<R: Read + Seek>(
         r: &mut R,
         lnks: Option<Vec<cfb::Entry>>,
         parser: &mut cfb::CompoundFile<&mut R>,
     ) -> Result<Self, JumplistParserError> {
-        let dlist_size = match &lnks {
-            Some(entries) => {
-                let mut size = 0;
-                for entry in entries.iter() {
-                    if entry.name() == "DestList" {
-                        size = entry.len();
-                    }
+        Self::from_reader_with_options(r, lnks, parser, &ParseOptions::default())
+    }
+
+    /// Same as [`DestList::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        r: &mut R,
+        lnks: Option<Vec<cfb::Entry>>,
+        parser: &mut cfb::CompoundFile<&mut R>,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let lnks = match lnks {
+            Some(lnks) => lnks,
+            None => return Self::parse_stream_with_options(r, options),
+        };
+        let dlist_size = find_destlist(&lnks).map_or(0, |entry| entry.len());
+        let mut destlist = match dlist_size {
+            0 => Self::parse_entries(r, DestListHeader::empty(), options)?,
+            _ => Self::parse_stream_with_options(r, options)?,
+        };
+
+        destlist.check_lnk_streams(&lnks, dlist_size != 0);
+        if !options.skip_lnks {
+            destlist.attach_cfb_lnks(&lnks, parser, options, &mut None)?;
+        }
+        options.check_warnings(&destlist.warnings)?;
+        Ok(destlist)
+    }
+
+    /// Parses the `DestList` stream of a CFB compound file and attaches the LNK streams,
+    /// streams are read one at a time directly from the compound file.
+    /// Records the time of both steps and what was read in `stats`, when collected.
+    pub(crate) fn from_compound_file<F: Read + Seek>(
+        parser: &mut cfb::CompoundFile<F>,
+        options: &ParseOptions,
+        stats: &mut Option<ParseStats>,
+    ) -> Result<Self, JumplistParserError> {
+        let destlist_timer = Timer::start(stats);
+        let entries: Vec<cfb::Entry> = parser.walk().collect();
+        let dlist = find_destlist(&entries);
+        let dlist_size = dlist.map_or(0, |entry| entry.len());
+        let mut destlist = match dlist {
+            Some(dlist) if dlist_size != 0 => {
+                let mut stream = parser.open_stream(dlist.path()).map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })?;
+                // Entries are parsed with many small reads and seeks, they are much faster
+                // in memory than on the compound file
+                let mut data = vec![];
+                stream.read_to_end(&mut data).map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })?;
+                if let Some(stats) = stats.as_mut() {
+                    stats.streams_opened += 1;
+                    stats.bytes_read += data.len() as u64;
                 }
-                size
+                Self::parse_stream_with_options(&mut Cursor::new(data), options)?
             }
-            None => 0,
+            _ => Self::parse_entries(&mut Cursor::new([]), DestListHeader::empty(), options)?,
         };
-        let header = match dlist_size {
-            0 => Ok(DestListHeader {
-                version: 0,
-                number_of_entries: 0,
-                number_of_pinned_entries: 0,
-            }),
-            _ => DestListHeader::from_reader(r),
-        }?;
-        let mut entries: Vec<DestListEntry> = vec![];
+        // `DestListPropertyStore` is written by Windows, any other stream is unexpected
+        for entry in entries.iter().filter(|entry| entry.is_stream()) {
+            let name = entry.name();
+            let numbered = !name.is_empty() && name.chars().all(|c| c.is_ascii_hexdigit());
+            if !numbered && !is_destlist_stream(entry) && name != "DestListPropertyStore" {
+                destlist.warnings.push(format!(
+                    "Unexpected stream '{}' in the compound file",
+                    entry.path().display()
+                ));
+            }
+        }
+        destlist.check_lnk_streams(&entries, dlist_size != 0);
+        if let Some(stats) = stats.as_mut() {
+            stats.destlist_parse_micros = destlist_timer.micros();
+        }
+        if !options.skip_lnks {
+            let lnk_timer = Timer::start(stats);
+            destlist.attach_cfb_lnks(&entries, parser, options, stats)?;
+            if let Some(stats) = stats.as_mut() {
+                stats.lnk_parse_micros = lnk_timer.micros();
+            }
+        }
+        options.check_warnings(&destlist.warnings)?;
+        Ok(destlist)
+    }
 
-        loop {
-            match &lnks {
-                Some(ls) => match DestListEntry::from_reader(r, header.version) {
-                    Ok(mut entry) => {
-                        for lnk in ls {
-                            if format!("{:x?}", entry.entry_number) == lnk.name() {
-                                let lnk_data = {
-                                    let stream = parser.open_stream(lnk.path()).map_err(|e| {
-                                        JumplistParserError::LnkEntry(
-                                            format!(
-                                                "Error reading LNK file '{}', CFB_ERROR: {}",
-                                                lnk.name(),
-                                                e
-                                            ),
-                                            line!(),
-                                            file!().to_string(),
-                                        )
-                                    });
-
-                                    match stream {
-                                        Ok(mut s) => {
-                                            let mut buffer = Vec::new();
-                                            s.read_to_end(&mut buffer).unwrap();
-                                            buffer
-                                        }
-                                        Err(e) => {
-                                            eprintln!("{}", e);
-                                            continue;
-                                        }
-                                    }
-                                };
-
-                                entry.process_lnk(&lnk_data)
-                            }
-                        }
-                        entries.push(entry);
+    /// Records the entries without a LNK stream and, when the compound file has a
+    /// `DestList` header, the LNK streams numbered after its last entry number.
+    fn check_lnk_streams(&mut self, cfb_entries: &[cfb::Entry], has_header: bool) {
+        let numbers: Vec<u32> = cfb_entries
+            .iter()
+            .filter(|entry| entry.is_stream())
+            .filter_map(|entry| u32::from_str_radix(entry.name(), 16).ok())
+            .collect();
+
+        let mut missing: Vec<u32> = self
+            .entries
+            .iter()
+            .map(|entry| entry.entry_number)
+            .filter(|number| !numbers.contains(number))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        if !missing.is_empty() {
+            self.warnings.push(format!(
+                "No LNK stream for the entries {}",
+                hex_list(&missing)
+            ));
+        }
+
+        let mut unexpected: Vec<u32> = numbers
+            .into_iter()
+            .filter(|number| has_header && *number > self.header.last_entry_number)
+            .collect();
+        unexpected.sort_unstable();
+        if !unexpected.is_empty() {
+            self.warnings.push(format!(
+                "The LNK streams {} are numbered after the last entry number {:x}",
+                hex_list(&unexpected),
+                self.header.last_entry_number
+            ));
+        }
+
+        self.missing_lnk_streams = missing;
+        self.unexpected_lnk_streams = unexpected;
+    }
+
+    /// Attaches the LNK streams of a compound file, only the streams referenced by an
+    /// entry are read.
+    fn attach_cfb_lnks<F: Read + Seek>(
+        &mut self,
+        cfb_entries: &[cfb::Entry],
+        parser: &mut cfb::CompoundFile<F>,
+        options: &ParseOptions,
+        stats: &mut Option<ParseStats>,
+    ) -> Result<(), JumplistParserError> {
+        // LNK streams are named after the entry number in hex
+        let streams: HashMap<&str, &cfb::Entry> = cfb_entries
+            .iter()
+            .filter(|entry| entry.is_stream())
+            .map(|entry| (entry.name(), entry))
+            .collect();
+        // Streams can share their sectors, the declared sizes are checked before any read
+        let mut tracker = LimitTracker::new(options.limits);
+        for entry in &self.entries {
+            if let Some(lnk) = streams.get(format!("{:x}", entry.entry_number).as_str()) {
+                tracker.add_lnk_bytes(lnk.len())?;
+            }
+        }
+        let mut warnings = vec![];
+        let mut unreadable = HashMap::new();
+        self.attach_lnks_with_options(
+            |entry_number| {
+                let lnk = streams.get(format!("{:x}", entry_number).as_str())?;
+                let mut buffer = Vec::with_capacity(lnk.len() as usize);
+                let read = parser
+                    .open_stream(lnk.path())
+                    .and_then(|mut stream| stream.read_to_end(&mut buffer));
+                if let Some(stats) = stats.as_mut() {
+                    stats.streams_opened += 1;
+                    stats.bytes_read += buffer.len() as u64;
+                }
+                match read {
+                    Ok(_) => Some(buffer),
+                    Err(e) => {
+                        warnings.push(format!(
+                            "The LNK stream '{}' can't be read: {}",
+                            lnk.name(),
+                            e
+                        ));
+                        unreadable.insert(entry_number, e.to_string());
+                        None
                     }
-                    Err(_) => break,
-                },
-                None => match DestListEntry::from_reader(r, header.version) {
-                    Ok(entry) => entries.push(entry),
-                    Err(_) => break,
-                },
+                }
+            },
+            options,
+        );
+        // The stream exists, it is not missing
+        for entry in self.entries.iter_mut() {
+            if let Some(error) = unreadable.get(&entry.entry_number) {
+                entry.lnk_status = LnkStatus::ParseFailed(error.clone());
             }
         }
-        entries.sort_by(|a, b| b.entry_number.cmp(&a.entry_number));
+        self.warnings.append(&mut warnings);
+        Ok(())
+    }
+
+    /// Whether the number of parsed entries matches the number declared in the header.
+    pub fn is_complete(&self) -> bool {
+        self.entries.len() == self.header.number_of_entries as usize
+    }
+
+    /// Number of entries by [`LnkStatus`] name, only the statuses of at least one entry.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let counts = parsed.destlist().unwrap().lnk_status_counts();
+    /// assert_eq!(counts.into_iter().collect::<Vec<_>>(), [("parsed", 5)]);
+    /// ```
+    pub fn lnk_status_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for entry in &self.entries {
+            *counts.entry(entry.lnk_status.name()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The entry whose LNK stream is named `entry_number` (in hex).
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let destlist = parsed.destlist().unwrap();
+    /// let entry = destlist.get_entry_by_number(0x19).unwrap();
+    /// assert_eq!(entry.path, r"C:\Users\u0041\Desktop\Jumplists");
+    /// // Entry 8 was removed from the Jumplist
+    /// assert!(destlist.get_entry_by_number(8).is_none());
+    /// ```
+    pub fn get_entry_by_number(&self, entry_number: u32) -> Option<&DestListEntry> {
+        self.entries.iter().find(|e| e.entry_number == entry_number)
+    }
+
+    /// The pinned entries, in `DestList` order.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let pinned: Vec<u32> = parsed
+    ///     .destlist()
+    ///     .unwrap()
+    ///     .pinned_entries()
+    ///     .map(|e| e.entry_number)
+    ///     .collect();
+    /// assert_eq!(pinned, [4, 3, 2, 1]);
+    /// ```
+    pub fn pinned_entries(&self) -> impl Iterator<Item = &DestListEntry> {
+        self.entries.iter().filter(|e| e.pinned)
+    }
+
+    /// The `n` entries with the most recent interaction time (`mtime`), most recent first.
+    /// Entries with the same time keep their `DestList` order.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let recent = parsed.destlist().unwrap().most_recent(3);
+    /// let numbers: Vec<u32> = recent.iter().map(|e| e.entry_number).collect();
+    /// // Pinned entry 1 was opened last, right after entry 0x19
+    /// assert_eq!(numbers, [1, 0x19, 0x18]);
+    /// ```
+    pub fn most_recent(&self, n: usize) -> Vec<&DestListEntry> {
+        let mut entries: Vec<&DestListEntry> = self.entries.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.mtime.filetime));
+        entries.truncate(n);
+        entries
+    }
 
-        Ok(Self { header, entries })
+    /// The path of every entry, in `DestList` order.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let destlist = parsed.destlist().unwrap();
+    /// assert_eq!(destlist.paths().count(), 5);
+    /// assert!(destlist.paths().all(|path| path.starts_with(r"C:\Users\u0041\")));
+    /// ```
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.path.as_str())
     }
 }
 
 impl Normalize for DestListEntry {
-    /// Normalizes the internal LNK entry (if present) and returns selected fields.
+    /// Normalizes the internal LNK entry (if present) and returns selected fields. The
+    /// `DestList` fields and the target path are there even without an LNK, the path then
+    /// comes from the `DestList`.
     fn normalize(&self) -> HashMap<String, String> {
-        let results: HashMap<String, String> = HashMap::new();
-        match &self.lnk {
+        let mut normalized = match &self.lnk {
             Some(l) => {
-                let mut lnk_normalized = l.normalize();
+                let mut lnk_normalized = l.normalize_target();
                 let name_string = match l.get_name_string() {
                     Some(s) => s.to_string(),
                     None => String::from(""),
@@ -352,21 +1318,94 @@ impl Normalize for DestListEntry {
                     Some(s) => s.to_string(),
                     None => String::from(""),
                 };
-                lnk_normalized.insert("name_string".to_string(), name_string);
-                lnk_normalized.insert("command_line_arguments".to_string(), command_line_arguments);
+                lnk_normalized.insert("lnk_description".to_string(), name_string);
+                lnk_normalized.insert(
+                    "lnk_command_line_arguments".to_string(),
+                    command_line_arguments,
+                );
+                if let Some(header) = &self.lnk_header {
+                    header.normalize_times_into(&mut lnk_normalized);
+                }
                 lnk_normalized
             }
-            None => results,
+            None => HashMap::new(),
+        };
+        let target_hostname = match &self.hostname {
+            Some(h) => h.clone(),
+            None => normalized
+                .get("target_hostname")
+                .and_then(|h| clean_hostname(h.as_bytes()))
+                .unwrap_or_default(),
+        };
+        normalized.insert("target_hostname".to_string(), target_hostname);
+        LnkDetails::normalize_target_path_into(
+            self.lnk_details.as_ref(),
+            Some(&self.path),
+            &mut normalized,
+        );
+        TargetInfo::normalize_into(&mut normalized, &self.path, self.file_attributes());
+        LnkHeader::normalize_into(self.lnk_header.as_ref(), &mut normalized);
+        LnkDetails::normalize_into(
+            self.lnk_details.as_ref(),
+            self.hostname.as_deref(),
+            &mut normalized,
+        );
+        let target_full_path_resolved = resolve_known_folder_path(
+            normalized
+                .get("target_full_path")
+                .map(|s| s.as_str())
+                .unwrap_or_default(),
+        );
+        normalized.insert(
+            "target_full_path_resolved".to_string(),
+            target_full_path_resolved,
+        );
+        normalized.insert("entry_offset".to_string(), self.stream_offset.to_string());
+        normalized.insert("entry_size".to_string(), self.entry_size.to_string());
+        for (key, value) in [
+            ("destlist_entry_number", self.entry_number.to_string()),
+            ("destlist_interaction_time", self.mtime.to_string()),
+            (
+                "destlist_access_count",
+                self.access_count.map(|c| c.to_string()).unwrap_or_default(),
+            ),
+            (
+                "destlist_hostname",
+                self.hostname.clone().unwrap_or_default(),
+            ),
+            ("destlist_path", self.path.clone()),
+            ("path_mismatch", self.path_mismatch.to_string()),
+            (
+                "destlist_volume_droid",
+                self.volume_droid.value().unwrap_or_default(),
+            ),
+            (
+                "destlist_file_droid",
+                self.file_droid.value().unwrap_or_default(),
+            ),
+            (
+                "destlist_volume_birth_droid",
+                self.volume_birth_droid.value().unwrap_or_default(),
+            ),
+            (
+                "destlist_file_birth_droid",
+                self.file_birth_droid.value().unwrap_or_default(),
+            ),
+        ] {
+            normalized.insert(key.to_string(), value);
         }
+        normalized
     }
 }
 
-impl Flaten for DestList {
+impl Flatten for DestList {
     /// Normalizes all entries and returns a list of `key` and `value` maps.
-    fn flaten(&self) -> Vec<HashMap<String, String>> {
-        let mut results: Vec<HashMap<String, String>> = Vec::new();
+    fn flatten(&self) -> Vec<NormalizedEntry> {
+        let mut results: Vec<NormalizedEntry> = Vec::new();
         for entry in &self.entries {
-            results.push(entry.normalize());
+            let mut normalized = entry.normalize().into_iter().collect();
+            sort_keys(&mut normalized);
+            results.push(normalized);
         }
         results
     }
@@ -0,0 +1,187 @@
+//! Comparison of two snapshots of the same Jumplist, e.g. taken at two points in time.
+//!
+//! Entries are matched by their `(entry_number, path)` key. Entry numbers are reused by
+//! the shell once an entry is deleted, so an entry number pointing to a different path is
+//! reported as a removed entry and an added entry rather than a modification.
+//! Custom Jumplist entries have no entry number and are matched by their target path.
+
+use crate::{entry::JumplistEntryRef, timestamp::Timestamp, JumplistParser};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// An entry present in only one of the two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DiffEntry {
+    /// Entry number of a `DestList` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_number: Option<u32>,
+    /// Target path of the entry.
+    pub path: Option<String>,
+    /// Last interaction time of a `DestList` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<Timestamp>,
+    /// Pin status of a `DestList` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+    /// Access count of a `DestList` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_count: Option<u32>,
+}
+
+impl From<&JumplistEntryRef<'_>> for DiffEntry {
+    fn from(entry: &JumplistEntryRef) -> Self {
+        DiffEntry {
+            entry_number: entry.entry_number(),
+            path: entry.target_path(),
+            mtime: entry.interaction_time().copied(),
+            pinned: entry.pinned(),
+            access_count: entry.access_count(),
+        }
+    }
+}
+
+/// A field with a different value in the two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FieldChange {
+    /// Name of the field: `mtime`, `pinned` or `access_count`.
+    pub field: String,
+    /// Value in the old snapshot.
+    pub old: Value,
+    /// Value in the new snapshot.
+    pub new: Value,
+}
+
+/// An entry present in both snapshots with changed fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ModifiedEntry {
+    /// Entry number of a `DestList` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_number: Option<u32>,
+    /// Target path of the entry.
+    pub path: Option<String>,
+    /// The fields that changed.
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between an old and a new snapshot of a Jumplist.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct JumplistDiff {
+    /// Entries only present in the new snapshot.
+    pub added: Vec<DiffEntry>,
+    /// Entries only present in the old snapshot.
+    pub removed: Vec<DiffEntry>,
+    /// Entries present in both snapshots with changed fields.
+    pub modified: Vec<ModifiedEntry>,
+}
+
+impl JumplistDiff {
+    /// Whether the two snapshots have the same entries.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Compare the entries of two snapshots of the same Jumplist.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{diff::diff, JumplistParser};
+///
+/// let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+/// let old = JumplistParser::from_path(path).unwrap();
+/// let mut new = old.clone();
+/// new.data.as_destlist_mut().unwrap().entries.remove(0);
+///
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes.removed.len(), 1);
+/// assert!(changes.added.is_empty() && changes.modified.is_empty());
+/// ```
+pub fn diff(old: &JumplistParser, new: &JumplistParser) -> JumplistDiff {
+    let keyed = |parsed: &JumplistParser| -> Vec<(_, DiffEntry)> {
+        parsed
+            .entries()
+            .map(|e| ((e.entry_number(), e.target_path()), DiffEntry::from(&e)))
+            .collect()
+    };
+    let old_entries = keyed(old);
+
+    // Entries with the same key (e.g. a path listed twice in a custom Jumplist) are
+    // matched in order.
+    let mut unmatched: HashMap<_, Vec<DiffEntry>> = HashMap::new();
+    for (key, entry) in old_entries.iter().rev() {
+        unmatched
+            .entry(key.clone())
+            .or_default()
+            .push(entry.clone());
+    }
+
+    let mut result = JumplistDiff::default();
+    for (key, new_entry) in keyed(new) {
+        match unmatched.get_mut(&key).and_then(|entries| entries.pop()) {
+            Some(old_entry) => {
+                let changes = changed_fields(&old_entry, &new_entry);
+                if !changes.is_empty() {
+                    result.modified.push(ModifiedEntry {
+                        entry_number: new_entry.entry_number,
+                        path: new_entry.path,
+                        changes,
+                    });
+                }
+            }
+            None => result.added.push(new_entry),
+        }
+    }
+    // Keep the order of the old snapshot for the removed entries
+    for (key, entry) in old_entries {
+        if let Some(entries) = unmatched.get_mut(&key) {
+            if entries.last() == Some(&entry) {
+                entries.pop();
+                result.removed.push(entry);
+            }
+        }
+    }
+    result
+}
+
+fn changed_fields(old: &DiffEntry, new: &DiffEntry) -> Vec<FieldChange> {
+    let mut changes = vec![];
+    let mut compare = |field: &str, changed: bool, old: Value, new: Value| {
+        if changed {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                old,
+                new,
+            });
+        }
+    };
+    // Compare the raw FILETIMEs, the serialized value may be truncated to seconds
+    let filetime = |entry: &DiffEntry| entry.mtime.map(|t| t.filetime);
+    compare(
+        "mtime",
+        filetime(old) != filetime(new),
+        json(&old.mtime),
+        json(&new.mtime),
+    );
+    compare(
+        "pinned",
+        old.pinned != new.pinned,
+        json(&old.pinned),
+        json(&new.pinned),
+    );
+    compare(
+        "access_count",
+        old.access_count != new.access_count,
+        json(&old.access_count),
+        json(&new.access_count),
+    );
+    changes
+}
+
+fn json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
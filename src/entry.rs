@@ -0,0 +1,255 @@
+//! A common view over DestList entries and CustomDestinations entries.
+
+use crate::{
+    custom_destinations::{Category, CategoryId, CategoryType, CustomDestinationsEntry},
+    destlist::{DestListEntry, LnkStatus},
+    filetype::{FileCategory, FiletypeMap},
+    lnk_info::{LinkInfo, LnkHeader, TargetPathSource},
+    timestamp::Timestamp,
+    utils::clean_hostname,
+};
+use lnk_parser::LNKParser;
+use std::collections::HashMap;
+use winparsingtools::traits::Normalize;
+
+/// A borrowed entry of an automatic or a custom Jumplist.
+///
+/// Returned by [`JumplistParser::entries`](crate::JumplistParser::entries).
+///
+/// # Example
+/// ```
+/// use jumplist_parser::JumplistParser;
+///
+/// let parsed = JumplistParser::from_path(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+/// )
+/// .unwrap();
+/// for entry in parsed.entries() {
+///     println!("{:?} {:?}", entry.entry_number(), entry.target_path());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub enum JumplistEntryRef<'a> {
+    /// An entry of the `DestList` stream of an automatic Jumplist.
+    DestList(&'a DestListEntry),
+    /// An LNK entry of a custom Jumplist and the category it belongs to.
+    Custom {
+        category: &'a Category,
+        entry: &'a CustomDestinationsEntry,
+    },
+    /// A standalone LNK file, see [`recent_lnk`](crate::recent_lnk).
+    RecentLnk(&'a CustomDestinationsEntry),
+}
+
+impl<'a> JumplistEntryRef<'a> {
+    /// Path of the target: the `DestList` path, or the LNK target path for custom entries.
+    pub fn target_path(&self) -> Option<String> {
+        match self {
+            JumplistEntryRef::DestList(entry) => Some(entry.path.clone()),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => entry
+                .lnk
+                .as_ref()
+                .and_then(|lnk| lnk.normalize().remove("target_full_path"))
+                .filter(|p| !p.is_empty()),
+        }
+    }
+
+    /// Best path of the target, the first non-empty of:
+    /// 1. the local path of the LNK `LinkInfo` (`LocalBasePath` and `CommonPathSuffix`),
+    /// 2. the UNC path of the LNK `LinkInfo` (`NetName` and `CommonPathSuffix`),
+    /// 3. the path rebuilt from the shell items of the LNK ID list,
+    /// 4. the `DestList` path, for `DestList` entries.
+    ///
+    /// `None` when there is none, see [`JumplistEntryRef::target_path_source`] for the one
+    /// used. This is the `target_full_path` of the normalized records.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{lnk_info::TargetPathSource, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// for entry in parsed.entries() {
+    ///     assert!(entry.best_target_path().is_some());
+    ///     assert_eq!(entry.target_path_source(), Some(TargetPathSource::LinkInfo));
+    /// }
+    /// ```
+    pub fn best_target_path(&self) -> Option<&'a str> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.best_target_path(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.best_target_path()
+            }
+        }
+    }
+
+    /// Where [`JumplistEntryRef::best_target_path`] comes from.
+    pub fn target_path_source(&self) -> Option<TargetPathSource> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.target_path_source(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.target_path_source()
+            }
+        }
+    }
+
+    /// The parsed LNK of the entry.
+    pub fn lnk(&self) -> Option<&'a LNKParser> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.lnk.as_deref(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.lnk.as_deref()
+            }
+        }
+    }
+
+    /// Last interaction time recorded in the `DestList`, custom entries don't have one.
+    pub fn interaction_time(&self) -> Option<&'a Timestamp> {
+        match self {
+            JumplistEntryRef::DestList(entry) => Some(&entry.mtime),
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => None,
+        }
+    }
+
+    /// Whether the entry is pinned. Custom entries don't have this flag, the entries of the
+    /// tasks and of the pinned items block count as pinned (see [`Category::is_pinned`]).
+    /// Standalone LNK files are never pinned.
+    pub fn pinned(&self) -> Option<bool> {
+        match self {
+            JumplistEntryRef::DestList(entry) => Some(entry.pinned),
+            JumplistEntryRef::Custom { category, .. } => Some(category.is_pinned()),
+            JumplistEntryRef::RecentLnk(_) => Some(false),
+        }
+    }
+
+    /// Number of times the entry was opened, recorded by version 2+ `DestList` streams only.
+    pub fn access_count(&self) -> Option<u32> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.access_count,
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => None,
+        }
+    }
+
+    /// NetBIOS name of the machine the target was on: the `DestList` hostname, or the
+    /// hostname recorded in the LNK.
+    pub fn hostname(&self) -> Option<String> {
+        let lnk_hostname = || {
+            self.lnk()
+                .and_then(|lnk| lnk.normalize().remove("target_hostname"))
+                .and_then(|h| clean_hostname(h.as_bytes()))
+        };
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.hostname.clone().or_else(lnk_hostname),
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => lnk_hostname(),
+        }
+    }
+
+    /// Category of a custom entry: the custom category name, `frequent`, `recent`,
+    /// `tasks` or `pinned`.
+    pub fn category(&self) -> Option<&'a str> {
+        match self {
+            JumplistEntryRef::DestList(_) | JumplistEntryRef::RecentLnk(_) => None,
+            JumplistEntryRef::Custom { category, .. } => match category.r#type {
+                CategoryType::Custom => category.name.as_deref(),
+                CategoryType::Task => Some("tasks"),
+                CategoryType::Pinned => Some("pinned"),
+                CategoryType::Known => match category.id {
+                    Some(CategoryId::Frequent) => Some("frequent"),
+                    Some(CategoryId::Recent) => Some("recent"),
+                    _ => None,
+                },
+            },
+        }
+    }
+
+    /// Entry number of a `DestList` entry (the name of its LNK stream, in hex).
+    pub fn entry_number(&self) -> Option<u32> {
+        match self {
+            JumplistEntryRef::DestList(entry) => Some(entry.entry_number),
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => None,
+        }
+    }
+
+    /// Whether the LNK of the entry was found and parsed, custom entries are LNKs
+    /// themselves and are always parsed.
+    pub fn lnk_status(&self) -> LnkStatus {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.lnk_status.clone(),
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => LnkStatus::Parsed,
+        }
+    }
+
+    /// The `target_category` of the entry in `filetype_map`, from its normalized
+    /// `target_full_path` and `target_type`.
+    pub fn target_category(&self, filetype_map: &FiletypeMap) -> FileCategory {
+        let normalized = self.normalize();
+        let value = |key: &str| normalized.get(key).map(|v| v.as_str()).unwrap_or_default();
+        filetype_map.target_category(value("target_full_path"), value("target_type"))
+    }
+
+    /// Deterministic ID of the entry, see [`fingerprint`](crate::fingerprint).
+    pub fn fingerprint(&self) -> &'a str {
+        match self {
+            JumplistEntryRef::DestList(entry) => &entry.entry_fingerprint,
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                &entry.entry_fingerprint
+            }
+        }
+    }
+
+    /// The `ShellLinkHeader` of the LNK, read from the raw LNK bytes.
+    pub fn lnk_header(&self) -> Option<&'a LnkHeader> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.lnk_header.as_ref(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.lnk_header.as_ref()
+            }
+        }
+    }
+
+    /// The `LinkInfo` of the LNK (volume and network share of the target), read from the
+    /// raw LNK bytes.
+    pub fn link_info(&self) -> Option<&'a LinkInfo> {
+        let details = match self {
+            JumplistEntryRef::DestList(entry) => entry.lnk_details.as_ref(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.lnk_details.as_ref()
+            }
+        };
+        details.and_then(|d| d.link_info.as_ref())
+    }
+
+    /// The raw bytes of the LNK, when parsed with [`ParseOptions::keep_raw_lnk`](crate::ParseOptions::keep_raw_lnk).
+    pub fn raw_lnk(&self) -> Option<&'a [u8]> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.raw_lnk.as_deref(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.raw_lnk.as_deref()
+            }
+        }
+    }
+}
+
+impl Normalize for JumplistEntryRef<'_> {
+    /// Custom entries get a `entry_category_id` key, empty for categories without an ID.
+    fn normalize(&self) -> HashMap<String, String> {
+        match self {
+            JumplistEntryRef::DestList(entry) => entry.normalize(),
+            JumplistEntryRef::Custom { entry, category } => {
+                let mut normalized = entry.normalize();
+                normalized.insert(
+                    "entry_category_id".to_string(),
+                    category
+                        .id
+                        .as_ref()
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                );
+                normalized
+            }
+            JumplistEntryRef::RecentLnk(entry) => entry.normalize(),
+        }
+    }
+}
@@ -20,6 +20,70 @@ pub enum JumplistParserError {
     General(String, u32, String),
     #[error("Empty JumpList (No DestList) '{2}:{1}'. ERROR: '{0}'")]
     NoDestList(String, u32, String),
-    #[error("Unable to indentify Jumplist type (doesn't end with '.automaticDestinations-ms' or '.customDestinations-ms') '{2}:{1}'. Filename: '{0}'")]
+    #[error("Limit exceeded on line '{2}:{1}'. ERROR: '{0}'")]
+    LimitExceeded(String, u32, String),
+    #[error("Unable to indentify Jumplist type (neither the file name nor the content gives it) '{2}:{1}'. Filename: '{0}'")]
     FileType(String, u32, String),
+    #[error("Empty or truncated Jumplist file '{path}' ({size} bytes), too small to hold a Jumplist header")]
+    EmptyOrTruncatedFile { path: String, size: u64 },
+}
+
+impl JumplistParserError {
+    /// Stable name of the error variant, e.g. `no_destlist`, for machine readable reports.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{errors::JumplistParserError, JumplistParser};
+    ///
+    /// let error = JumplistParser::from_bytes(b"not a Jumplist", Some("notes.txt")).unwrap_err();
+    /// assert_eq!(error.kind(), "file_type");
+    /// assert_eq!(error.message(), "notes.txt");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JumplistParserError::DestList(..) => "destlist",
+            JumplistParserError::DestListHeader(..) => "destlist_header",
+            JumplistParserError::DestListEntry(..) => "destlist_entry",
+            JumplistParserError::LnkEntry(..) => "lnk_entry",
+            JumplistParserError::JumplistParser(..) => "jumplist_parser",
+            JumplistParserError::FileStructure(..) => "file_structure",
+            JumplistParserError::General(..) => "general",
+            JumplistParserError::NoDestList(..) => "no_destlist",
+            JumplistParserError::LimitExceeded(..) => "limit_exceeded",
+            JumplistParserError::FileType(..) => "file_type",
+            JumplistParserError::EmptyOrTruncatedFile { .. } => "empty_or_truncated_file",
+        }
+    }
+
+    /// The message of the error, without the variant and source location of the
+    /// [`Display`](std::fmt::Display) form.
+    pub fn message(&self) -> &str {
+        match self {
+            JumplistParserError::DestList(message, ..)
+            | JumplistParserError::DestListHeader(message, ..)
+            | JumplistParserError::DestListEntry(message, ..)
+            | JumplistParserError::LnkEntry(message, ..)
+            | JumplistParserError::JumplistParser(message, ..)
+            | JumplistParserError::FileStructure(message, ..)
+            | JumplistParserError::General(message, ..)
+            | JumplistParserError::NoDestList(message, ..)
+            | JumplistParserError::LimitExceeded(message, ..)
+            | JumplistParserError::FileType(message, ..) => message,
+            JumplistParserError::EmptyOrTruncatedFile { path, .. } => path,
+        }
+    }
+
+    /// Set the path of an [`JumplistParserError::EmptyOrTruncatedFile`] error, returned
+    /// without one by the functions parsing readers.
+    pub(crate) fn with_path(self, path: &str) -> Self {
+        match self {
+            JumplistParserError::EmptyOrTruncatedFile { size, .. } => {
+                JumplistParserError::EmptyOrTruncatedFile {
+                    path: path.to_string(),
+                    size,
+                }
+            }
+            e => e,
+        }
+    }
 }
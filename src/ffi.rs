@@ -0,0 +1,154 @@
+//! C-compatible interface (`ffi` feature).
+//!
+//! Every function returns `0` on success and stores the serialized [`JumplistParser`] (the
+//! same JSON as `serde_json::to_string`) in `*out_json`. On failure it returns a negative
+//! [status code](JUMPLIST_ERR_ARGUMENT) and stores the error message in `*out_err`.
+//! Strings returned through `out_json` and `out_err` are owned by the caller and must be
+//! released with [`jumplist_free_string`].
+//!
+//! Panics are caught and reported as [`JUMPLIST_ERR_PANIC`], they never unwind into C.
+//! The C header is generated with `cbindgen --config cbindgen.toml --output include/jumplist_parser.h`.
+
+use crate::{errors::JumplistParserError, JumplistParser};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+/// Success.
+pub const JUMPLIST_OK: i32 = 0;
+/// A required pointer is null or a string isn't valid UTF-8.
+pub const JUMPLIST_ERR_ARGUMENT: i32 = -1;
+/// The Jumplist can't be read or parsed.
+pub const JUMPLIST_ERR_PARSE: i32 = -2;
+/// The parsed Jumplist can't be serialized.
+pub const JUMPLIST_ERR_SERIALIZE: i32 = -3;
+/// The parser panicked.
+pub const JUMPLIST_ERR_PANIC: i32 = -4;
+
+/// Copy `s` into a C string owned by the caller, NUL bytes are dropped.
+fn into_c_string(s: String) -> *mut c_char {
+    let s = s.replace('\0', "");
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// `None` for a null pointer.
+unsafe fn str_arg<'a>(s: *const c_char) -> Option<Result<&'a str, (i32, String)>> {
+    match s.is_null() {
+        true => None,
+        false => Some(CStr::from_ptr(s).to_str().map_err(|e| {
+            (
+                JUMPLIST_ERR_ARGUMENT,
+                format!("Invalid UTF-8 string: {}", e),
+            )
+        })),
+    }
+}
+
+/// Run `parse` without letting panics escape and store its result in the out parameters.
+unsafe fn run(
+    out_json: *mut *mut c_char,
+    out_err: *mut *mut c_char,
+    parse: impl FnOnce() -> Result<JumplistParser, (i32, String)>,
+) -> i32 {
+    if !out_json.is_null() {
+        *out_json = ptr::null_mut();
+    }
+    if !out_err.is_null() {
+        *out_err = ptr::null_mut();
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if out_json.is_null() {
+            return Err((JUMPLIST_ERR_ARGUMENT, "out_json is null".to_string()));
+        }
+        let parsed = parse()?;
+        serde_json::to_string(&parsed).map_err(|e| (JUMPLIST_ERR_SERIALIZE, e.to_string()))
+    }))
+    .unwrap_or_else(|payload| {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => payload
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_else(|| "unknown panic".to_string()),
+        };
+        Err((
+            JUMPLIST_ERR_PANIC,
+            format!("The parser panicked: {}", message),
+        ))
+    });
+
+    match result {
+        Ok(json) => {
+            *out_json = into_c_string(json);
+            JUMPLIST_OK
+        }
+        Err((code, message)) => {
+            if !out_err.is_null() {
+                *out_err = into_c_string(message);
+            }
+            code
+        }
+    }
+}
+
+/// Parse the Jumplist file at `path`, the type is detected from the file extension as
+/// in [`JumplistParser::from_path`].
+///
+/// # Safety
+/// `path` must be a valid NUL-terminated string. `out_json` must be a valid pointer,
+/// `out_err` must be a valid pointer or null.
+#[no_mangle]
+pub unsafe extern "C" fn jumplist_parse_path(
+    path: *const c_char,
+    out_json: *mut *mut c_char,
+    out_err: *mut *mut c_char,
+) -> i32 {
+    run(out_json, out_err, || {
+        let path =
+            str_arg(path).ok_or_else(|| (JUMPLIST_ERR_ARGUMENT, "path is null".to_string()))??;
+        JumplistParser::from_path(path).map_err(|e| (JUMPLIST_ERR_PARSE, e.to_string()))
+    })
+}
+
+/// Parse a Jumplist held in memory. `name_hint` is the original file name, it gives the
+/// Jumplist type and the AppID. When it's null, the type is detected from the content.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes. `name_hint` must be a valid NUL-terminated
+/// string or null. `out_json` must be a valid pointer, `out_err` a valid pointer or null.
+#[no_mangle]
+pub unsafe extern "C" fn jumplist_parse_buffer(
+    data: *const u8,
+    len: usize,
+    name_hint: *const c_char,
+    out_json: *mut *mut c_char,
+    out_err: *mut *mut c_char,
+) -> i32 {
+    run(out_json, out_err, || {
+        if data.is_null() {
+            return Err((JUMPLIST_ERR_ARGUMENT, "data is null".to_string()));
+        }
+        let data = slice::from_raw_parts(data, len);
+        let name_hint = str_arg(name_hint).transpose()?;
+        JumplistParser::from_bytes(data, name_hint).map_err(|e| match e {
+            JumplistParserError::FileType(..) => (JUMPLIST_ERR_ARGUMENT, e.to_string()),
+            _ => (JUMPLIST_ERR_PARSE, e.to_string()),
+        })
+    })
+}
+
+/// Release a string returned by this library. Null pointers are ignored.
+///
+/// # Safety
+/// `s` must be null or a string returned through `out_json` or `out_err` that hasn't
+/// been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn jumplist_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
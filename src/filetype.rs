@@ -0,0 +1,278 @@
+//! Extension and category of the target of an entry, the `target_extension` and
+//! `target_category` keys of the normalized entries.
+//!
+//! The categories come from an internal table of common extensions. A [`FiletypeMap`] read
+//! from a JSON object (`--filetype-map`) overrides it, e.g. to count `.one` files as
+//! documents or `.lnk` files as executables:
+//!
+//! ```json
+//! {"one": "document", ".lnk": "executable", "log": "other"}
+//! ```
+//!
+//! # Example
+//! ```
+//! use jumplist_parser::filetype::{target_extension, FileCategory, FiletypeMap};
+//!
+//! let extension = target_extension(r"C:\Users\user\Desktop\Q3 Report.DOCX", "file");
+//! assert_eq!(extension, "docx");
+//! assert_eq!(FiletypeMap::default().category(&extension), FileCategory::Document);
+//!
+//! let map = FiletypeMap::from_json(r#"{".docx": "other"}"#).unwrap();
+//! assert_eq!(map.category("docx"), FileCategory::Other);
+//! ```
+
+use crate::{errors::JumplistParserError, NormalizedEntry};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Kind of file a target is, from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Document,
+    Spreadsheet,
+    Presentation,
+    Image,
+    Archive,
+    Executable,
+    Script,
+    Media,
+    /// Any other extension, and the targets without one (directories, URLs, ...).
+    Other,
+}
+
+impl FileCategory {
+    /// Every category, in order.
+    pub const ALL: &'static [FileCategory] = &[
+        FileCategory::Document,
+        FileCategory::Spreadsheet,
+        FileCategory::Presentation,
+        FileCategory::Image,
+        FileCategory::Archive,
+        FileCategory::Executable,
+        FileCategory::Script,
+        FileCategory::Media,
+        FileCategory::Other,
+    ];
+
+    /// Name of the category, e.g. `executable`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileCategory::Document => "document",
+            FileCategory::Spreadsheet => "spreadsheet",
+            FileCategory::Presentation => "presentation",
+            FileCategory::Image => "image",
+            FileCategory::Archive => "archive",
+            FileCategory::Executable => "executable",
+            FileCategory::Script => "script",
+            FileCategory::Media => "media",
+            FileCategory::Other => "other",
+        }
+    }
+}
+
+impl Display for FileCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FileCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FileCategory::ALL
+            .iter()
+            .find(|category| category.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = FileCategory::ALL.iter().map(|c| c.name()).collect();
+                format!(
+                    "unknown file category '{}', expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Extensions of the internal table, by category. The extensions of none are
+/// [`FileCategory::Other`].
+const DEFAULT_EXTENSIONS: &[(FileCategory, &[&str])] = &[
+    (
+        FileCategory::Document,
+        &[
+            "doc", "docm", "docx", "dot", "dotm", "dotx", "epub", "md", "odt", "pdf", "rtf", "txt",
+            "xps",
+        ],
+    ),
+    (
+        FileCategory::Spreadsheet,
+        &[
+            "csv", "ods", "tsv", "xls", "xlsb", "xlsm", "xlsx", "xlt", "xltm", "xltx",
+        ],
+    ),
+    (
+        FileCategory::Presentation,
+        &[
+            "key", "odp", "pot", "potm", "potx", "pps", "ppsm", "ppsx", "ppt", "pptm", "pptx",
+        ],
+    ),
+    (
+        FileCategory::Image,
+        &[
+            "bmp", "gif", "heic", "ico", "jpeg", "jpg", "png", "psd", "svg", "tif", "tiff", "webp",
+        ],
+    ),
+    (
+        FileCategory::Archive,
+        &[
+            "7z", "bz2", "cab", "gz", "img", "iso", "rar", "tar", "tgz", "vhd", "vhdx", "xz", "zip",
+        ],
+    ),
+    (
+        FileCategory::Executable,
+        &[
+            "appx", "com", "cpl", "dll", "exe", "jar", "msi", "msix", "msp", "scr", "sys",
+        ],
+    ),
+    (
+        FileCategory::Script,
+        &[
+            "bat", "cmd", "hta", "js", "jse", "ps1", "psm1", "py", "sh", "vbe", "vbs", "wsf", "wsh",
+        ],
+    ),
+    (
+        FileCategory::Media,
+        &[
+            "3gp", "aac", "avi", "flac", "m4a", "mkv", "mov", "mp3", "mp4", "ogg", "wav", "webm",
+            "wma", "wmv",
+        ],
+    ),
+];
+
+/// Categories of the extensions: the internal table, with the categories read from a user
+/// mapping taking precedence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiletypeMap {
+    /// Category of the extensions of the user mapping, lowercase without a leading `.`.
+    overrides: HashMap<String, FileCategory>,
+}
+
+impl FiletypeMap {
+    /// The internal table overridden by the JSON object `json`, mapping extensions (with or
+    /// without a leading `.`, in any case) to category names.
+    pub fn from_json(json: &str) -> Result<Self, JumplistParserError> {
+        let mapping: HashMap<String, String> = serde_json::from_str(json).map_err(|e| {
+            JumplistParserError::General(
+                format!("Invalid file type mapping, expected a JSON object: {}", e),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+        let mut overrides = HashMap::new();
+        for (extension, category) in mapping {
+            let category = category.parse::<FileCategory>().map_err(|e| {
+                JumplistParserError::General(
+                    format!("Invalid file type mapping of '{}': {}", extension, e),
+                    line!(),
+                    file!().to_string(),
+                )
+            })?;
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            overrides.insert(extension, category);
+        }
+        Ok(FiletypeMap { overrides })
+    }
+
+    /// [`FiletypeMap::from_json`] with the contents of the file at `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, JumplistParserError> {
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            JumplistParserError::General(
+                format!(
+                    "Unable to read the file type mapping '{}': {}",
+                    path.as_ref().display(),
+                    e
+                ),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Category of `extension`, lowercase without its `.` like [`target_extension`] returns
+    /// it. [`FileCategory::Other`] for an empty or unknown extension.
+    pub fn category(&self, extension: &str) -> FileCategory {
+        if let Some(category) = self.overrides.get(extension) {
+            return *category;
+        }
+        DEFAULT_EXTENSIONS
+            .iter()
+            .find(|(_, extensions)| extensions.contains(&extension))
+            .map(|(category, _)| *category)
+            .unwrap_or(FileCategory::Other)
+    }
+
+    /// Category of the target `path` of type `target_type`, from its [`target_extension`].
+    pub fn target_category(&self, path: &str, target_type: &str) -> FileCategory {
+        self.category(&target_extension(path, target_type))
+    }
+
+    /// Add the `target_extension` and `target_category` keys to a normalized entry, from
+    /// its `target_full_path` and `target_type`.
+    pub(crate) fn normalize_into(&self, normalized: &mut NormalizedEntry) {
+        let extension = target_extension(
+            normalized
+                .get("target_full_path")
+                .map(|p| p.as_str())
+                .unwrap_or_default(),
+            normalized
+                .get("target_type")
+                .map(|t| t.as_str())
+                .unwrap_or_default(),
+        );
+        let category = self.category(&extension);
+        normalized.insert("target_extension".to_string(), extension);
+        normalized.insert("target_category".to_string(), category.to_string());
+    }
+}
+
+/// Extension of the target `path` of type `target_type` (see
+/// [`TargetType`](crate::target::TargetType)), lowercase without its `.`.
+///
+/// Empty for directories and URLs, and when the last component of the path has no `.`
+/// after its first character or the text after it isn't alphanumeric.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::filetype::target_extension;
+///
+/// assert_eq!(target_extension(r"\\fileserver\finance\Q3.XLSX", "unc"), "xlsx");
+/// assert_eq!(target_extension(r"C:\Users\user\archive.tar.gz", "file"), "gz");
+/// assert_eq!(target_extension(r"C:\Users\user\.gitconfig", "file"), "");
+/// assert_eq!(target_extension(r"C:\Users\user\v1.2 notes", "file"), "");
+/// assert_eq!(target_extension("https://example.com/index.html", "url"), "");
+/// ```
+pub fn target_extension(path: &str, target_type: &str) -> String {
+    if target_type == "directory" || target_type == "url" {
+        return String::new();
+    }
+    let name = path.rsplit(['\\', '/']).next().unwrap_or_default();
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => {
+            let extension = &name[dot + 1..];
+            match !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+                true => extension.to_ascii_lowercase(),
+                false => String::new(),
+            }
+        }
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,99 @@
+//! Deterministic entry fingerprints (`entry_fingerprint`), to correlate the entries reported
+//! by different tools or runs.
+//!
+//! A fingerprint is `v1:` followed by the lower case hex SHA-1 of the UTF-8 string made of
+//! these fields, in this order, joined with `|`:
+//!
+//! 1. the AppID, lower case, empty when unknown;
+//! 2. the position of the entry: the `DestList` entry number in decimal, or
+//!    `<category index>:<entry index>` for custom entries (both 0-based, in file order,
+//!    the category of carved entries included), or `0` for standalone LNK files;
+//! 3. the target path, lower cased with the Unicode lower case mapping, empty when the
+//!    entry has none: the `DestList` path as stored, or the LNK target path of custom entries
+//!    and standalone LNK files;
+//! 4. the `DestList` last interaction time as the raw FILETIME in decimal, empty for custom
+//!    entries;
+//! 5. the `DestList` file droid as `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` (upper case, no
+//!    braces), empty for custom entries.
+//!
+//! A change to this recipe bumps [`FINGERPRINT_VERSION`].
+
+use crate::{custom_destinations::CustomDestinations, destlist::DestList, guid::Guid};
+use sha1::{Digest, Sha1};
+use winparsingtools::traits::Normalize;
+
+/// Version prefix of the fingerprints.
+pub const FINGERPRINT_VERSION: &str = "v1";
+
+/// The fingerprint of an entry, see the [module documentation](self) for the fields.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::fingerprint::entry_fingerprint;
+///
+/// let fingerprint = entry_fingerprint(Some("5F7B5F1E01B83767"), "3", Some(r"C:\Test.txt"), None, None);
+/// assert_eq!(
+///     fingerprint,
+///     entry_fingerprint(Some("5f7b5f1e01b83767"), "3", Some(r"c:\test.txt"), None, None)
+/// );
+/// assert!(fingerprint.starts_with("v1:"));
+/// ```
+pub fn entry_fingerprint(
+    app_id: Option<&str>,
+    position: &str,
+    target_path: Option<&str>,
+    mtime: Option<u64>,
+    file_droid: Option<&Guid>,
+) -> String {
+    let fields = [
+        app_id.unwrap_or_default().to_lowercase(),
+        position.to_string(),
+        target_path.unwrap_or_default().to_lowercase(),
+        mtime.map(|t| t.to_string()).unwrap_or_default(),
+        file_droid.map(|g| g.to_string()).unwrap_or_default(),
+    ];
+    let digest = Sha1::digest(fields.join("|").as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}:{}", FINGERPRINT_VERSION, hex)
+}
+
+impl DestList {
+    /// Set the fingerprint of every entry, `app_id` is the AppID of the Jumplist. The
+    /// [`JumplistParser`](crate::JumplistParser) parse functions set them, a standalone
+    /// stream has none until this is called.
+    pub fn update_fingerprints(&mut self, app_id: Option<&str>) {
+        for entry in self.entries.iter_mut() {
+            entry.entry_fingerprint = entry_fingerprint(
+                app_id,
+                &entry.entry_number.to_string(),
+                Some(&entry.path),
+                Some(entry.mtime.filetime),
+                Some(&entry.file_droid.guid),
+            );
+        }
+    }
+}
+
+impl CustomDestinations {
+    /// Set the fingerprint of every entry, `app_id` is the AppID of the Jumplist. The
+    /// [`JumplistParser`](crate::JumplistParser) parse functions set them, a standalone
+    /// stream has none until this is called.
+    pub fn update_fingerprints(&mut self, app_id: Option<&str>) {
+        for (category_index, category) in self.entries.iter_mut().enumerate() {
+            let entries = category.entries.iter_mut().flatten();
+            for (entry_index, entry) in entries.enumerate() {
+                let target_path = entry
+                    .lnk
+                    .as_ref()
+                    .and_then(|lnk| lnk.normalize().remove("target_full_path"));
+                entry.entry_fingerprint = entry_fingerprint(
+                    app_id,
+                    &format!("{}:{}", category_index, entry_index),
+                    target_path.as_deref(),
+                    None,
+                    None,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! GUIDs as stored in Jumplist structures.
+//!
+//! [`Guid`] is always displayed in upper case without braces. The droids of the `DestList`
+//! entries are [`Droid`]s, serialized with the [`GuidFormat`] of
+//! [`ParseOptions::guid_format`](crate::ParseOptions::guid_format).
+
+use crate::errors::JumplistParserError;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display},
+    io::Read,
+    str::FromStr,
+};
+
+/// A GUID in its on-disk layout: the first three groups are little-endian.
+///
+/// Displayed and serialized as `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` (upper case, no braces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    /// Read a GUID from the first 16 bytes of `buf`.
+    pub fn from_buffer(buf: &[u8]) -> Result<Self, JumplistParserError> {
+        Self::from_reader(&mut &buf[..])
+    }
+
+    /// Read a GUID from `r`.
+    pub fn from_reader<R: Read>(r: &mut R) -> Result<Self, JumplistParserError> {
+        let mut bytes = [0; 16];
+        r.read_exact(&mut bytes).map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        Ok(Guid(bytes))
+    }
+}
+
+impl Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-",
+            b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9]
+        )?;
+        b[10..]
+            .iter()
+            .try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
+
+impl FromStr for Guid {
+    type Err = String;
+
+    /// Parse `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` in any case, braces or the `urn:uuid:`
+    /// prefix are optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unprefixed = match s.get(..9) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("urn:uuid:") => &s[9..],
+            _ => s,
+        };
+        let hex: String = unprefixed
+            .chars()
+            .filter(|c| !matches!(c, '{' | '}' | '-'))
+            .collect();
+        let invalid = || format!("invalid GUID '{}'", s);
+        if hex.len() != 32 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        bytes[0..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+        Ok(Guid(bytes))
+    }
+}
+
+impl Serialize for Guid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Guid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Decoration of a formatted GUID, see [`GuidFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GuidStyle {
+    /// `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` (the default).
+    #[default]
+    Plain,
+    /// `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`, as in the registry.
+    Braced,
+    /// `urn:uuid:XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` (RFC 4122).
+    Urn,
+}
+
+/// How an all-zero GUID (not set) is serialized, see [`GuidFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroGuid {
+    /// Like any other GUID (the default).
+    #[default]
+    Keep,
+    /// As `null` (an empty string in the normalized output).
+    Null,
+    /// Not at all (an empty string in the normalized output).
+    Omit,
+}
+
+impl FromStr for ZeroGuid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(ZeroGuid::Keep),
+            "null" => Ok(ZeroGuid::Null),
+            "omit" => Ok(ZeroGuid::Omit),
+            _ => Err(format!("Unknown zero GUID mode '{}'", s)),
+        }
+    }
+}
+
+/// How [`Droid`] values are serialized, in the raw and the normalized output.
+///
+/// The default is the [`Guid`] display: upper case, no braces, zeros kept.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::guid::{Guid, GuidFormat, GuidStyle};
+///
+/// let guid: Guid = "C75B8B3C-0DF7-11F0-A4C2-000C29A4C2E1".parse().unwrap();
+/// let format = GuidFormat {
+///     lowercase: true,
+///     style: GuidStyle::Braced,
+///     ..Default::default()
+/// };
+/// assert_eq!(format.format(&guid), "{c75b8b3c-0df7-11f0-a4c2-000c29a4c2e1}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GuidFormat {
+    /// Lower case hex digits.
+    pub lowercase: bool,
+    pub style: GuidStyle,
+    pub zero: ZeroGuid,
+}
+
+impl GuidFormat {
+    /// `guid` in this format, zero or not.
+    pub fn format(&self, guid: &Guid) -> String {
+        let hex = match self.lowercase {
+            true => guid.to_string().to_lowercase(),
+            false => guid.to_string(),
+        };
+        match self.style {
+            GuidStyle::Plain => hex,
+            GuidStyle::Braced => format!("{{{}}}", hex),
+            GuidStyle::Urn => format!("urn:uuid:{}", hex),
+        }
+    }
+}
+
+impl FromStr for GuidFormat {
+    type Err = String;
+
+    /// Parse the case and style: `upper`, `lower`, `braced`, `braced-lower` or `urn` (lower
+    /// case, as RFC 4122 recommends). [`GuidFormat::zero`] is [`ZeroGuid::Keep`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lowercase, style) = match s {
+            "upper" => (false, GuidStyle::Plain),
+            "lower" => (true, GuidStyle::Plain),
+            "braced" => (false, GuidStyle::Braced),
+            "braced-lower" => (true, GuidStyle::Braced),
+            "urn" => (true, GuidStyle::Urn),
+            _ => return Err(format!("Unknown GUID format '{}'", s)),
+        };
+        Ok(GuidFormat {
+            lowercase,
+            style,
+            zero: ZeroGuid::Keep,
+        })
+    }
+}
+
+/// A droid (distributed link tracking GUID) of a `DestList` entry together with the format
+/// it is serialized with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Droid {
+    pub guid: Guid,
+    /// Serialization format.
+    pub format: GuidFormat,
+}
+
+impl Droid {
+    pub fn new(guid: Guid, format: GuidFormat) -> Self {
+        Self { guid, format }
+    }
+
+    /// Whether the GUID is all zeros (not set).
+    pub fn is_zero(&self) -> bool {
+        self.guid == Guid::default()
+    }
+
+    /// The formatted GUID, `None` for a zero GUID unless [`ZeroGuid::Keep`].
+    pub fn value(&self) -> Option<String> {
+        match self.is_zero() && self.format.zero != ZeroGuid::Keep {
+            true => None,
+            false => Some(self.format.format(&self.guid)),
+        }
+    }
+
+    /// Whether the field is left out of the serialized output, for `skip_serializing_if`.
+    pub fn is_omitted(&self) -> bool {
+        self.is_zero() && self.format.zero == ZeroGuid::Omit
+    }
+}
+
+impl PartialEq<Guid> for Droid {
+    fn eq(&self, other: &Guid) -> bool {
+        self.guid == *other
+    }
+}
+
+impl Display for Droid {
+    /// The GUID in its format, zero or not.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format.format(&self.guid))
+    }
+}
+
+impl Serialize for Droid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.value() {
+            Some(value) => serializer.serialize_str(&value),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Droid {
+    /// Any format [`Guid`] parses, `null` is a zero GUID.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let guid = match Option::<String>::deserialize(deserializer)? {
+            Some(s) => s.parse().map_err(de::Error::custom)?,
+            None => Guid::default(),
+        };
+        Ok(Droid::new(guid, GuidFormat::default()))
+    }
+}
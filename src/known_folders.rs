@@ -0,0 +1,159 @@
+//! Known folder (`KNOWNFOLDERID`) and shell namespace GUIDs to friendly names.
+//!
+//! Jumplist paths often reference folders by GUID, e.g. `knownfolder:{374DE290-123F-4565-9164-39C4925E467B}`
+//! for the Downloads folder or `::{20D04FE0-3AEA-1069-A2D8-08002B30309D}` for This PC.
+use phf::phf_map;
+
+/// Taken from : <https://learn.microsoft.com/en-us/windows/win32/shell/knownfolderid> and the shell namespace CLSIDs.
+///
+/// Keys are upper case GUIDs without braces.
+pub static KNOWN_FOLDERS: phf::Map<&'static str, &'static str> = phf_map! {
+    // KNOWNFOLDERID
+    "B4BFCC3A-DB2C-424C-B029-7FE99A87C641" => "Desktop",
+    "FDD39AD0-238F-46AF-ADB4-6C85480369C7" => "Documents",
+    "374DE290-123F-4565-9164-39C4925E467B" => "Downloads",
+    "4BD8D571-6D19-48D3-BE97-422220080E43" => "Music",
+    "33E28130-4E1E-4676-835A-98395C3BC3BB" => "Pictures",
+    "18989B1D-99B5-455B-841C-AB7C74E4DDFC" => "Videos",
+    "31C0DD25-9439-4F12-BF41-7FF4EDA38722" => "3D Objects",
+    "5E6C858F-0E22-4760-9AFE-EA3317B67173" => "Profile",
+    "0762D272-C50A-4BB0-A382-697DCD729B80" => "Users",
+    "F3CE0F7C-4901-4ACC-8648-D5D44B04EF8F" => "User Files",
+    "DFDF76A2-C82A-4D63-906A-5644AC457385" => "Public",
+    "C4AA340D-F20F-4863-AFEF-F87EF2E6BA25" => "Public Desktop",
+    "ED4824AF-DCE4-45A8-81E2-FC7965083634" => "Public Documents",
+    "3D644C9B-1FB8-4F30-9B45-F670235F79C0" => "Public Downloads",
+    "3214FAB5-9757-4298-BB61-92A9DEAA44FF" => "Public Music",
+    "B6EBFB86-6907-413C-9AF7-4FC2ABF07CC5" => "Public Pictures",
+    "2400183A-6185-49FB-A2D8-4A392A602BA3" => "Public Videos",
+    "48DAF80B-E6CF-4F4E-B800-0E69D84EE384" => "Public Libraries",
+    "3EB685DB-65F9-4CF6-A03A-E3EF65729F3D" => "AppData\\Roaming",
+    "F1B32785-6FBA-4FCF-9D55-7B8E7F157091" => "AppData\\Local",
+    "A520A1A4-1780-4FF6-BD18-167343C5AF16" => "AppData\\LocalLow",
+    "62AB5D82-FDC1-4DC3-A9DD-070D1D495D97" => "ProgramData",
+    "905E63B6-C1BF-494E-B29C-65B732D3D21A" => "Program Files",
+    "6D809377-6AF0-444B-8957-A3773F02200E" => "Program Files (x64)",
+    "7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E" => "Program Files (x86)",
+    "F7F1ED05-9F6D-47A2-AAAE-29D317C6F066" => "Common Files",
+    "6365D5A7-0F0D-45E5-87F6-0DA56B6A4F7D" => "Common Files (x64)",
+    "DE974D24-D9C6-4D3E-BF91-F4455120B917" => "Common Files (x86)",
+    "5CD7AEE2-2219-4A67-B85D-6C9CE15660CB" => "User Programs",
+    "F38BF404-1D43-42F2-9305-67DE0B28FC23" => "Windows",
+    "1AC14E77-02E7-4E5D-B744-2EB1AE5198B7" => "System32",
+    "D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27" => "SysWOW64",
+    "FD228CB7-AE11-4AE3-864C-16F3910AB8FE" => "Fonts",
+    "1777F761-68AD-4D8A-87BD-30B759FA33DD" => "Favorites",
+    "BFB9D5E0-C6A9-404C-B2B2-AE6DB6AF4968" => "Links",
+    "4C5C32FF-BB9D-43B0-B5B4-2D72E54EAAA4" => "Saved Games",
+    "7D1D3A04-DEBB-4115-95CF-2F29DA2920DA" => "Searches",
+    "56784854-C6CB-462B-8169-88E350ACB882" => "Contacts",
+    "AE50C081-EBD2-438A-8655-8A092E34987A" => "Recent Items",
+    "8983036C-27C0-404B-8F08-102D10DCFD74" => "SendTo",
+    "625B53C3-AB48-4EC1-BA1F-A1EF4146FC19" => "Start Menu",
+    "A77F5D77-2E2B-44C3-A6A2-ABA601054A51" => "Programs",
+    "B97D20BB-F46A-4C97-BA10-5E3608430854" => "Startup",
+    "A4115719-D62E-491D-AA7C-E74B8BE3B067" => "Common Start Menu",
+    "0139D44E-6AFE-49F2-8690-3DAFCAE6FFB8" => "Common Programs",
+    "82A5EA35-D9CD-47C5-9629-E15D2F714E6E" => "Common Startup",
+    "A63293E8-664E-48DB-A079-DF759E0509F7" => "Templates",
+    "B94237E7-57AC-4347-9151-B08C6C32D1F7" => "Common Templates",
+    "724EF170-A42D-4FEF-9F26-B60E846FBA4F" => "Administrative Tools",
+    "52A4F021-7B75-48A9-9F6B-4B87A210BC8F" => "Quick Launch",
+    "A3918781-E5F2-4890-B3D9-A7E54332328C" => "Application Shortcuts",
+    "2B0F765D-C0E9-4171-908E-08A611B84FF6" => "Cookies",
+    "D9DC8A3B-B784-432E-A781-5A1130A75963" => "History",
+    "352481E8-33BE-4251-BA85-6007CAEDCF9D" => "Temporary Internet Files",
+    "C5ABBF53-E17F-4121-8900-86626FC2C973" => "Network Shortcuts",
+    "9274BD8D-CFD1-41C3-B35E-B13F55A758F4" => "Printer Shortcuts",
+    "A52BBA46-E9E1-435F-B3D9-28DAA648C0F6" => "OneDrive",
+    "AB5FB87B-7CE2-4F83-915D-550846C9537B" => "Camera Roll",
+    "B7BEDE81-DF94-4682-A7D8-57A52620B86F" => "Screenshots",
+    "1B3EA5DC-B587-4786-B4EF-BD1DC332AEAE" => "Libraries",
+    "7B0DB17D-9CD2-4A93-9733-46CC89022E7C" => "Documents Library",
+    "2112AB0A-C86A-4FFE-A368-0DE96E47012E" => "Music Library",
+    "A990AE9F-A03B-4E80-94BC-9912D7504104" => "Pictures Library",
+    "491E922F-5643-4AF4-A7EB-4E7A138D8174" => "Videos Library",
+    "0AC0837C-BBF8-452A-850D-79D08E667CA7" => "This PC",
+    "82A74AEB-AEB4-465C-A014-D097EE346D63" => "Control Panel",
+    "B7534046-3ECB-4C18-BE4E-64CD4CB7D6AC" => "Recycle Bin",
+    "D20BEEC4-5CA8-4905-AE3B-BF251EA09B53" => "Network",
+    // Shell namespace CLSIDs
+    "20D04FE0-3AEA-1069-A2D8-08002B30309D" => "This PC",
+    "679F85CB-0220-4080-B29B-5540CC05AAB6" => "Quick Access",
+    "F874310E-B6B7-47DC-BC84-B9E6B38F5903" => "Home",
+    "59031A47-3F72-44A7-89C5-5595FE6B30EE" => "User Files",
+    "26EE0668-A00A-44D7-9371-BEB064C98683" => "Control Panel",
+    "21EC2020-3AEA-1069-A2DD-08002B30309D" => "All Control Panel Items",
+    "BB06C0E4-D293-4F75-8A90-CB05B6477EEE" => "System",
+    "645FF040-5081-101B-9F08-00AA002F954E" => "Recycle Bin",
+    "F02C1A0D-BE21-4350-88B0-7367FC96EF3C" => "Network",
+    "208D2C60-3AEA-1069-A2D7-08002B30309D" => "My Network Places",
+    "031E4825-7B94-4DC3-B131-E946B44C8DD5" => "Libraries",
+    "018D5C66-4533-4307-9B53-224DE2ED1FE6" => "OneDrive",
+    "088E3905-0323-4B02-9826-5D99428E115F" => "Downloads",
+    "D3162B92-9365-467A-956B-92703ACA08AF" => "Documents",
+    "24AD3AD4-A569-4530-98E1-AB02F9417AA8" => "Pictures",
+    "3DFDF296-DBEC-4FB4-81D1-6A3438BCF4DE" => "Music",
+    "F86FA3AB-70D2-4FC7-9C99-FCBF05467F3A" => "Videos",
+    "0DB7E03F-FC29-4DC6-9020-FF41B59E513A" => "3D Objects",
+};
+
+/// Look up the friendly name of a known folder or shell namespace GUID.
+///
+/// The GUID may be braced and in any case.
+pub fn known_folder_name(guid: &str) -> Option<&'static str> {
+    let guid = guid
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_uppercase();
+    KNOWN_FOLDERS.get(guid.as_str()).copied()
+}
+
+/// Whether `path` has a `knownfolder:` prefix or a `::` component, which
+/// [`resolve_known_folder_path`] may rewrite.
+pub(crate) fn may_reference_known_folder(path: &str) -> bool {
+    let has_prefix = path
+        .get(..12)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("knownfolder:"));
+    has_prefix || path.contains("::")
+}
+
+/// Rewrite known folder references in `path` into readable names.
+///
+/// Both `knownfolder:{GUID}` prefixes and `::{GUID}` path components are replaced with
+/// `[Name]`, unknown GUIDs are left untouched.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::known_folders::resolve_known_folder_path;
+///
+/// assert_eq!(
+///     resolve_known_folder_path("knownfolder:{374DE290-123F-4565-9164-39C4925E467B}\\file.pdf"),
+///     "[Downloads]\\file.pdf"
+/// );
+/// ```
+pub fn resolve_known_folder_path(path: &str) -> String {
+    // Most paths have no known folder reference
+    if !may_reference_known_folder(path) {
+        return path.to_string();
+    }
+    path.split('\\')
+        .enumerate()
+        .map(|(i, component)| {
+            let guid = match component.get(..13) {
+                Some(prefix) if i == 0 && prefix.eq_ignore_ascii_case("knownfolder:{") => {
+                    Some(&component[12..])
+                }
+                _ => component.strip_prefix("::"),
+            };
+            match guid
+                .filter(|g| g.starts_with('{') && g.ends_with('}'))
+                .and_then(known_folder_name)
+            {
+                Some(name) => format!("[{}]", name),
+                None => component.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\\")
+}
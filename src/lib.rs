@@ -4,36 +4,143 @@
 //! - `automaticDestinations-ms` (DestList + LNKs in CFB)
 //! - `customDestinations-ms` (CustomDestinations format)
 //!
+//! and the standalone LNK files of the `Recent` folder, see [`recent_lnk`].
+//!
 
+pub mod aggregate;
+pub mod anomaly;
 pub mod appids;
+pub mod builder;
+pub mod cfb_info;
 pub mod custom_destinations;
 pub mod destlist;
+pub mod diff;
+pub mod entry;
 pub mod errors;
+#[cfg(all(feature = "ffi", not(target_arch = "wasm32")))]
+pub mod ffi;
+pub mod filetype;
+pub mod fingerprint;
+pub mod guid;
+pub mod known_folders;
+#[cfg(windows)]
+pub mod live;
+pub mod lnk_info;
+pub mod locations;
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+mod mmap;
+pub mod options;
+pub mod os_hint;
+pub mod outcome;
+pub mod output;
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+mod parallel;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod path_pattern;
+pub mod prelude;
+pub mod property_store;
+pub mod recent_lnk;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod search;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+pub mod sink;
+pub mod sort;
+pub mod stats;
+pub mod table;
+pub mod target;
+pub mod timestamp;
+pub mod tln;
+pub mod user;
+mod utils;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub mod watch;
 
 use cfb::CompoundFile;
 use destlist::DestList;
 use errors::JumplistParserError;
 use std::{
-    collections::HashMap,
     fmt::{self, Display},
+    io::{Cursor, Read, Seek, SeekFrom},
+    sync::Arc,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
     fs::File,
-    io::{Cursor, Read},
+    io::BufReader,
+    path::{Path, PathBuf},
 };
 
-use winparsingtools::traits::Normalize;
+/// The LNK parser of the entries, from the `lnk_parser` crate this crate is built with.
+pub use lnk_parser::LNKParser;
+/// Types of the `winparsingtools` crate this crate is built with, found in the fields of
+/// [`LNKParser`]. Use these re-exports rather than a direct `winparsingtools` dependency,
+/// whose version could differ: [`Normalize`] is then a different trait and
+/// `LNKParser::normalize` isn't found. This crate's own GUIDs are [`guid::Guid`].
+pub use winparsingtools::{date_time::FileTime, structs::Guid, traits::Normalize};
 
-use serde::Serialize;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    anomaly::Anomaly,
+    cfb_info::{CfbInfo, ExtraStream},
+    custom_destinations::{CustomDestinations, CustomDestinationsHeader},
+    filetype::{FileCategory, FiletypeMap},
+    os_hint::OsHint,
+    recent_lnk::RecentLnk,
+    stats::{ParseStats, Timer},
+    target::canonicalize_target_path,
+};
+pub use entry::JumplistEntryRef;
+pub use options::{
+    FileNameSuffixes, ParseLimits, ParseMode, ParseOptions, Progress, ProgressEvent,
+    PROGRESS_INTERVAL,
+};
+pub use outcome::{ParseOutcome, ParseWarning};
+pub use utils::to_hex;
 
-use crate::{appids::APPID_TO_NAME, custom_destinations::CustomDestinations};
+/// A normalized record: the [`output::FIELDS`] names and their values, see
+/// [`JumplistParser::records`].
+///
+/// The keys are in the canonical order of [`output::sort_keys`], the order of
+/// [`output::FIELDS`], so that the serialized records are the same on every run.
+pub type NormalizedEntry = IndexMap<String, String>;
+
+/// Signature of the compound files automatic Jumplists are stored in.
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
 
 /// Type of Jumplist file.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum JumplistType {
     /// Automatic Jumplist (CFB + DestList + LNKs). File extension: `.automaticDestinations-ms`.
     Automatic,
     /// Custom Jumplist (`.customDestinations-ms`). File extension: `.customDestinations-ms`.
     Custom,
+    /// Standalone LNK file of the `Recent` folder (`.lnk`), only parsed as such on request,
+    /// see [`parse_lnk_file`].
+    #[serde(rename = "recent_lnk")]
+    RecentLnk,
+}
+
+impl JumplistType {
+    /// Size of the smallest file of this type: the 512 bytes header of the compound file of
+    /// automatic Jumplists, the 12 bytes header of custom Jumplists and the 76 bytes
+    /// `ShellLinkHeader` of LNK files. Smaller files fail with
+    /// [`JumplistParserError::EmptyOrTruncatedFile`].
+    pub fn min_file_size(&self) -> u64 {
+        match self {
+            JumplistType::Automatic => 512,
+            JumplistType::Custom => 12,
+            JumplistType::RecentLnk => lnk_info::LNK_HEADER_SIZE as u64,
+        }
+    }
 }
 
 impl Display for JumplistType {
@@ -41,112 +148,326 @@ impl Display for JumplistType {
         let s = match self {
             JumplistType::Automatic => "automatic",
             JumplistType::Custom => "custom",
+            JumplistType::RecentLnk => "recent_lnk",
         };
         write!(f, "{}", s)
     }
 }
 
 /// Wrapper enum to hold parsed Jumplist data.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum JumplistData {
     DestList(DestList),
     CustomDestinations(CustomDestinations),
+    RecentLnk(Box<RecentLnk>),
+}
+
+impl JumplistData {
+    /// The `DestList` of an automatic Jumplist, `None` for a custom Jumplist.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(parsed.data.as_destlist().unwrap().header.number_of_entries, 24);
+    /// assert!(parsed.data.as_custom_destinations().is_none());
+    /// ```
+    pub fn as_destlist(&self) -> Option<&DestList> {
+        match self {
+            JumplistData::DestList(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Mutable variant of [`JumplistData::as_destlist`].
+    pub fn as_destlist_mut(&mut self) -> Option<&mut DestList> {
+        match self {
+            JumplistData::DestList(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// The parsed CustomDestinations of a custom Jumplist, `None` for an automatic Jumplist.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert!(!parsed.data.as_custom_destinations().unwrap().entries.is_empty());
+    /// assert!(parsed.data.as_destlist().is_none());
+    /// ```
+    pub fn as_custom_destinations(&self) -> Option<&CustomDestinations> {
+        match self {
+            JumplistData::CustomDestinations(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Mutable variant of [`JumplistData::as_custom_destinations`].
+    pub fn as_custom_destinations_mut(&mut self) -> Option<&mut CustomDestinations> {
+        match self {
+            JumplistData::CustomDestinations(data) => Some(data),
+            _ => None,
+        }
+    }
 }
 
 /// Parse & represent a Jumplist file data.
-#[derive(Debug, Serialize)]
+///
+/// Parsed output can be deserialized back (e.g. from JSON). Fields owned by this crate
+/// round trip losslessly, the embedded LNK structures (`lnk`) come from the `lnk_parser`
+/// crate which can't be deserialized, so they are dropped and the raw LNK bytes aren't
+/// serialized at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct JumplistParser {
     pub app_id: Option<String>,
+    /// Whether `app_id` was found in the file name as 16 hex digits, see
+    /// [`appids::app_id_from_file_name`].
+    #[serde(default)]
+    pub app_id_valid: bool,
     pub app_name: Option<String>,
     pub r#type: JumplistType,
     pub source_path: Option<String>,
+    /// User whose profile the file was found in, see [`user::user_from_path`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Windows generation inferred from the format versions, see [`JumplistParser::os_hint`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_hint: Option<OsHint>,
+    /// Metadata of the compound file container, automatic Jumplists only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cfb_info: Option<CfbInfo>,
+    /// Streams of an automatic Jumplist that are neither `DestList` nor an LNK stream.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_streams: Vec<ExtraStream>,
+    /// Signs of tampering, only looked for with [`ParseOptions::detect_anomalies`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anomalies: Vec<Anomaly>,
+    /// Add `target_full_path_canonical` to the normalized entries, set from
+    /// [`ParseOptions::canonicalize_paths`].
+    #[serde(skip)]
+    pub canonicalize_paths: bool,
+    /// Write the normalized entries with their legacy key names, set from
+    /// [`ParseOptions::legacy_keys`].
+    #[serde(skip)]
+    pub legacy_keys: bool,
+    /// Categories of the `target_category` key of the normalized entries, set from
+    /// [`ParseOptions::filetype_map`]. The internal table when `None`.
+    #[serde(skip)]
+    pub filetype_map: Option<Arc<FiletypeMap>>,
+    /// Parse durations and counters, only collected with [`ParseOptions::collect_stats`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ParseStats>,
     pub data: JumplistData,
 }
 
 impl JumplistParser {
-    /// Parse a Jumplist from a buffer (Cursor).
+    /// Parse a Jumplist from a reader (e.g. a `Cursor` or a buffered `File`).
+    ///
+    /// Streams of automatic Jumplists are read from `r` one at a time as they are parsed,
+    /// the file is never read into memory as a whole.
+    ///
+    /// When parsing a custom Jumplist fails or stops early, its LNK entries are recovered
+    /// with [`CustomDestinations::carve_from_reader`] if that finds more of them, unless
+    /// [`ParseOptions::disable_carving`] is set.
+    ///
+    /// In [`ParseMode::Strict`] the first anomaly that would otherwise be reported as a
+    /// warning is returned as an error, and carving is disabled.
+    ///
+    /// A file exceeding [`ParseOptions::limits`] is never recovered, the
+    /// [`JumplistParserError::LimitExceeded`] error is returned in both modes.
     ///
     /// # Arguments
-    /// * `r` - Cursor over the file contents.
+    /// * `r` - Reader over the file contents.
     /// * `jumplist_type` - Whether it's automatic or custom format.
+    pub fn from_reader<R: Read + Seek>(
+        r: &mut R,
+        jumplist_type: JumplistType,
+    ) -> Result<Self, JumplistParserError> {
+        Self::from_reader_with_options(r, jumplist_type, &ParseOptions::default())
+    }
 
-    pub fn from_reader(
-        r: &mut Cursor<Vec<u8>>,
+    /// Same as [`JumplistParser::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        r: &mut R,
         jumplist_type: JumplistType,
+        options: &ParseOptions,
     ) -> Result<Self, JumplistParserError> {
-        match jumplist_type {
+        // Windows leaves zero-byte custom Jumplists behind, tell them from corrupt files
+        let size = utils::remaining_len(r)?;
+        if size < jumplist_type.min_file_size() {
+            return Err(JumplistParserError::EmptyOrTruncatedFile {
+                path: String::new(),
+                size,
+            });
+        }
+        let mut stats = ParseStats::for_options(options);
+        let parse_timer = Timer::start(&stats);
+        let mut parsed = match jumplist_type {
             JumplistType::Automatic => {
-                let mut parser = match CompoundFile::open(r) {
-                    Ok(r) => Ok(r),
-                    Err(e) => Err(e),
-                }
-                .map_err(|_| {
+                let cfb_timer = Timer::start(&stats);
+                let mut parser = CompoundFile::open(r).map_err(|_| {
                     JumplistParserError::FileStructure(
                         "Unable to parse the file".to_string(),
                         line!(),
                         file!().to_string(),
                     )
                 })?;
-
-                let mut destlist_data: Cursor<Vec<u8>> = Cursor::new(vec![]);
-                let entries: Vec<cfb::Entry> = parser.walk().collect();
-
-                for entry in entries.iter() {
-                    if entry.name() == "DestList" {
-                        if entry.len() > 0 {
-                            let dl_data = {
-                                let mut stream = parser.open_stream("DestList").unwrap();
-                                let mut buffer = Vec::new();
-                                stream.read_to_end(&mut buffer).unwrap();
-                                buffer
-                            };
-
-                            destlist_data = Cursor::new(dl_data);
-                        } else {
-                            // TODO: Handle empty DestList
-                        }
-                    }
+                if let Some(stats) = stats.as_mut() {
+                    stats.cfb_open_micros = cfb_timer.micros();
                 }
 
-                let data = match destlist::DestList::from_reader(
-                    &mut destlist_data,
-                    Some(entries),
-                    &mut parser,
-                ) {
-                    Ok(dlist) => Some(dlist),
+                let cfb_info = CfbInfo::from_compound_file(&parser, options.ts_format);
+                let extra_streams = ExtraStream::from_compound_file(&mut parser, options);
+                let results = match DestList::from_compound_file(&mut parser, options, &mut stats) {
+                    Ok(dlist) => dlist,
+                    Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                    Err(e) if options.is_strict() => return Err(e),
                     Err(e) => {
-                        eprintln!("ERROR: {}", e);
-                        None
+                        return Err(JumplistParserError::NoDestList(
+                            format!("Unable to parse the 'DestList' stream: {}", e),
+                            line!(),
+                            file!().to_string(),
+                        ))
                     }
                 };
 
-                match data {
-                    Some(results) => Ok(Self {
-                        app_id: None,
-                        app_name: None,
-                        source_path: None,
-                        r#type: jumplist_type,
-                        data: JumplistData::DestList(results),
-                    }),
-                    None => Err(JumplistParserError::NoDestList(
-                        "No entry with the name 'DestList' (Empty JumpList)".to_string(),
-                        line!(),
-                        file!().to_string(),
-                    )),
-                }
+                Ok(Self {
+                    app_id: None,
+                    app_id_valid: false,
+                    app_name: None,
+                    source_path: None,
+                    user: None,
+                    r#type: jumplist_type,
+                    os_hint: OsHint::from_destlist_version(results.header.version),
+                    cfb_info: Some(cfb_info),
+                    extra_streams,
+                    anomalies: vec![],
+                    canonicalize_paths: false,
+                    legacy_keys: false,
+                    filetype_map: None,
+                    stats,
+                    data: JumplistData::DestList(results),
+                })
             }
             JumplistType::Custom => {
-                let results = CustomDestinations::from_reader(r)?;
+                // A file with an impossible format version isn't a custom Jumplist, there is
+                // nothing to carve
+                let start = r.stream_position().map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })?;
+                let mut version = [0; 4];
+                let implausible = r.read_exact(&mut version).is_ok()
+                    && !CustomDestinationsHeader::is_plausible_version(u32::from_le_bytes(version));
+                r.seek(SeekFrom::Start(start)).map_err(|e| {
+                    JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+                })?;
+                let lnk_timer = Timer::start(&stats);
+                let results = match CustomDestinations::from_reader_with_options(r, options) {
+                    Ok(results)
+                        if options.disable_carving || options.is_strict() || !results.truncated =>
+                    {
+                        results
+                    }
+                    // Keep what the structured parser got unless carving recovers more
+                    Ok(results) => {
+                        let parsed = results.total_entries();
+                        match CustomDestinations::carve_from_reader_with_options(r, options) {
+                            Ok(mut carved) if carved.total_entries() > parsed => {
+                                let mut warnings = results.warnings;
+                                warnings.append(&mut carved.warnings);
+                                carved.warnings = warnings;
+                                carved
+                            }
+                            Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                            _ => results,
+                        }
+                    }
+                    Err(e @ JumplistParserError::LimitExceeded(..)) => return Err(e),
+                    Err(e) if options.disable_carving || options.is_strict() || implausible => {
+                        return Err(e)
+                    }
+                    Err(e) => {
+                        let mut carved =
+                            CustomDestinations::carve_from_reader_with_options(r, options)?;
+                        carved
+                            .warnings
+                            .insert(0, format!("The file structure can't be parsed: {}", e));
+                        carved
+                    }
+                };
+                if let Some(stats) = stats.as_mut() {
+                    stats.lnk_parse_micros = lnk_timer.micros();
+                    stats.bytes_read = size;
+                }
                 Ok(Self {
                     app_id: None,
+                    app_id_valid: false,
                     app_name: None,
                     source_path: None,
+                    user: None,
                     r#type: jumplist_type,
+                    os_hint: None,
+                    cfb_info: None,
+                    extra_streams: vec![],
+                    anomalies: vec![],
+                    canonicalize_paths: false,
+                    legacy_keys: false,
+                    filetype_map: None,
+                    stats,
                     data: JumplistData::CustomDestinations(results),
                 })
             }
+            JumplistType::RecentLnk => {
+                let lnk_timer = Timer::start(&stats);
+                let lnk = RecentLnk::from_reader_with_options(r, options)?;
+                if let Some(stats) = stats.as_mut() {
+                    stats.lnk_parse_micros = lnk_timer.micros();
+                    stats.bytes_read = size;
+                }
+                Ok(Self {
+                    app_id: None,
+                    app_id_valid: false,
+                    app_name: None,
+                    source_path: None,
+                    user: None,
+                    r#type: jumplist_type,
+                    os_hint: None,
+                    cfb_info: None,
+                    extra_streams: vec![],
+                    anomalies: vec![],
+                    canonicalize_paths: false,
+                    legacy_keys: false,
+                    filetype_map: None,
+                    stats,
+                    data: JumplistData::RecentLnk(Box::new(lnk)),
+                })
+            }
+        }?;
+        if options.detect_anomalies {
+            parsed.anomalies = parsed.detect_anomalies(None);
+        }
+        parsed.canonicalize_paths = options.canonicalize_paths;
+        parsed.legacy_keys = options.legacy_keys;
+        parsed.filetype_map = options.filetype_map.clone();
+        parsed.update_fingerprints();
+        if let Some(mut stats) = parsed.stats.take() {
+            stats.count_entries(&parsed);
+            stats.total_micros = parse_timer.micros();
+            parsed.stats = Some(stats);
         }
+        Ok(parsed)
     }
 
     /// Parse a Jumplist from a file on disk.
@@ -166,114 +487,1028 @@ impl JumplistParser {
     /// use jumplist_parser::JumplistParser;
     ///
     /// fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let parsed = JumplistParser::from_path("samples/win11/automaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms")?;
+    ///     let parsed = JumplistParser::from_path("samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms")?;
     ///
     ///     println!("App ID: {:?}", parsed.app_id);
     ///     println!("Entries: {:?}", parsed);
     ///     Ok(())
     /// }
     /// ```
-
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_path(path: &str) -> Result<Self, JumplistParserError> {
-        let mut file = File::open(path).map_err(|e| {
+        Self::try_from_path(path, &ParseOptions::default()).into_result()
+    }
+
+    /// Parse the Jumplist at `path`, handing back what was parsed with the error that
+    /// stopped the parse, see [`ParseOutcome`]. A file failing a [`ParseMode::Strict`] parse
+    /// is parsed again leniently to recover its entries.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{JumplistParser, ParseMode, ParseOptions};
+    ///
+    /// let options = ParseOptions {
+    ///     mode: ParseMode::Strict,
+    ///     ..Default::default()
+    /// };
+    /// let outcome = JumplistParser::try_from_path(
+    ///     "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    ///     &options,
+    /// );
+    /// assert!(!outcome.is_partial());
+    /// assert!(outcome.into_result().is_ok());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_from_path(path: &str, options: &ParseOptions) -> ParseOutcome {
+        ParseOutcome::parse_with(options, |options| {
+            Self::from_path_with_options(path, options)
+        })
+    }
+
+    /// Same as [`JumplistParser::from_path`] but with explicit [`ParseOptions`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path_with_options(
+        path: &str,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let file = File::open(path).map_err(|e| {
             JumplistParserError::JumplistParser(
                 format!("Can't open the file '{}', ERROR: {}", path, e),
                 line!(),
                 file!().to_string(),
             )
         })?;
+        Self::from_file(file, path, None, options)
+    }
+
+    /// Parse the Jumplist at `path` as `jumplist_type`, whatever its file name, e.g. a custom
+    /// Jumplist renamed with the automatic extension or carved data without a name. The
+    /// AppID is still taken from the file name.
+    ///
+    /// A file of the other type fails with the errors of a corrupt file of `jumplist_type`.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{JumplistParser, JumplistType};
+    ///
+    /// let path = "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    /// let parsed = JumplistParser::from_path_with_type(path, JumplistType::Custom).unwrap();
+    /// assert_eq!(parsed.app_id.as_deref(), Some("1ced32d74a95c7bc"));
+    /// assert!(JumplistParser::from_path_with_type(path, JumplistType::Automatic).is_err());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path_with_type(
+        path: &str,
+        jumplist_type: JumplistType,
+    ) -> Result<Self, JumplistParserError> {
+        Self::from_path_with_type_and_options(path, jumplist_type, &ParseOptions::default())
+    }
 
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer).map_err(|e| {
+    /// Same as [`JumplistParser::from_path_with_type`] but with explicit [`ParseOptions`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path_with_type_and_options(
+        path: &str,
+        jumplist_type: JumplistType,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let file = File::open(path).map_err(|e| {
             JumplistParserError::JumplistParser(
-                format!("Can't read the file '{}', ERROR: {}", path, e),
+                format!("Can't open the file '{}', ERROR: {}", path, e),
                 line!(),
                 file!().to_string(),
             )
         })?;
-        let mut cursor = Cursor::new(buffer);
+        Self::from_file(file, path, Some(jumplist_type), options)
+    }
 
-        let mut app_id = String::new();
-        let mut app_name = String::new();
+    /// Parse the opened Jumplist file at `path`, the file name gives the AppID and, unless
+    /// `jumplist_type` is given, the type.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn from_file(
+        file: File,
+        path: &str,
+        jumplist_type: Option<JumplistType>,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        Self::from_reader_at_path(
+            &mut BufReader::new(&file),
+            path,
+            jumplist_type,
+            options,
+            || anomaly::file_mtime(&file),
+        )
+    }
 
-        let file_name = std::path::PathBuf::from(path)
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        let jumplist_type = match file_name.ends_with(".automaticDestinations-ms") {
-            true => JumplistType::Automatic,
-            false => match file_name.ends_with(".customDestinations-ms") {
-                true => JumplistType::Custom,
-                false => {
-                    return Err(JumplistParserError::FileType(
-                        file_name,
-                        line!(),
-                        file!().to_string(),
-                    ))
-                }
-            },
-        };
+    /// Same as [`JumplistParser::from_path_with_options`] for the content of the file at
+    /// `path` already read into `data`, e.g. to hash the exact bytes that are parsed. The
+    /// type is given by `jumplist_type`, or else by the file name or the content.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{JumplistParser, ParseOptions};
+    ///
+    /// let path = "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    /// let data = std::fs::read(path).unwrap();
+    /// let parsed =
+    ///     JumplistParser::from_file_bytes(&data, path, None, &ParseOptions::default()).unwrap();
+    /// assert_eq!(parsed, JumplistParser::from_path(path).unwrap());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file_bytes(
+        data: &[u8],
+        path: &str,
+        jumplist_type: Option<JumplistType>,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        Self::from_reader_at_path(&mut Cursor::new(data), path, jumplist_type, options, || {
+            File::open(path)
+                .ok()
+                .and_then(|file| anomaly::file_mtime(&file))
+        })
+    }
 
-        if let Some(stem) = file_name.split('.').next() {
-            app_id = stem.to_string();
-            if let Some(name) = APPID_TO_NAME.get(stem) {
-                app_name = name.to_string();
+    /// Parse the content of the file at `path` read by `reader`, `file_mtime` gives the
+    /// modification time of the file for [`ParseOptions::detect_anomalies`].
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from_reader_at_path<R: Read + Seek>(
+        reader: &mut R,
+        path: &str,
+        jumplist_type: Option<JumplistType>,
+        options: &ParseOptions,
+        file_mtime: impl FnOnce() -> Option<timestamp::Timestamp>,
+    ) -> Result<Self, JumplistParserError> {
+        let file_name = Self::file_name(path);
+        let jumplist_type = match jumplist_type
+            .or_else(|| Self::type_from_name(&file_name, &options.file_name_suffixes))
+        {
+            Some(jumplist_type) => jumplist_type,
+            None => {
+                let size = utils::remaining_len(reader)?;
+                if size < JumplistType::Custom.min_file_size() {
+                    return Err(JumplistParserError::EmptyOrTruncatedFile {
+                        path: path.to_string(),
+                        size,
+                    });
+                }
+                Self::type_from_reader(reader).ok_or_else(|| Self::file_type_error(&file_name))?
             }
-        }
+        };
 
-        let parsed = Self::from_reader(&mut cursor, jumplist_type);
+        let parsed = Self::from_reader_with_options(reader, jumplist_type, options)
+            .map_err(|e| e.with_path(path));
         match parsed {
             Ok(mut parsed) => {
-                parsed.app_id = Some(app_id);
-                parsed.app_name = Some(app_name);
+                // The name of a LNK file is the one of its target, not an AppID
+                if parsed.r#type != JumplistType::RecentLnk {
+                    parsed.set_app_id_from_file_name(&file_name);
+                }
                 parsed.source_path = Some(path.to_string());
+                parsed.user = user::user_from_path(path);
+                if options.detect_anomalies {
+                    let file_mtime = file_mtime();
+                    parsed.anomalies = parsed.detect_anomalies(file_mtime.as_ref());
+                }
                 Ok(parsed)
             }
             Err(e) => Err(e),
         }
     }
-}
 
-/// Trait to normalize parsed structures into a consistent `key` and `value` format.
-pub trait Flaten {
-    /// Converts the structure into a list of `key` and `value` maps.
-    fn flaten(&self) -> Vec<HashMap<String, String>>;
-}
+    /// Parse a Jumplist held in memory, without filesystem access.
+    ///
+    /// `name_hint` is the original file name. It gives the Jumplist type, the AppID and the
+    /// application name as in [`JumplistParser::from_path`]. Without it, the type is detected
+    /// from the content: automatic Jumplists are compound files, anything else is parsed as
+    /// a custom Jumplist.
+    ///
+    /// A `name_hint` that doesn't give the type falls back to the content, see
+    /// [`JumplistParser::type_from_content`].
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{JumplistParser, JumplistType};
+    ///
+    /// let data = std::fs::read(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+    /// assert_eq!(parsed.r#type, JumplistType::Custom);
+    /// assert_eq!(parsed.app_id, None);
+    /// ```
+    pub fn from_bytes(data: &[u8], name_hint: Option<&str>) -> Result<Self, JumplistParserError> {
+        Self::from_bytes_with_options(data, name_hint, &ParseOptions::default())
+    }
 
-impl Flaten for JumplistParser {
-    /// Normalize parsed Jumplist entries to flat `key` and `value` maps.
+    /// Same as [`JumplistParser::from_bytes`] but with explicit [`ParseOptions`].
+    pub fn from_bytes_with_options(
+        data: &[u8],
+        name_hint: Option<&str>,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let name_type =
+            name_hint.and_then(|name| Self::type_from_name(name, &options.file_name_suffixes));
+        if name_type.is_none() && (data.len() as u64) < JumplistType::Custom.min_file_size() {
+            return Err(JumplistParserError::EmptyOrTruncatedFile {
+                path: name_hint.unwrap_or_default().to_string(),
+                size: data.len() as u64,
+            });
+        }
+        let jumplist_type = match name_hint {
+            Some(name) => name_type
+                .or_else(|| Self::type_from_content(data, data))
+                .ok_or_else(|| Self::file_type_error(name))?,
+            None => match data.starts_with(&CFB_SIGNATURE) {
+                true => JumplistType::Automatic,
+                false => JumplistType::Custom,
+            },
+        };
+
+        let mut parsed =
+            Self::from_reader_with_options(&mut Cursor::new(data), jumplist_type, options)
+                .map_err(|e| e.with_path(name_hint.unwrap_or_default()))?;
+        if let Some(name) = name_hint {
+            parsed.set_app_id_from_file_name(name);
+        }
+        Ok(parsed)
+    }
+
+    /// The file name of `path`, empty when it has none.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn file_name(path: &str) -> String {
+        Path::new(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// The Jumplist type given by a file name: its extension, whatever the case, followed by
+    /// nothing or by one of `suffixes`.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{FileNameSuffixes, JumplistParser, JumplistType};
+    ///
+    /// let suffixes = FileNameSuffixes::default();
+    /// let type_from_name = |name| JumplistParser::type_from_name(name, &suffixes);
+    /// assert_eq!(
+    ///     type_from_name("5F7B5F1E01B83767.AUTOMATICDESTINATIONS-MS"),
+    ///     Some(JumplistType::Automatic)
+    /// );
+    /// assert_eq!(
+    ///     type_from_name("1ced32d74a95c7bc.customDestinations-ms.bak"),
+    ///     Some(JumplistType::Custom)
+    /// );
+    /// assert_eq!(type_from_name("1ced32d74a95c7bc.customDestinations-ms.txt"), None);
+    /// ```
+    pub fn type_from_name(file_name: &str, suffixes: &FileNameSuffixes) -> Option<JumplistType> {
+        let (_, jumplist_type, suffix) = Self::split_extension(file_name)?;
+        suffixes.allows(suffix).then_some(jumplist_type)
+    }
+
+    /// Split a file name around its last Jumplist extension, found whatever the case, into
+    /// the stem, the type and what follows the extension.
+    pub(crate) fn split_extension(file_name: &str) -> Option<(&str, JumplistType, &str)> {
+        // ASCII lowercasing keeps the byte offsets
+        let lowercase = file_name.to_ascii_lowercase();
+        [
+            (".automaticdestinations-ms", JumplistType::Automatic),
+            (".customdestinations-ms", JumplistType::Custom),
+        ]
+        .iter()
+        .find_map(|(extension, jumplist_type)| {
+            let start = lowercase.rfind(extension)?;
+            Some((
+                &file_name[..start],
+                jumplist_type.clone(),
+                &file_name[start + extension.len()..],
+            ))
+        })
+    }
+
+    /// The Jumplist type given by the content, `head` and `tail` being its first and last
+    /// bytes: automatic Jumplists are compound files, custom ones start with a
+    /// [plausible](CustomDestinationsHeader::is_plausible_version) format version, and that
+    /// version is 2 or they end with the category footer.
+    pub fn type_from_content(head: &[u8], tail: &[u8]) -> Option<JumplistType> {
+        let version = head
+            .get(..4)
+            .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]));
+        if head.starts_with(&CFB_SIGNATURE) {
+            Some(JumplistType::Automatic)
+        } else if !version.is_some_and(CustomDestinationsHeader::is_plausible_version) {
+            None
+        } else if version == Some(custom_destinations::KNOWN_VERSION)
+            || tail.ends_with(&custom_destinations::CATEGORY_FOOTER.to_le_bytes())
+        {
+            Some(JumplistType::Custom)
+        } else {
+            None
+        }
+    }
+
+    /// [`JumplistParser::type_from_content`] of the file read by `reader`, which is left at
+    /// its start. Only the first 8 and the last 4 bytes are read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn type_from_reader<R: Read + Seek>(reader: &mut R) -> Option<JumplistType> {
+        let mut head = [0; 8];
+        let mut tail = [0; 4];
+        let read = reader
+            .read_exact(&mut head)
+            .and_then(|_| reader.seek(SeekFrom::End(-4)))
+            .and_then(|_| reader.read_exact(&mut tail));
+        reader.seek(SeekFrom::Start(0)).ok()?;
+        read.ok()?;
+        Self::type_from_content(&head, &tail)
+    }
+
+    fn file_type_error(file_name: &str) -> JumplistParserError {
+        JumplistParserError::FileType(file_name.to_string(), line!(), file!().to_string())
+    }
+
+    /// Set `app_id`, `app_id_valid` and `app_name` from the name of the Jumplist file, as
+    /// the parse functions taking a path or a name do, and compute the fingerprints again.
     ///
-    /// Adds a `jumplist_file_path` key for traceability.
-    fn flaten(&self) -> Vec<HashMap<String, String>> {
-        let mut results: Vec<HashMap<String, String>> = Vec::new();
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let data = std::fs::read(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let mut parsed = JumplistParser::from_bytes(&data, None).unwrap();
+    /// parsed.set_app_id_from_file_name("PC01_1CED32D74A95C7BC (2).customDestinations-ms");
+    /// assert_eq!(parsed.app_id.as_deref(), Some("1ced32d74a95c7bc"));
+    /// assert!(parsed.app_id_valid);
+    /// ```
+    pub fn set_app_id_from_file_name(&mut self, file_name: &str) {
+        let (app_id, valid) = appids::app_id_from_file_name(file_name);
+        self.app_name = Some(appids::app_name(&app_id).unwrap_or_default().to_string());
+        self.app_id = Some(app_id);
+        self.app_id_valid = valid;
+        self.update_fingerprints();
+    }
 
+    /// The `DestList` of an automatic Jumplist, see [`JumplistData::as_destlist`].
+    pub fn destlist(&self) -> Option<&DestList> {
+        self.data.as_destlist()
+    }
+
+    /// The CustomDestinations of a custom Jumplist, see [`JumplistData::as_custom_destinations`].
+    pub fn custom_destinations(&self) -> Option<&CustomDestinations> {
+        self.data.as_custom_destinations()
+    }
+
+    /// Compute the [`fingerprint`] of every entry again, after a change of
+    /// [`JumplistParser::app_id`]. The parse functions set them.
+    pub fn update_fingerprints(&mut self) {
+        let app_id = self.app_id.as_deref();
+        match &mut self.data {
+            JumplistData::DestList(destlist) => destlist.update_fingerprints(app_id),
+            JumplistData::CustomDestinations(custom) => custom.update_fingerprints(app_id),
+            JumplistData::RecentLnk(recent) => recent.update_fingerprints(app_id),
+        }
+    }
+
+    /// Whether this is an automatic Jumplist (`.automaticDestinations-ms`).
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let automatic = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert!(automatic.is_automatic() && !automatic.is_custom());
+    ///
+    /// let custom = JumplistParser::from_path(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert!(custom.is_custom() && !custom.is_automatic());
+    /// ```
+    pub fn is_automatic(&self) -> bool {
+        matches!(self.r#type, JumplistType::Automatic)
+    }
+
+    /// Whether this is a custom Jumplist (`.customDestinations-ms`).
+    pub fn is_custom(&self) -> bool {
+        matches!(self.r#type, JumplistType::Custom)
+    }
+
+    /// Whether this is a standalone LNK file, see [`parse_lnk_file`].
+    pub fn is_recent_lnk(&self) -> bool {
+        matches!(self.r#type, JumplistType::RecentLnk)
+    }
+
+    /// Format version of the Jumplist: the `DestList` version of an automatic Jumplist, or
+    /// the header version of a custom one. `None` for an empty `DestList` and standalone LNK
+    /// files.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(parsed.format_version(), Some(2));
+    /// ```
+    pub fn format_version(&self) -> Option<u32> {
         match &self.data {
             JumplistData::DestList(data) => {
-                for entry in &data.entries {
-                    let mut e = entry.normalize();
-                    let path = match &self.source_path {
-                        Some(p) => p.to_owned(),
-                        None => String::new(),
-                    };
-                    e.insert("jumplist_file_path".to_string(), path);
-                    results.push(e);
+                Some(data.header.version.as_u32()).filter(|version| *version != 0)
+            }
+            JumplistData::CustomDestinations(data) => Some(data.header.version),
+            JumplistData::RecentLnk(_) => None,
+        }
+    }
+
+    /// Windows generation that last wrote the Jumplist, inferred from the `DestList`
+    /// version. Custom Jumplists don't give a hint.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{os_hint::OsHint, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(parsed.os_hint(), Some(OsHint::Win10Creators));
+    /// ```
+    pub fn os_hint(&self) -> Option<OsHint> {
+        self.os_hint
+    }
+
+    /// Whether every entry declared in the file headers was parsed.
+    pub fn is_complete(&self) -> bool {
+        match &self.data {
+            JumplistData::DestList(data) => data.is_complete(),
+            JumplistData::CustomDestinations(data) => data.is_complete(),
+            JumplistData::RecentLnk(_) => true,
+        }
+    }
+
+    /// Inconsistencies found while parsing the file.
+    pub fn warnings(&self) -> &[String] {
+        match &self.data {
+            JumplistData::DestList(data) => &data.warnings,
+            JumplistData::CustomDestinations(data) => &data.warnings,
+            JumplistData::RecentLnk(data) => &data.warnings,
+        }
+    }
+
+    /// Iterate over the entries of the Jumplist, whatever its type.
+    ///
+    /// DestList entries are returned in the order of [`DestList::entries`], custom entries in
+    /// file order. The order is the same every time a file is parsed. A standalone LNK file
+    /// has one entry.
+    pub fn entries(&self) -> impl Iterator<Item = JumplistEntryRef<'_>> {
+        let (destlist, custom, recent) = match &self.data {
+            JumplistData::DestList(data) => (Some(&data.entries), None, None),
+            JumplistData::CustomDestinations(data) => (None, Some(data), None),
+            JumplistData::RecentLnk(data) => (None, None, data.lnk_file.as_ref()),
+        };
+        let destlist = destlist
+            .into_iter()
+            .flatten()
+            .map(JumplistEntryRef::DestList);
+        let custom = custom
+            .into_iter()
+            .flat_map(CustomDestinations::iter_entries)
+            .map(|(category, entry)| JumplistEntryRef::Custom { category, entry });
+        let recent = recent.into_iter().map(JumplistEntryRef::RecentLnk);
+        destlist.chain(custom).chain(recent)
+    }
+
+    /// The pinned entries: the pinned `DestList` entries, or the entries of the tasks and of
+    /// the pinned items block of a custom Jumplist (see [`JumplistEntryRef::pinned`]).
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(parsed.pinned_entries().count(), 4);
+    ///
+    /// let custom = JumplistParser::from_path(
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(custom.pinned_entries().count(), 1);
+    /// ```
+    pub fn pinned_entries(&self) -> impl Iterator<Item = JumplistEntryRef<'_>> {
+        self.entries().filter(|e| e.pinned() == Some(true))
+    }
+
+    /// Keep only the entries for which `keep` returns true. The headers and the counts read
+    /// from the file are left as is.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let mut parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// parsed.retain_entries(|e| e.pinned() == Some(true));
+    /// assert_eq!(parsed.entry_count(), 4);
+    /// ```
+    pub fn retain_entries(&mut self, mut keep: impl FnMut(&JumplistEntryRef) -> bool) {
+        let kept: Vec<bool> = self.entries().map(|e| keep(&e)).collect();
+        let mut kept = kept.into_iter();
+        match &mut self.data {
+            JumplistData::DestList(destlist) => {
+                destlist.entries.retain(|_| kept.next().unwrap_or(true))
+            }
+            JumplistData::CustomDestinations(custom) => {
+                for entries in custom.entries.iter_mut().filter_map(|c| c.entries.as_mut()) {
+                    entries.retain(|_| kept.next().unwrap_or(true));
                 }
-                results
             }
-            JumplistData::CustomDestinations(data) => {
-                let data = data.flaten();
-                for mut entry in data {
-                    let path = match &self.source_path {
-                        Some(p) => p.to_owned(),
-                        None => String::new(),
-                    };
-                    entry.insert("jumplist_file_path".to_string(), path);
-                    results.push(entry);
+            JumplistData::RecentLnk(recent) => {
+                if !kept.next().unwrap_or(true) {
+                    recent.lnk_file = None;
                 }
-                results
             }
         }
     }
+
+    /// The `target_category` of every entry, in the order of [`JumplistParser::entries`], from
+    /// [`JumplistParser::filetype_map`] or the internal table of the [`filetype`] module.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{filetype::FileCategory, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let categories = parsed.target_categories();
+    /// assert_eq!(categories.len(), parsed.entry_count());
+    /// assert!(categories.contains(&FileCategory::Other));
+    /// ```
+    pub fn target_categories(&self) -> Vec<FileCategory> {
+        let default_filetype_map = FiletypeMap::default();
+        let filetype_map = self
+            .filetype_map
+            .as_deref()
+            .unwrap_or(&default_filetype_map);
+        self.entries()
+            .map(|entry| entry.target_category(filetype_map))
+            .collect()
+    }
+
+    /// The `n` most recent entries, most recent first.
+    ///
+    /// `DestList` entries are ordered by their interaction time. Custom entries don't have
+    /// one, so the last modification time of their LNK target is used instead. Entries
+    /// without either come last, and entries with the same time keep their order.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let recent = parsed.most_recent(2);
+    /// assert_eq!(recent[0].entry_number(), Some(1));
+    /// assert_eq!(recent[1].target_path().unwrap(), r"C:\Users\u0041\Desktop\Jumplists");
+    ///
+    /// let custom = JumplistParser::from_path(
+    ///     "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let recent = custom.most_recent(10);
+    /// assert_eq!(recent.len(), custom.entry_count());
+    /// let write_times: Vec<u64> = recent
+    ///     .iter()
+    ///     .map(|e| e.lnk_header().unwrap().write_time.filetime)
+    ///     .collect();
+    /// assert!(write_times.windows(2).all(|w| w[0] >= w[1]));
+    /// ```
+    pub fn most_recent(&self, n: usize) -> Vec<JumplistEntryRef<'_>> {
+        let mut entries: Vec<JumplistEntryRef<'_>> = self.entries().collect();
+        entries.sort_by_key(|e| {
+            let time = e
+                .interaction_time()
+                .or_else(|| e.lnk_header().map(|h| &h.write_time));
+            std::cmp::Reverse(time.map(|t| t.filetime).unwrap_or_default())
+        });
+        entries.truncate(n);
+        entries
+    }
+
+    /// Number of entries in the Jumplist.
+    pub fn entry_count(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Whether the Jumplist has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    /// Write the raw bytes of every embedded LNK to `dir` as `<appid>_<entry_number>.lnk`.
+    ///
+    /// Custom Jumplist entries have no entry number, so their position in the file is used
+    /// instead. File names are sanitized and an existing file is never overwritten: a
+    /// numeric suffix (`_1`, `_2`, ...) is appended instead.
+    ///
+    /// The Jumplist must be parsed with [`ParseOptions::keep_raw_lnk`] set.
+    ///
+    /// # Returns
+    /// The paths of the written files, in entry order.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_lnks(&self, dir: &Path) -> Result<Vec<PathBuf>, JumplistParserError> {
+        let mut lnks: Vec<(String, Option<&[u8]>)> = vec![];
+        for entry in self.entries().filter(|e| e.lnk().is_some()) {
+            let number = match entry.entry_number() {
+                Some(number) => number.to_string(),
+                None => lnks.len().to_string(),
+            };
+            lnks.push((number, entry.raw_lnk()));
+        }
+
+        if lnks.iter().any(|(_, raw)| raw.is_none()) {
+            return Err(JumplistParserError::General(
+                "LNK raw bytes were not kept, parse the file with 'ParseOptions::keep_raw_lnk'"
+                    .to_string(),
+                line!(),
+                file!().to_string(),
+            ));
+        }
+
+        std::fs::create_dir_all(dir).map_err(|e| {
+            JumplistParserError::General(
+                format!(
+                    "Can't create the directory '{}', ERROR: {}",
+                    dir.display(),
+                    e
+                ),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+
+        let app_id = sanitize_file_name(self.app_id.as_deref().unwrap_or_default());
+        let mut exported = vec![];
+        for (number, raw) in lnks {
+            let stem = format!("{}_{}", app_id, sanitize_file_name(&number));
+            let mut path = dir.join(format!("{}.lnk", stem));
+            let mut suffix = 1;
+            while path.exists() {
+                path = dir.join(format!("{}_{}.lnk", stem, suffix));
+                suffix += 1;
+            }
+            std::fs::write(&path, raw.unwrap_or_default()).map_err(|e| {
+                JumplistParserError::General(
+                    format!("Can't write the file '{}', ERROR: {}", path.display(), e),
+                    line!(),
+                    file!().to_string(),
+                )
+            })?;
+            exported.push(path);
+        }
+        Ok(exported)
+    }
+}
+
+/// Replace every character that is not safe in a file name with `_`, leading and trailing
+/// dots are removed and an empty name becomes `unknown`. Used for the files written by
+/// [`JumplistParser::export_lnks`] and the `--output-dir` of the CLI.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::sanitize_file_name;
+///
+/// assert_eq!(sanitize_file_name("5f7b5f1e01b83767"), "5f7b5f1e01b83767");
+/// assert_eq!(sanitize_file_name(r"..\a b.c"), "_a_b.c");
+/// assert_eq!(sanitize_file_name(""), "unknown");
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sanitize_file_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect::<String>();
+    match name.trim_matches('.') {
+        "" => "unknown".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Trait to normalize parsed structures into a consistent `key` and `value` format, one map
+/// per entry. Implemented by [`JumplistParser`], [`DestList`] and
+/// [`CustomDestinations`](custom_destinations::CustomDestinations).
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{Flatten, JumplistParser};
+///
+/// let rows = JumplistParser::from_path(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+/// )
+/// .unwrap()
+/// .flatten();
+/// assert_eq!(rows.len(), 24);
+/// println!("{}", rows[0]["target_full_path"]);
+/// ```
+pub trait Flatten {
+    /// Converts the structure into a list of `key` and `value` maps, their keys in the
+    /// canonical order of [`output::sort_keys`].
+    fn flatten(&self) -> Vec<NormalizedEntry>;
+}
+
+/// The former, misspelled name of [`Flatten`].
+#[deprecated(note = "renamed to `Flatten`")]
+pub trait Flaten {
+    /// Same as [`Flatten::flatten`].
+    #[deprecated(note = "renamed to `Flatten::flatten`")]
+    fn flaten(&self) -> Vec<NormalizedEntry>;
+}
+
+#[allow(deprecated)]
+impl<T: Flatten + ?Sized> Flaten for T {
+    fn flaten(&self) -> Vec<NormalizedEntry> {
+        self.flatten()
+    }
+}
+
+impl Flatten for JumplistParser {
+    /// Normalize parsed Jumplist entries to flat `key` and `value` maps.
+    ///
+    /// Adds a `jumplist_file_path` key for traceability, the `jumplist_user`,
+    /// `jumplist_os_hint`, `jumplist_format_version`, `entry_pinned`, `entry_fingerprint` and
+    /// `lnk_status` keys, the `target_extension` and `target_category` keys (see
+    /// [`filetype`]), and `target_full_path_canonical` with
+    /// [`ParseOptions::canonicalize_paths`]. The keys are renamed to their legacy names with
+    /// [`ParseOptions::legacy_keys`].
+    fn flatten(&self) -> Vec<NormalizedEntry> {
+        let path = match &self.source_path {
+            Some(p) => p.to_owned(),
+            None => String::new(),
+        };
+        let user = self.user.clone().unwrap_or_default();
+        let os_hint = self.os_hint.map(|h| h.to_string()).unwrap_or_default();
+        let format_version = self
+            .format_version()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        let default_filetype_map = FiletypeMap::default();
+        let filetype_map = self
+            .filetype_map
+            .as_deref()
+            .unwrap_or(&default_filetype_map);
+        self.entries()
+            .map(|entry| {
+                let mut e: NormalizedEntry = entry.normalize().into_iter().collect();
+                e.insert("jumplist_file_path".to_string(), path.clone());
+                e.insert("jumplist_user".to_string(), user.clone());
+                e.insert("jumplist_os_hint".to_string(), os_hint.clone());
+                e.insert(
+                    "jumplist_format_version".to_string(),
+                    format_version.clone(),
+                );
+                e.insert(
+                    "entry_pinned".to_string(),
+                    entry.pinned().map(|p| p.to_string()).unwrap_or_default(),
+                );
+                e.insert(
+                    "entry_fingerprint".to_string(),
+                    entry.fingerprint().to_string(),
+                );
+                e.insert("lnk_status".to_string(), entry.lnk_status().to_string());
+                filetype_map.normalize_into(&mut e);
+                if self.canonicalize_paths {
+                    let canonical = canonicalize_target_path(
+                        e.get("target_full_path")
+                            .map(|p| p.as_str())
+                            .unwrap_or_default(),
+                        self.user.as_deref(),
+                    );
+                    e.insert("target_full_path_canonical".to_string(), canonical);
+                }
+                output::sort_keys(&mut e);
+                match self.legacy_keys {
+                    true => output::with_legacy_keys(e),
+                    false => e,
+                }
+            })
+            .collect()
+    }
+}
+
+impl JumplistParser {
+    /// The normalized entries of [`Flatten::flatten`] with the fields of the Jumplist added
+    /// to each: `jumplist_app_id`, `jumplist_app_name` and `jumplist_type`. These are the
+    /// records written by the CLI.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let records = parsed.records();
+    /// assert_eq!(records[0]["jumplist_app_id"], "5d696d521de238c3");
+    /// assert_eq!(records[0]["jumplist_type"], "custom");
+    /// ```
+    pub fn records(&self) -> Vec<NormalizedEntry> {
+        let mut records = self.flatten();
+        let fields = ["jumplist_app_id", "jumplist_app_name", "jumplist_type"]
+            .iter()
+            .filter_map(|name| output::find_field(name).ok());
+        for field in fields {
+            for record in records.iter_mut() {
+                let value = field.value(self, record);
+                record.insert(field.key(self.legacy_keys).to_string(), value);
+            }
+        }
+        records.iter_mut().for_each(output::sort_keys);
+        records
+    }
+
+    /// [`JumplistParser::records`] with the size and the modification time of the Jumplist
+    /// file added to each (`jumplist_file_size` and `jumplist_file_modified`), from
+    /// `metadata`. The modification time is formatted with `format`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn records_with_metadata(
+        &self,
+        metadata: &std::fs::Metadata,
+        format: timestamp::TimestampFormat,
+    ) -> Vec<NormalizedEntry> {
+        let size = metadata.len().to_string();
+        let modified = metadata
+            .modified()
+            .map(|time| timestamp::Timestamp::from_system_time(time, format).to_string())
+            .unwrap_or_default();
+        let mut records = self.records();
+        for record in records.iter_mut() {
+            record.insert("jumplist_file_size".to_string(), size.clone());
+            record.insert("jumplist_file_modified".to_string(), modified.clone());
+            output::sort_keys(record);
+        }
+        records
+    }
+}
+
+/// Parse the Jumplist at `path` and return its normalized records, as written by the CLI:
+/// the entries of [`Flatten::flatten`] with the AppID, application name and type of the
+/// Jumplist, its path, the user and the size and modification time of the file added to
+/// each. See [`JumplistParser::records_with_metadata`].
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{parse_to_records, ParseOptions};
+///
+/// let records = parse_to_records(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+///     &ParseOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(records.len(), 5);
+/// for record in &records {
+///     assert_eq!(record["jumplist_app_id"], "4cb9c5750d51c07f");
+///     assert_eq!(record["jumplist_type"], "automatic");
+///     assert!(!record["jumplist_file_size"].is_empty());
+/// }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_to_records<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<Vec<NormalizedEntry>, JumplistParserError> {
+    let path = path.as_ref();
+    let parsed = JumplistParser::from_path_with_options(&path.to_string_lossy(), options)?;
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        JumplistParserError::JumplistParser(
+            format!(
+                "Can't read the metadata of '{}', ERROR: {}",
+                path.display(),
+                e
+            ),
+            line!(),
+            file!().to_string(),
+        )
+    })?;
+    Ok(parsed.records_with_metadata(&metadata, options.ts_format))
+}
+
+/// Parse the standalone LNK file at `path`, e.g. a shortcut of the `Recent` folder, as a
+/// [`JumplistParser`] of type [`JumplistType::RecentLnk`] with one entry. Its records have
+/// the keys of the Jumplist entries, with empty `jumplist_app_id` and `jumplist_app_name`
+/// and `jumplist_type` set to `recent_lnk`.
+///
+/// Files that don't start with a `ShellLinkHeader` (header size and LNK CLSID) are
+/// rejected with [`JumplistParserError::LnkEntry`] rather than parsed as an empty entry.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{builder::lnk_bytes, parse_lnk_file, ParseOptions};
+///
+/// let path = std::env::temp_dir().join("jumplist_parser_doc_notes.txt.lnk");
+/// std::fs::write(&path, lnk_bytes(r"C:\Users\user\Desktop\notes.txt")).unwrap();
+/// let parsed = parse_lnk_file(path.to_str().unwrap(), &ParseOptions::default()).unwrap();
+/// let records = parsed.records();
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0]["jumplist_type"], "recent_lnk");
+/// assert_eq!(records[0]["jumplist_app_id"], "");
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_lnk_file(
+    path: &str,
+    options: &ParseOptions,
+) -> Result<JumplistParser, JumplistParserError> {
+    JumplistParser::from_path_with_type_and_options(path, JumplistType::RecentLnk, options)
+}
+
+/// Number of leading bytes of a file read by [`sniff_jumplist`].
+pub const SNIFF_SIZE: usize = 512;
+
+/// Whether the name of `path` ends with a Jumplist extension, whatever its case, followed
+/// by nothing or by one of the default [`FileNameSuffixes`]. Used to skip the stray files
+/// matched by wide patterns such as `*ms`, e.g. `desktop.ini.customDestinations-ms.lnk`.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::is_probably_jumplist;
+/// use std::path::Path;
+///
+/// assert!(is_probably_jumplist(Path::new(r"C:\Recent\5f7b5f1e01b83767.automaticDestinations-ms")));
+/// assert!(is_probably_jumplist(Path::new("1ced32d74a95c7bc.customDestinations-ms.bak")));
+/// assert!(!is_probably_jumplist(Path::new("desktop.ini.customDestinations-ms.lnk")));
+/// assert!(!is_probably_jumplist(Path::new("~tmp1234.tms")));
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn is_probably_jumplist(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| {
+        JumplistParser::type_from_name(&name.to_string_lossy(), &FileNameSuffixes::default())
+            .is_some()
+    })
+}
+
+/// Whether the first [`SNIFF_SIZE`] bytes of the file at `path` can start a Jumplist: the
+/// signature of a compound file, or a custom Jumplist header with a
+/// [plausible](CustomDestinationsHeader::is_plausible_version) format version whose first
+/// category, if any, has a known type.
+///
+/// The files that can't be read, and those too short to tell (e.g. the empty custom
+/// Jumplists Windows leaves behind), are left to the parser which reports why they can't
+/// be parsed.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::sniff_jumplist;
+/// use std::path::Path;
+///
+/// assert!(sniff_jumplist(Path::new(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms"
+/// )));
+/// assert!(sniff_jumplist(Path::new(
+///     "samples/win10/CustomDestinations/7e4dca80246863e3.customDestinations-ms"
+/// )));
+/// assert!(!sniff_jumplist(Path::new("README.md")));
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sniff_jumplist(path: &Path) -> bool {
+    let mut head = Vec::with_capacity(SNIFF_SIZE);
+    let read =
+        File::open(path).and_then(|file| file.take(SNIFF_SIZE as u64).read_to_end(&mut head));
+    if read.is_err() || head.len() < JumplistType::Custom.min_file_size() as usize {
+        return true;
+    }
+    if head.starts_with(&CFB_SIGNATURE) {
+        return true;
+    }
+    let u32_at = |offset: usize| {
+        head.get(offset..offset + 4)
+            .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+    };
+    let version = u32_at(0).unwrap_or_default();
+    let categories = u32_at(4).unwrap_or_default();
+    CustomDestinationsHeader::is_plausible_version(version)
+        && (categories == 0
+            || u32_at(12)
+                .is_none_or(|r#type| r#type <= custom_destinations::CategoryType::Pinned as u32))
 }
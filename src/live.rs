@@ -0,0 +1,133 @@
+//! Live acquisition on Windows.
+//!
+//! On a running system Explorer keeps the Jumplists of the logged on user open, and profiles
+//! can be stored outside `C:\Users`. Files are opened with every sharing mode and retried
+//! while another process holds them with an incompatible one, and the profile directories
+//! are read from the registry.
+
+use crate::{errors::JumplistParserError, JumplistParser, ParseOptions};
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    os::windows::fs::OpenOptionsExt,
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+const FILE_SHARE_READ: u32 = 0x1;
+const FILE_SHARE_WRITE: u32 = 0x2;
+const FILE_SHARE_DELETE: u32 = 0x4;
+const ERROR_SHARING_VIOLATION: i32 = 32;
+const ERROR_LOCK_VIOLATION: i32 = 33;
+/// Number of times a file held by another process is opened before giving up.
+const OPEN_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+/// Registry key with one subkey per profile, named after the SID of its user.
+const PROFILE_LIST: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList";
+
+/// Directories of the Jumplists, relative to a profile directory.
+pub const JUMPLIST_DIRECTORIES: &[&str] = &[
+    r"AppData\Roaming\Microsoft\Windows\Recent\AutomaticDestinations",
+    r"AppData\Roaming\Microsoft\Windows\Recent\CustomDestinations",
+];
+
+/// Directory of the standalone LNK files of the `Recent` folder, relative to a profile
+/// directory.
+pub const RECENT_DIRECTORY: &str = r"AppData\Roaming\Microsoft\Windows\Recent";
+
+/// Whether `e` is caused by another process holding the file open.
+pub fn is_sharing_violation(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(ERROR_SHARING_VIOLATION) | Some(ERROR_LOCK_VIOLATION)
+    )
+}
+
+/// Open `path` for reading with `FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE`,
+/// retrying for about a second while the file is held by another process.
+pub fn open_shared(path: &str) -> io::Result<File> {
+    let mut attempt = 1;
+    loop {
+        let opened = OpenOptions::new()
+            .read(true)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)
+            .open(path);
+        match opened {
+            Err(e) if is_sharing_violation(&e) && attempt < OPEN_ATTEMPTS => {
+                attempt += 1;
+                thread::sleep(RETRY_DELAY);
+            }
+            opened => return opened,
+        }
+    }
+}
+
+/// The existing profile directories listed in
+/// `HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList`, including the ones
+/// redirected outside `C:\Users`.
+pub fn profile_directories() -> io::Result<Vec<PathBuf>> {
+    let profiles = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(PROFILE_LIST)?;
+    let mut directories = vec![];
+    for sid in profiles.enum_keys() {
+        let path: String = match profiles
+            .open_subkey(sid?)
+            .and_then(|profile| profile.get_value("ProfileImagePath"))
+        {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        let path = PathBuf::from(expand_environment_strings(&path));
+        if path.is_dir() {
+            directories.push(path);
+        }
+    }
+    Ok(directories)
+}
+
+/// Replace the `%NAME%` environment variables of a `REG_EXPAND_SZ` value, unknown
+/// variables are kept as is.
+fn expand_environment_strings(value: &str) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let end = match after.find('%') {
+            Some(end) => end,
+            None => break,
+        };
+        let name = &after[..end];
+        expanded.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(variable) if !name.is_empty() => expanded.push_str(&variable),
+            _ => {
+                expanded.push('%');
+                expanded.push_str(name);
+                expanded.push('%');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+impl JumplistParser {
+    /// Same as [`JumplistParser::from_path_with_options`] for the Jumplists of a running
+    /// system: the file is opened with [`open_shared`], and a file that is still locked is
+    /// reported as a sharing violation.
+    pub fn from_path_live(path: &str, options: &ParseOptions) -> Result<Self, JumplistParserError> {
+        let file = open_shared(path).map_err(|e| {
+            let message = match is_sharing_violation(&e) {
+                true => format!(
+                    "The file '{}' is locked by another process (sharing violation), ERROR: {}",
+                    path, e
+                ),
+                false => format!("Can't open the file '{}', ERROR: {}", path, e),
+            };
+            JumplistParserError::JumplistParser(message, line!(), file!().to_string())
+        })?;
+        Self::from_file(file, path, None, options)
+    }
+}
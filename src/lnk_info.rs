@@ -0,0 +1,1070 @@
+//! Fields read directly from raw LNK bytes that are not exposed by `lnk_parser`.
+//!
+//! Reference: [MS-SHLLINK](https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-shllink/16cb4ca1-9339-4d0c-a68d-bf1d6cc0f943)
+
+use crate::{
+    errors::JumplistParserError,
+    guid::Guid,
+    target::{file_attribute_names, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN},
+    timestamp::{Timestamp, TimestampFormat},
+    utils::{clean_hostname, remaining_len},
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use lnk_parser::LNKParser;
+use serde::{Deserialize, Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{Read, Seek, SeekFrom},
+    ops::Deref,
+    sync::Arc,
+};
+use winparsingtools::traits::Normalize;
+
+/// Size of the `ShellLinkHeader` structure.
+pub const LNK_HEADER_SIZE: usize = 0x4C;
+/// CLSID of the LNKs (`00021401-0000-0000-C000-000000000046`) in its on-disk layout, in the
+/// `ShellLinkHeader` and before the entries of custom Jumplists.
+pub(crate) const LNK_CLSID: [u8; 16] = [
+    0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+/// `LinkFlags` bit: a `LinkTargetIDList` follows the header.
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+/// `LinkFlags` bit: a `LinkInfo` structure follows the ID list.
+pub(crate) const HAS_LINK_INFO: u32 = 0x02;
+/// `LinkFlags` bits of the optional `StringData` structures, in file order.
+const STRING_DATA_FLAGS: [u32; 5] = [
+    HAS_NAME,
+    HAS_RELATIVE_PATH,
+    HAS_WORKING_DIR,
+    HAS_ARGUMENTS,
+    HAS_ICON_LOCATION,
+];
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const HAS_ICON_LOCATION: u32 = 0x40;
+/// `LinkFlags` bit: `StringData` is UTF-16.
+pub(crate) const IS_UNICODE: u32 = 0x80;
+/// `LinkInfoFlags` bit: `VolumeID` and `LocalBasePath` are present.
+pub(crate) const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+/// `LinkInfoFlags` bit: `CommonNetworkRelativeLink` is present.
+const COMMON_NETWORK_RELATIVE_LINK: u32 = 0x02;
+/// Signature of the shell item extension block holding the file reference of file entries.
+const SHELL_ITEM_FILE_EXTENSION: u32 = 0xBEEF_0004;
+/// Signature of the control panel category shell items.
+const CONTROL_PANEL_CATEGORY: u32 = 0x39DE_2184;
+/// Signature of the `TrackerDataBlock` extra data block.
+const TRACKER_DATA_BLOCK: u32 = 0xA000_0003;
+/// Signature of the `IconEnvironmentDataBlock` extra data block.
+const ICON_ENVIRONMENT_DATA_BLOCK: u32 = 0xA000_0007;
+/// `HotKeyFlags` modifier bits, in the high byte of the hotkey.
+const HOTKEY_MODIFIERS: [(u8, &str); 3] = [(0x02, "Ctrl"), (0x04, "Alt"), (0x01, "Shift")];
+/// Number of 100-nanosecond intervals between `1582-10-15` (UUID epoch) and `1601-01-01`.
+const UUID_FILETIME_OFFSET: u64 = 5_748_192_000_000_000;
+
+/// Read a little-endian `u32` at `offset` of `buf`.
+fn u32_at(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Read a little-endian `u16` at `offset` of `buf`.
+fn u16_at(buf: &[u8], offset: usize) -> Option<u16> {
+    buf.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+/// Read a NUL-terminated string of single byte characters at `offset` of `buf`.
+fn ansi_string_at(buf: &[u8], offset: usize) -> Option<String> {
+    let bytes = buf.get(offset..)?;
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Read a NUL-terminated UTF-16LE string at `offset` of `buf`.
+fn utf16_string_at(buf: &[u8], offset: usize) -> Option<String> {
+    let units: Vec<u16> = buf
+        .get(offset..)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|u| *u != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+/// Read a little-endian `u64` at `offset` of `buf`.
+fn u64_at(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u32_at(buf, offset)? as u64 | (u32_at(buf, offset + 4)? as u64) << 32)
+}
+
+/// A parsed LNK, shared between clones.
+///
+/// `LNKParser` can't be cloned or compared, so it is kept behind an [`Arc`] and two `Lnk`
+/// are equal when they serialize to the same value. It dereferences to [`LNKParser`].
+#[derive(Clone)]
+pub struct Lnk(Arc<LNKParser>);
+
+impl Lnk {
+    /// Wrap a parsed LNK.
+    pub fn new(lnk: LNKParser) -> Self {
+        Lnk(Arc::new(lnk))
+    }
+
+    /// The `target_*` keys of the normalized LNK of `lnk_parser`. Its `lnk_*` keys are the
+    /// metadata of the LNK file on disk, always empty for the LNKs parsed from a reader, and
+    /// its `mac_address` is the `lnk_tracker_mac_address` key.
+    pub(crate) fn normalize_target(&self) -> HashMap<String, String> {
+        let mut normalized = self.0.normalize();
+        normalized.retain(|key, _| key.starts_with("target_"));
+        normalized
+    }
+}
+
+impl Deref for Lnk {
+    type Target = LNKParser;
+
+    fn deref(&self) -> &LNKParser {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Lnk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for Lnk {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || match (
+                serde_json::to_value(&*self.0),
+                serde_json::to_value(&*other.0),
+            ) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            }
+    }
+}
+
+impl Serialize for Lnk {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Fields of the `ShellLinkHeader` structure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LnkHeader {
+    /// `LinkFlags`, which optional structures are present in the LNK.
+    pub link_flags: u32,
+    /// `FileAttributes` of the link target.
+    pub file_attributes: u32,
+    /// Names of the `file_attributes` bits, see [`file_attribute_names`].
+    #[serde(default)]
+    pub file_attribute_names: String,
+    /// Creation time of the link target.
+    pub creation_time: Timestamp,
+    /// Last access time of the link target.
+    pub access_time: Timestamp,
+    /// Last modification time of the link target.
+    pub write_time: Timestamp,
+    /// Size of the link target (lower 32 bits).
+    pub file_size: u32,
+    /// `ShowCommand`, the state of the window opened by the link, see
+    /// [`LnkHeader::show_command_name`].
+    #[serde(default)]
+    pub show_command: u32,
+    /// `HotKey` of the link, the virtual key in the low byte and the modifiers in the high
+    /// byte, 0 when none. See [`LnkHeader::hotkey_name`].
+    #[serde(default)]
+    pub hotkey: u16,
+}
+
+impl LnkHeader {
+    /// Parse the header at the start of `raw_lnk`, `None` if it isn't a valid `ShellLinkHeader`.
+    pub fn from_buffer(raw_lnk: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        if u32_at(raw_lnk, 0)? as usize != LNK_HEADER_SIZE || raw_lnk.len() < LNK_HEADER_SIZE {
+            return None;
+        }
+        let file_attributes = u32_at(raw_lnk, 0x18)?;
+        Some(Self {
+            link_flags: u32_at(raw_lnk, 0x14)?,
+            file_attributes,
+            file_attribute_names: file_attribute_names(file_attributes),
+            creation_time: Timestamp::new(u64_at(raw_lnk, 0x1C)?, ts_format),
+            access_time: Timestamp::new(u64_at(raw_lnk, 0x24)?, ts_format),
+            write_time: Timestamp::new(u64_at(raw_lnk, 0x2C)?, ts_format),
+            file_size: u32_at(raw_lnk, 0x34)?,
+            show_command: u32_at(raw_lnk, 0x3C)?,
+            hotkey: u16_at(raw_lnk, 0x40)?,
+        })
+    }
+
+    /// Name of the `SW_*` value of [`LnkHeader::show_command`] (e.g. `normal`, `maximized`,
+    /// `minimized_no_activate`), the number for values without a name.
+    ///
+    /// Explorer only writes `normal`, `maximized` and `minimized_no_activate`, any other
+    /// value was set by another tool.
+    pub fn show_command_name(&self) -> String {
+        let name = match self.show_command {
+            0 => "hidden",
+            1 => "normal",
+            2 => "minimized",
+            3 => "maximized",
+            4 => "show_no_activate",
+            5 => "show",
+            6 => "minimize",
+            7 => "minimized_no_activate",
+            8 => "show_na",
+            9 => "restore",
+            10 => "show_default",
+            other => return other.to_string(),
+        };
+        name.to_string()
+    }
+
+    /// [`LnkHeader::hotkey`] as keys joined with `+` (e.g. `Ctrl+Alt+K`), `None` when the
+    /// link has no hotkey.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{lnk_info::LnkHeader, timestamp::TimestampFormat};
+    ///
+    /// let mut lnk = vec![0u8; 0x4C];
+    /// lnk[0] = 0x4C;
+    /// lnk[0x40..0x42].copy_from_slice(&[0x4B, 0x06]);
+    /// let header = LnkHeader::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+    /// assert_eq!(header.hotkey_name().as_deref(), Some("Ctrl+Alt+K"));
+    /// ```
+    pub fn hotkey_name(&self) -> Option<String> {
+        let [key, modifiers] = self.hotkey.to_le_bytes();
+        let key = match key {
+            0 => return None,
+            0x30..=0x39 | 0x41..=0x5A => (key as char).to_string(),
+            0x70..=0x87 => format!("F{}", key - 0x6F),
+            0x90 => "NumLock".to_string(),
+            0x91 => "ScrollLock".to_string(),
+            other => format!("{:#04x}", other),
+        };
+        let mut keys: Vec<String> = HOTKEY_MODIFIERS
+            .iter()
+            .filter(|(bit, _)| modifiers & bit != 0)
+            .map(|(_, name)| name.to_string())
+            .collect();
+        keys.push(key);
+        Some(keys.join("+"))
+    }
+
+    /// Replace the `target_*_time` keys of a normalized entry with the header timestamps,
+    /// formatted with their [`TimestampFormat`].
+    pub(crate) fn normalize_times_into(&self, normalized: &mut HashMap<String, String>) {
+        for (key, ts) in [
+            ("target_modification_time", &self.write_time),
+            ("target_access_time", &self.access_time),
+            ("target_creation_time", &self.creation_time),
+        ] {
+            normalized.insert(key.to_string(), ts.to_string());
+        }
+    }
+
+    /// Add the `target_attributes` (names), `target_attributes_value`, `target_is_hidden`,
+    /// `target_is_directory`, `lnk_show_command` and `lnk_hotkey` keys of `header` to a
+    /// normalized entry, empty without a header.
+    pub(crate) fn normalize_into(header: Option<&Self>, normalized: &mut HashMap<String, String>) {
+        let attributes = header.map(|h| h.file_attributes);
+        let flag = |bit: u32| attributes.map(|a| (a & bit != 0).to_string());
+        for (key, value) in [
+            (
+                "target_attributes",
+                header.map(|h| h.file_attribute_names.clone()),
+            ),
+            ("target_attributes_value", attributes.map(|a| a.to_string())),
+            ("target_is_hidden", flag(FILE_ATTRIBUTE_HIDDEN)),
+            ("target_is_directory", flag(FILE_ATTRIBUTE_DIRECTORY)),
+            ("lnk_show_command", header.map(|h| h.show_command_name())),
+            ("lnk_hotkey", header.and_then(|h| h.hotkey_name())),
+        ] {
+            normalized.insert(key.to_string(), value.unwrap_or_default());
+        }
+    }
+}
+
+/// Check the `HeaderSize` and `LinkCLSID` of the `ShellLinkHeader` at the start of `header`.
+pub(crate) fn check_lnk_header(header: &[u8]) -> Result<(), JumplistParserError> {
+    if u32_at(header, 0) != Some(LNK_HEADER_SIZE as u32) {
+        return Err(JumplistParserError::LnkEntry(
+            "Invalid LNK header size".to_string(),
+            line!(),
+            file!().to_string(),
+        ));
+    }
+    if header.get(4..20) != Some(&LNK_CLSID[..]) {
+        return Err(JumplistParserError::LnkEntry(
+            "Invalid LNK header CLSID".to_string(),
+            line!(),
+            file!().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Move `r` past the LNK at its current position and return the LNK size, without parsing
+/// it: only the `LinkFlags` and the sizes of the optional structures are read.
+pub(crate) fn skip_lnk<R: Read + Seek>(r: &mut R) -> Result<u64, JumplistParserError> {
+    let to_error = |e: std::io::Error| {
+        JumplistParserError::LnkEntry(e.to_string(), line!(), file!().to_string())
+    };
+    let start = r.stream_position().map_err(to_error)?;
+    let mut header = [0; LNK_HEADER_SIZE];
+    r.read_exact(&mut header).map_err(to_error)?;
+    check_lnk_header(&header)?;
+    let link_flags = u32_at(&header, 0x14).unwrap_or_default();
+    // Sizes are checked against the end of the stream before skipping
+    let skip = |r: &mut R, size: u64| {
+        if remaining_len(r)? < size {
+            return Err(JumplistParserError::LnkEntry(
+                "The LNK is truncated".to_string(),
+                line!(),
+                file!().to_string(),
+            ));
+        }
+        r.seek(SeekFrom::Current(size as i64)).map_err(to_error)
+    };
+    if link_flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        let size = r.read_u16::<LittleEndian>().map_err(to_error)?;
+        skip(r, size as u64)?;
+    }
+    if link_flags & HAS_LINK_INFO != 0 {
+        // The size includes the size field
+        let size = r.read_u32::<LittleEndian>().map_err(to_error)?;
+        skip(r, (size as u64).saturating_sub(4))?;
+    }
+    let char_size = match link_flags & IS_UNICODE != 0 {
+        true => 2,
+        false => 1,
+    };
+    for flag in STRING_DATA_FLAGS {
+        if link_flags & flag != 0 {
+            let count = r.read_u16::<LittleEndian>().map_err(to_error)?;
+            skip(r, count as u64 * char_size)?;
+        }
+    }
+    // The extra data blocks end with a terminal block smaller than 4 bytes
+    loop {
+        let size = r.read_u32::<LittleEndian>().map_err(to_error)?;
+        if size < 4 {
+            break;
+        }
+        skip(r, size as u64 - 4)?;
+    }
+    Ok(r.stream_position().map_err(to_error)? - start)
+}
+
+/// The optional structures of an LNK, located from the `LinkFlags` of its header.
+struct LnkSections<'a> {
+    /// Shell items of the `LinkTargetIDList`, each with its size field.
+    shell_items: Vec<&'a [u8]>,
+    /// The `LinkInfo` structure.
+    link_info: Option<&'a [u8]>,
+    /// `StringData` structures, by `LinkFlags` bit.
+    string_data: Vec<(u32, String)>,
+    /// Extra data blocks, by signature.
+    extra_data: Vec<(u32, &'a [u8])>,
+}
+
+impl<'a> LnkSections<'a> {
+    fn from_buffer(raw_lnk: &'a [u8]) -> Option<Self> {
+        if u32_at(raw_lnk, 0)? as usize != LNK_HEADER_SIZE {
+            return None;
+        }
+        let link_flags = u32_at(raw_lnk, 0x14)?;
+        let mut offset = LNK_HEADER_SIZE;
+        let mut shell_items = vec![];
+        if link_flags & HAS_LINK_TARGET_ID_LIST != 0 {
+            let size = u16_at(raw_lnk, offset)? as usize;
+            let mut remaining = raw_lnk
+                .get(offset + 2..offset + 2 + size)
+                .unwrap_or_default();
+            // The list ends with a 2 bytes `TerminalID`
+            while let Some(item_size) = u16_at(remaining, 0) {
+                let item_size = item_size as usize;
+                if item_size < 2 || item_size > remaining.len() {
+                    break;
+                }
+                shell_items.push(&remaining[..item_size]);
+                remaining = &remaining[item_size..];
+            }
+            offset += 2 + size;
+        }
+        let mut link_info = None;
+        if link_flags & HAS_LINK_INFO != 0 {
+            let size = u32_at(raw_lnk, offset)? as usize;
+            link_info = raw_lnk.get(offset..offset + size);
+            offset += size;
+        }
+        let char_size = match link_flags & IS_UNICODE != 0 {
+            true => 2,
+            false => 1,
+        };
+        let mut string_data = vec![];
+        for flag in STRING_DATA_FLAGS {
+            if link_flags & flag != 0 {
+                // `CountCharacters` then the characters, not NUL-terminated
+                let count = u16_at(raw_lnk, offset)? as usize;
+                // A truncated string ends the structures that can be read
+                let chars = match raw_lnk.get(offset + 2..offset + 2 + count * char_size) {
+                    Some(chars) => chars,
+                    None => break,
+                };
+                let value = match char_size {
+                    2 => String::from_utf16_lossy(
+                        &chars
+                            .chunks_exact(2)
+                            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                            .collect::<Vec<u16>>(),
+                    ),
+                    _ => String::from_utf8_lossy(chars).into_owned(),
+                };
+                string_data.push((flag, value));
+                offset += 2 + count * char_size;
+            }
+        }
+
+        let mut extra_data = vec![];
+        let mut remaining = raw_lnk.get(offset..).unwrap_or_default();
+        // The list ends with a terminal block smaller than 4 bytes
+        while let (Some(size), Some(signature)) = (u32_at(remaining, 0), u32_at(remaining, 4)) {
+            let size = size as usize;
+            if size < 8 || size > remaining.len() {
+                break;
+            }
+            extra_data.push((signature, &remaining[..size]));
+            remaining = &remaining[size..];
+        }
+        Some(Self {
+            shell_items,
+            link_info,
+            string_data,
+            extra_data,
+        })
+    }
+
+    fn string_data(&self, flag: u32) -> Option<String> {
+        self.string_data
+            .iter()
+            .find(|(f, _)| *f == flag)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn extra_data_block(&self, signature: u32) -> Option<&'a [u8]> {
+        self.extra_data
+            .iter()
+            .find(|(s, _)| *s == signature)
+            .map(|(_, block)| *block)
+    }
+}
+
+/// Where the path of a target comes from, the sources are listed in order of precedence.
+/// See [`JumplistEntryRef::best_target_path`](crate::entry::JumplistEntryRef::best_target_path).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetPathSource {
+    /// The local path of the LNK `LinkInfo`: `LocalBasePath` followed by
+    /// `CommonPathSuffix`.
+    LinkInfo,
+    /// The UNC path of the LNK `LinkInfo`: the `NetName` of the network share followed by
+    /// `CommonPathSuffix`.
+    NetworkShare,
+    /// The display names of the shell items of the LNK ID list, see
+    /// [`LnkDetails::shell_item_path`].
+    ShellItems,
+    /// The path of the `DestList` entry.
+    Destlist,
+}
+
+impl fmt::Display for TargetPathSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TargetPathSource::LinkInfo => "link_info",
+            TargetPathSource::NetworkShare => "network_share",
+            TargetPathSource::ShellItems => "shell_items",
+            TargetPathSource::Destlist => "destlist",
+        })
+    }
+}
+
+/// Structures read from the raw LNK bytes that `lnk_parser` doesn't expose.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct LnkDetails {
+    /// Path of the target recorded by the LNK: the local path of the `LinkInfo`, else its
+    /// UNC path, else the shell items path. See [`LnkDetails::target_path_source`].
+    #[serde(default)]
+    pub target_path: Option<String>,
+    /// Where [`LnkDetails::target_path`] comes from, never [`TargetPathSource::Destlist`].
+    #[serde(default)]
+    pub target_path_source: Option<TargetPathSource>,
+    /// Best-effort path of the target built from the display names of the shell items.
+    pub shell_item_path: Option<String>,
+    /// NTFS file reference of the target, from the last shell item of the ID list.
+    pub target_mft_reference: Option<MftReference>,
+    /// The `LinkInfo` structure (volume and network share of the target).
+    pub link_info: Option<LinkInfo>,
+    /// The `TrackerDataBlock` (Distributed Link Tracker data).
+    pub tracker: Option<TrackerData>,
+    /// Path of the target relative to the LNK, from the `StringData`.
+    #[serde(default)]
+    pub relative_path: Option<String>,
+    /// Working directory of the target, from the `StringData`.
+    #[serde(default)]
+    pub working_directory: Option<String>,
+    /// Icon location, from the `StringData` or else the `IconEnvironmentDataBlock`. It often
+    /// names the real binary behind the target.
+    #[serde(default)]
+    pub icon_location: Option<String>,
+}
+
+impl LnkDetails {
+    /// Read the structures of `raw_lnk`, `None` if it doesn't start with a `ShellLinkHeader`.
+    pub fn from_buffer(raw_lnk: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        let sections = LnkSections::from_buffer(raw_lnk)?;
+        let link_info = sections.link_info.and_then(LinkInfo::from_buffer);
+        let shell_item_path = shell_item_path(&sections.shell_items);
+        let target_path = vec![
+            (
+                link_info.as_ref().and_then(|l| l.local_path()),
+                TargetPathSource::LinkInfo,
+            ),
+            (
+                link_info.as_ref().and_then(|l| l.network_path()),
+                TargetPathSource::NetworkShare,
+            ),
+            (shell_item_path.clone(), TargetPathSource::ShellItems),
+        ]
+        .into_iter()
+        .find_map(|(path, source)| Some((path.filter(|p| !p.is_empty())?, source)));
+        Some(Self {
+            target_path_source: target_path.as_ref().map(|(_, source)| *source),
+            target_path: target_path.map(|(path, _)| path),
+            shell_item_path,
+            target_mft_reference: sections
+                .shell_items
+                .last()
+                .and_then(|item| MftReference::from_shell_item(item)),
+            link_info,
+            tracker: sections
+                .extra_data_block(TRACKER_DATA_BLOCK)
+                .and_then(|block| TrackerData::from_block(block, ts_format)),
+            relative_path: sections.string_data(HAS_RELATIVE_PATH),
+            working_directory: sections.string_data(HAS_WORKING_DIR),
+            icon_location: sections.string_data(HAS_ICON_LOCATION).or_else(|| {
+                sections
+                    .extra_data_block(ICON_ENVIRONMENT_DATA_BLOCK)
+                    .and_then(icon_environment_target)
+            }),
+        })
+    }
+
+    /// The path of the target following the precedence of [`TargetPathSource`], with its
+    /// source. `destlist_path` is the last resort.
+    pub(crate) fn best_target_path<'a>(
+        details: Option<&'a Self>,
+        destlist_path: Option<&'a str>,
+    ) -> Option<(&'a str, TargetPathSource)> {
+        details
+            .and_then(|d| Some((d.target_path.as_deref()?, d.target_path_source?)))
+            .or_else(|| {
+                destlist_path
+                    .filter(|p| !p.is_empty())
+                    .map(|p| (p, TargetPathSource::Destlist))
+            })
+    }
+
+    /// Set `target_full_path` of a normalized entry to [`LnkDetails::best_target_path`],
+    /// and record where it came from in `target_path_source` (both empty when unknown).
+    pub(crate) fn normalize_target_path_into(
+        details: Option<&Self>,
+        destlist_path: Option<&str>,
+        normalized: &mut HashMap<String, String>,
+    ) {
+        let (path, source) = match Self::best_target_path(details, destlist_path) {
+            Some((path, source)) => (path.to_string(), source.to_string()),
+            None => (String::new(), String::new()),
+        };
+        normalized.insert("target_full_path".to_string(), path);
+        normalized.insert("target_path_source".to_string(), source);
+    }
+
+    /// Add the `target_volume_*`, `target_drive_type`, `target_network_*`,
+    /// `target_on_removable_media`, `lnk_relative_path`, `lnk_working_directory`,
+    /// `lnk_icon_location` and `lnk_tracker_*` keys to a normalized entry, empty when unknown.
+    ///
+    /// `hostname` is the hostname recorded next to the LNK (the `DestList` hostname), the
+    /// `destlist_hostname_mismatch` key tells whether it differs from the tracker machine ID.
+    pub(crate) fn normalize_into(
+        details: Option<&Self>,
+        hostname: Option<&str>,
+        normalized: &mut HashMap<String, String>,
+    ) {
+        let link_info = details.and_then(|d| d.link_info.as_ref());
+        let drive_type = link_info.and_then(|l| l.drive_type);
+        for (key, value) in [
+            (
+                "target_volume_serial",
+                link_info.and_then(|l| l.volume_serial_number()),
+            ),
+            ("target_drive_type", drive_type.map(|d| d.to_string())),
+            (
+                "target_volume_label",
+                link_info.and_then(|l| l.volume_label.clone()),
+            ),
+            (
+                "target_network_share",
+                link_info.and_then(|l| l.network_share.clone()),
+            ),
+            (
+                "target_network_provider",
+                link_info.and_then(|l| l.network_provider_name()),
+            ),
+            (
+                "target_on_removable_media",
+                drive_type.map(|d| (d == DriveType::Removable).to_string()),
+            ),
+            (
+                "lnk_relative_path",
+                details.and_then(|d| d.relative_path.clone()),
+            ),
+            (
+                "lnk_working_directory",
+                details.and_then(|d| d.working_directory.clone()),
+            ),
+            (
+                "lnk_icon_location",
+                details.and_then(|d| d.icon_location.clone()),
+            ),
+        ] {
+            normalized.insert(key.to_string(), value.unwrap_or_default());
+        }
+
+        // Omitted rather than empty: most shell items don't carry a file reference
+        if let Some(reference) = details.and_then(|d| d.target_mft_reference) {
+            normalized.insert("target_mft_entry".to_string(), reference.entry.to_string());
+            normalized.insert(
+                "target_mft_sequence".to_string(),
+                reference.sequence.to_string(),
+            );
+        }
+
+        let tracker = details.and_then(|d| d.tracker.as_ref());
+        let machine_id = tracker.and_then(|t| t.machine_id.as_deref());
+        normalized.insert(
+            "lnk_tracker_machine_id".to_string(),
+            machine_id.unwrap_or_default().to_string(),
+        );
+        normalized.insert(
+            "lnk_tracker_mac_address".to_string(),
+            tracker
+                .and_then(|t| t.mac_address.clone())
+                .unwrap_or_default(),
+        );
+        normalized.insert(
+            "lnk_tracker_created_time".to_string(),
+            tracker
+                .and_then(|t| t.created_time)
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        );
+        normalized.insert(
+            "destlist_hostname_mismatch".to_string(),
+            match (machine_id, hostname) {
+                (Some(machine_id), Some(hostname)) => {
+                    (!machine_id.eq_ignore_ascii_case(hostname)).to_string()
+                }
+                _ => String::new(),
+            },
+        );
+    }
+}
+
+/// The path of an `IconEnvironmentDataBlock`, its `TargetUnicode` field or else its
+/// `TargetAnsi` field. The environment variables of the path are not expanded.
+fn icon_environment_target(block: &[u8]) -> Option<String> {
+    let unicode = block
+        .get(0x10C..0x314)
+        .and_then(|field| utf16_string_at(field, 0))
+        .filter(|s| !s.is_empty());
+    unicode.or_else(|| ansi_string_at(block.get(..0x10C)?, 8).filter(|s| !s.is_empty()))
+}
+
+/// Join the display names of `items` (a `LinkTargetIDList`) into a path.
+///
+/// Root folders and control panel items are named `::{GUID}` like in `DestList` paths.
+/// Volume, network and URI items start a new path. `None` when an item can't be named.
+fn shell_item_path(items: &[&[u8]]) -> Option<String> {
+    let mut path = String::new();
+    for item in items {
+        let class_type = *item.get(2)?;
+        let segment = match class_type & 0x70 {
+            // Control panel category
+            0x00 if u32_at(item, 4)? == CONTROL_PANEL_CATEGORY => u32_at(item, 8)?.to_string(),
+            // Root folder
+            0x10 => {
+                path = format!("::{{{}}}", Guid::from_buffer(item.get(4..20)?).ok()?);
+                continue;
+            }
+            // Shell folder of This PC (Desktop, Downloads, ...)
+            0x20 if class_type == 0x2E && item.get(3)? & 0x80 != 0 => {
+                format!("::{{{}}}", Guid::from_buffer(item.get(4..20)?).ok()?)
+            }
+            // Volume, e.g. `C:\`
+            0x20 => {
+                path = ansi_string_at(item, 3)?;
+                continue;
+            }
+            0x30 => file_entry_name(item)?,
+            // Network location, e.g. `\server\share`
+            0x40 => {
+                path = ansi_string_at(item, 5)?;
+                continue;
+            }
+            // URI, only when the string directly follows the header
+            0x60 if class_type == 0x61 && u16_at(item, 4)? == 0 => {
+                path = match item.get(3)? & 0x80 != 0 {
+                    true => utf16_string_at(item, 8)?,
+                    false => ansi_string_at(item, 8)?,
+                };
+                continue;
+            }
+            // Control panel item
+            0x70 if class_type == 0x71 => {
+                format!("::{{{}}}", Guid::from_buffer(item.get(14..30)?).ok()?)
+            }
+            _ => return None,
+        };
+        if !path.is_empty() && !path.ends_with('\\') {
+            path.push('\\');
+        }
+        path.push_str(&segment);
+    }
+    Some(path).filter(|p| !p.is_empty())
+}
+
+/// The `BEEF0004` extension block of a file entry shell item.
+fn file_entry_extension(item: &[u8]) -> Option<&[u8]> {
+    let signature = SHELL_ITEM_FILE_EXTENSION.to_le_bytes();
+    // The extension block starts 4 bytes (size and version) before its signature
+    let start = item
+        .windows(4)
+        .position(|w| w == signature)?
+        .checked_sub(4)?;
+    item.get(start..start + u16_at(item, start)? as usize)
+}
+
+/// Name of a file entry shell item: the long name of its `BEEF0004` extension block, or else
+/// its primary (8.3) name.
+fn file_entry_name(item: &[u8]) -> Option<String> {
+    let long_name = file_entry_extension(item)
+        .and_then(|block| {
+            // The long name follows fields added over the versions of the block
+            let offset = match u16_at(block, 2)? {
+                0..=2 => return None,
+                3..=6 => 0x14,
+                7 => 0x26,
+                8 => 0x2A,
+                _ => 0x2E,
+            };
+            utf16_string_at(block, offset)
+        })
+        .filter(|name| !name.is_empty());
+    long_name.or_else(|| match item.get(2)? & 0x04 != 0 {
+        true => utf16_string_at(item, 0x0E),
+        false => ansi_string_at(item, 0x0E),
+    })
+}
+
+/// An NTFS file reference: MFT entry number and sequence number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MftReference {
+    pub entry: u64,
+    pub sequence: u16,
+}
+
+impl MftReference {
+    /// The file reference of a file entry shell item, from its `BEEF0004` extension block.
+    fn from_shell_item(item: &[u8]) -> Option<Self> {
+        // File entry shell items have a class type of 0x3X
+        if item.get(2)? & 0x70 != 0x30 {
+            return None;
+        }
+        let block = file_entry_extension(item)?;
+        // The file reference was added in version 7 (Windows Vista)
+        if u16_at(block, 2)? < 7 {
+            return None;
+        }
+        let reference = u64_at(block, 0x14)?;
+        let entry = reference & 0x0000_FFFF_FFFF_FFFF;
+        if entry == 0 {
+            return None;
+        }
+        Some(Self {
+            entry,
+            sequence: (reference >> 48) as u16,
+        })
+    }
+}
+
+/// Type of the drive a LNK target was on (`VolumeID.DriveType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriveType {
+    Unknown,
+    NoRootDir,
+    Removable,
+    Fixed,
+    Remote,
+    Cdrom,
+    Ramdisk,
+}
+
+impl DriveType {
+    fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(DriveType::Unknown),
+            1 => Some(DriveType::NoRootDir),
+            2 => Some(DriveType::Removable),
+            3 => Some(DriveType::Fixed),
+            4 => Some(DriveType::Remote),
+            5 => Some(DriveType::Cdrom),
+            6 => Some(DriveType::Ramdisk),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DriveType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            DriveType::Unknown => "unknown",
+            DriveType::NoRootDir => "no_root_dir",
+            DriveType::Removable => "removable",
+            DriveType::Fixed => "fixed",
+            DriveType::Remote => "remote",
+            DriveType::Cdrom => "cdrom",
+            DriveType::Ramdisk => "ramdisk",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Names of the common `CommonNetworkRelativeLink.NetworkProviderType` values.
+const NETWORK_PROVIDERS: &[(u32, &str)] = &[
+    (0x0002_0000, "WNNC_NET_LANMAN"),
+    (0x0026_0000, "WNNC_NET_CSC"),
+    (0x002E_0000, "WNNC_NET_DAV"),
+    (0x0036_0000, "WNNC_NET_TERMSRV"),
+    (0x003B_0000, "WNNC_NET_DFS"),
+    (0x003F_0000, "WNNC_NET_VMWARE"),
+    (0x0042_0000, "WNNC_NET_MS_NFS"),
+    (0x0043_0000, "WNNC_NET_GOOGLE"),
+];
+
+/// The `LinkInfo` structure of an LNK: where the target was when the LNK was written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkInfo {
+    /// Type of the drive of a local target.
+    pub drive_type: Option<DriveType>,
+    /// Serial number of the volume of a local target.
+    pub volume_serial: Option<u32>,
+    /// Label of the volume of a local target.
+    pub volume_label: Option<String>,
+    /// Path of a local target.
+    pub local_base_path: Option<String>,
+    /// Share (`\\server\share`) of a network target.
+    pub network_share: Option<String>,
+    /// End of the path of the target, after `local_base_path` or `network_share`. Usually
+    /// empty for a local target.
+    #[serde(default)]
+    pub common_path_suffix: Option<String>,
+    /// Drive letter the share was mapped to, e.g. `Z:`.
+    pub network_device_name: Option<String>,
+    /// `NetworkProviderType` of a network target.
+    pub network_provider: Option<u32>,
+}
+
+impl LinkInfo {
+    /// Parse a `LinkInfo` structure (size included).
+    fn from_buffer(buf: &[u8]) -> Option<Self> {
+        let header_size = u32_at(buf, 4)?;
+        let flags = u32_at(buf, 8)?;
+        let mut link_info = LinkInfo {
+            drive_type: None,
+            volume_serial: None,
+            volume_label: None,
+            local_base_path: None,
+            network_share: None,
+            common_path_suffix: None,
+            network_device_name: None,
+            network_provider: None,
+        };
+
+        if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+            let volume_id = buf.get(u32_at(buf, 12)? as usize..)?;
+            link_info.drive_type = u32_at(volume_id, 4).and_then(DriveType::from_u32);
+            link_info.volume_serial = u32_at(volume_id, 8);
+            link_info.volume_label = match u32_at(volume_id, 12) {
+                // The label is UTF-16 when its offset points to the unicode offset field
+                Some(0x14) => u32_at(volume_id, 16)
+                    .and_then(|offset| utf16_string_at(volume_id, offset as usize)),
+                Some(offset) => ansi_string_at(volume_id, offset as usize),
+                None => None,
+            };
+            link_info.local_base_path = match header_size >= 0x24 {
+                true => u32_at(buf, 28)
+                    .filter(|offset| *offset != 0)
+                    .and_then(|offset| utf16_string_at(buf, offset as usize)),
+                false => None,
+            }
+            .or_else(|| ansi_string_at(buf, u32_at(buf, 16)? as usize));
+        }
+
+        link_info.common_path_suffix = match header_size >= 0x24 {
+            true => u32_at(buf, 32)
+                .filter(|offset| *offset != 0)
+                .and_then(|offset| utf16_string_at(buf, offset as usize)),
+            false => None,
+        }
+        .or_else(|| ansi_string_at(buf, u32_at(buf, 24)? as usize));
+
+        if flags & COMMON_NETWORK_RELATIVE_LINK != 0 {
+            let network = buf.get(u32_at(buf, 20)? as usize..)?;
+            let network_flags = u32_at(network, 4)?;
+            let net_name_offset = u32_at(network, 8)?;
+            let unicode = net_name_offset > 0x14;
+            let string_at = |ansi_offset: usize, unicode_offset: usize| match unicode {
+                true => utf16_string_at(network, u32_at(network, unicode_offset)? as usize),
+                false => ansi_string_at(network, u32_at(network, ansi_offset)? as usize),
+            };
+            link_info.network_share = string_at(8, 20);
+            // ValidDevice
+            if network_flags & 0x01 != 0 {
+                link_info.network_device_name = string_at(12, 24);
+            }
+            // ValidNetType
+            if network_flags & 0x02 != 0 {
+                link_info.network_provider = u32_at(network, 16);
+            }
+        }
+        Some(link_info)
+    }
+
+    /// Full path of a local target: `local_base_path` followed by `common_path_suffix`.
+    pub fn local_path(&self) -> Option<String> {
+        let base = self.local_base_path.as_deref().filter(|p| !p.is_empty())?;
+        Some(format!(
+            "{}{}",
+            base,
+            self.common_path_suffix.as_deref().unwrap_or_default()
+        ))
+    }
+
+    /// Full UNC path of a network target: `network_share` and `common_path_suffix`
+    /// separated by a `\`.
+    pub fn network_path(&self) -> Option<String> {
+        let share = self.network_share.as_deref().filter(|p| !p.is_empty())?;
+        Some(match self.common_path_suffix.as_deref() {
+            Some(suffix) if !suffix.is_empty() => format!("{}\\{}", share, suffix),
+            _ => share.to_string(),
+        })
+    }
+
+    /// The volume serial number as displayed by Windows, e.g. `A4C2-11F0`.
+    pub fn volume_serial_number(&self) -> Option<String> {
+        self.volume_serial
+            .map(|s| format!("{:04X}-{:04X}", s >> 16, s & 0xFFFF))
+    }
+
+    /// The `WNNC_NET_*` name of the network provider, or its hex value when unknown.
+    pub fn network_provider_name(&self) -> Option<String> {
+        self.network_provider.map(|provider| {
+            NETWORK_PROVIDERS
+                .iter()
+                .find(|(value, _)| *value == provider)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| format!("0x{:08X}", provider))
+        })
+    }
+}
+
+/// The `TrackerDataBlock` of an LNK, written by the Distributed Link Tracking service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackerData {
+    /// NetBIOS name of the machine the target was last seen on.
+    pub machine_id: Option<String>,
+    /// Volume droid (current).
+    pub droid_volume: Guid,
+    /// File droid (current object ID).
+    pub droid_file: Guid,
+    /// Volume droid when the object ID was created.
+    pub birth_droid_volume: Guid,
+    /// File droid when the object ID was created.
+    pub birth_droid_file: Guid,
+    /// MAC address of the machine that created the object ID, from the file droid
+    /// (a version 1 UUID).
+    pub mac_address: Option<String>,
+    /// Creation time of the object ID, from the file droid.
+    pub created_time: Option<Timestamp>,
+}
+
+impl TrackerData {
+    /// Parse a `TrackerDataBlock` (size and signature included).
+    fn from_block(block: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        if block.len() < 0x60 {
+            return None;
+        }
+        let guid = |offset: usize| Guid::from_buffer(&block[offset..offset + 16]).ok();
+        let droid_file = guid(0x30)?;
+        let (mac_address, created_time) = match uuid_version(&droid_file) {
+            1 => {
+                let node = &droid_file.0[10..16];
+                let mac = node
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<Vec<String>>()
+                    .join(":");
+                let time = uuid_timestamp(&droid_file)
+                    .and_then(|t| t.checked_sub(UUID_FILETIME_OFFSET))
+                    .map(|filetime| Timestamp::new(filetime, ts_format));
+                (Some(mac), time)
+            }
+            _ => (None, None),
+        };
+        Some(Self {
+            machine_id: clean_hostname(&block[0x10..0x20]),
+            droid_volume: guid(0x20)?,
+            droid_file,
+            birth_droid_volume: guid(0x40)?,
+            birth_droid_file: guid(0x50)?,
+            mac_address,
+            created_time,
+        })
+    }
+}
+
+/// Version of a UUID (the high nibble of `time_hi_and_version`).
+fn uuid_version(guid: &Guid) -> u8 {
+    guid.0[7] >> 4
+}
+
+/// The 60-bit timestamp of a version 1 UUID, in 100-nanosecond intervals since 1582-10-15.
+fn uuid_timestamp(guid: &Guid) -> Option<u64> {
+    let time_low = u32_at(&guid.0, 0)? as u64;
+    let time_mid = u16_at(&guid.0, 4)? as u64;
+    let time_hi = (u16_at(&guid.0, 6)? & 0x0FFF) as u64;
+    Some(time_hi << 48 | time_mid << 32 | time_low)
+}
@@ -0,0 +1,126 @@
+//! Default locations of the Jumplists, the patterns parsed by the CLI without `--path`.
+//!
+//! The Jumplists of a profile are in `AppData\Roaming\Microsoft\Windows\Recent`, in its
+//! `AutomaticDestinations` and `CustomDestinations` directories. The patterns match every
+//! profile of `Users` under a root: `C:\` on Windows, the `/mnt/c` of WSL elsewhere, or the
+//! mount point of an image with [`default_paths_under`].
+//!
+//! # Example
+//! ```
+//! use jumplist_parser::locations::{default_paths_under, LocationOptions};
+//!
+//! assert_eq!(
+//!     default_paths_under("/mnt/image/C"),
+//!     [
+//!         "/mnt/image/C/Users/*/AppData/Roaming/Microsoft/Windows/Recent/AutomaticDestinations/*ms",
+//!         "/mnt/image/C/Users/*/AppData/Roaming/Microsoft/Windows/Recent/CustomDestinations/*ms",
+//!     ]
+//! );
+//! let options = LocationOptions {
+//!     documents_and_settings: true,
+//!     ..Default::default()
+//! };
+//! assert_eq!(options.paths_under(r"E:\").len(), 4);
+//! ```
+
+use std::path::{Path, PathBuf};
+
+/// Directories of the Jumplists, relative to the `Recent` directory of a profile.
+pub const DESTINATIONS_DIRECTORIES: &[&str] = &["AutomaticDestinations", "CustomDestinations"];
+
+/// Components of the `Recent` directory, relative to a profile directory.
+const RECENT_COMPONENTS: &[&str] = &["AppData", "Roaming", "Microsoft", "Windows", "Recent"];
+
+/// Components of the `Recent` directory of a `Documents and Settings` profile, relative to
+/// the profile directory.
+const LEGACY_RECENT_COMPONENTS: &[&str] = &["Application Data", "Microsoft", "Windows", "Recent"];
+
+/// Which files the default patterns match.
+#[derive(Debug, Clone, Default)]
+pub struct LocationOptions {
+    /// Add the standalone LNK files of the `Recent` directories, see
+    /// [`recent_lnk`](crate::recent_lnk).
+    pub recent_lnks: bool,
+    /// Add the profiles of `Documents and Settings`, the layout of Windows XP and 2003 still
+    /// found on upgraded systems and old images.
+    pub documents_and_settings: bool,
+}
+
+impl LocationOptions {
+    /// The patterns under the root of the system drive of the platform, `C:\` on Windows and
+    /// `/mnt/c` (WSL) elsewhere.
+    pub fn paths(&self) -> Vec<String> {
+        self.paths_under(match cfg!(windows) {
+            true => r"C:\",
+            false => "/mnt/c",
+        })
+    }
+
+    /// The patterns under `root`, the root of a Windows volume, e.g. the mount point of an
+    /// image. `root` is a glob pattern itself, and the patterns are joined with `\` when it
+    /// has `\` but no `/`.
+    pub fn paths_under(&self, root: &str) -> Vec<String> {
+        let separator = match root.contains('\\') && !root.contains('/') {
+            true => "\\",
+            false => "/",
+        };
+        let root = root.trim_end_matches(['\\', '/']);
+        let mut layouts = vec![("Users", RECENT_COMPONENTS)];
+        if self.documents_and_settings {
+            layouts.push(("Documents and Settings", LEGACY_RECENT_COMPONENTS));
+        }
+        let mut paths = vec![];
+        for (profiles, recent) in layouts {
+            let recent = [&[root, profiles, "*"], recent].concat().join(separator);
+            for dir in DESTINATIONS_DIRECTORIES {
+                paths.push([recent.as_str(), dir, "*ms"].join(separator));
+            }
+            if self.recent_lnks {
+                paths.push([recent.as_str(), "*.lnk"].join(separator));
+            }
+        }
+        paths
+    }
+}
+
+/// The patterns of the Jumplists of every profile of the platform, see
+/// [`LocationOptions::paths`].
+pub fn default_paths() -> Vec<String> {
+    LocationOptions::default().paths()
+}
+
+/// The patterns of the Jumplists of every profile under `root`, e.g. `/mnt/image/C` for a
+/// mounted image. See [`LocationOptions::paths_under`].
+pub fn default_paths_under(root: &str) -> Vec<String> {
+    LocationOptions::default().paths_under(root)
+}
+
+/// The `Recent` directory of the profile in `profile_dir`, e.g. `C:\Users\user`.
+pub fn recent_directory<P: AsRef<Path>>(profile_dir: P) -> PathBuf {
+    RECENT_COMPONENTS
+        .iter()
+        .fold(profile_dir.as_ref().to_path_buf(), |dir, component| {
+            dir.join(component)
+        })
+}
+
+/// The `AutomaticDestinations` and `CustomDestinations` directories of the profile in
+/// `profile_dir`.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::locations::for_user;
+/// use std::path::Path;
+///
+/// let dirs = for_user("/mnt/c/Users/user");
+/// assert_eq!(dirs.len(), 2);
+/// assert!(dirs[0].ends_with("Windows/Recent/AutomaticDestinations"));
+/// assert!(dirs[1].starts_with(Path::new("/mnt/c/Users/user/AppData/Roaming")));
+/// ```
+pub fn for_user<P: AsRef<Path>>(profile_dir: P) -> Vec<PathBuf> {
+    let recent = recent_directory(profile_dir);
+    DESTINATIONS_DIRECTORIES
+        .iter()
+        .map(|dir| recent.join(dir))
+        .collect()
+}
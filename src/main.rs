@@ -1,24 +1,72 @@
+use chrono_tz::Tz;
 use clap::{Arg, ArgAction, ArgMatches, Command};
-use glob::glob;
-use jumplist_parser::{errors::JumplistParserError, Flaten, JumplistParser};
+use flate2::{write::GzEncoder, Compression};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+#[cfg(target_os = "windows")]
+use jumplist_parser::locations::{for_user, recent_directory};
+#[cfg(feature = "http")]
+use jumplist_parser::sink::{HttpSink, HttpSinkOptions};
+#[cfg(feature = "watch")]
+use jumplist_parser::watch::watch;
+use jumplist_parser::{
+    aggregate::{
+        AggregateOptions, Summary, TargetActivity, AGGREGATE_CSV_COLUMNS, SUMMARY_CSV_COLUMNS,
+    },
+    anomaly::Anomaly,
+    appids::app_id_from_file_name,
+    diff::diff,
+    errors::JumplistParserError,
+    filetype::{FileCategory, FiletypeMap},
+    guid::GuidFormat,
+    is_probably_jumplist,
+    locations::LocationOptions,
+    output::{
+        csv_columns, csv_rows, encode_rows, find_field, select_fields, CsvOptions, FIELDS,
+        OFFSET_CSV_COLUMNS, RAW_FIELDS, UTF8_BOM,
+    },
+    path_pattern::expand_pattern,
+    sanitize_file_name, sniff_jumplist,
+    sort::{SortField, SortKey},
+    stats::ParseStats,
+    table::{render_rows, table_rows, table_title, TableOptions, TABLE_COLUMNS},
+    timestamp::TimestampFormat,
+    tln::{tln_records, TlnRecord},
+    to_hex,
+    verify::{verify_path, VerifyResult, VerifyStatus},
+    FileNameSuffixes, JumplistParser, JumplistType, NormalizedEntry, ParseLimits, ParseMode,
+    ParseOptions, ParseOutcome, Progress, ProgressEvent,
+};
+use regex::Regex;
 use serde::Serialize;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, Write};
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{File, Metadata};
+use std::io::{self, BufRead, BufReader, Cursor, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
 
 enum OutputFormat {
-    JSON,
-    JSONL,
-    CSV,
+    Json,
+    Jsonl,
+    Csv,
+    Tsv,
+    Table,
+    Tln,
 }
 
 impl OutputFormat {
     pub fn from_str(s: &str) -> OutputFormat {
         match s {
-            "json" => OutputFormat::JSON,
-            "jsonl" => OutputFormat::JSONL,
-            "csv" => OutputFormat::CSV,
-            _ => OutputFormat::CSV,
+            "json" => OutputFormat::Json,
+            "jsonl" => OutputFormat::Jsonl,
+            "csv" => OutputFormat::Csv,
+            "tsv" => OutputFormat::Tsv,
+            "table" => OutputFormat::Table,
+            "tln" => OutputFormat::Tln,
+            _ => OutputFormat::Csv,
         }
     }
 }
@@ -46,10 +94,71 @@ Reference: https://u0041.co/posts/articals/jumplist-files-artifacts/
                 .short('p')
                 .long("path")
                 .value_name("PATH")
-                .help("Path(s) to Jumplist files to be parsed - accepts glob (defaults to 'AutomaticDestinations' & 'CustomDestinations' for all users)")
+                .help("Path(s) to Jumplist files to be parsed - accepts glob (defaults to 'AutomaticDestinations' & 'CustomDestinations' for all users). A ':automatic' or ':custom' suffix forces the type of the matched files, e.g. 'carved.bin:custom'")
                 .action(ArgAction::Append)
                 .required(false),
         )
+        .arg(
+            Arg::new("input-list")
+                .long("input-list")
+                .value_name("FILE")
+                .help("Parse the paths listed in FILE ('-' for stdin), one per line. Blank lines and lines starting with '#' are skipped, can be combined with --path")
+        )
+        .arg(
+            Arg::new("null")
+                .long("null")
+                .requires("input-list")
+                .help("The paths of --input-list are separated by NUL characters (e.g. 'find -print0') and used as is")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("stdin")
+                .long("stdin")
+                .conflicts_with_all(["path", "input-list"])
+                .help("Parse a single Jumplist file read from stdin, the type is detected from the content unless --type or --name are given")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .requires("stdin")
+                .value_parser(["automatic", "custom"])
+                .help("Type of the Jumplist read with --stdin")
+        )
+        .arg(
+            Arg::new("force-type")
+                .long("force-type")
+                .value_name("TYPE")
+                .value_parser(["automatic", "custom"])
+                .conflicts_with_all(["stdin", "watch"])
+                .help("Parse every file as TYPE whatever its extension, the AppID is still taken from the file name. Overridden by the ':automatic' or ':custom' suffix of a --path")
+        )
+        .arg(
+            Arg::new("include-recent-lnks")
+                .long("include-recent-lnks")
+                .conflicts_with_all(["stdin", "watch"])
+                .help("Also parse the standalone LNK files of the Recent folder ('Recent\\*.lnk' is added to the default paths), every '.lnk' file is parsed as a 'recent_lnk' Jumplist without AppID")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("documents-and-settings")
+                .long("documents-and-settings")
+                .help("Also look for the Jumplists of the 'Documents and Settings' profiles of old images and upgraded systems in the default paths")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("sniff")
+                .long("sniff")
+                .help("Also read the first 512 bytes of the files matched by a wildcard, and skip those that start neither like a compound file nor like a custom Jumplist. Matched files without a Jumplist extension are always skipped")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("FILENAME")
+                .requires("stdin")
+                .help("Original file name of the Jumplist read with --stdin, it gives the type and the AppID")
+        )
         .arg(
             Arg::new("output")
                 .short('o')
@@ -58,12 +167,102 @@ Reference: https://u0041.co/posts/articals/jumplist-files-artifacts/
                 .help("The file path to write the output to")
                 .default_value("stdout")
         )
+        .arg(
+            Arg::new("compress")
+                .long("compress")
+                .help("Compress the output with gzip, always done when the --output path ends with '.gz'")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("output-url")
+                .long("output-url")
+                .value_name("URL")
+                .conflicts_with_all(["output", "compress", "output-dir", "output-format", "aggregate", "summary", "anomalies-only", "sort-by", "limit"])
+                .help("POST the normalized entries in batches to URL as NDJSON, or as Splunk HEC events with --hec (needs the 'http' feature)")
+        )
+        .arg(
+            Arg::new("output-auth")
+                .long("output-auth")
+                .value_name("TOKEN")
+                .requires("output-url")
+                .help("Token of --output-url, sent as 'Splunk TOKEN' with --hec and 'Bearer TOKEN' otherwise. A value with a space is sent as is")
+        )
+        .arg(
+            Arg::new("hec")
+                .long("hec")
+                .requires("output-url")
+                .help("Wrap the entries sent to --output-url in Splunk HTTP Event Collector envelopes")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("batch-size")
+                .long("batch-size")
+                .value_name("N")
+                .requires("output-url")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("500")
+                .help("Number of entries per request to --output-url")
+        )
+        .arg(
+            Arg::new("spool")
+                .long("spool")
+                .value_name("FILE")
+                .requires("output-url")
+                .default_value("jumplist_parser_spool.jsonl")
+                .help("File the entries of the batches that could not be sent to --output-url are appended to")
+        )
+        .arg(
+            Arg::new("insecure")
+                .long("insecure")
+                .requires("output-url")
+                .help("Don't verify the TLS certificate of --output-url, for lab use only")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("output-dir")
+                .long("output-dir")
+                .value_name("DIR")
+                .conflicts_with_all(["output", "compress", "output-format", "aggregate", "summary", "anomalies-only", "search", "search-regex", "sort-by", "limit"])
+                .help("Write one JSON file per Jumplist to DIR, named '<appid>.<type>.json', and an 'index.json' manifest with the source path, size, SHA-256 and parse status of every file")
+        )
+        .arg(
+            Arg::new("error-report")
+                .long("error-report")
+                .value_name("PATH")
+                .help("Also write a JSON line to PATH for every file that failed to parse (source path, size, type, error kind and message) or parsed with warnings (warnings and recovered entries), and partially parsed files with both")
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .conflicts_with_all(["stdin", "input-list", "output-dir", "output-url", "output-format", "aggregate", "summary", "anomalies-only", "group-by-user", "search", "search-regex", "sort-by", "limit"])
+                .help("Keep watching the directories of the --path patterns (or the default ones) and output the added and changed entries as JSON lines until Ctrl-C. The existing entries are not output (needs the 'watch' feature)")
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("output-format")
                 .long("output-format")
-                .value_parser(["csv", "jsonl", "json"])
+                .value_parser(["csv", "tsv", "jsonl", "json", "table", "tln"])
                 .default_value("csv")
-                .help("Output format")
+                .help("Output format, 'table' prints aligned columns for reading in a terminal, 'tln' prints 'Time|Source|Host|User|Description' timeline lines and leaves out the entries without a timestamp")
+        )
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .value_name("COLUMNS")
+                .value_delimiter(',')
+                .help("Comma separated columns of the table output [default: app_name,target_full_path,interaction_time,pinned]")
+        )
+        .arg(
+            Arg::new("wide")
+                .long("wide")
+                .help("Don't shorten the values of the table output to the terminal width")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .help("Don't colorize the table output, it is only colorized when writing to a terminal")
+                .action(ArgAction::SetTrue)
         )
         .arg(
             Arg::new("no-headers")
@@ -71,167 +270,2094 @@ Reference: https://u0041.co/posts/articals/jumplist-files-artifacts/
                 .help("Don't print headers when using CSV as the output format")
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("delimiter")
+                .long("delimiter")
+                .value_name("CHAR")
+                .value_parser(parse_delimiter)
+                .default_value(",")
+                .help("Field delimiter for the CSV output format ('\\t' for tab)")
+        )
+        .arg(
+            Arg::new("safe-csv")
+                .long("safe-csv")
+                .help("Prefix CSV fields starting with '=', '+', '-' or '@' with a quote to prevent formula injection (default)")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("no-safe-csv")
+                .long("no-safe-csv")
+                .help("Write CSV fields as is, without formula injection protection")
+                .action(ArgAction::SetTrue)
+                .overrides_with("safe-csv")
+        )
+        .arg(
+            Arg::new("bom")
+                .long("bom")
+                .help("Start the CSV output with a UTF-8 byte order mark, for Excel to read non-ASCII paths")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("crlf")
+                .long("crlf")
+                .help("End the CSV lines with CRLF (Windows line endings)")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("excel")
+                .long("excel")
+                .help("CSV for Excel on Windows: same as --bom --crlf --safe-csv")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no-safe-csv")
+        )
         .arg(
             Arg::new("normalize")
                 .long("normalize")
                 .help("Normalize the result to the most important fields")
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("legacy-keys")
+                .long("legacy-keys")
+                .help("Write the normalized and CSV output with the key names used before the jumplist_, destlist_, entry_ and lnk_ prefixes (e.g. 'app_id', 'name_string'), and 'pined' without the custom header 'reserved' in the raw output. Deprecated")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Print a notice for every file with parsing inconsistencies, the number of entries by LNK status of automatic Jumplists, and the parse time of every file with the totals of the run")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("verbose-columns")
+                .long("verbose-columns")
+                .help("Add the 'jumplist_is_complete' and 'lnk_status' columns to the CSV output")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("extended-columns")
+                .long("extended-columns")
+                .help("Add the volume and network share columns of the LNK targets, and the working directory, icon location, relative path, show command and hotkey of the LNKs, and the DestList path with a 'path_mismatch' column to the CSV output")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("fields")
+                .long("fields")
+                .value_name("FIELDS")
+                .value_delimiter(',')
+                .value_parser(|name: &str| find_field(name.trim()).map(|f| f.name))
+                .help("Comma separated fields, in order, of the CSV output and of the normalized JSON output, instead of the default ones (see --list-fields)")
+        )
+        .arg(
+            Arg::new("raw-csv")
+                .long("raw-csv")
+                .help("Write one row per entry with the scalar fields of the Jumplist, of its DestList entries and of the categories of custom Jumplists, named like in the raw JSON output, instead of the normalized columns in the CSV and TSV output. The LNK fields are only in the normalized columns (see --list-fields --raw)")
+                .conflicts_with("fields")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("list-fields")
+                .long("list-fields")
+                .help("Print the fields accepted by --fields and exit")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("raw")
+                .long("raw")
+                .help("Print the columns of --raw-csv instead with --list-fields")
+                .requires("list-fields")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("offsets")
+                .long("offsets")
+                .help("Add the byte offset and size of every entry to the normalized and CSV output")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("capture-unknown-bytes")
+                .long("capture-unknown-bytes")
+                .help("Add the bytes of unknown meaning of every structure to the JSON output as hex strings")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("extra-stream-preview")
+                .long("extra-stream-preview")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("0")
+                .help("Add the first BYTES bytes of the unrecognized streams of automatic Jumplists to the JSON output as hex strings")
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Fail on any structural anomaly instead of reporting it as a warning. The entries of such a file are still recovered and written, and the file is reported as partially parsed")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("live")
+                .long("live")
+                .help("Parse the Jumplists of the running system (Windows only): open the files held by other processes and find the profiles in the registry")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("no-carving")
+                .long("no-carving")
+                .help("Don't recover the LNK entries of damaged custom Jumplists by scanning for LNK headers")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("mmap")
+                .long("mmap")
+                .help("Memory-map the Jumplist files instead of reading them, for large batches (needs the 'mmap' feature)")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("timestamp-format")
+                .long("timestamp-format")
+                .value_name("FORMAT")
+                .value_parser(["iso", "iso-subsecond", "epoch", "filetime"])
+                .default_value("iso")
+                .help("Format of the timestamps in the output")
+        )
+        .arg(
+            Arg::new("tz")
+                .long("tz")
+                .value_name("TIMEZONE")
+                .value_parser(|s: &str| {
+                    s.parse::<Tz>().map_err(|_| {
+                        format!("unknown timezone '{}', expected an IANA name such as 'Asia/Riyadh' or 'UTC'", s)
+                    })
+                })
+                .help("Display the timestamps of the CSV, table and normalized output in TIMEZONE (IANA name, e.g. 'Asia/Riyadh') with their UTC offset. The raw JSON output stays in UTC")
+        )
+        .arg(
+            Arg::new("guid-format")
+                .long("guid-format")
+                .value_name("FORMAT")
+                .value_parser(["upper", "lower", "braced", "braced-lower", "urn"])
+                .default_value("upper")
+                .help("Format of the DestList droid GUIDs in the output")
+        )
+        .arg(
+            Arg::new("zero-guids")
+                .long("zero-guids")
+                .value_name("MODE")
+                .value_parser(["keep", "null", "omit"])
+                .default_value("keep")
+                .help("Write the all-zero droid GUIDs as is, as null or not at all in the JSON output (empty in the normalized output)")
+        )
+        .arg(
+            Arg::new("resolve-known-folders")
+                .long("resolve-known-folders")
+                .help("Replace known folder GUIDs in the CSV 'target_full_path' column with the folder names")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("search")
+                .long("search")
+                .value_name("TERM")
+                .action(ArgAction::Append)
+                .help("Only output the entries with a field containing TERM (case-insensitive, repeatable, any term matches) and add a 'matched_field' column. Without --normalize, the JSON output keeps the whole Jumplists with a match")
+        )
+        .arg(
+            Arg::new("search-regex")
+                .long("search-regex")
+                .value_name("REGEX")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| Regex::new(s).map_err(|e| e.to_string()))
+                .help("Same as --search with a regular expression, can be combined with --search")
+        )
+        .arg(
+            Arg::new("pinned-only")
+                .long("pinned-only")
+                .help("Only output the pinned entries. The entries of the tasks and of the pinned items of custom Jumplists count as pinned")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("exclude-pinned")
+                .long("exclude-pinned")
+                .conflicts_with("pinned-only")
+                .help("Leave the pinned entries out of the output")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("only-category")
+                .long("only-category")
+                .value_name("CATEGORIES")
+                .value_delimiter(',')
+                .value_parser(|name: &str| name.trim().parse::<FileCategory>())
+                .help("Only output the entries whose target is of one of the comma separated categories: document, spreadsheet, presentation, image, archive, executable, script, media or other (e.g. 'executable,script')")
+        )
+        .arg(
+            Arg::new("aggregate")
+                .long("aggregate")
+                .conflicts_with_all(["search", "search-regex"])
+                .help("Output one row per unique target across all the parsed files instead of one row per entry")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("summary")
+                .long("summary")
+                .conflicts_with_all(["aggregate", "search", "search-regex"])
+                .help("Output one row per application with its number of files and entries, and its entries by type of the volume of their target (fixed, removable, remote, cdrom, other, unknown). Its entries by category of their target are in the 'categories' column. The JSON output also lists the volume serial numbers and the UNC servers seen. Files with more than 25% of their DestList stream after the last entry are noted on stderr")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("anomalies-only")
+                .long("anomalies-only")
+                .conflicts_with_all(["aggregate", "summary", "search", "search-regex"])
+                .help("Only output the signs of tampering and timestomping found in the entries, one JSON object per line")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("group-by-user")
+                .long("group-by-user")
+                .conflicts_with_all(["aggregate", "summary"])
+                .help("Write the rows of every user together, sorted by user name. With --verbose, print a summary line for every user")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("sort-by")
+                .long("sort-by")
+                .value_name("FIELD")
+                .value_parser(SortField::NAMES.to_vec())
+                .conflicts_with_all(["aggregate", "summary", "anomalies-only", "group-by-user"])
+                .help("Sort the entries of all the parsed files by FIELD, ties are ordered by file path and entry number. The JSON formats output one normalized entry per line or array element")
+        )
+        .arg(
+            Arg::new("descending")
+                .long("descending")
+                .requires("sort-by")
+                .help("Sort in descending order, entries without the --sort-by field stay last")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with_all(["aggregate", "summary", "anomalies-only"])
+                .help("Only output the first N entries, after filtering and sorting. The JSON formats output one normalized entry per line or array element")
+        )
+        .arg(
+            Arg::new("user-path-regex")
+                .long("user-path-regex")
+                .value_name("REGEX")
+                .value_parser(|s: &str| Regex::new(s).map_err(|e| e.to_string()))
+                .help("Regular expression extracting the user name from the file paths, from its 'user' named group or its first group, instead of the directory after 'Users' or 'Documents and Settings'")
+        )
+        .arg(
+            Arg::new("canonicalize-paths")
+                .long("canonicalize-paths")
+                .help("Add a 'target_full_path_canonical' column with the target paths canonicalized ('\\\\?\\' prefix, separators, drive letter case, trailing slashes, well-known environment variables such as %USERPROFILE%), and group them by it with --aggregate")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("filetype-map")
+                .long("filetype-map")
+                .value_name("PATH")
+                .value_parser(|path: &str| FiletypeMap::from_path(path).map(Arc::new).map_err(|e| e.message().to_string()))
+                .help("JSON object mapping extensions to categories (e.g. {\"one\": \"document\"}), overriding the internal table of the 'target_category' column")
+        )
+        .arg(
+            Arg::new("filetype-columns")
+                .long("filetype-columns")
+                .help("Add the 'target_extension' and 'target_category' columns to the CSV output")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("print-schema")
+                .long("print-schema")
+                .help("Print the JSON Schema of the output and exit")
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("extract-lnk")
+                .long("extract-lnk")
+                .value_name("DIR")
+                .help("Write the raw LNK entries embedded in the Jumplist files to this directory")
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two snapshots of the same Jumplist file and print the changes as JSON")
+                .arg(Arg::new("old").value_name("OLD").help("The older snapshot").required(true))
+                .arg(Arg::new("new").value_name("NEW").help("The newer snapshot").required(true))
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Validate the structure of Jumplist files and print one ok/warn/fail line per file. Files with more than 25% of their DestList stream after the last entry get a note. Exits with 0 when all are ok, 1 when the worst is a warning and 2 on a failure")
+                .arg(
+                    Arg::new("paths")
+                        .value_name("PATHS")
+                        .help("Path(s) to Jumplist files to be verified - accepts glob")
+                        .num_args(1..)
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["table", "jsonl"])
+                        .default_value("table")
+                        .help("Output format of the results")
+                )
+                .arg(
+                    Arg::new("deep")
+                        .long("deep")
+                        .help("Always parse the LNK streams of automatic Jumplists, not only when the DestList has findings")
+                        .action(ArgAction::SetTrue)
+                )
+        )
         .get_matches()
 }
 
-fn output_data_csv(parsed: JumplistParser) -> String {
-    let app_id = &parsed.app_id.clone().unwrap_or_default().to_owned();
-    let app_name = &parsed.app_name.clone().unwrap_or_default().to_owned();
-    let data = parsed.flaten();
-    let mut records: Vec<String> = vec![];
-    for i in 0..data.len() {
-        let row = data[i].to_owned();
-        records.push(format!(
-            "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"",
-            app_id,
-            app_name,
-            parsed.r#type,
-            row.get("target_full_path").unwrap(),
-            row.get("command_line_arguments")
-                .unwrap()
-                .replace("\"", "\"\""),
-            row.get("name_string").unwrap(),
-            row.get("target_modification_time").unwrap(),
-            row.get("target_access_time").unwrap(),
-            row.get("target_creation_time").unwrap(),
-            row.get("target_size").unwrap(),
-            row.get("target_hostname").unwrap(),
-        ));
+/// Parse the `--delimiter` value, accepting `\t` and `tab` for the tab character.
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err("the delimiter must be a single ASCII character".to_string()),
+    }
+}
+
+/// The paths listed in the `--input-list` file (`-` for stdin), one per line. Lines are
+/// trimmed, blank lines and `#` comments are skipped. With `null`, paths are separated by NUL
+/// characters and used as is. Paths that don't exist are returned as errors, to be reported
+/// and skipped.
+fn read_input_list(
+    source: &str,
+    null: bool,
+) -> io::Result<Box<dyn Iterator<Item = Result<PathBuf, String>> + '_>> {
+    let reader: Box<dyn BufRead> = match source {
+        "-" => Box::new(io::stdin().lock()),
+        _ => Box::new(BufReader::new(File::open(source)?)),
+    };
+    let delimiter = match null {
+        true => 0,
+        false => b'\n',
+    };
+    let paths = reader
+        .split(delimiter)
+        .map_while(move |item| match item {
+            Ok(item) => Some(item),
+            Err(e) => {
+                eprintln!("Can't read the input list '{}'. ERROR : '{}'", source, e);
+                None
+            }
+        })
+        .filter_map(move |item| {
+            let path = match String::from_utf8(item) {
+                Ok(path) => path,
+                Err(e) => {
+                    return Some(Err(format!(
+                        "The listed path '{}' isn't valid UTF-8, skipping it",
+                        String::from_utf8_lossy(e.as_bytes())
+                    )))
+                }
+            };
+            let path = match null {
+                true => path.as_str(),
+                false => path.trim(),
+            };
+            if path.is_empty() || (!null && path.starts_with('#')) {
+                return None;
+            }
+            match Path::new(path).exists() {
+                true => Some(Ok(PathBuf::from(path))),
+                false => Some(Err(format!(
+                    "The listed path '{}' doesn't exist, skipping it",
+                    path
+                ))),
+            }
+        });
+    Ok(Box::new(paths))
+}
+
+/// Parse the Jumplist read from stdin with `--stdin`, `jumplist_type` overrides the type
+/// given by `name` or detected from the content.
+fn parse_stdin(
+    jumplist_type: Option<&str>,
+    name: Option<&str>,
+    options: &ParseOptions,
+) -> ParseOutcome {
+    // stdin isn't seekable, the file is buffered in memory
+    let mut data = vec![];
+    if let Err(e) = io::stdin().read_to_end(&mut data) {
+        return ParseOutcome::from(Err(JumplistParserError::General(
+            format!("Can't read stdin: {}", e),
+            line!(),
+            file!().to_string(),
+        )));
+    }
+    ParseOutcome::parse_with(options, |options| {
+        parse_stdin_data(&data, jumplist_type, name, options)
+    })
+}
+
+/// Parse the `data` read from stdin.
+fn parse_stdin_data(
+    data: &[u8],
+    jumplist_type: Option<&str>,
+    name: Option<&str>,
+    options: &ParseOptions,
+) -> Result<JumplistParser, JumplistParserError> {
+    let jumplist_type = match jumplist_type {
+        Some("automatic") => JumplistType::Automatic,
+        Some(_) => JumplistType::Custom,
+        None => return JumplistParser::from_bytes_with_options(data, name, options),
+    };
+    let mut parsed =
+        JumplistParser::from_reader_with_options(&mut Cursor::new(data), jumplist_type, options)?;
+    if let Some(name) = name {
+        parsed.set_app_id_from_file_name(name);
     }
-    records.join("\n")
+    Ok(parsed)
+}
+
+/// The Jumplist type of a `--force-type` value or `--path` suffix.
+fn parse_jumplist_type(name: &str) -> Option<JumplistType> {
+    match name {
+        "automatic" => Some(JumplistType::Automatic),
+        "custom" => Some(JumplistType::Custom),
+        _ => None,
+    }
+}
+
+/// Split the `:automatic` or `:custom` suffix forcing the type of the files matched by a
+/// `--path` pattern. Other colons, such as the one of a drive letter, are part of the path.
+fn split_path_type(pattern: &str) -> (&str, Option<JumplistType>) {
+    match pattern.rsplit_once(':') {
+        Some((path, suffix)) => match parse_jumplist_type(suffix) {
+            Some(jumplist_type) => (path, Some(jumplist_type)),
+            None => (pattern, None),
+        },
+        None => (pattern, None),
+    }
+}
+
+/// The `--search` and `--search-regex` filters, an entry matches if any of them does.
+struct Search {
+    terms: Vec<String>,
+    regexes: Vec<Regex>,
+}
+
+impl Search {
+    fn from_args(args: &ArgMatches) -> Option<Self> {
+        let terms: Vec<String> = args
+            .get_many::<String>("search")
+            .map(|terms| terms.map(|t| t.to_lowercase()).collect())
+            .unwrap_or_default();
+        let regexes: Vec<Regex> = args
+            .get_many::<Regex>("search-regex")
+            .map(|regexes| regexes.cloned().collect())
+            .unwrap_or_default();
+        (!terms.is_empty() || !regexes.is_empty()).then_some(Self { terms, regexes })
+    }
+
+    /// The matching entries of `parsed`: their index in the output rows and the names of
+    /// the fields that matched, separated by `;`.
+    fn matches(&self, parsed: &JumplistParser) -> BTreeMap<usize, String> {
+        let mut matches: BTreeMap<usize, String> = BTreeMap::new();
+        let hits = parsed.search_by(|value| {
+            let lowercase = value.to_lowercase();
+            self.terms.iter().any(|t| lowercase.contains(t))
+                || self.regexes.iter().any(|r| r.is_match(value))
+        });
+        for hit in hits {
+            let fields = matches.entry(hit.index).or_default();
+            if !fields.is_empty() {
+                fields.push(';');
+            }
+            fields.push_str(&hit.field);
+        }
+        matches
+    }
+}
+
+/// Keep the rows of the matching entries and add their matched fields with `add_field`.
+fn filter_rows<T>(
+    rows: Vec<T>,
+    matches: &BTreeMap<usize, String>,
+    add_field: impl Fn(&mut T, String),
+) -> Vec<T> {
+    rows.into_iter()
+        .enumerate()
+        .filter_map(|(index, mut row)| {
+            let fields = matches.get(&index)?;
+            add_field(&mut row, fields.clone());
+            Some(row)
+        })
+        .collect()
+}
+
+/// The raw record of `parsed` for `--legacy-keys`: the `pinned` key of the `DestList`
+/// entries is named `pined` as before, and the `reserved` field of the custom header, not
+/// written before, is removed.
+fn legacy_raw_record(parsed: &JumplistParser) -> serde_json::Value {
+    let mut record = serde_json::to_value(parsed).unwrap_or_default();
+    if parsed.r#type == JumplistType::Custom {
+        if let Some(header) = record
+            .pointer_mut("/data/header")
+            .and_then(|header| header.as_object_mut())
+        {
+            header.remove("reserved");
+        }
+    }
+    let entries = record
+        .pointer_mut("/data/entries")
+        .and_then(|entries| entries.as_array_mut());
+    for entry in entries.into_iter().flatten() {
+        if let Some(entry) = entry.as_object_mut() {
+            if let Some(pinned) = entry.remove("pinned") {
+                entry.insert("pined".to_string(), pinned);
+            }
+        }
+    }
+    record
+}
+
+/// A file of the `--output-dir` manifest (`index.json`).
+#[derive(Serialize)]
+struct ManifestEntry {
+    /// Name of the JSON file in the output directory.
+    output_file: String,
+    source_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    /// `parsed` or `failed`.
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Size and SHA-256 of a source file for the `--output-dir` manifest, computed from the
+/// bytes that were parsed.
+struct SourceDigest {
+    size: u64,
+    sha256: String,
 }
 
+impl SourceDigest {
+    fn of(data: &[u8]) -> Self {
+        SourceDigest {
+            size: data.len() as u64,
+            sha256: to_hex(&Sha256::digest(data)),
+        }
+    }
+}
+
+/// Read the whole file at `path`, to parse and hash the same bytes with `--output-dir`.
+fn read_source(path: &str, live: bool) -> Result<Vec<u8>, JumplistParserError> {
+    #[cfg(target_os = "windows")]
+    let file = match live {
+        true => jumplist_parser::live::open_shared(path),
+        false => File::open(path),
+    };
+    #[cfg(not(target_os = "windows"))]
+    let file = {
+        let _ = live;
+        File::open(path)
+    };
+    let mut data = vec![];
+    file.and_then(|mut file| file.read_to_end(&mut data))
+        .map_err(|e| {
+            JumplistParserError::JumplistParser(
+                format!("Can't read the file '{}', ERROR: {}", path, e),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+    Ok(data)
+}
+
+/// Writes the JSON of every parsed file to its own file (`--output-dir`).
+struct OutputDir {
+    dir: PathBuf,
+    manifest: Vec<ManifestEntry>,
+}
+
+impl OutputDir {
+    fn create(dir: &str) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(OutputDir {
+            dir: PathBuf::from(dir),
+            manifest: vec![],
+        })
+    }
+
+    /// Write `json`, the output of `source_path`, to `<app_id>.<type>.json`. When another
+    /// Jumplist of this run already used the name (e.g. the same AppID for another user),
+    /// the first 8 hex digits of the SHA-256 of the source path are added to the name.
+    fn write(
+        &mut self,
+        source_path: &str,
+        app_id: &str,
+        jumplist_type: &str,
+        json: &str,
+        digest: Option<SourceDigest>,
+        error: Option<String>,
+    ) {
+        let stem = format!("{}.{}", sanitize_file_name(app_id), jumplist_type);
+        let mut output_file = format!("{}.json", stem);
+        if self.manifest.iter().any(|e| e.output_file == output_file) {
+            let path_hash = to_hex(&Sha256::digest(source_path.as_bytes()));
+            output_file = format!("{}.{}.json", stem, &path_hash[..8]);
+        }
+        if let Err(e) = std::fs::write(self.dir.join(&output_file), json) {
+            eprintln!(
+                "Can't write the file '{}'. ERROR : '{}'",
+                self.dir.join(&output_file).display(),
+                e
+            );
+        }
+        let (size, sha256) = match digest {
+            Some(digest) => (Some(digest.size), Some(digest.sha256)),
+            None => (None, None),
+        };
+        self.manifest.push(ManifestEntry {
+            output_file,
+            source_path: source_path.to_string(),
+            size,
+            sha256,
+            status: match error {
+                Some(_) => "failed",
+                None => "parsed",
+            },
+            error,
+        });
+    }
+
+    /// Record a file that failed to parse, with a stub JSON holding the error.
+    fn write_failed(&mut self, source_path: &str, digest: Option<SourceDigest>, error: String) {
+        let file_name = Path::new(source_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (app_id, _) = app_id_from_file_name(&file_name);
+        let jumplist_type =
+            JumplistParser::type_from_name(&file_name, &FileNameSuffixes::default())
+                .map_or("unknown".to_string(), |t| t.to_string());
+        let stub = serde_json::json!({ "source_path": source_path, "error": error });
+        let json = serde_json::to_string_pretty(&stub).unwrap_or("{}".to_string());
+        self.write(
+            source_path,
+            &app_id,
+            &jumplist_type,
+            &json,
+            digest,
+            Some(error),
+        );
+    }
+
+    /// Write `index.json`, sorted by source path.
+    fn finish(mut self) -> io::Result<()> {
+        self.manifest
+            .sort_by(|a, b| a.source_path.cmp(&b.source_path));
+        let json = serde_json::to_string_pretty(&self.manifest).unwrap_or("[]".to_string());
+        std::fs::write(self.dir.join("index.json"), json)
+    }
+}
+
+/// A file of the `--error-report`, that failed to parse or parsed with warnings.
+#[derive(Serialize)]
+struct ErrorReportRecord<'a> {
+    source_path: &'a str,
+    /// `failed`, `empty` (a file too small to be a Jumplist), `warning` or `partial` (a file
+    /// that failed with `--strict` and whose entries were recovered).
+    status: &'static str,
+    size: Option<u64>,
+    /// Type given by the name or the content of the file, `None` when neither does.
+    r#type: Option<JumplistType>,
+    /// [`JumplistParserError::kind`] of a failed or partially parsed file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    warnings: &'a [String],
+    /// Entries recovered from a file with warnings or partially parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entries: Option<usize>,
+    /// Entries the file declares, when it records a count.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    declared_entries: Option<usize>,
+}
+
+/// Writes a JSON line for every file that failed to parse or parsed with warnings
+/// (`--error-report`).
+struct ErrorReport {
+    file: File,
+}
+
+impl ErrorReport {
+    fn create(path: &str) -> io::Result<Self> {
+        Ok(ErrorReport {
+            file: File::create(path)?,
+        })
+    }
+
+    fn write(&mut self, record: &ErrorReportRecord) {
+        let mut line = serde_json::to_string(record).unwrap_or("{}".to_string());
+        line.push('\n');
+        if let Err(e) = self.file.write_all(line.as_bytes()) {
+            eprintln!("Can't write the error report. ERROR : '{}'", e);
+        }
+    }
+
+    /// Record the file at `path` that failed with `error`.
+    fn failed(&mut self, path: &str, error: &JumplistParserError, suffixes: &FileNameSuffixes) {
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let r#type = JumplistParser::type_from_name(&file_name, suffixes).or_else(|| {
+            let mut file = File::open(path).ok()?;
+            JumplistParser::type_from_reader(&mut file)
+        });
+        self.write(&ErrorReportRecord {
+            source_path: path,
+            status: match error {
+                JumplistParserError::EmptyOrTruncatedFile { .. } => "empty",
+                _ => "failed",
+            },
+            size: std::fs::metadata(path).ok().map(|m| m.len()),
+            r#type,
+            kind: Some(error.kind()),
+            message: Some(error.to_string()),
+            warnings: &[],
+            entries: None,
+            declared_entries: None,
+        });
+    }
+
+    /// Record the file at `path` when it was parsed with warnings.
+    fn warned(&mut self, path: &str, parsed: &JumplistParser) {
+        if parsed.warnings().is_empty() {
+            return;
+        }
+        self.recovered(path, parsed, "warning", None);
+    }
+
+    /// Record the file at `path` that failed with `error`, whose entries were recovered by
+    /// a lenient parse.
+    fn partial(&mut self, path: &str, parsed: &JumplistParser, error: &JumplistParserError) {
+        self.recovered(path, parsed, "partial", Some(error));
+    }
+
+    fn recovered(
+        &mut self,
+        path: &str,
+        parsed: &JumplistParser,
+        status: &'static str,
+        error: Option<&JumplistParserError>,
+    ) {
+        let declared_entries = match (parsed.destlist(), parsed.custom_destinations()) {
+            (Some(destlist), _) => Some(destlist.header.number_of_entries as usize),
+            (_, Some(custom)) => custom
+                .entries
+                .iter()
+                .map(|c| c.num_of_entries.map(|n| n as usize))
+                .sum(),
+            _ => None,
+        };
+        self.write(&ErrorReportRecord {
+            source_path: path,
+            status,
+            size: std::fs::metadata(path).ok().map(|m| m.len()),
+            r#type: Some(parsed.r#type.clone()),
+            kind: error.map(|e| e.kind()),
+            message: error.map(|e| e.to_string()),
+            warnings: parsed.warnings(),
+            entries: Some(parsed.entry_count()),
+            declared_entries,
+        });
+    }
+}
+
+/// The output row of an entry: the fields of the CSV or table output, or the normalized
+/// entry of the JSON formats.
+enum Row {
+    Fields(Vec<String>),
+    Normalized(NormalizedEntry),
+    /// `None` for the entries without a timestamp, left out of the output.
+    Tln(Option<TlnRecord>),
+}
+
+impl Row {
+    fn add_matched_field(&mut self, fields: String) {
+        match self {
+            Row::Fields(row) => row.push(fields),
+            Row::Normalized(row) => {
+                row.insert("matched_field".to_string(), fields);
+            }
+            // The TLN fields are fixed
+            Row::Tln(_) => {}
+        }
+    }
+}
+
+/// The TLN lines of `records`, the records left out are added to `skipped`.
+fn tln_lines(records: Vec<Option<TlnRecord>>, skipped: &mut usize) -> Vec<u8> {
+    let mut lines = String::new();
+    for record in records {
+        match record {
+            Some(record) => lines.push_str(&format!("{}\n", record)),
+            None => *skipped += 1,
+        }
+    }
+    lines.into_bytes()
+}
+
+/// Encode the rows of single entries (with `--sort-by` or `--limit`) in `format`, a JSON
+/// array or one JSON object per line for the JSON formats.
+fn encode_entry_rows(
+    format: &OutputFormat,
+    title: &str,
+    table_columns: &[String],
+    rows: Vec<Row>,
+    csv_options: &CsvOptions,
+    table_options: &TableOptions,
+) -> Vec<u8> {
+    let fields = |rows: Vec<Row>| -> Vec<Vec<String>> {
+        rows.into_iter()
+            .filter_map(|row| match row {
+                Row::Fields(row) => Some(row),
+                _ => None,
+            })
+            .collect()
+    };
+    let normalized = |rows: Vec<Row>| -> Vec<NormalizedEntry> {
+        rows.into_iter()
+            .filter_map(|row| match row {
+                Row::Normalized(row) => Some(row),
+                _ => None,
+            })
+            .collect()
+    };
+    match format {
+        OutputFormat::Json => serde_json::to_string(&normalized(rows))
+            .unwrap_or("[]".to_string())
+            .into_bytes(),
+        OutputFormat::Jsonl => normalized(rows)
+            .iter()
+            .filter_map(|row| serde_json::to_string(row).ok())
+            .map(|line| line + "\n")
+            .collect::<String>()
+            .into_bytes(),
+        OutputFormat::Csv | OutputFormat::Tsv => encode_rows(&fields(rows), csv_options),
+        OutputFormat::Table => {
+            render_rows(title, table_columns, &fields(rows), table_options).into_bytes()
+        }
+        OutputFormat::Tln => rows
+            .into_iter()
+            .filter_map(|row| match row {
+                Row::Tln(Some(record)) => Some(format!("{}\n", record)),
+                _ => None,
+            })
+            .collect::<String>()
+            .into_bytes(),
+    }
+}
+
+/// The records of `parsed` (see [`JumplistParser::records`]) with the size and the
+/// modification time from `metadata` when the file was read from disk, without the offset
+/// keys unless `offsets` is set.
+fn normalized_entries(
+    parsed: &JumplistParser,
+    metadata: Option<&Metadata>,
+    ts_format: TimestampFormat,
+    offsets: bool,
+) -> Vec<NormalizedEntry> {
+    let mut normalized = match metadata {
+        Some(metadata) => parsed.records_with_metadata(metadata, ts_format),
+        None => parsed.records(),
+    };
+    if !offsets {
+        normalized
+            .iter_mut()
+            .for_each(|e| e.retain(|k, _| !OFFSET_CSV_COLUMNS.contains(&k.as_str())));
+    }
+    normalized
+}
+
+/// Restrict normalized entries of `parsed` to `--fields`, when given.
+fn restrict_fields(
+    parsed: &JumplistParser,
+    entries: Vec<NormalizedEntry>,
+    fields: Option<&[&'static str]>,
+) -> Vec<NormalizedEntry> {
+    match fields {
+        Some(fields) => entries
+            .into_iter()
+            .map(|entry| select_fields(parsed, entry, fields))
+            .collect(),
+        None => entries,
+    }
+}
+
+/// Width of the terminal the output is written to, from the `COLUMNS` environment variable
+/// when it can't be queried.
+fn terminal_width() -> Option<usize> {
+    match terminal_size::terminal_size_of(io::stdout()) {
+        Some((terminal_size::Width(width), _)) => Some(width as usize),
+        None => std::env::var("COLUMNS").ok()?.parse().ok(),
+    }
+}
+
+/// The table output options: the values are shortened to the terminal width unless `--wide`
+/// is given, and colorized when writing to a terminal without `--no-color` or `NO_COLOR`.
+fn table_options(args: &ArgMatches, output_to: &str) -> TableOptions {
+    let to_terminal = output_to == "stdout" && io::stdout().is_terminal();
+    TableOptions {
+        columns: match args.get_many::<String>("columns") {
+            Some(columns) => columns.map(|c| c.trim().to_string()).collect(),
+            None => TABLE_COLUMNS.iter().map(|c| c.to_string()).collect(),
+        },
+        width: match args.get_flag("wide") || output_to != "stdout" {
+            true => None,
+            false => terminal_width(),
+        },
+        color: to_terminal
+            && !args.get_flag("no-color")
+            && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+    }
+}
+
+/// Columns of the `verify` table.
+const VERIFY_COLUMNS: &[&str] = &[
+    "path", "type", "status", "entries", "declared", "findings", "notes",
+];
+
+/// Set by the Ctrl-C handler installed for compressed output and `--watch`.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// The progress bar is drawn when more files than this are queued.
+const PROGRESS_MIN_FILES: usize = 5;
+
 fn main() {
     let args = parse_cli_args();
-    let output_format = OutputFormat::from_str(args.get_one::<String>("output-format").unwrap());
+    if args.get_flag("print-schema") {
+        #[cfg(feature = "schema")]
+        println!("{}", jumplist_parser::schema::json_schema());
+        #[cfg(not(feature = "schema"))]
+        eprintln!("jumplist_parser was built without the 'schema' feature");
+        return;
+    }
+    if args.get_flag("list-fields") && args.get_flag("raw") {
+        let width = RAW_FIELDS
+            .iter()
+            .map(|f| f.name.len())
+            .max()
+            .unwrap_or_default();
+        for field in RAW_FIELDS {
+            println!(
+                "{:width$}  {}",
+                field.name,
+                field.description,
+                width = width
+            );
+        }
+        return;
+    }
+    if args.get_flag("list-fields") {
+        let width = FIELDS
+            .iter()
+            .map(|f| f.name.len())
+            .max()
+            .unwrap_or_default();
+        for field in FIELDS {
+            let legacy_name = field
+                .legacy_name
+                .map(|name| format!(" (formerly '{}')", name))
+                .unwrap_or_default();
+            println!(
+                "{:width$}  {}{}",
+                field.name,
+                field.description,
+                legacy_name,
+                width = width
+            );
+        }
+        return;
+    }
+    let anomalies_only = args.get_flag("anomalies-only");
+    let output_format = match anomalies_only {
+        true => OutputFormat::Jsonl,
+        false => OutputFormat::from_str(args.get_one::<String>("output-format").unwrap()),
+    };
     let output_to = args.get_one::<String>("output").unwrap().clone();
     let normalize = args.get_flag("normalize");
-    let mut output: Box<dyn Write> = match output_to.as_str() {
+    let extract_lnk = args.get_one::<String>("extract-lnk");
+    let excel = args.get_flag("excel");
+    let csv_options = CsvOptions {
+        delimiter: match output_format {
+            OutputFormat::Tsv => b'\t',
+            _ => *args.get_one::<u8>("delimiter").unwrap(),
+        },
+        safe: excel || !args.get_flag("no-safe-csv"),
+        verbose_columns: args.get_flag("verbose-columns"),
+        resolve_known_folders: args.get_flag("resolve-known-folders"),
+        extended_columns: args.get_flag("extended-columns"),
+        offset_columns: args.get_flag("offsets"),
+        filetype_columns: args.get_flag("filetype-columns"),
+        canonical_paths: args.get_flag("canonicalize-paths"),
+        fields: args
+            .get_many::<&'static str>("fields")
+            .map(|fields| fields.copied().collect()),
+        crlf: excel || args.get_flag("crlf"),
+        legacy_keys: args.get_flag("legacy-keys"),
+        raw: args.get_flag("raw-csv"),
+    };
+    let table_options = table_options(&args, &output_to);
+    let verbose = args.get_flag("verbose");
+    let offsets = args.get_flag("offsets");
+    let fields = csv_options.fields.as_deref();
+    // Files processed and entries written, only drawn once there are enough files (see
+    // `PROGRESS_MIN_FILES`)
+    let progress_bar = ProgressBar::hidden();
+    let options = ParseOptions {
+        keep_raw_lnk: extract_lnk.is_some(),
+        ts_format: {
+            let format: TimestampFormat = args
+                .get_one::<String>("timestamp-format")
+                .unwrap()
+                .parse()
+                .unwrap();
+            match args.get_one::<Tz>("tz") {
+                Some(timezone) => format.with_timezone(*timezone),
+                None => format,
+            }
+        },
+        guid_format: GuidFormat {
+            zero: args
+                .get_one::<String>("zero-guids")
+                .unwrap()
+                .parse()
+                .unwrap(),
+            ..args
+                .get_one::<String>("guid-format")
+                .unwrap()
+                .parse()
+                .unwrap()
+        },
+        capture_unknown_bytes: args.get_flag("capture-unknown-bytes"),
+        extra_stream_preview: *args.get_one::<usize>("extra-stream-preview").unwrap(),
+        disable_carving: args.get_flag("no-carving"),
+        mode: match args.get_flag("strict") {
+            true => ParseMode::Strict,
+            false => ParseMode::Lenient,
+        },
+        detect_anomalies: anomalies_only,
+        skip_lnks: false,
+        canonicalize_paths: args.get_flag("canonicalize-paths"),
+        legacy_keys: args.get_flag("legacy-keys"),
+        filetype_map: args.get_one::<Arc<FiletypeMap>>("filetype-map").cloned(),
+        limits: ParseLimits::default(),
+        file_name_suffixes: FileNameSuffixes::default(),
+        collect_stats: verbose,
+        progress: Some(Progress::new({
+            let progress_bar = progress_bar.clone();
+            move |event| {
+                if let ProgressEvent::EntriesParsed { entries } = event {
+                    progress_bar.set_message(format!("reading entry {}", entries));
+                }
+            }
+        })),
+    };
+    let mmap = args.get_flag("mmap");
+    #[cfg(not(feature = "mmap"))]
+    if mmap {
+        eprintln!("jumplist_parser was built without the 'mmap' feature, --mmap is ignored");
+    }
+    let live = args.get_flag("live");
+    #[cfg(not(target_os = "windows"))]
+    if live {
+        eprintln!("--live is only supported on Windows, it is ignored");
+    }
+    let ts_format = options.ts_format;
+    let force_type = args
+        .get_one::<String>("force-type")
+        .and_then(|name| parse_jumplist_type(name));
+    let include_recent_lnks = args.get_flag("include-recent-lnks");
+    // The --output-dir manifest hashes the bytes that were parsed, by source path
+    let hash_sources = args.get_one::<String>("output-dir").is_some();
+    let source_digests = RefCell::new(BTreeMap::new());
+    let parse_file = |path: &str, jumplist_type: Option<JumplistType>, options: &ParseOptions| {
+        let jumplist_type = jumplist_type.or_else(|| {
+            let is_lnk = Path::new(path)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+            (include_recent_lnks && is_lnk).then_some(JumplistType::RecentLnk)
+        });
+        if hash_sources {
+            let data = read_source(path, live)?;
+            source_digests
+                .borrow_mut()
+                .insert(path.to_string(), SourceDigest::of(&data));
+            return JumplistParser::from_file_bytes(&data, path, jumplist_type, options);
+        }
+        if let Some(jumplist_type) = jumplist_type {
+            return JumplistParser::from_path_with_type_and_options(path, jumplist_type, options);
+        }
+        #[cfg(target_os = "windows")]
+        if live {
+            return JumplistParser::from_path_live(path, options);
+        }
+        #[cfg(feature = "mmap")]
+        if mmap {
+            return JumplistParser::from_path_mmap(path, options);
+        }
+        JumplistParser::from_path_with_options(path, options)
+    };
+    let compress = args.get_flag("compress") || output_to.ends_with(".gz");
+    let output: Box<dyn Write> = match output_to.as_str() {
         "stdout" => Box::new(io::stdout()),
-        _ => Box::new(File::create(output_to).unwrap()),
+        _ => Box::new(File::create(&output_to).unwrap()),
     };
-
-    if args.get_flag("no-headers") == false {
-        match output_format {
-            OutputFormat::CSV => {
-                output.write(r#""app_id","app_name","type","target_full_path","command_line_arguments","name_string","target_modification_time","target_access_time","target_creation_time","target_size","target_hostname""#.as_bytes()).expect("Error Writing Data !");
-                output.write(b"\n").expect("Error Writing Data !");
+    let mut output: Box<dyn Write> = match compress {
+        // The gzip stream is finished when the encoder is dropped, at the end of `main`
+        true => Box::new(GzEncoder::new(output, Compression::default())),
+        false => output,
+    };
+    let watching = args.get_flag("watch");
+    if compress || watching {
+        // Stop after the current file on Ctrl-C so the gzip stream is finished, a second
+        // Ctrl-C exits right away
+        let handler = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, atomic::Ordering::SeqCst) {
+                std::process::exit(130);
             }
-            _ => {}
+        });
+        if let Err(e) = handler {
+            eprintln!("Can't install the Ctrl-C handler. ERROR : '{}'", e);
+        }
+    }
+
+    if let Some(diff_args) = args.subcommand_matches("diff") {
+        let parse = |name: &str| {
+            let path = diff_args.get_one::<String>(name).unwrap();
+            parse_file(path, force_type.clone(), &options).unwrap_or_else(|e| {
+                eprintln!("Did not parse '{}' correctly. ERROR : '{}'", path, e);
+                std::process::exit(1);
+            })
         };
+        let changes = diff(&parse("old"), &parse("new"));
+        let json_data = serde_json::to_string_pretty(&changes).unwrap_or("{}".to_string());
+        output
+            .write_all(json_data.as_bytes())
+            .expect("Error Writing Data !");
+        output.write_all(b"\n").expect("Error Writing Data !");
+        return;
+    }
+
+    if let Some(verify_args) = args.subcommand_matches("verify") {
+        let deep = verify_args.get_flag("deep");
+        let jsonl = verify_args.get_one::<String>("format").unwrap() == "jsonl";
+        let mut results = vec![];
+        for pattern in verify_args.get_many::<String>("paths").unwrap() {
+            let mut paths = expand_pattern(pattern)
+                .expect("Failed to read glob pattern")
+                .into_iter()
+                .flatten()
+                .peekable();
+            let checked = match paths.peek() {
+                Some(_) => paths
+                    .map(|path| verify_path(&path.to_string_lossy(), deep))
+                    .collect(),
+                None => vec![VerifyResult {
+                    path: pattern.clone(),
+                    r#type: None,
+                    status: VerifyStatus::Fail,
+                    entries: 0,
+                    declared_entries: None,
+                    version_supported: None,
+                    findings: vec!["No file matches the path".to_string()],
+                    notes: vec![],
+                }],
+            };
+            for result in checked {
+                if jsonl {
+                    let line = serde_json::to_string(&result).unwrap_or("{}".to_string());
+                    output
+                        .write_all(line.as_bytes())
+                        .and_then(|_| output.write_all(b"\n"))
+                        .expect("Error Writing Data !");
+                }
+                results.push(result);
+            }
+        }
+        let worst = results
+            .iter()
+            .map(|r| r.status)
+            .max()
+            .unwrap_or(VerifyStatus::Ok);
+        if !jsonl {
+            let columns: Vec<String> = VERIFY_COLUMNS.iter().map(|c| c.to_string()).collect();
+            let rows: Vec<Vec<String>> = results
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.path.clone(),
+                        r.r#type.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+                        r.status.to_string(),
+                        r.entries.to_string(),
+                        r.declared_entries
+                            .map(|n| n.to_string())
+                            .unwrap_or_default(),
+                        r.findings.join("; "),
+                        r.notes.join("; "),
+                    ]
+                })
+                .collect();
+            let count = |status| results.iter().filter(|r| r.status == status).count();
+            let title = format!(
+                "{} files: {} ok, {} warn, {} fail",
+                results.len(),
+                count(VerifyStatus::Ok),
+                count(VerifyStatus::Warn),
+                count(VerifyStatus::Fail)
+            );
+            let table = render_rows(&title, &columns, &rows, &table_options);
+            output
+                .write_all(table.as_bytes())
+                .expect("Error Writing Data !");
+        }
+        // `exit` skips the destructors, finish the output first
+        drop(output);
+        std::process::exit(match worst {
+            VerifyStatus::Ok => 0,
+            VerifyStatus::Warn => 1,
+            VerifyStatus::Fail => 2,
+        });
     }
 
+    let mut output_dir = args.get_one::<String>("output-dir").map(|dir| {
+        OutputDir::create(dir).unwrap_or_else(|e| {
+            eprintln!(
+                "Can't create the output directory '{}'. ERROR : '{}'",
+                dir, e
+            );
+            std::process::exit(1);
+        })
+    });
+    let error_report = args.get_one::<String>("error-report").map(|path| {
+        ErrorReport::create(path).unwrap_or_else(|e| {
+            eprintln!("Can't create the error report '{}'. ERROR : '{}'", path, e);
+            std::process::exit(1);
+        })
+    });
+    let output_url = args.get_one::<String>("output-url");
+    #[cfg(not(feature = "http"))]
+    if output_url.is_some() {
+        eprintln!(
+            "jumplist_parser was built without the 'http' feature, --output-url is not available"
+        );
+        std::process::exit(1);
+    }
+    #[cfg(feature = "http")]
+    let mut sink = output_url.map(|url| {
+        HttpSink::new(HttpSinkOptions {
+            url: url.clone(),
+            auth: args.get_one::<String>("output-auth").cloned(),
+            batch_size: *args.get_one::<usize>("batch-size").unwrap(),
+            hec: args.get_flag("hec"),
+            insecure: args.get_flag("insecure"),
+            spool_path: PathBuf::from(args.get_one::<String>("spool").unwrap()),
+            ..Default::default()
+        })
+    });
+    let aggregate = args.get_flag("aggregate");
+    if aggregate && matches!(output_format, OutputFormat::Tln) {
+        eprintln!("The TLN output has one line per entry, it can't be used with --aggregate");
+        std::process::exit(1);
+    }
+    let mut summary = args.get_flag("summary").then(Summary::default);
+    if summary.is_some() && matches!(output_format, OutputFormat::Tln) {
+        eprintln!("The TLN output has one line per entry, it can't be used with --summary");
+        std::process::exit(1);
+    }
+    let group_by_user = args.get_flag("group-by-user");
+    let search = Search::from_args(&args);
+    let input_list = args.get_one::<String>("input-list");
+    let stdin = args.get_flag("stdin");
+    let stdin_name = args.get_one::<String>("name").map(|name| name.as_str());
+    let listed = match input_list {
+        Some(source) => read_input_list(source, args.get_flag("null")).unwrap_or_else(|e| {
+            eprintln!("Can't open the input list '{}'. ERROR : '{}'", source, e);
+            std::process::exit(1);
+        }),
+        None => Box::new(std::iter::empty()),
+    };
+    #[cfg(not(feature = "watch"))]
+    if watching {
+        eprintln!(
+            "jumplist_parser was built without the 'watch' feature, --watch is not available"
+        );
+        std::process::exit(1);
+    }
+    let bom = excel || args.get_flag("bom");
+    if bom && output_dir.is_none() && output_url.is_none() && !watching {
+        if let OutputFormat::Csv | OutputFormat::Tsv = output_format {
+            output.write_all(UTF8_BOM).expect("Error Writing Data !");
+        }
+    }
+    if !args.get_flag("no-headers") && output_dir.is_none() && output_url.is_none() && !watching {
+        if let OutputFormat::Csv | OutputFormat::Tsv = output_format {
+            let header = match (aggregate, summary.is_some()) {
+                (true, _) => encode_rows(
+                    &[AGGREGATE_CSV_COLUMNS
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect()],
+                    &csv_options,
+                ),
+                (_, true) => encode_rows(
+                    &[SUMMARY_CSV_COLUMNS.iter().map(|c| c.to_string()).collect()],
+                    &csv_options,
+                ),
+                (false, false) => {
+                    let mut columns: Vec<String> = csv_columns(&csv_options)
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect();
+                    if search.is_some() {
+                        columns.push("matched_field".to_string());
+                    }
+                    encode_rows(&[columns], &csv_options)
+                }
+            };
+            output.write_all(&header).expect("Error Writing Data !");
+        }
+    }
+
+    // Default paths for Jumplist files, under `C:\` on Windows and `/mnt/c` (WSL) elsewhere
+    let mut jumplist_paths = LocationOptions {
+        recent_lnks: include_recent_lnks,
+        documents_and_settings: args.get_flag("documents-and-settings"),
+    }
+    .paths();
+
+    #[cfg(target_os = "windows")]
+    // Jumplist directories of the profiles registered on the running system
+    let live_paths: Vec<String> = match live {
+        true => match jumplist_parser::live::profile_directories() {
+            Ok(profiles) => profiles
+                .iter()
+                .flat_map(|profile| {
+                    for_user(profile)
+                        .into_iter()
+                        .map(|dir| {
+                            format!("{}\\*ms", glob::Pattern::escape(&dir.to_string_lossy()))
+                        })
+                        .chain(include_recent_lnks.then(|| {
+                            let dir = recent_directory(profile);
+                            format!("{}\\*.lnk", glob::Pattern::escape(&dir.to_string_lossy()))
+                        }))
+                })
+                .collect(),
+            Err(e) => {
+                eprintln!(
+                    "Can't read the user profiles from the registry, using the default paths. ERROR : '{}'",
+                    e
+                );
+                vec![]
+            }
+        },
+        false => vec![],
+    };
     #[cfg(target_os = "windows")]
-    // Default paths for Jumplist files
-    let mut jumplist_paths = vec![
-        r"C:\Users\*\AppData\Roaming\Microsoft\Windows\Recent\AutomaticDestinations\*ms",
-        r"C:\Users\*\AppData\Roaming\Microsoft\Windows\Recent\CustomDestinations\*ms",
-    ];
-
-    #[cfg(target_os = "linux")]
-    // Default paths for Jumplist files while using WSL
-    let mut jumplist_paths = vec![
-        r"/mnt/c/Users/*/AppData/Roaming/Microsoft/Windows/Recent/AutomaticDestinations/*ms",
-        r"/mnt/c/Users/*/AppData/Roaming/Microsoft/Windows/Recent/CustomDestinations/*ms",
-    ];
+    if !live_paths.is_empty() {
+        jumplist_paths = live_paths;
+    }
 
     if let Some(paths) = args.get_many::<String>("path") {
-        jumplist_paths = paths.map(|s| s.as_str()).collect::<Vec<&str>>();
+        jumplist_paths = paths.cloned().collect();
+    } else if input_list.is_some() || stdin {
+        jumplist_paths = vec![];
+    }
+    #[cfg(feature = "watch")]
+    if watching {
+        // The directories holding the files matched by the patterns
+        let directories: std::collections::BTreeSet<PathBuf> = jumplist_paths
+            .iter()
+            .filter_map(|pattern| Path::new(pattern).parent())
+            .flat_map(|dir| {
+                expand_pattern(&dir.to_string_lossy()).expect("Failed to read glob pattern")
+            })
+            .flatten()
+            .filter(|dir| dir.is_dir())
+            .collect();
+        if directories.is_empty() {
+            eprintln!("No Jumplist directory to watch");
+            std::process::exit(1);
+        }
+        let directories: Vec<PathBuf> = directories.into_iter().collect();
+        if verbose {
+            for dir in &directories {
+                eprintln!("Watching '{}'", dir.display());
+            }
+        }
+        let watched = watch(&directories, &options, &INTERRUPTED, |event| {
+            let line = serde_json::to_string(&event).unwrap_or("{}".to_string());
+            output
+                .write_all(line.as_bytes())
+                .and_then(|_| output.write_all(b"\n"))
+                .and_then(|_| output.flush())
+                .expect("Error Writing Data !");
+        });
+        if let Err(e) = watched {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    // Stray files matched by a wildcard (e.g. `*ms`) are skipped unless a type is forced,
+    // and counted
+    let sniff = args.get_flag("sniff");
+    let mut non_jumplist_files = 0usize;
+    let mut is_wanted = |path: &Path| {
+        let is_lnk = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+        let wanted = (include_recent_lnks && is_lnk)
+            || (is_probably_jumplist(path) && (!sniff || sniff_jumplist(path)));
+        if !wanted {
+            non_jumplist_files += 1;
+        }
+        wanted
+    };
+    let mut globbed = vec![];
+    for pattern in jumplist_paths {
+        let (pattern, path_type) = split_path_type(&pattern);
+        let filtered =
+            path_type.is_none() && force_type.is_none() && pattern.contains(['*', '?', '[']);
+        for entry in expand_pattern(pattern).expect("Failed to read glob pattern") {
+            match entry {
+                Ok(path) if filtered && !is_wanted(&path) => {}
+                entry => globbed.push(entry.map(|path| (path, path_type.clone()))),
+            }
+        }
     }
+    if !verbose && globbed.len() > PROGRESS_MIN_FILES && io::stderr().is_terminal() {
+        progress_bar.set_length(globbed.len() as u64);
+        progress_bar.set_style(
+            ProgressStyle::with_template("{bar:30} {pos}/{len} files, {msg}")
+                .expect("Invalid progress bar template"),
+        );
+        progress_bar.set_draw_target(ProgressDrawTarget::stderr());
+    }
+    let mut emitted_entries = 0;
 
     #[derive(Debug, Serialize)]
     #[serde(untagged)]
     enum JsonRecord {
-        Raw(JumplistParser),
-        Normalize(Vec<HashMap<String, String>>),
+        Raw(Box<JumplistParser>),
+        Legacy(serde_json::Value),
+        Normalize(Vec<NormalizedEntry>),
+    }
+    #[derive(Serialize)]
+    struct AnomalyRecord<'a> {
+        source_path: &'a str,
+        #[serde(flatten)]
+        anomaly: &'a Anomaly,
     }
     let mut json_list = vec![];
-    for dir in jumplist_paths {
-        for entry in glob(dir).expect("Failed to read glob pattern") {
-            match entry {
-                Ok(path) => {
-                    let full_path = path.as_path().to_str().unwrap();
-                    match JumplistParser::from_path(full_path) {
-                        Ok(parsed) => match output_format {
-                            OutputFormat::JSONL => {
-                                let json_data;
-                                if normalize {
-                                    let mut normalized = parsed.flaten();
-                                    normalized.iter_mut().for_each(|e| {
-                                        e.insert(
-                                            "app_id".to_string(),
-                                            parsed.app_id.clone().unwrap_or_default(),
-                                        );
-                                        e.insert(
-                                            "app_name".to_string(),
-                                            parsed.app_name.clone().unwrap_or_default(),
-                                        );
-                                    });
-                                    json_data = serde_json::to_string(&normalized).unwrap_or("{}".to_string());
-                                } else {
-                                    json_data = serde_json::to_string(&parsed).unwrap_or("{}".to_string());
-                                }
-                                output
-                                    .write(json_data.as_bytes())
-                                    .expect("Error Writing Data !");
-                                output.write(b"\n").expect("Error Writing Data !");
-                                let _ = output.flush();
-                            }
-                            OutputFormat::JSON => {
-                                if normalize {
-                                    json_list.push(JsonRecord::Normalize(parsed.flaten()));
-                                } else {
-                                    json_list.push(JsonRecord::Raw(parsed));
-                                }
-                            }
-                            OutputFormat::CSV => {
-                                if parsed.flaten().len() > 0 {
-                                    output
-                                        .write(output_data_csv(parsed).as_bytes())
-                                        .expect("Error Writing Data !");
-                                    output.write(b"\n").expect("Error Writing Data !");
-                                    let _ = output.flush();
-                                }
-                            }
-                        },
-                        Err(e) => match e {
-                            JumplistParserError::NoDestList(s, l, f) => {
-                                //get the size of the file in full_path
-                                let file_size = std::fs::metadata(full_path)
-                                    .expect("Unable to get file size")
-                                    .len();
-                                eprintln!(
-                                    "Error parsing the file '{}', Size: {}, {}:{} : structure incorrect: {:?}",
-                                    full_path,
-                                    file_size,
-                                    l,
-                                    f,
-                                    s
-                                );
-                            }
-                            _ => {
-                                eprintln!(
-                                    "Did not parse '{}' correctly. ERROR : '{}'",
-                                    full_path, e
-                                );
-                            }
-                        },
-                    };
-                }
-                Err(e) => eprintln!("{:?}", e),
-            }
-        }
-    }
-    if let OutputFormat::JSON = output_format {
+    let sort_by = args
+        .get_one::<String>("sort-by")
+        .map(|field| field.parse::<SortField>().unwrap());
+    let descending = args.get_flag("descending");
+    let limit = args.get_one::<usize>("limit").copied();
+    // Entries are output one by one instead of per file, buffered when sorting
+    let by_entry = sort_by.is_some() || limit.is_some();
+    let mut remaining = limit;
+    let mut buffered: Vec<(Option<SortKey>, Row)> = vec![];
+    let mut tln_skipped = 0;
+    // Whether to keep the pinned entries only (`true`) or the unpinned ones (`false`)
+    let pinned_filter = match (
+        args.get_flag("pinned-only"),
+        args.get_flag("exclude-pinned"),
+    ) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let only_categories: Option<Vec<FileCategory>> = args
+        .get_many::<FileCategory>("only-category")
+        .map(|categories| categories.copied().collect());
+    // Pinned and total entries per application, for the --verbose summary
+    let mut pinned_counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    // Parse times and counters of the files, for the --verbose totals
+    let mut total_stats = (0usize, ParseStats::default());
+    let mut table_columns = table_options.columns.clone();
+    if search.is_some() {
+        table_columns.push("matched_field".to_string());
+    }
+    let mut aggregated = vec![];
+    // The name given to the file read from stdin in the messages
+    let stdin_input = stdin.then(|| Ok(PathBuf::from(stdin_name.unwrap_or("-"))));
+    let user_path_regex = args.get_one::<Regex>("user-path-regex");
+    // Files that failed to parse, for the stubs of --output-dir
+    let failed = RefCell::new(vec![]);
+    let keep_failures = output_dir.is_some();
+    // Files too small to be Jumplists, summarized at the end
+    let empty_files = Cell::new(0usize);
+    let error_report = RefCell::new(error_report);
+    let record_failure = |path: &str, e: &JumplistParserError| {
+        if keep_failures {
+            failed.borrow_mut().push((path.to_string(), e.to_string()));
+        }
+        if let Some(report) = error_report.borrow_mut().as_mut() {
+            report.failed(path, e, &options.file_name_suffixes);
+        }
+    };
+    let parsed_files = globbed
+        .into_iter()
+        .chain(listed.map(|entry| entry.map(|path| (path, None))))
+        .chain(stdin_input.map(|entry| entry.map(|path| (path, None))))
+        .filter_map(|entry| {
+            let (path, path_type) = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return None;
+                }
+            };
+            let full_path = path.as_path().to_str().unwrap();
+            let mut outcome = match stdin {
+                true => parse_stdin(
+                    args.get_one::<String>("type").map(|t| t.as_str()),
+                    stdin_name,
+                    &options,
+                ),
+                false => {
+                    let jumplist_type = path_type.or_else(|| force_type.clone());
+                    ParseOutcome::parse_with(&options, |options| {
+                        parse_file(full_path, jumplist_type.clone(), options)
+                    })
+                }
+            };
+            progress_bar.inc(1);
+            // The error of a file whose entries were recovered, reported as a warning
+            let partial_error = match outcome.is_partial() {
+                true => outcome.error.take(),
+                false => None,
+            };
+            match outcome.into_result() {
+                Ok(mut parsed) => {
+                    if let Some(regex) = user_path_regex {
+                        parsed.user = regex
+                            .captures(full_path)
+                            .and_then(|c| c.name("user").or_else(|| c.get(1)))
+                            .map(|user| user.as_str().to_string());
+                    }
+                    if let Some(e) = &partial_error {
+                        eprintln!(
+                            "Partially parsed '{}', {} entries recovered. ERROR : '{}'",
+                            full_path,
+                            parsed.entry_count(),
+                            e
+                        );
+                    }
+                    if let Some(report) = error_report.borrow_mut().as_mut() {
+                        match &partial_error {
+                            Some(e) => report.partial(full_path, &parsed, e),
+                            None => report.warned(full_path, &parsed),
+                        }
+                    }
+                    Some((full_path.to_string(), parsed))
+                }
+                Err(e) if stdin => {
+                    eprintln!("Did not parse '{}' correctly. ERROR : '{}'", full_path, e);
+                    record_failure(full_path, &e);
+                    std::process::exit(1);
+                }
+                Err(e @ JumplistParserError::EmptyOrTruncatedFile { .. }) => {
+                    // Windows leaves many of them behind, they are only counted
+                    if verbose {
+                        eprintln!("Skipped '{}': {}", full_path, e);
+                    }
+                    empty_files.set(empty_files.get() + 1);
+                    record_failure(full_path, &e);
+                    None
+                }
+                Err(JumplistParserError::NoDestList(s, l, f)) => {
+                    //get the size of the file in full_path
+                    let file_size = std::fs::metadata(full_path)
+                        .expect("Unable to get file size")
+                        .len();
+                    eprintln!(
+                        "Error parsing the file '{}', Size: {}, {}:{} : structure incorrect: {:?}",
+                        full_path, file_size, l, f, s
+                    );
+                    record_failure(full_path, &JumplistParserError::NoDestList(s, l, f));
+                    None
+                }
+                Err(e) => {
+                    eprintln!("Did not parse '{}' correctly. ERROR : '{}'", full_path, e);
+                    record_failure(full_path, &e);
+                    None
+                }
+            }
+        });
+    let mut user_summaries: BTreeMap<Option<String>, (usize, usize)> = BTreeMap::new();
+    let parsed_files: Box<dyn Iterator<Item = (String, JumplistParser)>> = match group_by_user {
+        // Every file is parsed before writing the output, to sort them by user
+        true => {
+            let mut parsed_files: Vec<_> = parsed_files.collect();
+            parsed_files.sort_by(|(_, a), (_, b)| a.user.cmp(&b.user));
+            for (_, parsed) in &parsed_files {
+                let summary = user_summaries.entry(parsed.user.clone()).or_default();
+                summary.0 += 1;
+                summary.1 += parsed.entry_count();
+            }
+            Box::new(parsed_files.into_iter())
+        }
+        false => Box::new(parsed_files),
+    };
+    let mut current_user = None;
+    for (full_path, mut parsed) in parsed_files {
+        let metadata = match stdin {
+            true => None,
+            false => std::fs::metadata(&full_path).ok(),
+        };
+        if INTERRUPTED.load(atomic::Ordering::SeqCst) {
+            eprintln!("Interrupted, finishing the output");
+            break;
+        }
+        emitted_entries += parsed.entry_count();
+        progress_bar.set_message(format!("{} entries", emitted_entries));
+        let full_path = full_path.as_str();
+        if verbose && group_by_user && current_user.as_ref() != Some(&parsed.user) {
+            let (files, entries) = user_summaries[&parsed.user];
+            eprintln!(
+                "User '{}': {} Jumplist files, {} entries",
+                parsed.user.as_deref().unwrap_or("unknown"),
+                files,
+                entries
+            );
+            current_user = Some(parsed.user.clone());
+        }
+        if verbose {
+            for warning in parsed.warnings() {
+                eprintln!("Notice: '{}': {}", full_path, warning);
+            }
+            if let Some(destlist) = parsed.destlist() {
+                let counts: Vec<String> = destlist
+                    .lnk_status_counts()
+                    .iter()
+                    .map(|(status, count)| format!("{} {}", count, status))
+                    .collect();
+                if !counts.is_empty() {
+                    eprintln!("LNK streams of '{}': {}", full_path, counts.join(", "));
+                }
+            }
+            if let Some(stats) = &parsed.stats {
+                eprintln!("Parsed '{}' in {}", full_path, stats);
+                total_stats.0 += 1;
+                total_stats.1.add(stats);
+            }
+        }
+        if let Some(dir) = extract_lnk {
+            match parsed.export_lnks(Path::new(dir)) {
+                Ok(exported) => {
+                    for lnk_path in exported {
+                        eprintln!(
+                            "Extracted LNK from '{}' to '{}'",
+                            full_path,
+                            lnk_path.display()
+                        );
+                    }
+                }
+                Err(e) => eprintln!(
+                    "Unable to extract LNKs from '{}'. ERROR : '{}'",
+                    full_path, e
+                ),
+            }
+        }
+        if verbose {
+            let app = parsed
+                .app_name
+                .clone()
+                .or_else(|| parsed.app_id.clone())
+                .unwrap_or("unknown".to_string());
+            let counts = pinned_counts.entry(app).or_default();
+            counts.0 += parsed.pinned_entries().count();
+            counts.1 += parsed.entry_count();
+        }
+        if let Some(pinned) = pinned_filter {
+            parsed.retain_entries(|e| (e.pinned() == Some(true)) == pinned);
+            // The --output-dir manifest keeps every file
+            if parsed.is_empty() && output_dir.is_none() {
+                continue;
+            }
+        }
+        if let Some(only_categories) = &only_categories {
+            let filetype_map = parsed.filetype_map.clone().unwrap_or_default();
+            parsed.retain_entries(|e| only_categories.contains(&e.target_category(&filetype_map)));
+            if parsed.is_empty() && output_dir.is_none() {
+                continue;
+            }
+        }
+        if let Some(output_dir) = output_dir.as_mut() {
+            let json_data = match normalize {
+                true => serde_json::to_string_pretty(&restrict_fields(
+                    &parsed,
+                    normalized_entries(&parsed, metadata.as_ref(), ts_format, offsets),
+                    fields,
+                )),
+                false if parsed.legacy_keys => {
+                    serde_json::to_string_pretty(&legacy_raw_record(&parsed))
+                }
+                false => serde_json::to_string_pretty(&parsed),
+            }
+            .unwrap_or("{}".to_string());
+            output_dir.write(
+                full_path,
+                parsed.app_id.as_deref().unwrap_or_default(),
+                &parsed.r#type.to_string(),
+                &json_data,
+                source_digests.borrow_mut().remove(full_path),
+                None,
+            );
+            continue;
+        }
+        if anomalies_only {
+            for anomaly in &parsed.anomalies {
+                let record = AnomalyRecord {
+                    source_path: full_path,
+                    anomaly,
+                };
+                let json_data = serde_json::to_string(&record).unwrap_or("{}".to_string());
+                output
+                    .write_all(json_data.as_bytes())
+                    .expect("Error Writing Data !");
+                output.write_all(b"\n").expect("Error Writing Data !");
+            }
+            let _ = output.flush();
+            continue;
+        }
+        if aggregate {
+            aggregated.push(parsed);
+            continue;
+        }
+        if let Some(summary) = summary.as_mut() {
+            if let Some(note) = parsed.destlist().and_then(|d| d.slack_note()) {
+                eprintln!("Note: '{}': {}", full_path, note);
+            }
+            summary.add(&parsed);
+            continue;
+        }
+        let matches = search.as_ref().map(|search| search.matches(&parsed));
+        if matches.as_ref().is_some_and(|m| m.is_empty()) {
+            continue;
+        }
+        if by_entry {
+            let rows: Vec<Row> = match output_format {
+                OutputFormat::Csv | OutputFormat::Tsv => csv_rows(&parsed, &csv_options)
+                    .into_iter()
+                    .map(Row::Fields)
+                    .collect(),
+                OutputFormat::Table => table_rows(&parsed, &table_options.columns)
+                    .into_iter()
+                    .map(Row::Fields)
+                    .collect(),
+                OutputFormat::Tln => tln_records(&parsed).into_iter().map(Row::Tln).collect(),
+                OutputFormat::Json | OutputFormat::Jsonl => {
+                    normalized_entries(&parsed, metadata.as_ref(), ts_format, offsets)
+                        .into_iter()
+                        .map(|row| {
+                            Row::Normalized(match fields {
+                                Some(fields) => select_fields(&parsed, row, fields),
+                                None => row,
+                            })
+                        })
+                        .collect()
+                }
+            };
+            let rows: Vec<(Option<SortKey>, Row)> = parsed
+                .entries()
+                .enumerate()
+                .map(|(index, entry)| sort_by.map(|f| SortKey::new(f, &parsed, index, &entry)))
+                .zip(rows)
+                .collect();
+            let mut rows = match &matches {
+                Some(matches) => filter_rows(rows, matches, |(_, row), fields| {
+                    row.add_matched_field(fields)
+                }),
+                None => rows,
+            };
+            let entries = rows.len();
+            rows.retain(|(_, row)| !matches!(row, Row::Tln(None)));
+            tln_skipped += entries - rows.len();
+            if let Some(remaining) = remaining.as_mut() {
+                // Without sorting, the first entries are final
+                if sort_by.is_none() {
+                    rows.truncate(*remaining);
+                    *remaining -= rows.len();
+                }
+            }
+            match (sort_by, &output_format) {
+                (Some(_), _) | (None, OutputFormat::Json) => buffered.extend(rows),
+                (None, _) => {
+                    let rows: Vec<Row> = rows.into_iter().map(|(_, row)| row).collect();
+                    let title = table_title(&parsed, rows.len());
+                    output
+                        .write_all(&encode_entry_rows(
+                            &output_format,
+                            &title,
+                            &table_columns,
+                            rows,
+                            &csv_options,
+                            &table_options,
+                        ))
+                        .expect("Error Writing Data !");
+                    let _ = output.flush();
+                }
+            }
+            if remaining == Some(0) {
+                break;
+            }
+            continue;
+        }
+        let normalized = || {
+            let rows = normalized_entries(&parsed, metadata.as_ref(), ts_format, offsets);
+            match &matches {
+                Some(matches) => filter_rows(rows, matches, |row, fields| {
+                    row.insert("matched_field".to_string(), fields);
+                }),
+                None => rows,
+            }
+        };
+        #[cfg(feature = "http")]
+        if let Some(sink) = sink.as_mut() {
+            for entry in restrict_fields(&parsed, normalized(), fields) {
+                sink.push(entry);
+            }
+            continue;
+        }
+        match output_format {
+            OutputFormat::Jsonl => {
+                let json_data = if normalize {
+                    let normalized = restrict_fields(&parsed, normalized(), fields);
+                    serde_json::to_string(&normalized).unwrap_or("{}".to_string())
+                } else if parsed.legacy_keys {
+                    serde_json::to_string(&legacy_raw_record(&parsed)).unwrap_or("{}".to_string())
+                } else {
+                    serde_json::to_string(&parsed).unwrap_or("{}".to_string())
+                };
+                output
+                    .write_all(json_data.as_bytes())
+                    .expect("Error Writing Data !");
+                output.write_all(b"\n").expect("Error Writing Data !");
+                let _ = output.flush();
+            }
+            OutputFormat::Json => {
+                if normalize {
+                    json_list.push(JsonRecord::Normalize(restrict_fields(
+                        &parsed,
+                        normalized(),
+                        fields,
+                    )));
+                } else if parsed.legacy_keys {
+                    json_list.push(JsonRecord::Legacy(legacy_raw_record(&parsed)));
+                } else {
+                    json_list.push(JsonRecord::Raw(Box::new(parsed)));
+                }
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let rows = csv_rows(&parsed, &csv_options);
+                let rows = match &matches {
+                    Some(matches) => filter_rows(rows, matches, Vec::push),
+                    None => rows,
+                };
+                output
+                    .write_all(&encode_rows(&rows, &csv_options))
+                    .expect("Error Writing Data !");
+                let _ = output.flush();
+            }
+            OutputFormat::Table => {
+                let mut columns = table_options.columns.clone();
+                let rows = table_rows(&parsed, &columns);
+                let rows = match &matches {
+                    Some(matches) => {
+                        columns.push("matched_field".to_string());
+                        filter_rows(rows, matches, Vec::push)
+                    }
+                    None => rows,
+                };
+                let title = table_title(&parsed, rows.len());
+                output
+                    .write_all(render_rows(&title, &columns, &rows, &table_options).as_bytes())
+                    .expect("Error Writing Data !");
+                let _ = output.flush();
+            }
+            OutputFormat::Tln => {
+                let records = tln_records(&parsed);
+                let records = match &matches {
+                    Some(matches) => filter_rows(records, matches, |_, _| {}),
+                    None => records,
+                };
+                output
+                    .write_all(&tln_lines(records, &mut tln_skipped))
+                    .expect("Error Writing Data !");
+                let _ = output.flush();
+            }
+        }
+    }
+    progress_bar.finish_and_clear();
+    if empty_files.get() > 0 {
+        eprintln!("Skipped {} empty jumplist files", empty_files.get());
+    }
+    if non_jumplist_files > 0 {
+        eprintln!("Skipped {} non-jumplist files", non_jumplist_files);
+    }
+    if tln_skipped > 0 {
+        eprintln!(
+            "{} entries without a timestamp were left out of the TLN output",
+            tln_skipped
+        );
+    }
+    for (app, (pinned, entries)) in &pinned_counts {
+        eprintln!("App '{}': {} pinned of {} entries", app, pinned, entries);
+    }
+    if total_stats.0 > 0 {
+        eprintln!("Parsed {} files in {}", total_stats.0, total_stats.1);
+    }
+    #[cfg(feature = "http")]
+    if let Some(sink) = sink {
+        let summary = sink.finish();
+        eprintln!(
+            "Sent {} entries in {} batches to '{}', {} batches failed",
+            summary.sent_entries,
+            summary.sent_batches,
+            output_url.unwrap(),
+            summary.failed_batches
+        );
+        if summary.failed_batches > 0 {
+            eprintln!(
+                "{} entries of the failed batches were written to '{}'",
+                summary.spooled_entries,
+                args.get_one::<String>("spool").unwrap()
+            );
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(mut output_dir) = output_dir {
+        for (path, error) in failed.into_inner() {
+            let digest = source_digests.borrow_mut().remove(&path);
+            output_dir.write_failed(&path, digest, error);
+        }
+        if let Err(e) = output_dir.finish() {
+            eprintln!("Can't write the manifest 'index.json'. ERROR : '{}'", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if by_entry {
+        if sort_by.is_some() {
+            buffered.sort_by(|(a, _), (b, _)| match (a, b) {
+                (Some(a), Some(b)) => a.compare(b, descending),
+                // Every entry has a key when sorting
+                _ => Ordering::Equal,
+            });
+        }
+        if let Some(limit) = limit {
+            buffered.truncate(limit);
+        }
+        if sort_by.is_some() || matches!(output_format, OutputFormat::Json) {
+            let title = match sort_by {
+                Some(field) => format!("{} entries sorted by {}", buffered.len(), field),
+                None => format!("{} entries", buffered.len()),
+            };
+            let rows = buffered.into_iter().map(|(_, row)| row).collect();
+            output
+                .write_all(&encode_entry_rows(
+                    &output_format,
+                    &title,
+                    &table_columns,
+                    rows,
+                    &csv_options,
+                    &table_options,
+                ))
+                .expect("Error Writing Data !");
+        }
+        return;
+    }
+    if aggregate {
+        let aggregate_options = AggregateOptions {
+            canonicalize_paths: args.get_flag("canonicalize-paths"),
+        };
+        let activities = TargetActivity::from_parsers_with_options(&aggregated, &aggregate_options);
+        let data = match output_format {
+            OutputFormat::Json => serde_json::to_string(&activities)
+                .unwrap_or("[]".to_string())
+                .into_bytes(),
+            OutputFormat::Jsonl => activities
+                .iter()
+                .filter_map(|a| serde_json::to_string(a).ok())
+                .map(|line| line + "\n")
+                .collect::<String>()
+                .into_bytes(),
+            OutputFormat::Csv | OutputFormat::Tsv => encode_rows(
+                &activities.iter().map(|a| a.csv_row()).collect::<Vec<_>>(),
+                &csv_options,
+            ),
+            OutputFormat::Table => render_rows(
+                &format!("{} targets", activities.len()),
+                &AGGREGATE_CSV_COLUMNS
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>(),
+                &activities.iter().map(|a| a.csv_row()).collect::<Vec<_>>(),
+                &table_options,
+            )
+            .into_bytes(),
+            // Rejected with --aggregate
+            OutputFormat::Tln => vec![],
+        };
+        output.write_all(&data).expect("Error Writing Data !");
+        return;
+    }
+    if let Some(summary) = summary {
+        let data = match output_format {
+            OutputFormat::Json => serde_json::to_string(&summary.apps)
+                .unwrap_or("[]".to_string())
+                .into_bytes(),
+            OutputFormat::Jsonl => summary
+                .apps
+                .iter()
+                .filter_map(|a| serde_json::to_string(a).ok())
+                .map(|line| line + "\n")
+                .collect::<String>()
+                .into_bytes(),
+            OutputFormat::Csv | OutputFormat::Tsv => encode_rows(
+                &summary.apps.iter().map(|a| a.csv_row()).collect::<Vec<_>>(),
+                &csv_options,
+            ),
+            OutputFormat::Table => render_rows(
+                &format!("{} applications", summary.apps.len()),
+                &SUMMARY_CSV_COLUMNS
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>(),
+                &summary.apps.iter().map(|a| a.csv_row()).collect::<Vec<_>>(),
+                &table_options,
+            )
+            .into_bytes(),
+            // Rejected with --summary
+            OutputFormat::Tln => vec![],
+        };
+        output.write_all(&data).expect("Error Writing Data !");
+        return;
+    }
+    if let OutputFormat::Json = output_format {
         let json_data = serde_json::to_string(&json_list).unwrap_or("{}".to_string());
         output
-            .write(json_data.as_bytes())
+            .write_all(json_data.as_bytes())
             .expect("Error Writing Data !");
     }
 }
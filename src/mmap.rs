@@ -0,0 +1,44 @@
+//! Memory-mapped file input (`mmap` feature).
+
+use crate::{anomaly, errors::JumplistParserError, JumplistParser, ParseOptions};
+use memmap2::Mmap;
+use std::fs::File;
+
+impl JumplistParser {
+    /// Same as [`JumplistParser::from_path_with_options`] but the file is mapped read-only
+    /// and parsed in place instead of being read through buffered I/O, which avoids most of
+    /// the allocations and copies when parsing large batches of files.
+    ///
+    /// Falls back to [`JumplistParser::from_path_with_options`] when the file can't be
+    /// mapped (e.g. empty files or some network filesystems).
+    ///
+    /// A file modified while it's mapped can be seen half-written by the parser. This is
+    /// reported like any other corrupt file, by a parse error or warnings.
+    pub fn from_path_mmap(path: &str, options: &ParseOptions) -> Result<Self, JumplistParserError> {
+        let file = File::open(path).map_err(|e| {
+            JumplistParserError::JumplistParser(
+                format!("Can't open the file '{}', ERROR: {}", path, e),
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+        // Safety: the mapping is read-only and only read through a slice for the duration
+        // of this call. Concurrent modifications can't cause undefined behaviour in safe
+        // code reading plain bytes, only inconsistent data, which the parser handles.
+        let map = match unsafe { Mmap::map(&file) } {
+            Ok(map) => map,
+            Err(_) => return Self::from_path_with_options(path, options),
+        };
+
+        let file_name = Self::file_name(path);
+        let mut parsed = Self::from_bytes_with_options(&map, Some(&file_name), options)
+            .map_err(|e| e.with_path(path))?;
+        parsed.source_path = Some(path.to_string());
+        parsed.user = crate::user::user_from_path(path);
+        if options.detect_anomalies {
+            let file_mtime = anomaly::file_mtime(&file);
+            parsed.anomalies = parsed.detect_anomalies(file_mtime.as_ref());
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,351 @@
+//! Options controlling how Jumplist files are parsed.
+
+use crate::{
+    errors::JumplistParserError, filetype::FiletypeMap, guid::GuidFormat,
+    timestamp::TimestampFormat,
+};
+use std::{
+    fmt,
+    io::{Read, Seek, SeekFrom},
+    sync::Arc,
+};
+
+/// How structural anomalies (count mismatches, bad footers, LNKs that can't be parsed,
+/// unexpected streams, ...) are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Record anomalies as warnings and recover as much data as possible (the default).
+    #[default]
+    Lenient,
+    /// Fail on the first anomaly, for validation and fuzzing.
+    Strict,
+}
+
+/// Options controlling how Jumplist files are parsed.
+///
+/// The default options match the behaviour of [`crate::JumplistParser::from_path`].
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Keep the raw bytes of every embedded LNK in `raw_lnk` so they can be exported
+    /// with [`crate::JumplistParser::export_lnks`]. Disabled by default to avoid
+    /// holding every LNK in memory twice.
+    pub keep_raw_lnk: bool,
+    /// Format of the timestamps in the serialized and normalized output, see
+    /// [`TimestampFormat::with_timezone`] to display them in a timezone.
+    pub ts_format: TimestampFormat,
+    /// Case, decoration and handling of zero GUIDs of the droids of the `DestList` entries,
+    /// in the serialized and normalized output. The fingerprints always hash the default
+    /// format.
+    pub guid_format: GuidFormat,
+    /// Record the regions of unknown meaning that are normally skipped (e.g. the first
+    /// 8 bytes of every `DestList` entry) as hex strings in the `unknown_fields` of the
+    /// parsed structures, to compare them across Windows builds.
+    pub capture_unknown_bytes: bool,
+    /// Number of leading bytes of every unrecognized stream of automatic Jumplists to
+    /// record as hex in [`crate::cfb_info::ExtraStream::preview`], 0 to record none.
+    pub extra_stream_preview: usize,
+    /// Don't fall back to [`crate::custom_destinations::CustomDestinations::carve_from_reader`]
+    /// when a custom Jumplist can't be parsed completely, for strict validation.
+    pub disable_carving: bool,
+    /// Whether anomalies are warnings or errors. [`ParseMode::Strict`] also disables
+    /// carving.
+    pub mode: ParseMode,
+    /// Look for signs of tampering and timestomping and record them in
+    /// [`crate::JumplistParser::anomalies`], see [`crate::JumplistParser::detect_anomalies`].
+    pub detect_anomalies: bool,
+    /// Don't parse the LNK streams of automatic Jumplists, only the `DestList` stream and
+    /// the layout of the compound file, for fast structural validation. The entries have no
+    /// LNK data. The LNK entries of custom Jumplists are always parsed, they delimit the
+    /// entries.
+    pub skip_lnks: bool,
+    /// Add a `target_full_path_canonical` key to the normalized entries, see
+    /// [`crate::target::canonicalize_target_path`]. The user given by the path of the
+    /// Jumplist expands the variables of the user profile.
+    pub canonicalize_paths: bool,
+    /// Write the normalized entries with the key names they had before the `jumplist_*`,
+    /// `destlist_*`, `lnk_*` and `entry_*` prefixes, see
+    /// [`Field::legacy_name`](crate::output::Field::legacy_name). Deprecated, for the
+    /// pipelines that read the old names.
+    pub legacy_keys: bool,
+    /// Categories of the extensions overriding the internal table in the `target_category`
+    /// key of the normalized entries, see [`FiletypeMap`]. Shared by the files of a run.
+    pub filetype_map: Option<Arc<FiletypeMap>>,
+    /// Bounds on what is read from a file, exceeding one fails with
+    /// [`JumplistParserError::LimitExceeded`] in both modes.
+    pub limits: ParseLimits,
+    /// Suffixes accepted after the extension of a Jumplist file name, see
+    /// [`FileNameSuffixes`].
+    pub file_name_suffixes: FileNameSuffixes,
+    /// Called while a file is parsed, for the files with thousands of entries (e.g. the
+    /// Quick Access Jumplist of a shared workstation). See [`ProgressEvent`] for how often.
+    pub progress: Option<Progress>,
+    /// Time the phases of the parse and count the streams, bytes and LNKs read, in
+    /// [`crate::JumplistParser::stats`]. Nothing is timed when not set. Ignored on `wasm32`.
+    pub collect_stats: bool,
+}
+
+/// Number of entries, or of LNK streams, between two [`ProgressEvent`]s of the same kind.
+pub const PROGRESS_INTERVAL: usize = 100;
+
+/// What [`ParseOptions::progress`] is called with.
+///
+/// A file gives one [`ProgressEvent::HeaderParsed`], then one event every
+/// [`PROGRESS_INTERVAL`] entries and LNK streams, so the callback costs little next to the
+/// parsing. Files with fewer entries only give the header event and the last LNK event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// The header was parsed, `declared` is the number of entries of a `DestList` or of
+    /// categories of a custom Jumplist.
+    HeaderParsed { declared: u32 },
+    /// `entries` entries of the file were parsed so far.
+    EntriesParsed { entries: usize },
+    /// `attached` of the `total` LNK streams of a `DestList` were looked up, also sent
+    /// after the last one.
+    LnksAttached { attached: usize, total: usize },
+}
+
+/// A callback of [`ParseOptions::progress`], shared by the clones of the options and
+/// between threads.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{JumplistParser, ParseOptions, Progress, ProgressEvent};
+/// use std::sync::{Arc, Mutex};
+///
+/// let events = Arc::new(Mutex::new(vec![]));
+/// let recorded = events.clone();
+/// let options = ParseOptions {
+///     progress: Some(Progress::new(move |event| recorded.lock().unwrap().push(event))),
+///     ..Default::default()
+/// };
+/// JumplistParser::from_path_with_options(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+///     &options,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     events.lock().unwrap()[..],
+///     [
+///         ProgressEvent::HeaderParsed { declared: 24 },
+///         ProgressEvent::LnksAttached { attached: 24, total: 24 },
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Progress(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl Progress {
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Progress(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
+
+/// Suffixes that copies of Jumplists get after the extension (e.g.
+/// `<appid>.automaticDestinations-ms.bak`), compared without case. The extension still
+/// gives the type of these files, other names fall back to the content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileNameSuffixes(pub Vec<String>);
+
+impl Default for FileNameSuffixes {
+    fn default() -> Self {
+        FileNameSuffixes(
+            [".bak", ".copy", ".old", ".orig", "_1", "_2", "_3"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        )
+    }
+}
+
+impl FileNameSuffixes {
+    /// Whether `suffix`, what follows the extension, is empty or allowed.
+    pub(crate) fn allows(&self, suffix: &str) -> bool {
+        suffix.is_empty() || self.0.iter().any(|s| s.eq_ignore_ascii_case(suffix))
+    }
+}
+
+/// Bounds on the structures read from a file, so that adversarial input (huge sizes, LNK
+/// streams sharing the same sectors, ...) can't make the parser allocate without bound or
+/// run for long, e.g. when fuzzing or parsing untrusted evidence.
+///
+/// The limits apply to what is read, not to the declared counts: a count that doesn't fit
+/// in the file is an anomaly that lenient parsing recovers from. The defaults are far
+/// above what Windows writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum number of entries of a `DestList`, or of all the categories of a custom
+    /// Jumplist.
+    pub max_entries: u64,
+    /// Maximum length of a string (paths, category names), in UTF-16 code units.
+    pub max_string_length: u64,
+    /// Maximum total size of the LNK entries parsed from a file, in bytes.
+    pub max_lnk_bytes: u64,
+    /// Maximum number of categories of a custom Jumplist.
+    pub max_categories: u64,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_entries: 100_000,
+            // The longest path Windows accepts
+            max_string_length: 32_767,
+            max_lnk_bytes: 32 * 1024 * 1024,
+            max_categories: 1_024,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Fail when `value` is above `limit`.
+    pub(crate) fn check(name: &str, value: u64, limit: u64) -> Result<(), JumplistParserError> {
+        match value > limit {
+            true => Err(JumplistParserError::LimitExceeded(
+                format!("{} is {}, the limit is {}", name, value, limit),
+                line!(),
+                file!().to_string(),
+            )),
+            false => Ok(()),
+        }
+    }
+}
+
+/// What was read from a file so far, checked against [`ParseLimits`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LimitTracker {
+    limits: ParseLimits,
+    entries: u64,
+    lnk_bytes: u64,
+}
+
+impl LimitTracker {
+    pub(crate) fn new(limits: ParseLimits) -> Self {
+        LimitTracker {
+            limits,
+            entries: 0,
+            lnk_bytes: 0,
+        }
+    }
+
+    /// Number of entries counted so far.
+    pub(crate) fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Count `count` more entries.
+    pub(crate) fn add_entries(&mut self, count: u64) -> Result<(), JumplistParserError> {
+        self.entries = self.entries.saturating_add(count);
+        ParseLimits::check(
+            "The number of entries",
+            self.entries,
+            self.limits.max_entries,
+        )
+    }
+
+    /// Count `size` more bytes of LNK entries.
+    pub(crate) fn add_lnk_bytes(&mut self, size: u64) -> Result<(), JumplistParserError> {
+        self.lnk_bytes = self.lnk_bytes.saturating_add(size);
+        ParseLimits::check(
+            "The total size of the LNK entries",
+            self.lnk_bytes,
+            self.limits.max_lnk_bytes,
+        )
+    }
+
+    /// Wrap `reader` so that the LNK parser can't read more than the bytes left in the
+    /// budget, the bytes it read are counted by [`LimitTracker::end_lnk`].
+    pub(crate) fn start_lnk<'a, R: Read + Seek>(
+        &self,
+        reader: &'a mut R,
+    ) -> Result<LimitedReader<'a, R>, JumplistParserError> {
+        let start = reader.stream_position().map_err(|e| {
+            JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+        })?;
+        Ok(LimitedReader {
+            inner: reader,
+            start,
+            position: start,
+            // One more byte than the budget, so that reading it all exceeds the limit
+            limit: self.limits.max_lnk_bytes.saturating_sub(self.lnk_bytes) + 1,
+            read: 0,
+        })
+    }
+
+    /// Count the bytes read through `reader`, whether the LNK could be parsed or not:
+    /// failed attempts on garbage are what costs the most.
+    pub(crate) fn end_lnk<R>(
+        &mut self,
+        reader: LimitedReader<'_, R>,
+    ) -> Result<(), JumplistParserError> {
+        self.add_lnk_bytes(reader.read)
+    }
+}
+
+/// A reader that ends `limit` bytes after its start, see [`LimitTracker::start_lnk`].
+pub(crate) struct LimitedReader<'a, R> {
+    inner: &'a mut R,
+    start: u64,
+    position: u64,
+    limit: u64,
+    /// Furthest byte read, from the start.
+    read: u64,
+}
+
+impl<R: Read> Read for LimitedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = (self.start + self.limit).saturating_sub(self.position);
+        let size = (buf.len() as u64).min(available) as usize;
+        let size = self.inner.read(&mut buf[..size])?;
+        self.position += size as u64;
+        self.read = self.read.max(self.position.saturating_sub(self.start));
+        Ok(size)
+    }
+}
+
+impl<R: Seek> Seek for LimitedReader<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+impl ParseOptions {
+    /// Whether the options are [`ParseMode::Strict`].
+    pub fn is_strict(&self) -> bool {
+        self.mode == ParseMode::Strict
+    }
+
+    /// Call the [`ParseOptions::progress`] callback.
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        if let Some(Progress(callback)) = &self.progress {
+            callback(event);
+        }
+    }
+
+    /// Report the entries parsed so far, every [`PROGRESS_INTERVAL`] entries.
+    pub(crate) fn report_entries(&self, entries: u64) {
+        if entries.is_multiple_of(PROGRESS_INTERVAL as u64) {
+            self.report(ProgressEvent::EntriesParsed {
+                entries: entries as usize,
+            });
+        }
+    }
+
+    /// In [`ParseMode::Strict`], turn the first warning into an error.
+    pub(crate) fn check_warnings(&self, warnings: &[String]) -> Result<(), JumplistParserError> {
+        match (self.mode, warnings.first()) {
+            (ParseMode::Strict, Some(warning)) => Err(JumplistParserError::FileStructure(
+                format!("Strict mode: {}", warning),
+                line!(),
+                file!().to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
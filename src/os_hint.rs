@@ -0,0 +1,49 @@
+//! Heuristic detection of the Windows generation that wrote a Jumplist.
+//!
+//! The `DestList` format version changed with the major Windows releases, so it tells
+//! which generation of Windows last wrote an automatic Jumplist. Custom Jumplists have
+//! used the same header version since Windows 7 and give no hint.
+
+use crate::destlist::DestListVersion;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// Windows generation inferred from the format versions of a Jumplist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum OsHint {
+    /// `DestList` version 1.
+    Win7OrWin8,
+    /// `DestList` version 2 or 3, Windows 10 before the Creators Update (1703).
+    Win10,
+    /// `DestList` version 4, Windows 10 1703 and later, and early Windows 11 builds.
+    Win10Creators,
+    /// `DestList` version 6, recent Windows 11 builds.
+    Win11,
+}
+
+impl OsHint {
+    /// The hint given by a `DestList` version, `None` for unknown versions.
+    pub fn from_destlist_version(version: DestListVersion) -> Option<Self> {
+        match version {
+            DestListVersion::V1Win7 => Some(OsHint::Win7OrWin8),
+            DestListVersion::Unknown(2) | DestListVersion::V3Win10 => Some(OsHint::Win10),
+            DestListVersion::V4Win10_1703Plus => Some(OsHint::Win10Creators),
+            DestListVersion::V6Win11 => Some(OsHint::Win11),
+            DestListVersion::Unknown(_) => None,
+        }
+    }
+}
+
+impl Display for OsHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            OsHint::Win7OrWin8 => "win7_or_win8",
+            OsHint::Win10 => "win10",
+            OsHint::Win10Creators => "win10_creators",
+            OsHint::Win11 => "win11",
+        };
+        write!(f, "{}", s)
+    }
+}
@@ -0,0 +1,119 @@
+//! Result of a parse that can succeed in part, see [`JumplistParser::try_from_path`].
+//!
+//! A lenient parse recovers what it can and reports the rest as warnings, a strict one fails
+//! on the first of them. A [`ParseOutcome`] holds both: the Jumplist parsed leniently, and
+//! the error that stopped the strict parse.
+
+use crate::{errors::JumplistParserError, JumplistParser, ParseMode, ParseOptions};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// A problem found in a file that didn't stop its parse, e.g. a header declaring more
+/// entries than the file holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    pub message: String,
+}
+
+impl ParseWarning {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        ParseWarning {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// What was parsed of a file, and what went wrong.
+///
+/// - a clean file has `parsed` and no `error`;
+/// - a file that only parses leniently has both, `parsed` holding the recovered entries;
+/// - a file nothing could be parsed from has only `error`.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{JumplistParser, ParseOptions};
+///
+/// let outcome = JumplistParser::try_from_path(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+///     &ParseOptions::default(),
+/// );
+/// assert!(outcome.error.is_none() && outcome.warnings.is_empty());
+/// assert_eq!(outcome.parsed.unwrap().entry_count(), 24);
+///
+/// let outcome = JumplistParser::try_from_path("missing.customDestinations-ms", &ParseOptions::default());
+/// assert!(outcome.parsed.is_none());
+/// assert_eq!(outcome.error.unwrap().kind(), "jumplist_parser");
+/// ```
+#[derive(Debug)]
+pub struct ParseOutcome {
+    /// The parsed Jumplist, `None` when nothing could be parsed.
+    pub parsed: Option<JumplistParser>,
+    /// Warnings of the parsed Jumplist, see [`JumplistParser::warnings`].
+    pub warnings: Vec<ParseWarning>,
+    /// The error that stopped the parse, with `parsed` when the entries could be recovered
+    /// by a lenient parse.
+    pub error: Option<JumplistParserError>,
+}
+
+impl ParseOutcome {
+    /// Parse with `parse` and `options`. When a [`ParseMode::Strict`] parse fails, the file
+    /// is parsed again leniently to recover its entries, kept with the strict error.
+    pub fn parse_with<F>(options: &ParseOptions, parse: F) -> Self
+    where
+        F: Fn(&ParseOptions) -> Result<JumplistParser, JumplistParserError>,
+    {
+        match parse(options) {
+            Err(error) if options.is_strict() => {
+                let lenient = ParseOptions {
+                    mode: ParseMode::Lenient,
+                    ..options.clone()
+                };
+                let mut outcome = ParseOutcome::from(parse(&lenient));
+                outcome.error = Some(error);
+                outcome
+            }
+            result => ParseOutcome::from(result),
+        }
+    }
+
+    /// Whether entries were recovered from a file that failed to parse.
+    pub fn is_partial(&self) -> bool {
+        self.parsed.is_some() && self.error.is_some()
+    }
+
+    /// The parsed Jumplist, or the error when nothing was parsed.
+    pub fn into_result(self) -> Result<JumplistParser, JumplistParserError> {
+        match (self.parsed, self.error) {
+            (Some(parsed), _) => Ok(parsed),
+            (None, Some(error)) => Err(error),
+            (None, None) => Err(JumplistParserError::General(
+                "Nothing was parsed".to_string(),
+                line!(),
+                file!().to_string(),
+            )),
+        }
+    }
+}
+
+impl From<Result<JumplistParser, JumplistParserError>> for ParseOutcome {
+    fn from(result: Result<JumplistParser, JumplistParserError>) -> Self {
+        match result {
+            Ok(parsed) => ParseOutcome {
+                warnings: parsed.warnings().iter().map(ParseWarning::new).collect(),
+                parsed: Some(parsed),
+                error: None,
+            },
+            Err(error) => ParseOutcome {
+                parsed: None,
+                warnings: vec![],
+                error: Some(error),
+            },
+        }
+    }
+}
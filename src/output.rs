@@ -0,0 +1,1001 @@
+//! Tabular (CSV/TSV) output of normalized Jumplist entries, or of the raw entry fields
+//! with [`CsvOptions::raw`].
+//!
+//! Encoding is done with the `csv` crate so that fields containing the delimiter,
+//! quotes or newlines are always escaped, whatever delimiter is chosen.
+//!
+//! The fields that can be selected for the CSV and normalized JSON output are listed in
+//! [`FIELDS`].
+//!
+//! # Key names
+//! The keys of the normalized entries are snake case and prefixed by where their data comes
+//! from:
+//! - `jumplist_*`: the Jumplist file, the same for all its entries (e.g. `jumplist_app_id`);
+//! - `destlist_*`: the `DestList` entry of an automatic Jumplist (e.g.
+//!   `destlist_interaction_time`), not present for custom entries;
+//! - `entry_*`: the entry in its stream or category (e.g. `entry_pinned`);
+//! - `lnk_*`: the LNK itself (e.g. `lnk_command_line_arguments`);
+//! - `target_*`: the target described by the LNK (e.g. `target_modification_time`).
+//!
+//! Keys renamed to follow this have a [`Field::legacy_name`], written instead with
+//! [`ParseOptions::legacy_keys`](crate::ParseOptions::legacy_keys).
+
+use crate::{
+    custom_destinations::Category, destlist::DestListEntry, target::TargetInfo, Flatten,
+    JumplistEntryRef, JumplistParser, NormalizedEntry,
+};
+use csv::{QuoteStyle, Terminator, WriterBuilder};
+use std::borrow::Cow;
+
+/// Columns emitted for every normalized entry, in order.
+pub const CSV_COLUMNS: &[&str] = &[
+    "jumplist_app_id",
+    "jumplist_app_name",
+    "jumplist_type",
+    "target_full_path",
+    "lnk_command_line_arguments",
+    "lnk_description",
+    "target_modification_time",
+    "target_access_time",
+    "target_creation_time",
+    "target_size",
+    "target_hostname",
+    "target_type",
+    "target_attributes",
+    "jumplist_os_hint",
+    "jumplist_user",
+    "entry_fingerprint",
+];
+
+/// Extra columns emitted after [`CSV_COLUMNS`] when [`CsvOptions::verbose_columns`] is set.
+pub const VERBOSE_CSV_COLUMNS: &[&str] = &["jumplist_is_complete", "lnk_status"];
+
+/// Extra columns emitted after the verbose columns when [`CsvOptions::extended_columns`] is
+/// set: volume and network share of the target, from the LNK `LinkInfo`, the LNK fields
+/// telling how the target was started, then the `DestList` path and whether it differs from
+/// the target path.
+pub const EXTENDED_CSV_COLUMNS: &[&str] = &[
+    "target_volume_serial",
+    "target_drive_type",
+    "target_volume_label",
+    "target_network_share",
+    "target_network_provider",
+    "target_on_removable_media",
+    "lnk_working_directory",
+    "lnk_icon_location",
+    "lnk_relative_path",
+    "lnk_show_command",
+    "lnk_hotkey",
+    "destlist_path",
+    "path_mismatch",
+];
+
+/// Extra columns emitted last when [`CsvOptions::offset_columns`] is set.
+pub const OFFSET_CSV_COLUMNS: &[&str] = &["entry_offset", "entry_size"];
+
+/// Extra columns emitted after `target_type` when [`CsvOptions::filetype_columns`] is set,
+/// see the [`filetype`](crate::filetype) module.
+pub const FILETYPE_CSV_COLUMNS: &[&str] = &["target_extension", "target_category"];
+
+/// A field of the CSV and normalized JSON output.
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Name of the field before the current key names, see [`with_legacy_keys`].
+    pub legacy_name: Option<&'static str>,
+    /// Value of the field for a Jumplist, `None` for the fields of the normalized entries.
+    extract: Option<fn(&JumplistParser) -> String>,
+}
+
+impl Field {
+    /// A key of the normalized entries, see [`Flatten`].
+    const fn entry(name: &'static str, description: &'static str) -> Self {
+        Field {
+            name,
+            description,
+            legacy_name: None,
+            extract: None,
+        }
+    }
+
+    /// A value of the whole Jumplist, the same for all its entries.
+    const fn jumplist(
+        name: &'static str,
+        description: &'static str,
+        extract: fn(&JumplistParser) -> String,
+    ) -> Self {
+        Field {
+            name,
+            description,
+            legacy_name: None,
+            extract: Some(extract),
+        }
+    }
+
+    /// The same field, named `legacy_name` before.
+    const fn legacy(mut self, legacy_name: &'static str) -> Self {
+        self.legacy_name = Some(legacy_name);
+        self
+    }
+
+    /// Key of the field in the output, its legacy name with `legacy_keys` if it has one.
+    pub fn key(&self, legacy_keys: bool) -> &'static str {
+        match (legacy_keys, self.legacy_name) {
+            (true, Some(legacy_name)) => legacy_name,
+            _ => self.name,
+        }
+    }
+
+    /// The value of the field for `entry`, a normalized entry of `parsed`. Empty when the
+    /// entry doesn't have it.
+    pub fn value(&self, parsed: &JumplistParser, entry: &NormalizedEntry) -> String {
+        match self.extract {
+            Some(extract) => extract(parsed),
+            None => entry
+                .get(self.name)
+                .or_else(|| self.legacy_name.and_then(|name| entry.get(name)))
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Every field that can be selected with [`CsvOptions::fields`] and [`select_fields`]: the
+/// default columns, the optional ones, then the other keys of the normalized entries.
+pub const FIELDS: &[Field] = &[
+    Field::jumplist(
+        "jumplist_app_id",
+        "AppID of the Jumplist, from its file name",
+        |p| p.app_id.clone().unwrap_or_default(),
+    )
+    .legacy("app_id"),
+    Field::jumplist("jumplist_app_name", "Application name of the AppID", |p| {
+        p.app_name.clone().unwrap_or_default()
+    })
+    .legacy("app_name"),
+    Field::jumplist(
+        "jumplist_type",
+        "Jumplist type, 'automatic', 'custom' or 'recent_lnk'",
+        |p| p.r#type.to_string(),
+    )
+    .legacy("type"),
+    Field::entry("target_full_path", "Path of the target"),
+    Field::entry(
+        "lnk_command_line_arguments",
+        "Command line arguments of the LNK",
+    )
+    .legacy("command_line_arguments"),
+    Field::entry("lnk_description", "Description of the LNK").legacy("name_string"),
+    Field::entry(
+        "target_modification_time",
+        "Modification time of the target, from the LNK",
+    ),
+    Field::entry(
+        "target_access_time",
+        "Access time of the target, from the LNK",
+    ),
+    Field::entry(
+        "target_creation_time",
+        "Creation time of the target, from the LNK",
+    ),
+    Field::entry("target_size", "Size of the target in bytes"),
+    Field::entry(
+        "target_hostname",
+        "Host name of the target, from the DestList or the LNK",
+    ),
+    Field::entry(
+        "target_type",
+        "Kind of target: file, directory, url, unc or shell",
+    ),
+    Field::entry(
+        "target_attributes",
+        "File attributes of the target, by name",
+    ),
+    Field::entry(
+        "jumplist_os_hint",
+        "Windows generation inferred from the format versions",
+    )
+    .legacy("os_hint"),
+    Field::entry(
+        "jumplist_user",
+        "User whose profile the Jumplist was found in",
+    )
+    .legacy("user"),
+    Field::entry(
+        "entry_fingerprint",
+        "Versioned identifier of the entry, stable across runs",
+    ),
+    Field::entry(
+        "target_full_path_canonical",
+        "Target path with the user profile folders replaced, with --canonicalize-paths",
+    ),
+    Field::jumplist(
+        "jumplist_is_complete",
+        "Whether the Jumplist was parsed without inconsistencies",
+        |p| p.is_complete().to_string(),
+    )
+    .legacy("is_complete"),
+    Field::entry(
+        "lnk_status",
+        "Whether the LNK stream was parsed, missing or corrupt",
+    ),
+    Field::entry(
+        "target_volume_serial",
+        "Serial number of the volume of the target",
+    ),
+    Field::entry("target_drive_type", "Type of the drive of the target"),
+    Field::entry("target_volume_label", "Label of the volume of the target"),
+    Field::entry("target_network_share", "Network share of the target"),
+    Field::entry("target_network_provider", "Network provider of the share"),
+    Field::entry(
+        "target_on_removable_media",
+        "Whether the target is on a removable drive",
+    ),
+    Field::entry("lnk_working_directory", "Working directory of the LNK")
+        .legacy("working_directory"),
+    Field::entry("lnk_icon_location", "Icon location of the LNK").legacy("icon_location"),
+    Field::entry(
+        "lnk_relative_path",
+        "Path of the target relative to the LNK",
+    )
+    .legacy("relative_path"),
+    Field::entry("lnk_show_command", "Window state the target is started in")
+        .legacy("show_command"),
+    Field::entry("lnk_hotkey", "Keyboard shortcut of the LNK").legacy("hotkey"),
+    Field::entry("destlist_path", "Path recorded in the DestList entry"),
+    Field::entry(
+        "path_mismatch",
+        "Whether the DestList path differs from the target path of the LNK",
+    ),
+    Field::entry(
+        "destlist_volume_droid",
+        "GUID of the volume of the target, from the DestList",
+    ),
+    Field::entry(
+        "destlist_file_droid",
+        "GUID of the target file, from the DestList",
+    ),
+    Field::entry(
+        "destlist_volume_birth_droid",
+        "Birth GUID of the volume of the target, from the DestList",
+    ),
+    Field::entry(
+        "destlist_file_birth_droid",
+        "Birth GUID of the target file, from the DestList",
+    ),
+    Field::entry(
+        "entry_offset",
+        "Byte offset of the entry in its stream or file",
+    ),
+    Field::entry("entry_size", "Size of the entry in bytes"),
+    Field::entry(
+        "target_extension",
+        "Extension of the target, lowercase, empty for directories and URLs",
+    ),
+    Field::entry(
+        "target_category",
+        "Kind of file of the target: document, spreadsheet, presentation, image, archive, \
+         executable, script, media or other",
+    ),
+    Field::entry("jumplist_file_path", "Path of the Jumplist file"),
+    Field::entry(
+        "jumplist_file_size",
+        "Size of the Jumplist file in bytes, not set for stdin",
+    ),
+    Field::entry(
+        "jumplist_file_modified",
+        "Modification time of the Jumplist file, not set for stdin",
+    ),
+    Field::entry(
+        "entry_pinned",
+        "Whether the entry is pinned, custom tasks and pinned items count as pinned",
+    )
+    .legacy("pinned"),
+    Field::entry(
+        "destlist_entry_number",
+        "Number of the DestList entry, also the name of its LNK stream in hex",
+    ),
+    Field::entry(
+        "destlist_interaction_time",
+        "Last time the target was opened, from the DestList",
+    ),
+    Field::entry(
+        "destlist_access_count",
+        "Number of times the target was opened, from the DestList",
+    ),
+    Field::entry("destlist_hostname", "Host name recorded in the DestList"),
+    Field::entry(
+        "target_full_path_resolved",
+        "Target path with known folder GUIDs resolved",
+    ),
+    Field::entry(
+        "target_path_source",
+        "Structure the target path was read from",
+    ),
+    Field::entry("target_drive_letter", "Drive letter of a local target"),
+    Field::entry("target_share", "Share of a network target"),
+    Field::entry(
+        "target_attributes_value",
+        "File attributes of the target, as a number",
+    ),
+    Field::entry(
+        "target_is_hidden",
+        "Whether the target has the hidden attribute",
+    ),
+    Field::entry("target_is_directory", "Whether the target is a directory"),
+    Field::entry("target_mft_entry", "MFT entry number of the target"),
+    Field::entry("target_mft_sequence", "MFT sequence number of the target"),
+    Field::entry(
+        "lnk_tracker_machine_id",
+        "NetBIOS name of the machine the LNK was created on",
+    )
+    .legacy("tracker_machine_id"),
+    Field::entry(
+        "lnk_tracker_mac_address",
+        "MAC address from the LNK tracker data",
+    )
+    .legacy("tracker_mac_address"),
+    Field::entry("lnk_tracker_created_time", "Time from the LNK tracker data")
+        .legacy("tracker_created_time"),
+    Field::entry(
+        "destlist_hostname_mismatch",
+        "Whether the DestList host name differs from the tracker machine ID",
+    )
+    .legacy("hostname_mismatch"),
+    Field::entry(
+        "entry_category_id",
+        "Category ID of custom Jumplist entries",
+    )
+    .legacy("category_id"),
+    Field::entry(
+        "jumplist_format_version",
+        "Format version of the DestList stream or of the custom Jumplist header",
+    ),
+];
+
+/// Where a [`RawField`] takes its value from.
+#[derive(Debug, Clone, Copy)]
+enum RawExtract {
+    /// The Jumplist, the same for all its entries.
+    Jumplist(fn(&JumplistParser) -> String),
+    /// A `DestList` entry, empty for the other entries.
+    DestList(fn(&DestListEntry) -> String),
+    /// The category of a custom entry, empty for the other entries.
+    Category(fn(&Category) -> String),
+    /// Any entry.
+    Entry(fn(&JumplistEntryRef) -> String),
+}
+
+/// A column of the raw CSV output ([`CsvOptions::raw`]): a scalar field of the Jumplist, of
+/// its entry structs or of the category of a custom entry, named like in the raw JSON
+/// output. The LNK fields are left to the normalized output.
+#[derive(Debug, Clone, Copy)]
+pub struct RawField {
+    pub name: &'static str,
+    pub description: &'static str,
+    extract: RawExtract,
+}
+
+impl RawField {
+    const fn new(name: &'static str, description: &'static str, extract: RawExtract) -> Self {
+        RawField {
+            name,
+            description,
+            extract,
+        }
+    }
+
+    /// The value of the field for `entry`, an entry of `parsed`. Empty when the entry
+    /// doesn't have it.
+    pub fn value(&self, parsed: &JumplistParser, entry: &JumplistEntryRef) -> String {
+        match (self.extract, entry) {
+            (RawExtract::Jumplist(extract), _) => extract(parsed),
+            (RawExtract::DestList(extract), JumplistEntryRef::DestList(entry)) => extract(entry),
+            (RawExtract::Category(extract), JumplistEntryRef::Custom { category, .. }) => {
+                extract(category)
+            }
+            (RawExtract::Entry(extract), entry) => extract(entry),
+            _ => String::new(),
+        }
+    }
+}
+
+/// `value` as a string, empty when `None`.
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+/// The fields that `DestList` entries and LNK entries have in common.
+struct CommonFields<'a> {
+    entry_size: u64,
+    target: &'a TargetInfo,
+    target_mft_entry: Option<u64>,
+    target_mft_sequence: Option<u16>,
+}
+
+impl<'a> CommonFields<'a> {
+    fn of(entry: &JumplistEntryRef<'a>) -> Self {
+        match *entry {
+            JumplistEntryRef::DestList(entry) => CommonFields {
+                entry_size: entry.entry_size,
+                target: &entry.target,
+                target_mft_entry: entry.target_mft_entry,
+                target_mft_sequence: entry.target_mft_sequence,
+            },
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                CommonFields {
+                    entry_size: entry.entry_size,
+                    target: &entry.target,
+                    target_mft_entry: entry.target_mft_entry,
+                    target_mft_sequence: entry.target_mft_sequence,
+                }
+            }
+        }
+    }
+}
+
+/// Columns of the raw CSV output, in order: the fields of the Jumplist, of the `DestList`
+/// entries, of the categories and LNK entries of custom Jumplists, then the fields of every
+/// entry. Listed by `--list-fields --raw`.
+pub const RAW_FIELDS: &[RawField] = &[
+    RawField::new(
+        "source_path",
+        "Path of the Jumplist file",
+        RawExtract::Jumplist(|p| optional(&p.source_path)),
+    ),
+    RawField::new(
+        "app_id",
+        "AppID of the Jumplist, from its file name",
+        RawExtract::Jumplist(|p| optional(&p.app_id)),
+    ),
+    RawField::new(
+        "app_name",
+        "Application name of the AppID",
+        RawExtract::Jumplist(|p| optional(&p.app_name)),
+    ),
+    RawField::new(
+        "type",
+        "Jumplist type, 'automatic', 'custom' or 'recent_lnk'",
+        RawExtract::Jumplist(|p| p.r#type.to_string()),
+    ),
+    RawField::new(
+        "user",
+        "User whose profile the Jumplist was found in",
+        RawExtract::Jumplist(|p| optional(&p.user)),
+    ),
+    RawField::new(
+        "format_version",
+        "Format version of the DestList stream or of the custom Jumplist header",
+        RawExtract::Jumplist(|p| optional(&p.format_version())),
+    ),
+    RawField::new(
+        "entry_number",
+        "Number of the DestList entry, the name of its LNK stream in hex",
+        RawExtract::DestList(|e| e.entry_number.to_string()),
+    ),
+    RawField::new(
+        "mtime",
+        "Last time the target was opened",
+        RawExtract::DestList(|e| e.mtime.to_string()),
+    ),
+    RawField::new(
+        "pinned",
+        "Whether the DestList entry is pinned",
+        RawExtract::DestList(|e| e.pinned.to_string()),
+    ),
+    RawField::new(
+        "access_count",
+        "Number of times the target was opened, empty for version 1 DestList streams",
+        RawExtract::DestList(|e| optional(&e.access_count)),
+    ),
+    RawField::new(
+        "path",
+        "Path recorded in the DestList entry",
+        RawExtract::DestList(|e| e.path.clone()),
+    ),
+    RawField::new(
+        "path_resolved",
+        "'path' with known folder GUIDs replaced by their names",
+        RawExtract::DestList(|e| optional(&e.path_resolved)),
+    ),
+    RawField::new(
+        "path_raw_len",
+        "Size of the path as stored, in bytes",
+        RawExtract::DestList(|e| e.path_raw_len.to_string()),
+    ),
+    RawField::new(
+        "hostname",
+        "NetBIOS name of the machine the target was opened on",
+        RawExtract::DestList(|e| optional(&e.hostname)),
+    ),
+    RawField::new(
+        "hostname_is_ip",
+        "Whether 'hostname' is an IP address",
+        RawExtract::DestList(|e| e.hostname_is_ip.to_string()),
+    ),
+    RawField::new(
+        "volume_droid",
+        "GUID of the volume of the target",
+        RawExtract::DestList(|e| optional(&e.volume_droid.value())),
+    ),
+    RawField::new(
+        "file_droid",
+        "GUID of the target",
+        RawExtract::DestList(|e| optional(&e.file_droid.value())),
+    ),
+    RawField::new(
+        "volume_birth_droid",
+        "Birth GUID of the volume of the target",
+        RawExtract::DestList(|e| optional(&e.volume_birth_droid.value())),
+    ),
+    RawField::new(
+        "file_birth_droid",
+        "Birth GUID of the target",
+        RawExtract::DestList(|e| optional(&e.file_birth_droid.value())),
+    ),
+    RawField::new(
+        "path_mismatch",
+        "Whether 'path' and the target path of the LNK name different targets",
+        RawExtract::DestList(|e| e.path_mismatch.to_string()),
+    ),
+    RawField::new(
+        "lnk_status",
+        "'parsed', 'missing', 'parse_failed' or 'skipped'",
+        RawExtract::DestList(|e| e.lnk_status.to_string()),
+    ),
+    RawField::new(
+        "trailing_value",
+        "The 4 bytes after the path of version 3+ DestList entries, as a number",
+        RawExtract::DestList(|e| optional(&e.trailing_value)),
+    ),
+    RawField::new(
+        "stream_offset",
+        "Offset of the entry within the DestList stream",
+        RawExtract::DestList(|e| e.stream_offset.to_string()),
+    ),
+    RawField::new(
+        "category_type",
+        "Type of the category of a custom entry, 'custom', 'known', 'task' or 'pinned'",
+        RawExtract::Category(|c| format!("{:?}", c.r#type).to_lowercase()),
+    ),
+    RawField::new(
+        "category_name",
+        "Name of a custom category",
+        RawExtract::Category(|c| optional(&c.name)),
+    ),
+    RawField::new(
+        "category_id",
+        "ID of a known category, e.g. 'frequent' or 'recent'",
+        RawExtract::Category(|c| optional(&c.id)),
+    ),
+    RawField::new(
+        "category_num_of_entries",
+        "Number of entries declared by the category",
+        RawExtract::Category(|c| optional(&c.num_of_entries)),
+    ),
+    RawField::new(
+        "category_file_offset",
+        "Offset of the category within the custom Jumplist file",
+        RawExtract::Category(|c| c.file_offset.to_string()),
+    ),
+    RawField::new(
+        "category_recovered",
+        "Whether the category holds the LNK entries carved from a damaged file",
+        RawExtract::Category(|c| c.recovered.to_string()),
+    ),
+    RawField::new(
+        "file_offset",
+        "Offset of the LNK entry within the custom Jumplist or LNK file",
+        RawExtract::Entry(|e| match e {
+            JumplistEntryRef::DestList(_) => String::new(),
+            JumplistEntryRef::Custom { entry, .. } | JumplistEntryRef::RecentLnk(entry) => {
+                entry.file_offset.to_string()
+            }
+        }),
+    ),
+    RawField::new(
+        "entry_size",
+        "Size of the entry in bytes",
+        RawExtract::Entry(|e| CommonFields::of(e).entry_size.to_string()),
+    ),
+    RawField::new(
+        "target_type",
+        "Kind of the target, 'file', 'directory', 'url', 'unc' or 'shell'",
+        RawExtract::Entry(|e| CommonFields::of(e).target.target_type.to_string()),
+    ),
+    RawField::new(
+        "target_drive_letter",
+        "Drive letter of a local target",
+        RawExtract::Entry(|e| optional(&CommonFields::of(e).target.target_drive_letter)),
+    ),
+    RawField::new(
+        "target_share",
+        "Share of a UNC target",
+        RawExtract::Entry(|e| optional(&CommonFields::of(e).target.target_share)),
+    ),
+    RawField::new(
+        "target_local_path",
+        "Windows path of a file:// URL target",
+        RawExtract::Entry(|e| optional(&CommonFields::of(e).target.target_local_path)),
+    ),
+    RawField::new(
+        "target_mft_entry",
+        "MFT entry number of the target, from the shell items of the LNK",
+        RawExtract::Entry(|e| optional(&CommonFields::of(e).target_mft_entry)),
+    ),
+    RawField::new(
+        "target_mft_sequence",
+        "MFT sequence number of the target, from the shell items of the LNK",
+        RawExtract::Entry(|e| optional(&CommonFields::of(e).target_mft_sequence)),
+    ),
+    RawField::new(
+        "entry_fingerprint",
+        "Deterministic ID of the entry",
+        RawExtract::Entry(|e| e.fingerprint().to_string()),
+    ),
+];
+
+/// `entry`, a normalized entry, with the keys that have a [`Field::legacy_name`] renamed
+/// to it.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::output::with_legacy_keys;
+/// use jumplist_parser::NormalizedEntry;
+///
+/// let entry = NormalizedEntry::from([
+///     ("lnk_description".to_string(), "Notes".to_string()),
+///     ("target_size".to_string(), "42".to_string()),
+/// ]);
+/// let legacy = with_legacy_keys(entry);
+/// assert_eq!(legacy["name_string"], "Notes");
+/// assert_eq!(legacy["target_size"], "42");
+/// ```
+pub fn with_legacy_keys(entry: NormalizedEntry) -> NormalizedEntry {
+    entry
+        .into_iter()
+        .map(|(key, value)| match legacy_key(&key) {
+            Some(legacy_name) => (legacy_name.to_string(), value),
+            None => (key, value),
+        })
+        .collect()
+}
+
+/// Sort the keys of `entry` in the canonical order of the normalized output: the order of
+/// [`FIELDS`], a legacy name (see [`Field::legacy_name`]) taking the place of its field,
+/// then the other keys (e.g. `matched_field`) by name.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{output::sort_keys, NormalizedEntry};
+///
+/// let mut entry = NormalizedEntry::from([
+///     ("matched_field".to_string(), "target_full_path".to_string()),
+///     ("target_size".to_string(), "42".to_string()),
+///     ("jumplist_app_id".to_string(), "f01b4d95cf55d32a".to_string()),
+/// ]);
+/// sort_keys(&mut entry);
+/// let keys: Vec<&str> = entry.keys().map(|k| k.as_str()).collect();
+/// assert_eq!(keys, ["jumplist_app_id", "target_size", "matched_field"]);
+/// ```
+pub fn sort_keys(entry: &mut NormalizedEntry) {
+    entry.sort_by_cached_key(|key, _| {
+        let position = FIELDS
+            .iter()
+            .position(|f| f.name == key || f.legacy_name == Some(key.as_str()));
+        (position.unwrap_or(FIELDS.len()), key.clone())
+    });
+}
+
+/// The [`Field::legacy_name`] of the field named `name`.
+pub fn legacy_key(name: &str) -> Option<&'static str> {
+    FIELDS
+        .iter()
+        .find(|f| f.name == name)
+        .and_then(|f| f.legacy_name)
+}
+
+/// The field named `name`, or named so before (see [`Field::legacy_name`]). Unknown names
+/// are an error naming the closest fields.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::output::find_field;
+///
+/// assert_eq!(find_field("target_size").unwrap().name, "target_size");
+/// assert_eq!(find_field("name_string").unwrap().name, "lnk_description");
+/// let error = find_field("target_sise").unwrap_err();
+/// assert!(error.starts_with("Unknown field 'target_sise', did you mean 'target_size' or "));
+/// ```
+pub fn find_field(name: &str) -> Result<&'static Field, String> {
+    if let Some(field) = FIELDS
+        .iter()
+        .find(|f| f.name == name || f.legacy_name == Some(name))
+    {
+        return Ok(field);
+    }
+    let mut near: Vec<(usize, &str)> = FIELDS
+        .iter()
+        .map(|f| (edit_distance(name, f.name), f.name))
+        .filter(|(distance, field)| *distance <= 3 || field.contains(name))
+        .collect();
+    near.sort();
+    let near: Vec<String> = near
+        .iter()
+        .take(3)
+        .map(|(_, field)| format!("'{}'", field))
+        .collect();
+    Err(match near.is_empty() {
+        true => format!("Unknown field '{}'. See --list-fields", name),
+        false => format!(
+            "Unknown field '{}', did you mean {}? See --list-fields",
+            name,
+            near.join(" or ")
+        ),
+    })
+}
+
+/// Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Restrict a normalized entry of `parsed` to `fields`, in their order. Fields of the
+/// Jumplist (e.g. `jumplist_type`) are added, and the `matched_field` of a search is kept
+/// last. The keys are the legacy names with [`JumplistParser::legacy_keys`].
+pub fn select_fields(
+    parsed: &JumplistParser,
+    mut entry: NormalizedEntry,
+    fields: &[&'static str],
+) -> NormalizedEntry {
+    let mut selected: NormalizedEntry = fields
+        .iter()
+        .filter_map(|name| find_field(name).ok())
+        .map(|field| {
+            let key = field.key(parsed.legacy_keys);
+            (key.to_string(), field.value(parsed, &entry))
+        })
+        .collect();
+    if let Some(matched) = entry.shift_remove("matched_field") {
+        selected.insert("matched_field".to_string(), matched);
+    }
+    selected
+}
+
+/// Options for the delimited (CSV/TSV) output.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field delimiter, `,` for CSV and `\t` for TSV.
+    pub delimiter: u8,
+    /// Neutralize fields that a spreadsheet would evaluate as a formula (see [`sanitize_field`]).
+    pub safe: bool,
+    /// Append the [`VERBOSE_CSV_COLUMNS`].
+    pub verbose_columns: bool,
+    /// Write `target_full_path` with known folder GUIDs replaced by their names
+    /// (see [`resolve_known_folder_path`](crate::known_folders::resolve_known_folder_path)).
+    pub resolve_known_folders: bool,
+    /// Append the [`EXTENDED_CSV_COLUMNS`].
+    pub extended_columns: bool,
+    /// Append the [`OFFSET_CSV_COLUMNS`].
+    pub offset_columns: bool,
+    /// Add the [`FILETYPE_CSV_COLUMNS`] after `target_type`.
+    pub filetype_columns: bool,
+    /// Add the `target_full_path_canonical` column after `target_full_path`, the entries
+    /// must be parsed with [`ParseOptions::canonicalize_paths`](crate::ParseOptions::canonicalize_paths).
+    pub canonical_paths: bool,
+    /// Columns to emit instead of the default ones and the optional columns above, in order.
+    /// Names that aren't in [`FIELDS`] give empty columns, see [`find_field`].
+    pub fields: Option<Vec<&'static str>>,
+    /// End the lines with `\r\n` instead of `\n`.
+    pub crlf: bool,
+    /// Name the columns with their [`Field::legacy_name`], see
+    /// [`ParseOptions::legacy_keys`](crate::ParseOptions::legacy_keys).
+    pub legacy_keys: bool,
+    /// Emit the [`RAW_FIELDS`] of every entry instead of the normalized fields, the options
+    /// choosing the columns above are then ignored.
+    pub raw: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            safe: true,
+            verbose_columns: false,
+            resolve_known_folders: false,
+            extended_columns: false,
+            offset_columns: false,
+            filetype_columns: false,
+            canonical_paths: false,
+            fields: None,
+            crlf: false,
+            legacy_keys: false,
+            raw: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Options for tab-separated output.
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: b'\t',
+            ..Default::default()
+        }
+    }
+}
+
+/// Byte order mark written at the start of the delimited output for Excel, which only
+/// reads a CSV as UTF-8 when it starts with it.
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Characters that make a spreadsheet treat a cell as a formula when they lead the value.
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r', '\n'];
+
+/// Prefix `field` with a single quote if it starts with a character that Excel (and other
+/// spreadsheets) would interpret as the start of a formula.
+///
+/// Values such as `name_string` and `command_line_arguments` come straight from LNK data
+/// that an attacker can control, so opening the CSV must not execute them.
+pub fn sanitize_field(field: &str) -> Cow<'_, str> {
+    match field.starts_with(FORMULA_PREFIXES) {
+        true => Cow::Owned(format!("'{}", field)),
+        false => Cow::Borrowed(field),
+    }
+}
+
+/// Encode rows as delimited text, one line per row.
+///
+/// With the default `,` delimiter every field is quoted (the historical output of the CLI).
+/// With any other delimiter, fields are only quoted when they contain the delimiter,
+/// a quote or a newline. Fields are passed through [`sanitize_field`] when
+/// [`CsvOptions::safe`] is set, and lines end with `\r\n` when [`CsvOptions::crlf`] is.
+pub fn encode_rows(rows: &[Vec<String>], options: &CsvOptions) -> Vec<u8> {
+    let quote_style = match options.delimiter {
+        b',' => QuoteStyle::Always,
+        _ => QuoteStyle::Necessary,
+    };
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote_style(quote_style)
+        .terminator(match options.crlf {
+            true => Terminator::CRLF,
+            false => Terminator::Any(b'\n'),
+        })
+        .from_writer(vec![]);
+    for row in rows {
+        // Writing to a `Vec<u8>` can't fail
+        let _ = match options.safe {
+            true => writer.write_record(row.iter().map(|f| sanitize_field(f).into_owned())),
+            false => writer.write_record(row),
+        };
+    }
+    writer.into_inner().unwrap_or_default()
+}
+
+/// The columns emitted with `options`, in order.
+pub fn csv_columns(options: &CsvOptions) -> Vec<&'static str> {
+    if options.raw {
+        return RAW_FIELDS.iter().map(|f| f.name).collect();
+    }
+    let mut columns = match &options.fields {
+        Some(fields) => fields.clone(),
+        None => default_csv_columns(options),
+    };
+    if options.legacy_keys {
+        for column in columns.iter_mut() {
+            *column = legacy_key(column).unwrap_or(column);
+        }
+    }
+    columns
+}
+
+/// The default columns and the optional ones enabled in `options`.
+fn default_csv_columns(options: &CsvOptions) -> Vec<&'static str> {
+    let mut columns = CSV_COLUMNS.to_vec();
+    if options.canonical_paths {
+        let position = columns
+            .iter()
+            .position(|c| *c == "target_full_path")
+            .unwrap_or(0);
+        columns.insert(position + 1, "target_full_path_canonical");
+    }
+    if options.filetype_columns {
+        let position = columns
+            .iter()
+            .position(|c| *c == "target_type")
+            .map_or(columns.len(), |position| position + 1);
+        columns.splice(position..position, FILETYPE_CSV_COLUMNS.iter().copied());
+    }
+    if options.verbose_columns {
+        columns.extend_from_slice(VERBOSE_CSV_COLUMNS);
+    }
+    if options.extended_columns {
+        columns.extend_from_slice(EXTENDED_CSV_COLUMNS);
+    }
+    if options.offset_columns {
+        columns.extend_from_slice(OFFSET_CSV_COLUMNS);
+    }
+    columns
+}
+
+/// The header line of the delimited output.
+pub fn csv_header(options: &CsvOptions) -> Vec<u8> {
+    let header: Vec<String> = csv_columns(options).iter().map(|c| c.to_string()).collect();
+    encode_rows(&[header], options)
+}
+
+/// Build one row of [`csv_columns`] per normalized entry of `parsed`.
+pub fn csv_rows(parsed: &JumplistParser, options: &CsvOptions) -> Vec<Vec<String>> {
+    if options.raw {
+        return raw_csv_rows(parsed);
+    }
+    let columns: Vec<Option<&Field>> = csv_columns(options)
+        .iter()
+        .map(|column| find_field(column).ok())
+        .collect();
+
+    parsed
+        .flatten()
+        .into_iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|field| match field {
+                    Some(field)
+                        if field.name == "target_full_path" && options.resolve_known_folders =>
+                    {
+                        row.get("target_full_path_resolved")
+                            .cloned()
+                            .unwrap_or_default()
+                    }
+                    Some(field) => field.value(parsed, &row),
+                    None => String::new(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Build one row of [`RAW_FIELDS`] per entry of `parsed`.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{output::{raw_csv_rows, RAW_FIELDS}, JumplistParser};
+///
+/// let parsed = JumplistParser::from_path(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+/// )
+/// .unwrap();
+/// let rows = raw_csv_rows(&parsed);
+/// assert_eq!(rows.len(), parsed.entry_count());
+/// let entry_number = RAW_FIELDS.iter().position(|f| f.name == "entry_number").unwrap();
+/// assert_eq!(rows[0][entry_number], "25");
+/// ```
+pub fn raw_csv_rows(parsed: &JumplistParser) -> Vec<Vec<String>> {
+    parsed
+        .entries()
+        .map(|entry| {
+            RAW_FIELDS
+                .iter()
+                .map(|field| field.value(parsed, &entry))
+                .collect()
+        })
+        .collect()
+}
+
+/// Encode every normalized entry of `parsed` as delimited text.
+pub fn csv_records(parsed: &JumplistParser, options: &CsvOptions) -> Vec<u8> {
+    encode_rows(&csv_rows(parsed, options), options)
+}
@@ -0,0 +1,63 @@
+//! Parallel batch parsing (`parallel` feature).
+
+use crate::{errors::JumplistParserError, JumplistParser, ParseOptions};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+impl JumplistParser {
+    /// Parse many Jumplist files in parallel on the rayon thread pool.
+    ///
+    /// Every file is parsed as with [`JumplistParser::from_path_with_options`]. The results
+    /// are in the order of `paths`.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{JumplistParser, ParseOptions};
+    ///
+    /// let paths = [
+    ///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    ///     "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    /// ];
+    /// let results = JumplistParser::parse_many(&paths, &ParseOptions::default());
+    /// assert!(results.iter().all(|(_, result)| result.is_ok()));
+    /// ```
+    pub fn parse_many<P: AsRef<Path> + Sync>(
+        paths: &[P],
+        options: &ParseOptions,
+    ) -> Vec<(PathBuf, Result<JumplistParser, JumplistParserError>)> {
+        paths
+            .par_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                (path.to_path_buf(), Self::from_path_buf(path, options))
+            })
+            .collect()
+    }
+
+    /// Same as [`JumplistParser::parse_many`] but every result is handed to `callback` as
+    /// soon as it's parsed instead of being collected, so only the Jumplists being parsed
+    /// are held in memory.
+    ///
+    /// `callback` is called from the rayon worker threads, in completion order.
+    pub fn parse_many_with<P, F>(paths: &[P], options: &ParseOptions, callback: F)
+    where
+        P: AsRef<Path> + Sync,
+        F: Fn(&Path, Result<JumplistParser, JumplistParserError>) + Sync + Send,
+    {
+        paths.par_iter().for_each(|path| {
+            let path = path.as_ref();
+            callback(path, Self::from_path_buf(path, options))
+        });
+    }
+
+    fn from_path_buf(path: &Path, options: &ParseOptions) -> Result<Self, JumplistParserError> {
+        match path.to_str() {
+            Some(path) => Self::from_path_with_options(path, options),
+            None => Err(JumplistParserError::General(
+                format!("The path '{}' isn't valid UTF-8", path.display()),
+                line!(),
+                file!().to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Expansion of the `--path` glob patterns, including the Windows patterns that the `glob`
+//! crate can't handle.
+//!
+//! Supported pattern shapes:
+//! - local patterns, `C:\Users\*\AppData\...\*ms` or `/mnt/c/Users/*/AppData/.../*ms`,
+//!   expanded by `glob`;
+//! - UNC patterns, e.g. `\\SERVER\C$\Users\*\AppData\...\*ms` for an admin share;
+//! - extended-length and device patterns, e.g. `\\?\C:\Users\*\...`,
+//!   `\\?\UNC\SERVER\C$\Users\*\...` or `\\.\C:\Users\*\...`.
+//!
+//! On Windows the UNC, extended-length and device patterns are expanded one directory level
+//! at a time: the root (`\\SERVER\C$\`, `\\?\C:\`, ...) is used as is, the directories of a
+//! level with a wildcard are listed and their names matched case-insensitively. Wildcards
+//! can't be used in the root, and `**` matches a single level like `*`. Other systems don't
+//! have these paths, every pattern is handed to `glob`.
+
+use glob::{MatchOptions, Pattern, PatternError};
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+/// Windows file names are case-insensitive.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A pattern split into the root that is used as is and the components after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternParts {
+    /// Root of the pattern with its trailing separator, e.g. `\\SERVER\C$\` or `\\?\C:\`.
+    pub root: String,
+    /// Components after the root, the separators (`\` or `/`) and empty components
+    /// removed.
+    pub components: Vec<String>,
+}
+
+impl PatternParts {
+    /// The existing paths matching the components under the root, the entries of every
+    /// listed directory sorted. The directories that can't be listed are returned as errors,
+    /// except the missing ones.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::path_pattern::PatternParts;
+    ///
+    /// let parts = PatternParts {
+    ///     root: "samples".to_string(),
+    ///     components: vec!["win10".to_string(), "*destinations".to_string(), "f01b*".to_string()],
+    /// };
+    /// let paths: Vec<_> = parts.expand().unwrap().into_iter().flatten().collect();
+    /// assert_eq!(paths.len(), 2);
+    /// ```
+    pub fn expand(&self) -> Result<Vec<Result<PathBuf, String>>, PatternError> {
+        let mut paths = vec![PathBuf::from(&self.root)];
+        let mut errors = vec![];
+        for component in &self.components {
+            if !has_wildcard(component) {
+                paths = paths.into_iter().map(|path| path.join(component)).collect();
+                continue;
+            }
+            let pattern = Pattern::new(component)?;
+            let mut matched = vec![];
+            for dir in paths {
+                let entries = match fs::read_dir(&dir) {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        errors.push(format!("Can't list '{}': {}", dir.display(), e));
+                        continue;
+                    }
+                };
+                let mut names: Vec<PathBuf> = entries
+                    .flatten()
+                    .filter(|entry| {
+                        pattern.matches_with(&entry.file_name().to_string_lossy(), MATCH_OPTIONS)
+                    })
+                    .map(|entry| entry.path())
+                    .collect();
+                names.sort();
+                matched.append(&mut names);
+            }
+            paths = matched;
+        }
+        Ok(paths
+            .into_iter()
+            .filter(|path| path.exists())
+            .map(Ok)
+            .chain(errors.into_iter().map(Err))
+            .collect())
+    }
+}
+
+/// Whether `component` has a `glob` wildcard.
+fn has_wildcard(component: &str) -> bool {
+    component.contains(['*', '?', '['])
+}
+
+/// Split a UNC (`\\server\share\...`), extended-length (`\\?\C:\...`,
+/// `\\?\UNC\server\share\...`) or device (`\\.\C:\...`) pattern. `None` for the other
+/// patterns, and for a root without its share or volume.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::path_pattern::split_windows_pattern;
+///
+/// let parts = split_windows_pattern(r"\\SERVER\C$\Users\*\AppData").unwrap();
+/// assert_eq!(parts.root, r"\\SERVER\C$\");
+/// assert_eq!(parts.components, ["Users", "*", "AppData"]);
+/// assert_eq!(split_windows_pattern(r"C:\Users\*"), None);
+/// ```
+pub fn split_windows_pattern(pattern: &str) -> Option<PatternParts> {
+    let rest = pattern.strip_prefix(r"\\")?;
+    let components: Vec<&str> = rest.split(['\\', '/']).filter(|c| !c.is_empty()).collect();
+    // `\\?\UNC\server\share\`, or the server and share or the prefix and volume
+    let root_len = match (components.first()?, components.get(1)) {
+        (&"?" | &".", Some(volume)) if volume.eq_ignore_ascii_case("UNC") => 4,
+        _ => 2,
+    };
+    if components.len() < root_len {
+        return None;
+    }
+    Some(PatternParts {
+        root: format!(r"\\{}\", components[..root_len].join(r"\")),
+        components: components[root_len..]
+            .iter()
+            .map(|c| c.to_string())
+            .collect(),
+    })
+}
+
+/// The paths matching `pattern`, with the errors of the directories that can't be read.
+/// Fails on an invalid pattern.
+///
+/// On Windows the patterns split by [`split_windows_pattern`] are expanded with
+/// [`PatternParts::expand`], the others by `glob` like on every other system.
+pub fn expand_pattern(pattern: &str) -> Result<Vec<Result<PathBuf, String>>, PatternError> {
+    match split_windows_pattern(pattern) {
+        Some(parts) if cfg!(windows) => parts.expand(),
+        _ => Ok(glob::glob(pattern)?
+            .map(|entry| entry.map_err(|e| format!("{:?}", e)))
+            .collect()),
+    }
+}
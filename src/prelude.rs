@@ -0,0 +1,29 @@
+//! The types and traits needed by most users of the crate, to import with
+//! `use jumplist_parser::prelude::*;`.
+//!
+//! The traits are those of the `normalize` and `flatten` methods, [`Normalize`] being the one
+//! of `winparsingtools` that this crate is built with, so it always matches the
+//! [`LNKParser`] of the entries.
+//!
+//! # Example
+//! ```
+//! use jumplist_parser::prelude::*;
+//!
+//! let parsed = JumplistParser::from_path_with_options(
+//!     "samples/win10/CustomDestinations/7e4dca80246863e3.customDestinations-ms",
+//!     &ParseOptions::default(),
+//! )
+//! .unwrap();
+//! assert_eq!(parsed.r#type, JumplistType::Custom);
+//! assert_eq!(parsed.flatten().len(), parsed.entry_count());
+//! for entry in parsed.entries() {
+//!     if let Some(lnk) = entry.lnk() {
+//!         println!("{:?}", lnk.normalize().get("target_full_path"));
+//!     }
+//! }
+//! ```
+
+pub use crate::{
+    errors::JumplistParserError, Flatten, JumplistData, JumplistEntryRef, JumplistParser,
+    JumplistType, LNKParser, Normalize, ParseMode, ParseOptions, ParseOutcome,
+};
@@ -0,0 +1,214 @@
+//! Serialized property stores ([MS-PROPSTORE]).
+//!
+//! A serialized property store is a list of property storages, each holding the values of
+//! one property set (format ID). Properties are identified by a numeric ID, or by a name
+//! for the string named property set `D5CDD505-2E9C-101B-9397-08002B2CF9AE`.
+//!
+//! [MS-PROPSTORE]: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-propstore/
+
+use crate::{
+    guid::Guid,
+    timestamp::{Timestamp, TimestampFormat},
+    utils::to_hex,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+/// Version signature of a property storage, `1SPS`.
+const STORAGE_VERSION: u32 = 0x5350_5331;
+/// Format ID of the property set whose properties are identified by name.
+const STRING_NAMED_FORMAT_ID: &str = "D5CDD505-2E9C-101B-9397-08002B2CF9AE";
+
+/// A typed property value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum PropertyValue {
+    Empty,
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    FileTime(Timestamp),
+    Guid(Guid),
+    /// A value of a type that isn't decoded, with its raw bytes as hex.
+    Unsupported {
+        vt: u16,
+        data: String,
+    },
+}
+
+/// A property of a property storage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Property {
+    /// Property ID, for the properties of numeric property sets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    /// Property name, for the properties of the string named property set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub value: PropertyValue,
+}
+
+/// The properties of one property set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PropertyStorage {
+    pub format_id: Guid,
+    pub properties: Vec<Property>,
+}
+
+/// A serialized property store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PropertyStore {
+    pub storages: Vec<PropertyStorage>,
+}
+
+fn u16_at(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        buf.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        buf.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn u64_at(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        buf.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// UTF-16 string of `len` code units at `offset`, cut at the first NUL.
+fn utf16_at(buf: &[u8], offset: usize, len: usize) -> Option<String> {
+    let bytes = buf.get(offset..offset.checked_add(len.checked_mul(2)?)?)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+impl PropertyValue {
+    /// Decode a `TypedPropertyValue` (a `VARTYPE`, 2 bytes of padding and the value).
+    fn from_buffer(buf: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        let vt = u16_at(buf, 0)?;
+        let data = &buf[4.min(buf.len())..];
+        let value = match vt {
+            0x00 => PropertyValue::Empty,
+            0x01 => PropertyValue::Null,
+            // VT_I2, VT_I4, VT_I1, VT_I8, VT_INT
+            0x02 => PropertyValue::Int(u16_at(data, 0)? as i16 as i64),
+            0x03 | 0x16 => PropertyValue::Int(u32_at(data, 0)? as i32 as i64),
+            0x10 => PropertyValue::Int(*data.first()? as i8 as i64),
+            0x14 => PropertyValue::Int(u64_at(data, 0)? as i64),
+            // VT_UI1, VT_UI2, VT_UI4, VT_UI8, VT_UINT
+            0x11 => PropertyValue::UInt(*data.first()? as u64),
+            0x12 => PropertyValue::UInt(u16_at(data, 0)? as u64),
+            0x13 | 0x17 => PropertyValue::UInt(u32_at(data, 0)? as u64),
+            0x15 => PropertyValue::UInt(u64_at(data, 0)?),
+            // VT_R4, VT_R8
+            0x04 => PropertyValue::Float(f32::from_bits(u32_at(data, 0)?) as f64),
+            0x05 => PropertyValue::Float(f64::from_bits(u64_at(data, 0)?)),
+            // VT_BOOL, `VARIANT_TRUE` is 0xFFFF
+            0x0B => PropertyValue::Bool(u16_at(data, 0)? != 0),
+            // VT_LPSTR, VT_LPWSTR (the sizes include the terminating NUL)
+            0x1E => {
+                let len = u32_at(data, 0)? as usize;
+                let bytes = data.get(4..4usize.checked_add(len)?)?;
+                let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+                PropertyValue::String(String::from_utf8_lossy(&bytes[..end]).to_string())
+            }
+            0x1F => PropertyValue::String(utf16_at(data, 4, u32_at(data, 0)? as usize)?),
+            // VT_FILETIME, VT_CLSID
+            0x40 => PropertyValue::FileTime(Timestamp::new(u64_at(data, 0)?, ts_format)),
+            0x48 => PropertyValue::Guid(Guid::from_buffer(data).ok()?),
+            _ => PropertyValue::Unsupported {
+                vt,
+                data: to_hex(data),
+            },
+        };
+        Some(value)
+    }
+}
+
+impl PropertyStorage {
+    /// Decode a property storage, `buf` holds exactly the storage.
+    fn from_buffer(buf: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        if u32_at(buf, 4)? != STORAGE_VERSION {
+            return None;
+        }
+        let format_id = Guid::from_buffer(buf.get(8..24)?).ok()?;
+        let named = format_id.to_string() == STRING_NAMED_FORMAT_ID;
+
+        let mut properties = vec![];
+        let mut offset = 24;
+        loop {
+            let value_size = u32_at(buf, offset)? as usize;
+            // A zero size terminates the list
+            if value_size == 0 {
+                break;
+            }
+            let value = buf.get(offset..offset.checked_add(value_size)?)?;
+            let property = match named {
+                true => {
+                    let name_size = u32_at(value, 4)? as usize;
+                    let name = utf16_at(value, 9, name_size / 2)?;
+                    let typed_value = value.get(9usize.checked_add(name_size)?..)?;
+                    Property {
+                        id: None,
+                        name: Some(name),
+                        value: PropertyValue::from_buffer(typed_value, ts_format)?,
+                    }
+                }
+                false => Property {
+                    id: Some(u32_at(value, 4)?),
+                    name: None,
+                    value: PropertyValue::from_buffer(value.get(9..)?, ts_format)?,
+                },
+            };
+            properties.push(property);
+            offset += value_size;
+        }
+
+        Some(Self {
+            format_id,
+            properties,
+        })
+    }
+}
+
+impl PropertyStore {
+    /// Whether `buf` starts with a property storage.
+    pub fn is_property_store(buf: &[u8]) -> bool {
+        u32_at(buf, 4) == Some(STORAGE_VERSION)
+    }
+
+    /// Decode a serialized property store, `None` when `buf` isn't one or is corrupt.
+    pub fn from_buffer(buf: &[u8], ts_format: TimestampFormat) -> Option<Self> {
+        if !Self::is_property_store(buf) {
+            return None;
+        }
+        let mut storages = vec![];
+        let mut offset = 0;
+        loop {
+            let storage_size = match u32_at(buf, offset) {
+                // A zero size (or the end of the buffer) terminates the store
+                Some(0) | None => break,
+                Some(size) => size as usize,
+            };
+            let storage = buf.get(offset..offset.checked_add(storage_size)?)?;
+            storages.push(PropertyStorage::from_buffer(storage, ts_format)?);
+            offset += storage_size;
+        }
+        Some(Self { storages })
+    }
+}
@@ -0,0 +1,81 @@
+//! Standalone LNK files, such as the shortcuts of the `Recent` folder that holds the
+//! `AutomaticDestinations` and `CustomDestinations` folders.
+//!
+//! Windows creates one of these shortcuts for every file and folder opened through the
+//! shell. They are parsed into a [`JumplistParser`](crate::JumplistParser) of type
+//! [`JumplistType::RecentLnk`](crate::JumplistType::RecentLnk) with a single entry, see
+//! [`parse_lnk_file`](crate::parse_lnk_file), so that they get the same normalized records,
+//! filters and output as the entries of the Jumplists. They have no AppID.
+
+use crate::{
+    custom_destinations::CustomDestinationsEntry, errors::JumplistParserError,
+    fingerprint::entry_fingerprint, options::LimitTracker, utils::remaining_len, ParseOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+use winparsingtools::traits::Normalize;
+
+/// A parsed standalone LNK file.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{builder::lnk_bytes, recent_lnk::RecentLnk};
+/// use std::io::Cursor;
+///
+/// let lnk = lnk_bytes(r"C:\Users\user\Desktop\notes.txt");
+/// let parsed = RecentLnk::from_reader(&mut Cursor::new(lnk)).unwrap();
+/// assert!(parsed.lnk_file.unwrap().lnk.is_some());
+/// assert!(parsed.warnings.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RecentLnk {
+    /// The LNK, `None` once removed by
+    /// [`JumplistParser::retain_entries`](crate::JumplistParser::retain_entries).
+    pub lnk_file: Option<CustomDestinationsEntry>,
+    /// Inconsistencies found while parsing (e.g. data after the end of the LNK).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl RecentLnk {
+    /// Parse the LNK file read by `r`, from its current position to its end.
+    pub fn from_reader<R: Read + Seek>(r: &mut R) -> Result<Self, JumplistParserError> {
+        Self::from_reader_with_options(r, &ParseOptions::default())
+    }
+
+    /// Same as [`RecentLnk::from_reader`] but with explicit [`ParseOptions`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        r: &mut R,
+        options: &ParseOptions,
+    ) -> Result<Self, JumplistParserError> {
+        let size = remaining_len(r)?;
+        let mut tracker = LimitTracker::new(options.limits);
+        let lnk_file = CustomDestinationsEntry::from_lnk_reader(r, 0, options, &mut tracker)?;
+        let mut warnings = vec![];
+        if lnk_file.entry_size < size {
+            warnings.push(format!(
+                "{} bytes after the end of the LNK",
+                size - lnk_file.entry_size
+            ));
+        }
+        options.check_warnings(&warnings)?;
+        Ok(RecentLnk {
+            lnk_file: Some(lnk_file),
+            warnings,
+        })
+    }
+
+    /// Set the fingerprint of the LNK, its position is `0`. The
+    /// [`JumplistParser`](crate::JumplistParser) parse functions set it.
+    pub fn update_fingerprints(&mut self, app_id: Option<&str>) {
+        if let Some(entry) = self.lnk_file.as_mut() {
+            let target_path = entry
+                .lnk
+                .as_ref()
+                .and_then(|lnk| lnk.normalize().remove("target_full_path"));
+            entry.entry_fingerprint =
+                entry_fingerprint(app_id, "0", target_path.as_deref(), None, None);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! JSON Schema of the parser output (requires the `schema` feature, enabled by default).
+//!
+//! The schema covers both record shapes written by the CLI:
+//! - the raw [`JumplistParser`] records (`--output-format json/jsonl`), and
+//! - the normalized records produced by [`Flatten`](crate::Flatten) (`--normalize`).
+//!
+//! Structures coming from the `lnk_parser` crate are described as free-form objects: the
+//! `lnk` field of DestList entries, and the LNK fields flattened into custom entries.
+
+use crate::{
+    custom_destinations::CategoryId,
+    guid::{Droid, Guid},
+    timestamp::Timestamp,
+    JumplistParser,
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{
+        InstanceType, Metadata, RootSchema, Schema, SchemaObject, StringValidation,
+        SubschemaValidation,
+    },
+    JsonSchema,
+};
+
+/// A normalized entry, every value is a string (empty when unknown). See the
+/// [`output`](crate::output) module for the key names.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct NormalizedEntry {
+    /// Path of the parsed Jumplist file.
+    jumplist_file_path: String,
+    /// Path of the LNK target.
+    target_full_path: String,
+    /// Where `target_full_path` comes from, in order of precedence: `link_info` (the local
+    /// path of the LNK), `network_share` (the UNC path of the LNK), `shell_items` (the LNK ID
+    /// list) or `destlist` (the `DestList` entry path).
+    target_path_source: String,
+    /// `target_full_path` with known folder GUIDs replaced by their names.
+    target_full_path_resolved: String,
+    /// `target_full_path` canonicalized with its well-known environment variables expanded,
+    /// only present with `ParseOptions::canonicalize_paths`.
+    target_full_path_canonical: Option<String>,
+    /// `file`, `directory`, `url`, `unc` or `shell`.
+    target_type: String,
+    /// Drive letter of a local target.
+    target_drive_letter: String,
+    /// Share of a UNC target.
+    target_share: String,
+    /// Extension of the target, lowercase without its `.`, empty for directories and URLs.
+    target_extension: String,
+    /// `document`, `spreadsheet`, `presentation`, `image`, `archive`, `executable`, `script`,
+    /// `media` or `other`, from `target_extension`.
+    target_category: String,
+    /// Command line arguments of the LNK.
+    lnk_command_line_arguments: String,
+    /// Description of the LNK.
+    lnk_description: String,
+    /// Modification time of the LNK target.
+    target_modification_time: String,
+    /// Access time of the LNK target.
+    target_access_time: String,
+    /// Creation time of the LNK target.
+    target_creation_time: String,
+    /// Size of the LNK target.
+    target_size: String,
+    /// Names of the file attributes of the LNK target, comma separated, e.g.
+    /// `hidden,system,archive`.
+    target_attributes: String,
+    /// File attributes of the LNK target, as a number.
+    target_attributes_value: String,
+    /// `true` when the LNK target has the hidden attribute.
+    target_is_hidden: String,
+    /// `true` when the LNK target has the directory attribute.
+    target_is_directory: String,
+    /// NetBIOS name of the machine the target was on, the `DestList` hostname or the one
+    /// recorded in the LNK.
+    target_hostname: String,
+    /// Serial number of the volume of the LNK target, e.g. `405B-FCF0`.
+    target_volume_serial: String,
+    /// `unknown`, `no_root_dir`, `removable`, `fixed`, `remote`, `cdrom` or `ramdisk`.
+    target_drive_type: String,
+    /// Label of the volume of the LNK target.
+    target_volume_label: String,
+    /// Share of a network LNK target, from the LNK `LinkInfo`.
+    target_network_share: String,
+    /// Network provider of a network LNK target, e.g. `WNNC_NET_LANMAN`.
+    target_network_provider: String,
+    /// `true` when the LNK target was on a removable drive.
+    target_on_removable_media: String,
+    /// Working directory of the LNK target.
+    lnk_working_directory: String,
+    /// Icon location of the LNK, often the real binary behind the target.
+    lnk_icon_location: String,
+    /// Path of the LNK target relative to the LNK.
+    lnk_relative_path: String,
+    /// State of the window opened by the LNK, e.g. `normal`, `maximized` or
+    /// `minimized_no_activate`.
+    lnk_show_command: String,
+    /// Hotkey of the LNK, e.g. `Ctrl+Alt+K`.
+    lnk_hotkey: String,
+    /// MFT entry number of the LNK target, only present when the LNK shell items record it.
+    target_mft_entry: Option<String>,
+    /// MFT sequence number of the LNK target, only present with `target_mft_entry`.
+    target_mft_sequence: Option<String>,
+    /// Machine name from the LNK `TrackerDataBlock`.
+    lnk_tracker_machine_id: String,
+    /// MAC address from the LNK tracker file droid (version 1 UUID).
+    lnk_tracker_mac_address: String,
+    /// Creation time of the LNK tracker file droid.
+    lnk_tracker_created_time: String,
+    /// `true` when `lnk_tracker_machine_id` differs from the `DestList` hostname.
+    destlist_hostname_mismatch: String,
+    /// Number of the `DestList` entry. Not present for custom entries.
+    destlist_entry_number: Option<String>,
+    /// Last time the target was opened, from the `DestList` entry. Not present for custom
+    /// entries.
+    destlist_interaction_time: Option<String>,
+    /// Number of times the target was opened, empty for version 1 `DestList` streams. Not
+    /// present for custom entries.
+    destlist_access_count: Option<String>,
+    /// Hostname recorded in the `DestList` entry. Not present for custom entries.
+    destlist_hostname: Option<String>,
+    /// Path recorded in the `DestList` entry, next to `target_full_path` which comes from the
+    /// LNK when it has one. Not present for custom entries.
+    destlist_path: Option<String>,
+    /// `true` when `destlist_path` and the target path of the LNK name different targets,
+    /// compared case insensitively with `/` and `\` alike. Not present for custom entries.
+    path_mismatch: Option<String>,
+    /// Volume droid of the `DestList` entry, formatted like the raw output (see `Droid`),
+    /// empty for a zero GUID that isn't kept. Not present for custom entries.
+    destlist_volume_droid: Option<String>,
+    /// File droid of the `DestList` entry. Not present for custom entries.
+    destlist_file_droid: Option<String>,
+    /// Volume birth droid of the `DestList` entry. Not present for custom entries.
+    destlist_volume_birth_droid: Option<String>,
+    /// File birth droid of the `DestList` entry. Not present for custom entries.
+    destlist_file_birth_droid: Option<String>,
+    /// ID of the category of a custom entry (see `CategoryId`), empty for custom and task
+    /// categories. Not present for `DestList` entries.
+    entry_category_id: Option<String>,
+    /// `true` for a pinned entry, the entries of the tasks and of the pinned items block of
+    /// custom Jumplists count as pinned.
+    entry_pinned: String,
+    /// Windows generation inferred from the format versions, e.g. `win10_creators`.
+    jumplist_os_hint: String,
+    /// Format version of the `DestList` stream of an automatic Jumplist or of the header of
+    /// a custom one, empty for an empty `DestList` and standalone LNK files.
+    jumplist_format_version: String,
+    /// User whose profile the Jumplist was found in.
+    jumplist_user: String,
+    /// Deterministic ID of the entry (`v1:` and a SHA-1 in hex), see the `fingerprint` module
+    /// for the hashed fields.
+    entry_fingerprint: String,
+    /// `parsed`, `missing` (no LNK stream), `parse_failed` (the LNK stream is corrupt) or
+    /// `skipped` (the LNK streams were not looked up).
+    lnk_status: String,
+    /// Offset of the entry within the `DestList` stream or the custom Jumplist file.
+    entry_offset: String,
+    /// Size of the entry in bytes.
+    entry_size: String,
+    /// AppID of the Jumplist, empty for standalone LNK files.
+    jumplist_app_id: String,
+    /// Application name of the AppID.
+    jumplist_app_name: String,
+    /// `automatic`, `custom` or `recent_lnk` (a standalone LNK file of the `Recent` folder).
+    jumplist_type: String,
+    /// Size of the Jumplist file in bytes, not present for stdin.
+    jumplist_file_size: Option<String>,
+    /// Modification time of the Jumplist file, not present for stdin.
+    jumplist_file_modified: Option<String>,
+}
+
+impl JsonSchema for Timestamp {
+    fn schema_name() -> String {
+        "Timestamp".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(
+                vec![
+                    InstanceType::String,
+                    InstanceType::Integer,
+                    InstanceType::Null,
+                ]
+                .into(),
+            ),
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "ISO 8601 UTC string, Unix epoch seconds or raw FILETIME depending on the \
+                     timestamp format, null when not set"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl JsonSchema for Guid {
+    fn schema_name() -> String {
+        "Guid".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(
+                    "^[0-9A-F]{8}-[0-9A-F]{4}-[0-9A-F]{4}-[0-9A-F]{4}-[0-9A-F]{12}$".to_string(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl JsonSchema for Droid {
+    fn schema_name() -> String {
+        "Droid".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(vec![InstanceType::String, InstanceType::Null].into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(
+                    "^(urn:uuid:|\\{)?[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-\
+                     [0-9A-Fa-f]{12}\\}?$"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "GUID in the format of `ParseOptions::guid_format`, null (or left out) for \
+                     a zero GUID depending on the format"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl JsonSchema for CategoryId {
+    fn schema_name() -> String {
+        "CategoryId".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "`frequent`, `recent`, `pinned`, `none` or `unknown(0x0000000A)` for an \
+                     unknown ID"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// The JSON Schema (draft 7) of the output, pretty printed.
+///
+/// The root schema accepts either a raw record (`#/definitions/JumplistParser`) or a
+/// normalized record (`#/definitions/NormalizedEntry`).
+pub fn json_schema() -> String {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let raw = gen.subschema_for::<JumplistParser>();
+    let normalized = gen.subschema_for::<NormalizedEntry>();
+    let root = RootSchema {
+        meta_schema: gen.settings().meta_schema.clone(),
+        schema: SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some("jumplist_parser output".to_string()),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![raw, normalized]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        definitions: gen.take_definitions(),
+    };
+    serde_json::to_string_pretty(&root).unwrap_or_default()
+}
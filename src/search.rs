@@ -0,0 +1,87 @@
+//! Searching the entries of a parsed Jumplist.
+//!
+//! The search runs over the normalized representation of the entries (see
+//! [`JumplistEntryRef::normalize`](crate::JumplistEntryRef)), so automatic and custom
+//! Jumplists are searched the same way.
+
+use crate::{
+    output::{legacy_key, OFFSET_CSV_COLUMNS},
+    JumplistParser,
+};
+use serde::{Deserialize, Serialize};
+use winparsingtools::traits::Normalize;
+
+/// A field of a Jumplist entry that matched a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// Position of the entry in [`JumplistParser::entries`], which is also the position of its
+    /// row in the normalized and CSV output.
+    pub index: usize,
+    /// Entry number of a `DestList` entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_number: Option<u32>,
+    /// Category of a custom entry, see [`JumplistEntryRef::category`](crate::JumplistEntryRef::category).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Name of the normalized field that matched (e.g. `target_full_path`), its legacy name
+    /// with [`ParseOptions::legacy_keys`](crate::ParseOptions::legacy_keys).
+    pub field: String,
+    /// Value of the field.
+    pub value: String,
+}
+
+impl JumplistParser {
+    /// Every field of every entry that contains `needle`, in entry order and then by field
+    /// name. An entry with several matching fields gives one hit per field.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::JumplistParser;
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// for hit in parsed.search("users", true) {
+    ///     println!("entry {:?}: {} = {}", hit.entry_number, hit.field, hit.value);
+    /// }
+    /// ```
+    pub fn search(&self, needle: &str, case_insensitive: bool) -> Vec<SearchHit> {
+        match case_insensitive {
+            true => {
+                let needle = needle.to_lowercase();
+                self.search_by(|value| value.to_lowercase().contains(&needle))
+            }
+            false => self.search_by(|value| value.contains(needle)),
+        }
+    }
+
+    /// Same as [`JumplistParser::search`] with a custom matcher, e.g. a regular expression.
+    ///
+    /// Empty fields and the entry offset and size fields are never matched.
+    pub fn search_by(&self, is_match: impl Fn(&str) -> bool) -> Vec<SearchHit> {
+        let mut hits = vec![];
+        for (index, entry) in self.entries().enumerate() {
+            let mut fields: Vec<(String, String)> = entry
+                .normalize()
+                .into_iter()
+                .filter(|(field, value)| {
+                    !value.is_empty() && !OFFSET_CSV_COLUMNS.contains(&field.as_str())
+                })
+                .filter(|(_, value)| is_match(value))
+                .collect();
+            fields.sort();
+            hits.extend(fields.into_iter().map(|(field, value)| SearchHit {
+                index,
+                entry_number: entry.entry_number(),
+                category: entry.category().map(|c| c.to_string()),
+                field: match self.legacy_keys {
+                    true => legacy_key(&field).map(|f| f.to_string()).unwrap_or(field),
+                    false => field,
+                },
+                value,
+            }));
+        }
+        hits
+    }
+}
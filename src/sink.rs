@@ -0,0 +1,236 @@
+//! Sending normalized entries to an HTTP endpoint (`--output-url`), e.g. a Splunk HTTP Event
+//! Collector (HEC) or any endpoint accepting NDJSON.
+//!
+//! Entries are sent in batches with a blocking client. A batch that fails with a server
+//! error (5xx) or a network error is retried with an exponential backoff, a batch that
+//! still fails is appended to a local spool file so that no entry is lost.
+
+use crate::NormalizedEntry;
+use serde_json::json;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+use ureq::{tls::TlsConfig, Agent};
+
+/// Delay before the first retry of a batch, doubled for every following retry.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// `sourcetype` of the HEC events.
+const HEC_SOURCETYPE: &str = "jumplist_parser";
+
+/// Options of an [`HttpSink`].
+#[derive(Debug, Clone)]
+pub struct HttpSinkOptions {
+    pub url: String,
+    /// Token sent in the `Authorization` header: `Splunk <token>` with [`HttpSinkOptions::hec`],
+    /// `Bearer <token>` otherwise. A value with a space (e.g. `Basic dXNlcjpwYXNz`) is sent
+    /// as is.
+    pub auth: Option<String>,
+    /// Number of entries per request.
+    pub batch_size: usize,
+    /// Wrap every entry in a HEC event envelope instead of sending plain NDJSON.
+    pub hec: bool,
+    /// Don't verify the TLS certificate of the endpoint, for lab use.
+    pub insecure: bool,
+    /// Number of retries of a batch after a server or network error.
+    pub retries: u32,
+    /// File the entries of the failed batches are appended to, as NDJSON.
+    pub spool_path: PathBuf,
+}
+
+impl Default for HttpSinkOptions {
+    fn default() -> Self {
+        HttpSinkOptions {
+            url: String::new(),
+            auth: None,
+            batch_size: 500,
+            hec: false,
+            insecure: false,
+            retries: 3,
+            spool_path: PathBuf::from("jumplist_parser_spool.jsonl"),
+        }
+    }
+}
+
+/// Counts of the batches sent by an [`HttpSink`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpSinkSummary {
+    pub sent_batches: usize,
+    pub sent_entries: usize,
+    pub failed_batches: usize,
+    /// Entries of the failed batches, written to [`HttpSinkOptions::spool_path`].
+    pub spooled_entries: usize,
+}
+
+/// Batches normalized entries and POSTs them to [`HttpSinkOptions::url`].
+///
+/// # Example
+/// ```no_run
+/// use jumplist_parser::{sink::{HttpSink, HttpSinkOptions}, Flatten, JumplistParser};
+///
+/// let parsed = JumplistParser::from_path(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+/// )
+/// .unwrap();
+/// let mut sink = HttpSink::new(HttpSinkOptions {
+///     url: "https://splunk.example.com:8088/services/collector/event".to_string(),
+///     auth: Some("00000000-0000-0000-0000-000000000000".to_string()),
+///     hec: true,
+///     ..Default::default()
+/// });
+/// for entry in parsed.flatten() {
+///     sink.push(entry);
+/// }
+/// let summary = sink.finish();
+/// println!("{} batches sent, {} failed", summary.sent_batches, summary.failed_batches);
+/// ```
+pub struct HttpSink {
+    agent: Agent,
+    options: HttpSinkOptions,
+    batch: Vec<NormalizedEntry>,
+    summary: HttpSinkSummary,
+}
+
+impl HttpSink {
+    pub fn new(options: HttpSinkOptions) -> Self {
+        let agent = Agent::config_builder()
+            .tls_config(
+                TlsConfig::builder()
+                    .disable_verification(options.insecure)
+                    .build(),
+            )
+            .http_status_as_error(false)
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+        HttpSink {
+            agent,
+            batch: Vec::with_capacity(options.batch_size),
+            options,
+            summary: HttpSinkSummary::default(),
+        }
+    }
+
+    /// Queue `entry`, the batch is sent once it holds [`HttpSinkOptions::batch_size`] entries.
+    pub fn push(&mut self, entry: NormalizedEntry) {
+        self.batch.push(entry);
+        if self.batch.len() >= self.options.batch_size.max(1) {
+            self.flush();
+        }
+    }
+
+    /// Send the queued entries.
+    pub fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(&mut self.batch);
+        match self.send(&batch) {
+            Ok(()) => {
+                self.summary.sent_batches += 1;
+                self.summary.sent_entries += batch.len();
+            }
+            Err(e) => {
+                self.summary.failed_batches += 1;
+                eprintln!(
+                    "Can't send a batch of {} entries to '{}', spooling it to '{}'. ERROR : '{}'",
+                    batch.len(),
+                    self.options.url,
+                    self.options.spool_path.display(),
+                    e
+                );
+                match self.spool(&batch) {
+                    Ok(()) => self.summary.spooled_entries += batch.len(),
+                    Err(e) => eprintln!(
+                        "Can't write to the spool file '{}', {} entries are lost. ERROR : '{}'",
+                        self.options.spool_path.display(),
+                        batch.len(),
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Send the last batch and return the counts.
+    pub fn finish(mut self) -> HttpSinkSummary {
+        self.flush();
+        self.summary
+    }
+
+    /// The request body: one JSON object per line, HEC events with [`HttpSinkOptions::hec`].
+    fn body(&self, batch: &[NormalizedEntry]) -> String {
+        batch
+            .iter()
+            .map(|entry| {
+                let value = match self.options.hec {
+                    true => json!({
+                        "source": entry.get("jumplist_file_path").cloned().unwrap_or_default(),
+                        "sourcetype": HEC_SOURCETYPE,
+                        "event": entry,
+                    }),
+                    false => json!(entry),
+                };
+                value.to_string() + "\n"
+            })
+            .collect()
+    }
+
+    fn send(&self, batch: &[NormalizedEntry]) -> Result<(), String> {
+        let body = self.body(batch);
+        let content_type = match self.options.hec {
+            true => "application/json",
+            false => "application/x-ndjson",
+        };
+        let authorization =
+            self.options
+                .auth
+                .as_ref()
+                .map(|auth| match (auth.contains(' '), self.options.hec) {
+                    (true, _) => auth.clone(),
+                    (false, true) => format!("Splunk {}", auth),
+                    (false, false) => format!("Bearer {}", auth),
+                });
+
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .agent
+                .post(&self.options.url)
+                .header("Content-Type", content_type);
+            if let Some(authorization) = &authorization {
+                request = request.header("Authorization", authorization);
+            }
+            let error = match request.send(body.as_str()) {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) if response.status().is_server_error() => {
+                    format!("HTTP status {}", response.status())
+                }
+                // Client errors (bad token, bad request) don't get better by retrying
+                Ok(response) => return Err(format!("HTTP status {}", response.status())),
+                Err(e) => e.to_string(),
+            };
+            if attempt >= self.options.retries {
+                return Err(error);
+            }
+            thread::sleep(RETRY_DELAY * 2u32.pow(attempt));
+            attempt += 1;
+        }
+    }
+
+    fn spool(&self, batch: &[NormalizedEntry]) -> io::Result<()> {
+        let mut spool = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.options.spool_path)?;
+        for entry in batch {
+            serde_json::to_writer(&mut spool, entry)?;
+            spool.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Ordering of Jumplist entries across files (`--sort-by`).
+
+use crate::{entry::JumplistEntryRef, JumplistParser};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// Field the entries are sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    /// Last interaction time recorded in the `DestList`.
+    DestListMtime,
+    /// Modification time of the target, from the LNK header.
+    TargetModificationTime,
+    /// Path of the target, compared case-insensitively.
+    TargetFullPath,
+    /// Name of the application of the Jumplist.
+    AppName,
+    /// Entry number of a `DestList` entry.
+    EntryNumber,
+}
+
+impl SortField {
+    /// Names accepted by [`SortField::from_str`].
+    pub const NAMES: &'static [&'static str] = &[
+        "destlist_mtime",
+        "target_modification_time",
+        "target_full_path",
+        "app_name",
+        "entry_number",
+    ];
+
+    /// The value of this field for `entry` of `parsed`, `None` when the entry doesn't have it
+    /// (e.g. the `DestList` fields of custom entries).
+    pub fn value(&self, parsed: &JumplistParser, entry: &JumplistEntryRef) -> Option<SortValue> {
+        match self {
+            SortField::DestListMtime => entry
+                .interaction_time()
+                .map(|t| SortValue::Number(t.filetime)),
+            SortField::TargetModificationTime => entry
+                .lnk_header()
+                .filter(|h| !h.write_time.is_zero())
+                .map(|h| SortValue::Number(h.write_time.filetime)),
+            SortField::TargetFullPath => entry
+                .target_path()
+                .map(|p| SortValue::Text(p.to_lowercase())),
+            SortField::AppName => parsed
+                .app_name
+                .as_ref()
+                .map(|n| SortValue::Text(n.to_lowercase())),
+            SortField::EntryNumber => entry.entry_number().map(|n| SortValue::Number(n as u64)),
+        }
+    }
+}
+
+impl FromStr for SortField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "destlist_mtime" => Ok(SortField::DestListMtime),
+            "target_modification_time" => Ok(SortField::TargetModificationTime),
+            "target_full_path" => Ok(SortField::TargetFullPath),
+            "app_name" => Ok(SortField::AppName),
+            "entry_number" => Ok(SortField::EntryNumber),
+            _ => Err(format!("Unknown sort field '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for SortField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SortField::DestListMtime => "destlist_mtime",
+            SortField::TargetModificationTime => "target_modification_time",
+            SortField::TargetFullPath => "target_full_path",
+            SortField::AppName => "app_name",
+            SortField::EntryNumber => "entry_number",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Value of a [`SortField`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortValue {
+    Number(u64),
+    Text(String),
+}
+
+/// Position of an entry in a sorted output: the value of the sort field, then the path of
+/// its Jumplist, its entry number and its position in the Jumplist to break ties. No two
+/// entries of a run compare equal, so every sort is reproducible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub value: Option<SortValue>,
+    pub source_path: String,
+    /// Entry number of a `DestList` entry, position in the file for custom entries.
+    pub number: u32,
+    /// Position in [`JumplistParser::entries`], which tells apart `DestList` entries
+    /// sharing an entry number.
+    pub index: usize,
+}
+
+impl SortKey {
+    /// The key of `entry`, the entry at `index` of [`JumplistParser::entries`].
+    pub fn new(
+        field: SortField,
+        parsed: &JumplistParser,
+        index: usize,
+        entry: &JumplistEntryRef,
+    ) -> Self {
+        SortKey {
+            value: field.value(parsed, entry),
+            source_path: parsed.source_path.clone().unwrap_or_default(),
+            number: entry.entry_number().unwrap_or(index as u32),
+            index,
+        }
+    }
+
+    /// Compare by value, ascending or descending, then by path, entry number and position.
+    /// Entries without a value always come last.
+    pub fn compare(&self, other: &Self, descending: bool) -> Ordering {
+        let by_value = match (&self.value, &other.value) {
+            (Some(a), Some(b)) if descending => b.cmp(a),
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        by_value
+            .then_with(|| self.source_path.cmp(&other.source_path))
+            .then_with(|| self.number.cmp(&other.number))
+            .then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+impl JumplistParser {
+    /// The entries sorted by `field`, see [`SortKey::compare`].
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::{sort::SortField, JumplistParser};
+    ///
+    /// let parsed = JumplistParser::from_path(
+    ///     "samples/win11/AutomaticDestinations/5f7b5f1e01b83767.automaticDestinations-ms",
+    /// )
+    /// .unwrap();
+    /// let recent = parsed.sorted_entries(SortField::DestListMtime, true);
+    /// assert!(recent
+    ///     .windows(2)
+    ///     .all(|w| w[0].interaction_time().unwrap().filetime >= w[1].interaction_time().unwrap().filetime));
+    /// ```
+    pub fn sorted_entries(&self, field: SortField, descending: bool) -> Vec<JumplistEntryRef<'_>> {
+        let mut entries: Vec<(SortKey, JumplistEntryRef)> = self
+            .entries()
+            .enumerate()
+            .map(|(index, entry)| (SortKey::new(field, self, index, &entry), entry))
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.compare(b, descending));
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
@@ -0,0 +1,131 @@
+//! Parse durations and counters of a file, collected with
+//! [`ParseOptions::collect_stats`](crate::ParseOptions::collect_stats).
+//!
+//! The durations are wall-clock times measured with [`Instant`] around the phases of the
+//! parse. Nothing is timed when the stats aren't collected.
+
+use crate::{destlist::LnkStatus, entry::JumplistEntryRef, JumplistParser, ParseOptions};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    time::Instant,
+};
+
+/// Where the time went while parsing a file, in [`JumplistParser::stats`].
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{JumplistParser, ParseOptions};
+///
+/// let options = ParseOptions {
+///     collect_stats: true,
+///     ..Default::default()
+/// };
+/// let parsed = JumplistParser::from_path_with_options(
+///     "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+///     &options,
+/// )
+/// .unwrap();
+/// let stats = parsed.stats.unwrap();
+/// assert_eq!(stats.entries_parsed, 24);
+/// assert_eq!(stats.streams_opened, 25);
+/// println!("{}", stats);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ParseStats {
+    /// Time of the whole parse of the file contents, in microseconds.
+    pub total_micros: u64,
+    /// Time to open the compound file of an automatic Jumplist (its header, allocation
+    /// tables and directory), in microseconds.
+    pub cfb_open_micros: u64,
+    /// Time to read and parse the `DestList` stream, in microseconds.
+    pub destlist_parse_micros: u64,
+    /// Time to read and parse the LNK streams of an automatic Jumplist, in microseconds.
+    /// The LNKs of custom Jumplists and standalone LNK files are their entries, this is the
+    /// time of the whole parse, carving included.
+    pub lnk_parse_micros: u64,
+    /// Streams of the compound file read: the `DestList` and LNK streams, 0 for the other
+    /// types.
+    pub streams_opened: u64,
+    /// Bytes of the streams read, or the size of a custom Jumplist or LNK file.
+    pub bytes_read: u64,
+    /// Entries parsed.
+    pub entries_parsed: u64,
+    /// Entries with a parsed LNK.
+    pub lnks_parsed: u64,
+    /// Entries whose LNK can't be read or parsed.
+    pub lnks_failed: u64,
+}
+
+impl ParseStats {
+    /// Empty stats when `options` collect them, never on `wasm32` which has no clock.
+    pub(crate) fn for_options(options: &ParseOptions) -> Option<Self> {
+        (options.collect_stats && cfg!(not(target_arch = "wasm32"))).then(ParseStats::default)
+    }
+
+    /// Count the entries of `parsed` and their LNKs.
+    pub(crate) fn count_entries(&mut self, parsed: &JumplistParser) {
+        for entry in parsed.entries() {
+            self.entries_parsed += 1;
+            match (&entry, entry.lnk()) {
+                (_, Some(_)) => self.lnks_parsed += 1,
+                (JumplistEntryRef::DestList(entry), None) => {
+                    if let LnkStatus::ParseFailed(_) = entry.lnk_status {
+                        self.lnks_failed += 1;
+                    }
+                }
+                (_, None) => self.lnks_failed += 1,
+            }
+        }
+    }
+
+    /// Add the durations and counters of `other`, for the totals of many files.
+    pub fn add(&mut self, other: &ParseStats) {
+        self.total_micros += other.total_micros;
+        self.cfb_open_micros += other.cfb_open_micros;
+        self.destlist_parse_micros += other.destlist_parse_micros;
+        self.lnk_parse_micros += other.lnk_parse_micros;
+        self.streams_opened += other.streams_opened;
+        self.bytes_read += other.bytes_read;
+        self.entries_parsed += other.entries_parsed;
+        self.lnks_parsed += other.lnks_parsed;
+        self.lnks_failed += other.lnks_failed;
+    }
+}
+
+impl Display for ParseStats {
+    /// One line, with the durations in milliseconds.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = |micros: u64| micros as f64 / 1000.0;
+        write!(
+            f,
+            "{:.3} ms (CFB open {:.3} ms, DestList {:.3} ms, LNKs {:.3} ms), {} streams, {} \
+             bytes, {} entries, {} LNKs parsed, {} failed",
+            millis(self.total_micros),
+            millis(self.cfb_open_micros),
+            millis(self.destlist_parse_micros),
+            millis(self.lnk_parse_micros),
+            self.streams_opened,
+            self.bytes_read,
+            self.entries_parsed,
+            self.lnks_parsed,
+            self.lnks_failed
+        )
+    }
+}
+
+/// Times a phase of the parse, only when there are stats to record it in.
+pub(crate) struct Timer(Option<Instant>);
+
+impl Timer {
+    /// Start timing when `stats` is `Some`.
+    pub(crate) fn start(stats: &Option<ParseStats>) -> Self {
+        Timer(stats.as_ref().map(|_| Instant::now()))
+    }
+
+    /// Microseconds since the start, 0 when not timed.
+    pub(crate) fn micros(&self) -> u64 {
+        self.0.map_or(0, |start| start.elapsed().as_micros() as u64)
+    }
+}
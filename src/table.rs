@@ -0,0 +1,226 @@
+//! Aligned, human readable table output of Jumplist entries for terminals.
+//!
+//! Every Jumplist is rendered as its own table under a line naming the file, so the
+//! columns are only as wide as the values of that file. Values longer than their column
+//! are shortened, paths from the middle to keep the file name.
+
+use crate::{Flatten, JumplistParser};
+
+/// Columns of the table when none are chosen.
+pub const TABLE_COLUMNS: &[&str] = &["app_name", "target_full_path", "interaction_time", "pinned"];
+
+/// Space between two columns.
+const COLUMN_GAP: &str = "  ";
+/// Columns are never shortened below this width.
+const MIN_COLUMN_WIDTH: usize = 8;
+const ELLIPSIS: char = '…';
+const BOLD: &str = "\x1b[1m";
+const TITLE: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+
+/// Options for the table output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Columns to print: the normalized fields of the entries (see [`Flatten`]) and `app_id`,
+    /// `app_name`, `type`, `entry_number`, `interaction_time`, `pinned`, `access_count`
+    /// and `category`.
+    pub columns: Vec<String>,
+    /// Maximum width of a line, the widest columns are shortened to fit. `None` never
+    /// shortens the values.
+    pub width: Option<usize>,
+    /// Highlight the file lines and the column headers with ANSI escape codes.
+    pub color: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            columns: TABLE_COLUMNS.iter().map(|c| c.to_string()).collect(),
+            width: None,
+            color: false,
+        }
+    }
+}
+
+/// Shorten `path` to `width` characters by replacing its middle with `…`, keeping the file
+/// name. When the file name alone is too long, its end is kept.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::table::shorten_path;
+///
+/// assert_eq!(
+///     shorten_path(r"C:\Users\u0041\Documents\Reports\2024\report.docx", 30),
+///     r"C:\Users\u0041\Do…\report.docx"
+/// );
+/// assert_eq!(shorten_path(r"C:\report.docx", 30), r"C:\report.docx");
+/// ```
+pub fn shorten_path(path: &str, width: usize) -> String {
+    let length = path.chars().count();
+    if length <= width {
+        return path.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let name_start = path.rfind(['\\', '/']).unwrap_or(0);
+    let name_length = path[name_start..].chars().count();
+    let keep_end = name_length.min(width - 1);
+    let keep_start = width - 1 - keep_end;
+    let mut shortened: String = path.chars().take(keep_start).collect();
+    shortened.push(ELLIPSIS);
+    shortened.extend(path.chars().skip(length - keep_end));
+    shortened
+}
+
+/// Shorten `value` to `width` characters, from the middle for paths and from the end
+/// otherwise.
+fn shorten(value: &str, width: usize) -> String {
+    if value.contains(['\\', '/']) {
+        return shorten_path(value, width);
+    }
+    if value.chars().count() <= width {
+        return value.to_string();
+    }
+    let mut shortened: String = value.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        shortened.push(ELLIPSIS);
+    }
+    shortened
+}
+
+/// Build one row of `columns` per entry of `parsed`, in entry order.
+pub fn table_rows(parsed: &JumplistParser, columns: &[String]) -> Vec<Vec<String>> {
+    let app_id = parsed.app_id.clone().unwrap_or_default();
+    let app_name = parsed.app_name.clone().unwrap_or_default();
+    let jumplist_type = parsed.r#type.to_string();
+
+    parsed
+        .entries()
+        .zip(parsed.flatten())
+        .map(|(entry, row)| {
+            columns
+                .iter()
+                .map(|column| match column.as_str() {
+                    "app_id" => app_id.clone(),
+                    "app_name" => app_name.clone(),
+                    "type" => jumplist_type.clone(),
+                    "entry_number" => entry
+                        .entry_number()
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                    "interaction_time" => entry
+                        .interaction_time()
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    "pinned" => entry.pinned().map(|p| p.to_string()).unwrap_or_default(),
+                    "access_count" => entry
+                        .access_count()
+                        .map(|c| c.to_string())
+                        .unwrap_or_default(),
+                    "category" => entry.category().unwrap_or_default().to_string(),
+                    _ => row.get(column).cloned().unwrap_or_default(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Render `rows` of `columns` as an aligned table under a `title` line, followed by an
+/// empty line.
+pub fn render_rows(
+    title: &str,
+    columns: &[String],
+    rows: &[Vec<String>],
+    options: &TableOptions,
+) -> String {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    if let Some(max_width) = options.width {
+        let gaps = COLUMN_GAP.len() * columns.len().saturating_sub(1);
+        // Shrink the widest column until the lines fit
+        while widths.iter().sum::<usize>() + gaps > max_width {
+            match widths.iter_mut().filter(|w| **w > MIN_COLUMN_WIDTH).max() {
+                Some(widest) => *widest -= 1,
+                None => break,
+            }
+        }
+    }
+
+    let style = |text: String, code: &str| match options.color {
+        true => format!("{}{}{}", code, text, RESET),
+        false => text,
+    };
+    let line = |values: &[String]| {
+        let cells: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| {
+                let value = shorten(value, *width);
+                let padding = width - value.chars().count();
+                format!("{}{}", value, " ".repeat(padding))
+            })
+            .collect();
+        cells.join(COLUMN_GAP).trim_end().to_string()
+    };
+
+    let mut table = style(title.to_string(), TITLE);
+    table.push('\n');
+    // A file without entries only gets its title line
+    if !rows.is_empty() {
+        table.push_str(&style(line(columns), BOLD));
+        table.push('\n');
+    }
+    for row in rows {
+        table.push_str(&line(row));
+        table.push('\n');
+    }
+    table.push('\n');
+    table
+}
+
+/// The line naming `parsed` above its table: its path (or AppID) and its number of entries.
+pub fn table_title(parsed: &JumplistParser, entries: usize) -> String {
+    let name = parsed
+        .source_path
+        .as_deref()
+        .or(parsed.app_id.as_deref())
+        .unwrap_or("-");
+    match &parsed.app_name {
+        Some(app_name) => format!("{} ({}), {} entries", name, app_name, entries),
+        None => format!("{}, {} entries", name, entries),
+    }
+}
+
+/// Render the entries of `parsed` as an aligned table.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{table::{render_table, TableOptions}, JumplistParser};
+///
+/// let parsed = JumplistParser::from_path(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+/// )
+/// .unwrap();
+/// let options = TableOptions {
+///     width: Some(80),
+///     ..Default::default()
+/// };
+/// let table = render_table(&parsed, &options);
+/// // The first line names the file and is never shortened
+/// assert!(table.lines().skip(1).all(|line| line.chars().count() <= 80));
+/// print!("{}", table);
+/// ```
+pub fn render_table(parsed: &JumplistParser, options: &TableOptions) -> String {
+    let rows = table_rows(parsed, &options.columns);
+    render_rows(
+        &table_title(parsed, rows.len()),
+        &options.columns,
+        &rows,
+        options,
+    )
+}
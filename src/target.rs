@@ -0,0 +1,436 @@
+//! Classification of jumplist target paths by their shape.
+//!
+//! Browsers record URLs, Explorer records directories and shell namespace locations,
+//! and Office records files on UNC shares. [`TargetInfo`] tells them apart.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+/// `FILE_ATTRIBUTE_DIRECTORY` bit of the LNK target file attributes.
+pub const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
+/// `FILE_ATTRIBUTE_HIDDEN` bit of the LNK target file attributes.
+pub const FILE_ATTRIBUTE_HIDDEN: u32 = 0x02;
+
+/// Names of the `FILE_ATTRIBUTE_*` bits, in bit order.
+pub const FILE_ATTRIBUTE_NAMES: &[(u32, &str)] = &[
+    (0x0000_0001, "readonly"),
+    (FILE_ATTRIBUTE_HIDDEN, "hidden"),
+    (0x0000_0004, "system"),
+    (FILE_ATTRIBUTE_DIRECTORY, "directory"),
+    (0x0000_0020, "archive"),
+    (0x0000_0040, "device"),
+    (0x0000_0080, "normal"),
+    (0x0000_0100, "temporary"),
+    (0x0000_0200, "sparse_file"),
+    (0x0000_0400, "reparse_point"),
+    (0x0000_0800, "compressed"),
+    (0x0000_1000, "offline"),
+    (0x0000_2000, "not_content_indexed"),
+    (0x0000_4000, "encrypted"),
+    (0x0000_8000, "integrity_stream"),
+    (0x0001_0000, "virtual"),
+    (0x0002_0000, "no_scrub_data"),
+    (0x0004_0000, "recall_on_open"),
+    (0x0008_0000, "pinned"),
+    (0x0010_0000, "unpinned"),
+    (0x0040_0000, "recall_on_data_access"),
+];
+
+/// The names of the `FILE_ATTRIBUTE_*` bits set in `attributes`, comma separated. Bits
+/// without a name are written as one hex value at the end.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::target::file_attribute_names;
+///
+/// assert_eq!(file_attribute_names(0x26), "hidden,system,archive");
+/// assert_eq!(file_attribute_names(0x8000_0010), "directory,0x80000000");
+/// assert_eq!(file_attribute_names(0), "");
+/// ```
+pub fn file_attribute_names(attributes: u32) -> String {
+    let mut names: Vec<String> = FILE_ATTRIBUTE_NAMES
+        .iter()
+        .filter(|(bit, _)| attributes & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let unknown = FILE_ATTRIBUTE_NAMES
+        .iter()
+        .fold(attributes, |rest, (bit, _)| rest & !bit);
+    if unknown != 0 {
+        names.push(format!("{:#x}", unknown));
+    }
+    names.join(",")
+}
+
+/// Kind of target a jumplist entry points to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TargetType {
+    /// A local file (the default when nothing else matches).
+    File,
+    /// A local directory (LNK target with `FILE_ATTRIBUTE_DIRECTORY`).
+    Directory,
+    /// A URL or URI, e.g. `https://...` or `ms-settings:...`.
+    Url,
+    /// A path on a network share, e.g. `\\server\share\file.docx`.
+    Unc,
+    /// A shell namespace location, e.g. `::{GUID}` or `knownfolder:{GUID}`.
+    Shell,
+}
+
+impl Display for TargetType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            TargetType::File => "file",
+            TargetType::Directory => "directory",
+            TargetType::Url => "url",
+            TargetType::Unc => "unc",
+            TargetType::Shell => "shell",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Target classification of a jumplist entry.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TargetInfo {
+    /// Kind of the target.
+    pub target_type: TargetType,
+    /// Drive letter of a local target (e.g. `C`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_drive_letter: Option<String>,
+    /// Share of a UNC target (e.g. `\\server\share`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_share: Option<String>,
+    /// Windows path of a `file://` URL target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_local_path: Option<String>,
+}
+
+impl TargetInfo {
+    /// Classify `path`. `file_attributes` are the LNK target file attributes, when known.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::target::{TargetInfo, TargetType};
+    ///
+    /// let target = TargetInfo::from_path(r"\\fileserver\finance\q3.xlsx", None);
+    /// assert_eq!(target.target_type, TargetType::Unc);
+    /// assert_eq!(target.target_share.as_deref(), Some(r"\\fileserver\finance"));
+    ///
+    /// let target = TargetInfo::from_path("file:///C:/Users/user/report%201.pdf", None);
+    /// assert_eq!(target.target_type, TargetType::File);
+    /// assert_eq!(target.target_local_path.as_deref(), Some(r"C:\Users\user\report 1.pdf"));
+    /// ```
+    pub fn from_path(path: &str, file_attributes: Option<u32>) -> Self {
+        let target_local_path = file_url_to_path(path);
+        let path = target_local_path.as_deref().unwrap_or(path);
+        let is_directory = file_attributes
+            .map(|a| a & FILE_ATTRIBUTE_DIRECTORY != 0)
+            .unwrap_or(false);
+
+        let mut target = TargetInfo {
+            target_type: TargetType::File,
+            target_drive_letter: None,
+            target_share: None,
+            target_local_path: None,
+        };
+        if path.starts_with("::") || starts_with_ignore_case(path, "knownfolder:") {
+            target.target_type = TargetType::Shell;
+        } else if let Some(unc) = unc_path(path) {
+            target.target_type = TargetType::Unc;
+            target.target_share = Some(
+                format!("\\\\{}", unc)
+                    .split('\\')
+                    .take(4)
+                    .collect::<Vec<&str>>()
+                    .join("\\"),
+            );
+        } else if has_uri_scheme(path) {
+            target.target_type = TargetType::Url;
+        } else {
+            let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+            let bytes = path.as_bytes();
+            if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+                target.target_drive_letter = Some(path[..1].to_uppercase());
+            }
+            if is_directory {
+                target.target_type = TargetType::Directory;
+            }
+        }
+        target.target_local_path = target_local_path;
+        target
+    }
+
+    /// Add the `target_type`, `target_drive_letter` and `target_share` keys to a normalized entry.
+    ///
+    /// The entry's `target_full_path` is classified, or `fallback_path` if it is empty.
+    /// A `file://` URL in `target_full_path` is replaced with the local path.
+    pub(crate) fn normalize_into(
+        normalized: &mut HashMap<String, String>,
+        fallback_path: &str,
+        file_attributes: Option<u32>,
+    ) {
+        let target_full_path = normalized
+            .get("target_full_path")
+            .filter(|p| !p.is_empty())
+            .map(|p| p.as_str())
+            .unwrap_or(fallback_path);
+        let target = TargetInfo::from_path(target_full_path, file_attributes);
+        if let Some(local_path) = &target.target_local_path {
+            if normalized.contains_key("target_full_path") {
+                normalized.insert("target_full_path".to_string(), local_path.clone());
+            }
+        }
+        normalized.insert("target_type".to_string(), target.target_type.to_string());
+        normalized.insert(
+            "target_drive_letter".to_string(),
+            target.target_drive_letter.unwrap_or_default(),
+        );
+        normalized.insert(
+            "target_share".to_string(),
+            target.target_share.unwrap_or_default(),
+        );
+    }
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.get(..prefix.len())
+        .map(|p| p.eq_ignore_ascii_case(prefix))
+        .unwrap_or(false)
+}
+
+/// The `server\share\...` part of a UNC path (`\\server\...` or `\\?\UNC\server\...`).
+fn unc_path(path: &str) -> Option<&str> {
+    if starts_with_ignore_case(path, r"\\?\UNC\") {
+        return Some(&path[8..]);
+    }
+    match path.strip_prefix(r"\\") {
+        Some(rest) if !rest.starts_with(['?', '.']) && !rest.is_empty() => Some(rest),
+        _ => None,
+    }
+}
+
+/// Whether `path` starts with a URI scheme (at least two characters, so drive letters don't match).
+fn has_uri_scheme(path: &str) -> bool {
+    match path.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() >= 2
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// Convert a `file://` URL into a Windows path.
+///
+/// `file:///C:/dir/file` becomes `C:\dir\file` and `file://server/share/file` becomes
+/// `\\server\share\file`. Percent-encoded characters are decoded.
+pub fn file_url_to_path(url: &str) -> Option<String> {
+    if !starts_with_ignore_case(url, "file:") {
+        return None;
+    }
+    let rest = percent_decode(&url[5..]).replace('/', "\\");
+    let rest = rest.trim_start_matches('\\');
+    let path = match rest.split_once('\\') {
+        Some((host, path)) if starts_with_ignore_case(host, "localhost") && host.len() == 9 => {
+            path.to_string()
+        }
+        _ if rest.len() >= 2 && rest.as_bytes()[1] == b':' => rest.to_string(),
+        _ if url[5..].starts_with("//") => format!("\\\\{}", rest),
+        _ => rest.to_string(),
+    };
+    Some(path)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Canonicalize the shape of a Windows path so that the same target recorded differently
+/// compares equal: the `\\?\` prefix is removed, `/` separators become `\`, the drive
+/// letter is upper-cased and trailing separators are removed (except for a drive root).
+///
+/// URLs and shell namespace locations are returned as is.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::target::canonicalize_path;
+///
+/// assert_eq!(canonicalize_path(r"\\?\c:/Users/user/Documents/"), r"C:\Users\user\Documents");
+/// assert_eq!(canonicalize_path(r"c:\"), r"C:\");
+/// assert_eq!(canonicalize_path(r"\\?\UNC\server\share\"), r"\\server\share");
+/// ```
+pub fn canonicalize_path(path: &str) -> String {
+    match TargetInfo::from_path(path, None).target_type {
+        TargetType::Url | TargetType::Shell => return path.to_string(),
+        TargetType::File | TargetType::Directory | TargetType::Unc => {}
+    }
+    let mut canonical = match unc_path(path) {
+        Some(unc) => format!(r"\\{}", unc.replace('/', "\\")),
+        None => path
+            .strip_prefix(r"\\?\")
+            .unwrap_or(path)
+            .replace('/', "\\"),
+    };
+    if canonical.as_bytes().get(1) == Some(&b':') {
+        let drive_letter = canonical[..1].to_uppercase();
+        canonical.replace_range(..1, &drive_letter);
+    }
+    while canonical.ends_with('\\') && !(canonical.len() == 3 && canonical.as_bytes()[1] == b':') {
+        canonical.pop();
+    }
+    canonical
+}
+
+/// Whether `a` and `b` are both set and name different targets, compared case
+/// insensitively after [`canonicalize_path`].
+///
+/// Shell namespace locations (e.g. `knownfolder:{...}`) are not compared, they never differ.
+/// A U+FFFD matches any character: the LNK `LinkInfo` path is in the ANSI code page of the
+/// machine, its characters outside ASCII are often lost.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::target::paths_differ;
+///
+/// assert!(!paths_differ(r"C:\Users\user\report.docx", "c:/users/USER/Report.docx"));
+/// assert!(paths_differ(r"C:\Users\user\report.docx", r"C:\Users\user\report-v2.docx"));
+/// assert!(!paths_differ(r"C:\Users\user\report.docx", ""));
+/// assert!(!paths_differ(r"C:\640×426.gif", "C:\\640\u{FFFD}426.gif"));
+/// ```
+pub fn paths_differ(a: &str, b: &str) -> bool {
+    let comparable = |path: &str| {
+        !path.is_empty() && TargetInfo::from_path(path, None).target_type != TargetType::Shell
+    };
+    if !comparable(a) || !comparable(b) {
+        return false;
+    }
+    let (a, b) = (
+        canonicalize_path(a).to_lowercase(),
+        canonicalize_path(b).to_lowercase(),
+    );
+    a.chars().count() != b.chars().count()
+        || a.chars()
+            .zip(b.chars())
+            .any(|(ca, cb)| ca != cb && ca != '\u{FFFD}' && cb != '\u{FFFD}')
+}
+
+/// Well-known environment variables that don't depend on the user, the system drive is
+/// assumed to be `C:`.
+const SYSTEM_VARIABLES: &[(&str, &str)] = &[
+    ("SYSTEMDRIVE", r"C:"),
+    ("SYSTEMROOT", r"C:\Windows"),
+    ("WINDIR", r"C:\Windows"),
+    ("PROGRAMFILES", r"C:\Program Files"),
+    ("PROGRAMFILES(X86)", r"C:\Program Files (x86)"),
+    ("PROGRAMW6432", r"C:\Program Files"),
+    ("COMMONPROGRAMFILES", r"C:\Program Files\Common Files"),
+    ("PROGRAMDATA", r"C:\ProgramData"),
+    ("ALLUSERSPROFILE", r"C:\ProgramData"),
+    ("PUBLIC", r"C:\Users\Public"),
+];
+
+/// Well-known environment variables of a user profile, relative to `C:\Users\<user>`.
+const PROFILE_VARIABLES: &[(&str, &str)] = &[
+    ("USERPROFILE", ""),
+    ("APPDATA", r"\AppData\Roaming"),
+    ("LOCALAPPDATA", r"\AppData\Local"),
+    ("TEMP", r"\AppData\Local\Temp"),
+    ("TMP", r"\AppData\Local\Temp"),
+];
+
+/// Expand the well-known environment variables of `path` (e.g. `%SYSTEMROOT%`,
+/// `%PROGRAMFILES%` or `%APPDATA%`), case-insensitively. The variables of a user profile
+/// are only expanded when `user` is known, unknown variables are kept as is.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::target::expand_environment_variables;
+///
+/// assert_eq!(
+///     expand_environment_variables(r"%UserProfile%\Documents\x.docx", Some("bob")),
+///     r"C:\Users\bob\Documents\x.docx"
+/// );
+/// assert_eq!(
+///     expand_environment_variables(r"%APPDATA%\x.txt", None),
+///     r"%APPDATA%\x.txt"
+/// );
+/// assert_eq!(
+///     expand_environment_variables(r"%SystemRoot%\notepad.exe", None),
+///     r"C:\Windows\notepad.exe"
+/// );
+/// ```
+pub fn expand_environment_variables(path: &str, user: Option<&str>) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let end = match after.find('%') {
+            Some(end) => end,
+            None => break,
+        };
+        expanded.push_str(&rest[..start]);
+        let name = after[..end].to_uppercase();
+        let system = SYSTEM_VARIABLES.iter().find(|(n, _)| *n == name);
+        let profile = PROFILE_VARIABLES.iter().find(|(n, _)| *n == name);
+        match (system, profile, user) {
+            (Some((_, value)), _, _) => expanded.push_str(value),
+            (_, Some((_, relative)), Some(user)) => {
+                expanded.push_str(&format!(r"C:\Users\{}{}", user, relative))
+            }
+            _ => {
+                expanded.push('%');
+                expanded.push_str(&after[..end]);
+                expanded.push('%');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Canonicalize a target path for joins with other artifact sources: the well-known
+/// environment variables are expanded with [`expand_environment_variables`], then the
+/// shape is canonicalized with [`canonicalize_path`].
+///
+/// # Example
+/// ```
+/// use jumplist_parser::target::canonicalize_target_path;
+///
+/// let expected = r"C:\Users\bob\Documents\x.docx";
+/// assert_eq!(canonicalize_target_path(r"c:\Users\bob\Documents\x.docx", None), expected);
+/// assert_eq!(canonicalize_target_path(r"%USERPROFILE%\Documents\x.docx", Some("bob")), expected);
+/// assert_eq!(canonicalize_target_path(r"\\?\C:\Users\bob\Documents\x.docx", None), expected);
+/// ```
+pub fn canonicalize_target_path(path: &str, user: Option<&str>) -> String {
+    canonicalize_path(&expand_environment_variables(path, user))
+}
@@ -0,0 +1,261 @@
+//! FILETIME timestamps with a configurable serialization format.
+//!
+//! A FILETIME is the number of 100-nanosecond intervals since `1601-01-01T00:00:00Z`.
+//! A zero FILETIME means "not set" and is serialized as `null` (or an empty string in
+//! the normalized output) instead of `1601-01-01T00:00:00Z`.
+//!
+//! Timestamps are always stored in UTC. [`TimestampFormat::with_timezone`] only changes how
+//! they are displayed (the normalized, CSV and table output), they are still serialized in
+//! UTC.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of 100-nanosecond intervals between `1601-01-01` and `1970-01-01`.
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+/// Number of 100-nanosecond intervals in a second.
+const FILETIME_PER_SECOND: u64 = 10_000_000;
+
+/// How [`Timestamp`] values are serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimestampFormat {
+    /// ISO 8601 UTC with second precision, e.g. `2025-07-10T07:31:37Z` (the default).
+    #[default]
+    Iso,
+    /// ISO 8601 UTC with the full 100-nanosecond precision, e.g. `2025-07-10T07:31:37.1234567Z`.
+    IsoSubsecond,
+    /// Seconds since the Unix epoch.
+    Epoch,
+    /// The raw FILETIME value.
+    FileTime,
+    /// Local time in `timezone` with its UTC offset, e.g. `2025-07-10 10:31:37 +03:00`, with
+    /// the full 100-nanosecond precision when `subsecond` is set. Serialized as
+    /// [`TimestampFormat::Iso`] or [`TimestampFormat::IsoSubsecond`].
+    Zoned { timezone: Tz, subsecond: bool },
+}
+
+impl TimestampFormat {
+    /// This format displayed in `timezone`: the ISO formats become [`TimestampFormat::Zoned`],
+    /// the numeric formats don't depend on the timezone and are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use jumplist_parser::timestamp::{Timestamp, TimestampFormat};
+    ///
+    /// let format = TimestampFormat::Iso.with_timezone("Asia/Riyadh".parse().unwrap());
+    /// let ts = Timestamp::new(133_965_773_740_000_000, format);
+    /// assert_eq!(ts.to_string(), "2025-07-10 02:29:34 +03:00");
+    /// // Serialized in UTC
+    /// assert_eq!(serde_json::to_string(&ts).unwrap(), "\"2025-07-09T23:29:34Z\"");
+    /// ```
+    pub fn with_timezone(self, timezone: Tz) -> Self {
+        match self {
+            TimestampFormat::Iso => TimestampFormat::Zoned {
+                timezone,
+                subsecond: false,
+            },
+            TimestampFormat::IsoSubsecond
+            | TimestampFormat::Zoned {
+                subsecond: true, ..
+            } => TimestampFormat::Zoned {
+                timezone,
+                subsecond: true,
+            },
+            TimestampFormat::Zoned { .. } => TimestampFormat::Zoned {
+                timezone,
+                subsecond: false,
+            },
+            numeric => numeric,
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iso" => Ok(TimestampFormat::Iso),
+            "iso-subsecond" => Ok(TimestampFormat::IsoSubsecond),
+            "epoch" => Ok(TimestampFormat::Epoch),
+            "filetime" => Ok(TimestampFormat::FileTime),
+            _ => Err(format!("Unknown timestamp format '{}'", s)),
+        }
+    }
+}
+
+/// A FILETIME value together with the format it is serialized with.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Timestamp {
+    /// The raw FILETIME value.
+    pub filetime: u64,
+    /// Serialization format.
+    pub format: TimestampFormat,
+}
+
+impl Timestamp {
+    /// Create a timestamp from a raw FILETIME value.
+    pub fn new(filetime: u64, format: TimestampFormat) -> Self {
+        Self { filetime, format }
+    }
+
+    /// Create a timestamp from a `SystemTime`, times before `1601-01-01` are zero.
+    pub fn from_system_time(time: SystemTime, format: TimestampFormat) -> Self {
+        let filetime = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => FILETIME_UNIX_EPOCH as u128 + since.as_nanos() / 100,
+            Err(e) => (FILETIME_UNIX_EPOCH as u128).saturating_sub(e.duration().as_nanos() / 100),
+        };
+        Self::new(u64::try_from(filetime).unwrap_or(u64::MAX), format)
+    }
+
+    /// Whether the FILETIME is zero (not set).
+    pub fn is_zero(&self) -> bool {
+        self.filetime == 0
+    }
+
+    /// The timestamp as a UTC date and time, `None` when zero or out of range.
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        if self.is_zero() {
+            return None;
+        }
+        let since_epoch = self.filetime as i128 - FILETIME_UNIX_EPOCH as i128;
+        let seconds = since_epoch.div_euclid(FILETIME_PER_SECOND as i128) as i64;
+        let nanos = since_epoch.rem_euclid(FILETIME_PER_SECOND as i128) as u32 * 100;
+        DateTime::from_timestamp(seconds, nanos)
+    }
+
+    /// Seconds since the Unix epoch, `None` when zero.
+    pub fn to_epoch(&self) -> Option<i64> {
+        match self.is_zero() {
+            true => None,
+            false => Some(
+                (self.filetime as i128 - FILETIME_UNIX_EPOCH as i128)
+                    .div_euclid(FILETIME_PER_SECOND as i128) as i64,
+            ),
+        }
+    }
+}
+
+impl Display for Timestamp {
+    /// Formats the timestamp with its [`TimestampFormat`], a zero FILETIME is an empty string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return Ok(());
+        }
+        match self.format {
+            TimestampFormat::Iso => match self.to_datetime() {
+                Some(dt) => write!(f, "{}", dt.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                None => Ok(()),
+            },
+            TimestampFormat::IsoSubsecond => match self.to_datetime() {
+                Some(dt) => write!(
+                    f,
+                    "{}.{:07}Z",
+                    dt.format("%Y-%m-%dT%H:%M:%S"),
+                    self.filetime % FILETIME_PER_SECOND
+                ),
+                None => Ok(()),
+            },
+            TimestampFormat::Epoch => write!(f, "{}", self.to_epoch().unwrap_or_default()),
+            TimestampFormat::FileTime => write!(f, "{}", self.filetime),
+            TimestampFormat::Zoned {
+                timezone,
+                subsecond,
+            } => match self.to_datetime() {
+                Some(dt) => {
+                    let local = dt.with_timezone(&timezone);
+                    match subsecond {
+                        true => write!(
+                            f,
+                            "{}.{:07} {}",
+                            local.format("%Y-%m-%d %H:%M:%S"),
+                            self.filetime % FILETIME_PER_SECOND,
+                            local.format("%:z")
+                        ),
+                        false => write!(f, "{}", local.format("%Y-%m-%d %H:%M:%S %:z")),
+                    }
+                }
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.is_zero() {
+            return serializer.serialize_none();
+        }
+        match self.format {
+            TimestampFormat::Iso | TimestampFormat::IsoSubsecond => {
+                serializer.serialize_str(&self.to_string())
+            }
+            TimestampFormat::Epoch => serializer.serialize_i64(self.to_epoch().unwrap_or_default()),
+            TimestampFormat::FileTime => serializer.serialize_u64(self.filetime),
+            TimestampFormat::Zoned { subsecond, .. } => {
+                let format = match subsecond {
+                    true => TimestampFormat::IsoSubsecond,
+                    false => TimestampFormat::Iso,
+                };
+                serializer.serialize_str(&Timestamp::new(self.filetime, format).to_string())
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    /// Parses any serialized [`TimestampFormat`] back, `null` and empty strings are zero.
+    ///
+    /// Numbers are read as a FILETIME when they are too large to be Unix epoch seconds
+    /// (over `10^14`), and as epoch seconds otherwise.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Unsigned(u64),
+            Signed(i64),
+            Text(String),
+        }
+
+        let from_epoch = |seconds: i64, nanos: u32, format| {
+            let filetime = seconds as i128 * FILETIME_PER_SECOND as i128
+                + (nanos / 100) as i128
+                + FILETIME_UNIX_EPOCH as i128;
+            match u64::try_from(filetime) {
+                Ok(filetime) => Ok(Timestamp::new(filetime, format)),
+                Err(_) => Err(de::Error::custom("timestamp before 1601-01-01")),
+            }
+        };
+        match Option::<Repr>::deserialize(deserializer)? {
+            None => Ok(Timestamp::default()),
+            Some(Repr::Unsigned(n)) if n > 100_000_000_000_000 => {
+                Ok(Timestamp::new(n, TimestampFormat::FileTime))
+            }
+            Some(Repr::Unsigned(n)) => from_epoch(n as i64, 0, TimestampFormat::Epoch),
+            Some(Repr::Signed(n)) => from_epoch(n, 0, TimestampFormat::Epoch),
+            Some(Repr::Text(s)) if s.is_empty() => Ok(Timestamp::default()),
+            Some(Repr::Text(s)) => {
+                // ISO 8601, or the display of `TimestampFormat::Zoned`
+                let dt = DateTime::parse_from_rfc3339(&s)
+                    .or_else(|_| DateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S%.f %:z"))
+                    .map_err(|e| de::Error::custom(format!("invalid timestamp '{}': {}", s, e)))?;
+                let format = match s.contains('.') {
+                    true => TimestampFormat::IsoSubsecond,
+                    false => TimestampFormat::Iso,
+                };
+                from_epoch(dt.timestamp(), dt.timestamp_subsec_nanos(), format)
+            }
+        }
+    }
+}
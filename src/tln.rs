@@ -0,0 +1,107 @@
+//! TLN timeline output of Jumplist entries (`--output-format tln`).
+//!
+//! TLN is the 5 fields format `Time|Source|Host|User|Description` of timeline tools, with
+//! the time in seconds since the Unix epoch. A field can't hold a `|`, so pipes in the
+//! values are escaped as `\|` and line breaks are replaced by spaces.
+
+use crate::{entry::JumplistEntryRef, JumplistParser};
+use std::fmt::{self, Display};
+
+/// Source field of every record.
+pub const TLN_SOURCE: &str = "JUMPLIST";
+
+/// A TLN record of a Jumplist entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlnRecord {
+    /// Seconds since the Unix epoch: the `DestList` interaction time, or the modification
+    /// time of the target from the LNK header.
+    pub time: i64,
+    pub source: &'static str,
+    /// The `DestList` hostname, empty for custom entries.
+    pub host: String,
+    /// User whose profile the Jumplist was found in, see [`crate::user::user_from_path`].
+    pub user: String,
+    /// `app_name - target_full_path (args)`, without ` (args)` when the LNK has no
+    /// command line arguments.
+    pub description: String,
+}
+
+impl TlnRecord {
+    /// The record of `entry`, an entry of `parsed`. `None` when the entry has neither an
+    /// interaction time nor a target modification time.
+    pub fn new(parsed: &JumplistParser, entry: &JumplistEntryRef) -> Option<Self> {
+        let time = entry
+            .interaction_time()
+            .and_then(|t| t.to_epoch())
+            .or_else(|| entry.lnk_header().and_then(|h| h.write_time.to_epoch()))?;
+        let host = match entry {
+            JumplistEntryRef::DestList(entry) => entry.hostname.clone().unwrap_or_default(),
+            JumplistEntryRef::Custom { .. } | JumplistEntryRef::RecentLnk(_) => String::new(),
+        };
+        let mut description = format!(
+            "{} - {}",
+            parsed.app_name.as_deref().unwrap_or_default(),
+            entry.target_path().unwrap_or_default()
+        );
+        let arguments = entry
+            .lnk()
+            .and_then(|lnk| {
+                lnk.get_command_line_arguments()
+                    .as_ref()
+                    .map(|a| a.to_string())
+            })
+            .filter(|arguments| !arguments.is_empty());
+        if let Some(arguments) = arguments {
+            description.push_str(&format!(" ({})", arguments));
+        }
+        Some(TlnRecord {
+            time,
+            source: TLN_SOURCE,
+            host,
+            user: parsed.user.clone().unwrap_or_default(),
+            description,
+        })
+    }
+}
+
+impl Display for TlnRecord {
+    /// The TLN line of the record, without line break.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}|{}",
+            self.time,
+            escape(self.source),
+            escape(&self.host),
+            escape(&self.user),
+            escape(&self.description)
+        )
+    }
+}
+
+/// Escape the pipes of a TLN field and replace its line breaks by spaces.
+fn escape(field: &str) -> String {
+    field.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// The TLN record of every entry of `parsed`, in the order of
+/// [`JumplistParser::entries`]. `None` for the entries without a usable timestamp.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::{tln::tln_records, JumplistParser};
+///
+/// let parsed = JumplistParser::from_path(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+/// )
+/// .unwrap();
+/// for record in tln_records(&parsed).into_iter().flatten() {
+///     println!("{}", record);
+/// }
+/// ```
+pub fn tln_records(parsed: &JumplistParser) -> Vec<Option<TlnRecord>> {
+    parsed
+        .entries()
+        .map(|entry| TlnRecord::new(parsed, &entry))
+        .collect()
+}
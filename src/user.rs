@@ -0,0 +1,39 @@
+//! Extraction of the user a Jumplist belongs to from its path.
+//!
+//! Jumplists are stored in the profile of their user, so collected files keep the user
+//! name in their path: `C:\Users\<user>\AppData\...`, `/mnt/c/Users/<user>/AppData/...`
+//! or `C:\Documents and Settings\<user>\...` on older systems.
+
+/// Directories that hold the user profiles, compared case-insensitively.
+const PROFILE_DIRECTORIES: &[&str] = &["users", "documents and settings"];
+
+/// The user name in `path`: the directory following a profile directory (`Users` or
+/// `Documents and Settings`). Both `\` and `/` separators are accepted.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::user::user_from_path;
+///
+/// assert_eq!(
+///     user_from_path(r"C:\Users\u0041\AppData\Roaming\Microsoft\Windows\Recent\AutomaticDestinations\f01b4d95cf55d32a.automaticDestinations-ms"),
+///     Some("u0041".to_string())
+/// );
+/// assert_eq!(
+///     user_from_path("/mnt/c/users/bob/AppData/Roaming/Microsoft/Windows/Recent/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms"),
+///     Some("bob".to_string())
+/// );
+/// assert_eq!(user_from_path("samples/win10/a.automaticDestinations-ms"), None);
+/// ```
+pub fn user_from_path(path: &str) -> Option<String> {
+    let components: Vec<&str> = path.split(['\\', '/']).filter(|c| !c.is_empty()).collect();
+    // The user directory can't be the last component, that is the Jumplist itself
+    components
+        .windows(3)
+        .rev()
+        .find(|window| {
+            PROFILE_DIRECTORIES
+                .iter()
+                .any(|dir| window[0].eq_ignore_ascii_case(dir))
+        })
+        .map(|window| window[1].to_string())
+}
@@ -0,0 +1,116 @@
+//! Small helpers shared by the parsers.
+
+use crate::errors::JumplistParserError;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// Number of bytes left between the current position of `r` and the end of the stream.
+pub(crate) fn remaining_len<R: Seek>(r: &mut R) -> Result<u64, JumplistParserError> {
+    let to_error = |e: std::io::Error| {
+        JumplistParserError::FileStructure(e.to_string(), line!(), file!().to_string())
+    };
+    let position = r.stream_position().map_err(to_error)?;
+    let end = r.seek(SeekFrom::End(0)).map_err(to_error)?;
+    r.seek(SeekFrom::Start(position)).map_err(to_error)?;
+    Ok(end.saturating_sub(position))
+}
+
+/// Make sure `count` items of at least `min_size` bytes each can fit in what is left of `r`.
+///
+/// Corrupt or malicious files can declare huge counts and sizes, trusting them would make
+/// the parser allocate gigabytes or loop for a very long time.
+pub(crate) fn check_fits<R: Seek>(
+    r: &mut R,
+    field: &str,
+    count: u64,
+    min_size: u64,
+) -> Result<(), JumplistParserError> {
+    let remaining = remaining_len(r)?;
+    if count.saturating_mul(min_size) > remaining {
+        let offset = r.stream_position().unwrap_or_default();
+        return Err(JumplistParserError::FileStructure(
+            format!(
+                "The '{}' value {} at offset {} doesn't fit in the remaining {} bytes",
+                field, count, offset, remaining
+            ),
+            line!(),
+            file!().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Read a string of exactly `len` UTF-16 code units (`len * 2` bytes), cut at the first
+/// NUL. Surrogate pairs are decoded, unpaired surrogates become U+FFFD.
+pub(crate) fn read_utf16_units<R: Read>(r: &mut R, len: usize) -> io::Result<String> {
+    let mut bytes = vec![0; len * 2];
+    r.read_exact(&mut bytes)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|u| *u != 0)
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+/// Decode little endian UTF-16 `bytes` without their NUL code units (an odd last byte is
+/// ignored), unpaired surrogates become U+FFFD.
+pub(crate) fn decode_utf16_without_nuls(bytes: &[u8]) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .filter(|u| *u != 0);
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Clean a fixed size NetBIOS hostname: cut at the first NUL and trim trailing
+/// whitespace and control characters. Returns `None` when nothing is left.
+pub(crate) fn clean_hostname(raw: &[u8]) -> Option<String> {
+    let end = raw.iter().position(|b| *b == 0).unwrap_or(raw.len());
+    let hostname = String::from_utf8_lossy(&raw[..end]);
+    let hostname = hostname.trim_end_matches(|c: char| c.is_control() || c.is_whitespace());
+    match hostname.is_empty() {
+        true => None,
+        false => Some(hostname.to_string()),
+    }
+}
+
+/// Skip `len` bytes of unknown meaning.
+///
+/// When `captured` is set (see [`ParseOptions::capture_unknown_bytes`](crate::ParseOptions::capture_unknown_bytes))
+/// the bytes are read and recorded under `key` as a hex string. Like seeking, reading past
+/// the end of `r` is not an error, only the available bytes are recorded.
+pub(crate) fn skip_unknown<R: Read + Seek>(
+    r: &mut R,
+    len: u64,
+    key: &str,
+    captured: &mut Option<BTreeMap<String, String>>,
+) -> io::Result<()> {
+    match captured {
+        Some(fields) => {
+            let mut bytes = Vec::with_capacity(len as usize);
+            let read = r.by_ref().take(len).read_to_end(&mut bytes)? as u64;
+            r.seek(SeekFrom::Current((len - read) as i64))?;
+            fields.insert(key.to_string(), to_hex(&bytes));
+        }
+        None => {
+            r.seek(SeekFrom::Current(len as i64))?;
+        }
+    }
+    Ok(())
+}
+
+/// Lower case hex encoding of `bytes`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
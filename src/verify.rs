@@ -0,0 +1,161 @@
+//! Fast pass/fail validation of Jumplist files (`verify`), for QA of large collections.
+//!
+//! Every file is parsed leniently so that all the findings are collected, and gets the
+//! status it would get in [`ParseMode::Strict`](crate::ParseMode::Strict): a structural
+//! finding (count mismatch, missing footer, unexpected stream, ...) fails the file, a sign
+//! of tampering only warns. The LNK streams of automatic Jumplists are only parsed when the
+//! `DestList` pass has a finding, or with `deep`.
+
+use crate::{anomaly::AnomalyKind, JumplistParser, JumplistType, ParseOptions};
+use serde::Serialize;
+use std::fmt;
+
+/// Outcome of the validation of a file, ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyStatus {
+    /// No finding.
+    Ok,
+    /// Signs of tampering or timestomping, the structure is valid.
+    Warn,
+    /// The file can't be parsed or its structure is inconsistent.
+    Fail,
+}
+
+impl fmt::Display for VerifyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            VerifyStatus::Ok => "ok",
+            VerifyStatus::Warn => "warn",
+            VerifyStatus::Fail => "fail",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Result of the validation of a Jumplist file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerifyResult {
+    pub path: String,
+    /// Type given by the file name, `None` when the name isn't a Jumplist name.
+    #[serde(rename = "type")]
+    pub r#type: Option<JumplistType>,
+    pub status: VerifyStatus,
+    /// Number of parsed entries.
+    pub entries: usize,
+    /// Number of entries declared by the file headers: the `DestList` header, or the sum
+    /// of the category counts of custom Jumplists.
+    pub declared_entries: Option<usize>,
+    /// Whether the `DestList` version is known (see
+    /// [`DestListHeader::version_supported`](crate::destlist::DestListHeader::version_supported)),
+    /// `None` for custom Jumplists and files that can't be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_supported: Option<bool>,
+    /// Structural findings first, then the signs of tampering.
+    pub findings: Vec<String>,
+    /// Observations that don't change the status, e.g. a large `DestList` slack (see
+    /// [`DestList::slack_note`](crate::destlist::DestList::slack_note)).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Anomalies that repeat a structural finding of the `DestList`.
+const STRUCTURAL_ANOMALIES: &[AnomalyKind] = &[
+    AnomalyKind::DuplicateEntryNumber,
+    AnomalyKind::MissingLnkStream,
+    AnomalyKind::UnexpectedLnkStream,
+];
+
+/// Validate the Jumplist file at `path`. With `deep`, the LNK streams of automatic
+/// Jumplists are always parsed.
+///
+/// # Example
+/// ```
+/// use jumplist_parser::verify::{verify_path, VerifyStatus};
+///
+/// let result = verify_path(
+///     "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+///     false,
+/// );
+/// assert_eq!(result.status, VerifyStatus::Ok);
+/// assert_eq!(Some(result.entries), result.declared_entries);
+/// ```
+pub fn verify_path(path: &str, deep: bool) -> VerifyResult {
+    let mut options = ParseOptions {
+        disable_carving: true,
+        detect_anomalies: true,
+        skip_lnks: !deep,
+        ..Default::default()
+    };
+    let r#type = JumplistParser::type_from_name(
+        &JumplistParser::file_name(path),
+        &options.file_name_suffixes,
+    );
+    let mut result = verify_with(path, r#type.clone(), &options);
+    if result.status != VerifyStatus::Ok && options.skip_lnks {
+        // The LNK streams may explain the findings, or add their own
+        options.skip_lnks = false;
+        result = verify_with(path, r#type, &options);
+    }
+    result
+}
+
+fn verify_with(path: &str, r#type: Option<JumplistType>, options: &ParseOptions) -> VerifyResult {
+    let parsed = match JumplistParser::from_path_with_options(path, options) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return VerifyResult {
+                path: path.to_string(),
+                r#type,
+                status: VerifyStatus::Fail,
+                entries: 0,
+                declared_entries: None,
+                version_supported: None,
+                findings: vec![e.to_string()],
+                notes: vec![],
+            }
+        }
+    };
+
+    let mut findings = parsed.warnings().to_vec();
+    let mut status = match findings.is_empty() {
+        true => VerifyStatus::Ok,
+        false => VerifyStatus::Fail,
+    };
+    for anomaly in &parsed.anomalies {
+        if !STRUCTURAL_ANOMALIES.contains(&anomaly.kind) {
+            findings.push(match (anomaly.entry_number, anomaly.index) {
+                (Some(number), _) => format!("entry {:x}: {}", number, anomaly.description),
+                (None, Some(index)) => format!("entry #{}: {}", index, anomaly.description),
+                (None, None) => anomaly.description.clone(),
+            });
+            status = status.max(VerifyStatus::Warn);
+        }
+    }
+    let declared_entries = match (parsed.destlist(), parsed.custom_destinations()) {
+        (Some(destlist), _) => Some(destlist.header.number_of_entries as usize),
+        (_, Some(custom)) => Some(
+            custom
+                .categories()
+                .iter()
+                .filter_map(|c| c.declared_entry_count())
+                .map(|n| n as usize)
+                .sum(),
+        ),
+        _ => None,
+    };
+    VerifyResult {
+        path: path.to_string(),
+        r#type,
+        status,
+        entries: parsed.entry_count(),
+        declared_entries,
+        version_supported: parsed.destlist().map(|d| d.header.version_supported),
+        findings,
+        notes: parsed
+            .destlist()
+            .and_then(|d| d.slack_note())
+            .into_iter()
+            .collect(),
+    }
+}
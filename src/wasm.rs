@@ -0,0 +1,20 @@
+//! WebAssembly bindings (`wasm` feature), for parsing Jumplists in the browser.
+//!
+//! Build with `wasm-pack build --target web -- --no-default-features --features wasm`.
+
+use crate::JumplistParser;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Parse a Jumplist held in memory, see [`JumplistParser::from_bytes`].
+///
+/// Returns the parsed Jumplist as a plain JavaScript object with the same layout as the JSON
+/// output. Parsing errors are thrown as JavaScript `Error`s.
+#[wasm_bindgen]
+pub fn parse(data: &[u8], name_hint: Option<String>) -> Result<JsValue, JsError> {
+    let parsed = JumplistParser::from_bytes(data, name_hint.as_deref())
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    parsed
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
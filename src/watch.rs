@@ -0,0 +1,268 @@
+//! Following the changes of Jumplist directories (`--watch`).
+//!
+//! A baseline of every Jumplist is kept in memory. When a file changes it is parsed again,
+//! compared to its baseline with [`diff`], and only the added and changed entries are
+//! reported, which makes the watcher a lightweight user activity sensor.
+
+use crate::{
+    diff::{diff, DiffEntry, FieldChange},
+    errors::JumplistParserError,
+    is_probably_jumplist,
+    timestamp::Timestamp,
+    JumplistParser, ParseOptions,
+};
+use notify::{
+    event::{AccessKind, AccessMode},
+    EventKind, RecursiveMode, Watcher,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Time without changes to a file before it is parsed again, Windows writes a Jumplist in
+/// several steps.
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Number of times a changed file is parsed before giving up until its next change, it can
+/// be locked or half written.
+const PARSE_ATTEMPTS: u32 = 5;
+const PARSE_RETRY_DELAY: Duration = Duration::from_millis(200);
+/// How often the stop flag is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a [`WatchEvent`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchEventKind {
+    /// An entry that is not in the baseline of its Jumplist.
+    EntryAdded,
+    /// An entry of the baseline with changed fields (e.g. a new interaction time).
+    EntryChanged,
+    /// A Jumplist file of the baseline was deleted.
+    JumplistDeleted,
+}
+
+/// A change found in a watched directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchEvent {
+    pub event: WatchEventKind,
+    /// When the change was found.
+    pub event_time: Timestamp,
+    pub source_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// The added or changed entry, as it is in the new snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<DiffEntry>,
+    /// The changed fields of an [`WatchEventKind::EntryChanged`] event.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<FieldChange>,
+}
+
+/// The last parsed snapshot of every watched Jumplist.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    files: HashMap<PathBuf, JumplistParser>,
+}
+
+impl Baseline {
+    /// Record `parsed` as the baseline of `path`, without reporting its entries.
+    pub fn insert(&mut self, path: PathBuf, parsed: JumplistParser) {
+        self.files.insert(path, parsed);
+    }
+
+    /// Number of Jumplists in the baseline.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Compare `parsed`, a new snapshot of `path`, to its baseline and make it the new
+    /// baseline. Every entry of a Jumplist without a baseline is added.
+    pub fn update(
+        &mut self,
+        path: &Path,
+        parsed: JumplistParser,
+        event_time: Timestamp,
+    ) -> Vec<WatchEvent> {
+        let event = |kind, entry, changes| WatchEvent {
+            event: kind,
+            event_time,
+            source_path: path.to_string_lossy().to_string(),
+            app_id: parsed.app_id.clone(),
+            app_name: parsed.app_name.clone(),
+            entry: Some(entry),
+            changes,
+        };
+        let events = match self.files.get(path) {
+            Some(old) => {
+                let changes = diff(old, &parsed);
+                let mut events: Vec<WatchEvent> = changes
+                    .added
+                    .into_iter()
+                    .map(|entry| event(WatchEventKind::EntryAdded, entry, vec![]))
+                    .collect();
+                for modified in changes.modified {
+                    let entry = parsed
+                        .entries()
+                        .find(|e| {
+                            e.entry_number() == modified.entry_number
+                                && e.target_path() == modified.path
+                        })
+                        .map(|e| DiffEntry::from(&e));
+                    if let Some(entry) = entry {
+                        events.push(event(WatchEventKind::EntryChanged, entry, modified.changes));
+                    }
+                }
+                events
+            }
+            None => parsed
+                .entries()
+                .map(|e| event(WatchEventKind::EntryAdded, DiffEntry::from(&e), vec![]))
+                .collect(),
+        };
+        self.files.insert(path.to_path_buf(), parsed);
+        events
+    }
+
+    /// Forget the baseline of the deleted file `path`.
+    pub fn remove(&mut self, path: &Path, event_time: Timestamp) -> Option<WatchEvent> {
+        let parsed = self.files.remove(path)?;
+        Some(WatchEvent {
+            event: WatchEventKind::JumplistDeleted,
+            event_time,
+            source_path: path.to_string_lossy().to_string(),
+            app_id: parsed.app_id,
+            app_name: parsed.app_name,
+            entry: None,
+            changes: vec![],
+        })
+    }
+}
+
+/// Parse `path`, retrying while it is locked or half written by the shell.
+fn parse_settled(
+    path: &Path,
+    options: &ParseOptions,
+) -> Result<JumplistParser, JumplistParserError> {
+    let path = path.to_string_lossy();
+    let mut attempt = 1;
+    loop {
+        #[cfg(windows)]
+        let parsed = JumplistParser::from_path_live(&path, options);
+        #[cfg(not(windows))]
+        let parsed = JumplistParser::from_path_with_options(&path, options);
+        match parsed {
+            Err(_) if attempt < PARSE_ATTEMPTS => {
+                attempt += 1;
+                thread::sleep(PARSE_RETRY_DELAY);
+            }
+            parsed => return parsed,
+        }
+    }
+}
+
+/// Watch the Jumplists of `directories` until `stop` is set, calling `on_event` for every
+/// added or changed entry and every deleted Jumplist. The Jumplists already in the
+/// directories are the baseline, their entries are not reported.
+///
+/// # Example
+/// ```no_run
+/// use jumplist_parser::{watch::watch, ParseOptions};
+/// use std::{path::PathBuf, sync::atomic::AtomicBool};
+///
+/// let stop = AtomicBool::new(false);
+/// let recent = PathBuf::from(r"C:\Users\u0041\AppData\Roaming\Microsoft\Windows\Recent");
+/// let directories = [
+///     recent.join("AutomaticDestinations"),
+///     recent.join("CustomDestinations"),
+/// ];
+/// watch(&directories, &ParseOptions::default(), &stop, |event| {
+///     println!("{}", serde_json::to_string(&event).unwrap());
+/// })
+/// .unwrap();
+/// ```
+pub fn watch(
+    directories: &[PathBuf],
+    options: &ParseOptions,
+    stop: &AtomicBool,
+    mut on_event: impl FnMut(WatchEvent),
+) -> Result<(), JumplistParserError> {
+    let watch_error = |e: notify::Error| {
+        JumplistParserError::General(
+            format!("Can't watch the Jumplist directories, ERROR: {}", e),
+            line!(),
+            file!().to_string(),
+        )
+    };
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+    let mut baseline = Baseline::default();
+    for dir in directories {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+        let files = std::fs::read_dir(dir).into_iter().flatten().flatten();
+        for path in files.map(|f| f.path()).filter(|p| is_probably_jumplist(p)) {
+            if let Ok(parsed) = parse_settled(&path, options) {
+                baseline.insert(path, parsed);
+            }
+        }
+    }
+
+    // Changed files, parsed once they have not changed for `DEBOUNCE`
+    let mut pending = BTreeSet::new();
+    let mut last_change = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                // Reading a file is an event too, only the end of a write is a change
+                if let EventKind::Access(kind) = event.kind {
+                    if kind != AccessKind::Close(AccessMode::Write) {
+                        continue;
+                    }
+                }
+                pending.extend(event.paths.into_iter().filter(|p| is_probably_jumplist(p)));
+                last_change = Instant::now();
+            }
+            // Errors of the platform watcher (e.g. an event queue overflow) are not fatal
+            Ok(Err(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if pending.is_empty() || last_change.elapsed() < DEBOUNCE {
+            continue;
+        }
+        let event_time = Timestamp::from_system_time(SystemTime::now(), options.ts_format);
+        for path in std::mem::take(&mut pending) {
+            match path.exists() {
+                // A file that still can't be parsed is parsed again on its next change
+                true => {
+                    if let Ok(parsed) = parse_settled(&path, options) {
+                        baseline
+                            .update(&path, parsed, event_time)
+                            .into_iter()
+                            .for_each(&mut on_event);
+                    }
+                }
+                false => {
+                    if let Some(event) = baseline.remove(&path, event_time) {
+                        on_event(event);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
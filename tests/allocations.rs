@@ -0,0 +1,67 @@
+//! Allocation tests, in their own test binary because they install a global allocator.
+#![cfg(not(target_arch = "wasm32"))]
+
+use jumplist_parser::{JumplistParser, ParseOptions};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Tracks the size of the largest byte buffer (allocation with an alignment of 1), i.e.
+/// `Vec<u8>` and `String`, parsed structures have larger alignments.
+struct LargestAllocation;
+
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for LargestAllocation {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() == 1 {
+            LARGEST.fetch_max(layout.size(), Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if layout.align() == 1 {
+            LARGEST.fetch_max(new_size, Ordering::SeqCst);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LargestAllocation = LargestAllocation;
+
+#[cfg(test)]
+#[test]
+fn automatic_streams_are_read_lazily() {
+    let path = "samples/win11/AutomaticDestinations/5f7b5f1e01b83767.automaticDestinations-ms";
+    let file_size = std::fs::metadata(path).unwrap().len() as usize;
+    let compound = cfb::open(path).unwrap();
+    let largest_stream = compound
+        .walk()
+        .filter(|e| e.is_stream())
+        .map(|e| e.len() as usize)
+        .max()
+        .unwrap();
+    drop(compound);
+
+    for keep_raw_lnk in [false, true] {
+        let options = ParseOptions {
+            keep_raw_lnk,
+            ..Default::default()
+        };
+        LARGEST.store(0, Ordering::SeqCst);
+        let parsed = JumplistParser::from_path_with_options(path, &options).unwrap();
+        let largest = LARGEST.load(Ordering::SeqCst);
+        assert!(parsed.entries().count() > 0);
+        // The file is never read into a single buffer, the largest buffer is the largest
+        // stream or the 8 KiB read buffer
+        assert!(largest <= largest_stream.max(8192), "{}", largest);
+        assert!(largest < file_size / 4, "{} / {}", largest, file_size);
+    }
+}
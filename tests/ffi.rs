@@ -0,0 +1,83 @@
+//! Tests of the C interface, run with `cargo test --features ffi`.
+#![cfg(all(feature = "ffi", not(target_arch = "wasm32")))]
+
+use jumplist_parser::{ffi::*, JumplistParser};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+/// Take ownership of a string returned by the library.
+unsafe fn take(s: *mut c_char) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    let owned = CStr::from_ptr(s).to_string_lossy().to_string();
+    jumplist_free_string(s);
+    Some(owned)
+}
+
+#[cfg(test)]
+#[test]
+fn ffi_parse() {
+    let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let expected = serde_json::to_string(&JumplistParser::from_path(path).unwrap()).unwrap();
+    let c_path = CString::new(path).unwrap();
+    let (mut json, mut err) = (ptr::null_mut(), ptr::null_mut());
+
+    unsafe {
+        let rc = jumplist_parse_path(c_path.as_ptr(), &mut json, &mut err);
+        assert_eq!(rc, JUMPLIST_OK);
+        assert_eq!(take(err), None);
+        assert_eq!(take(json).unwrap(), expected);
+
+        // Buffers are identified by the name hint, or by their content without one
+        let data = std::fs::read(path).unwrap();
+        let name = CString::new("4cb9c5750d51c07f.automaticDestinations-ms").unwrap();
+        let rc = jumplist_parse_buffer(
+            data.as_ptr(),
+            data.len(),
+            name.as_ptr(),
+            &mut json,
+            &mut err,
+        );
+        assert_eq!(rc, JUMPLIST_OK);
+        let parsed: serde_json::Value = serde_json::from_str(&take(json).unwrap()).unwrap();
+        assert_eq!(parsed["app_id"], "4cb9c5750d51c07f");
+        assert_eq!(parsed["type"], "automatic");
+        let rc = jumplist_parse_buffer(data.as_ptr(), data.len(), ptr::null(), &mut json, &mut err);
+        assert_eq!(rc, JUMPLIST_OK);
+        let parsed: serde_json::Value = serde_json::from_str(&take(json).unwrap()).unwrap();
+        assert_eq!(parsed["type"], "automatic");
+        assert!(parsed["app_id"].is_null());
+
+        // Errors
+        let missing = CString::new("missing.automaticDestinations-ms").unwrap();
+        let rc = jumplist_parse_path(missing.as_ptr(), &mut json, &mut err);
+        assert_eq!(rc, JUMPLIST_ERR_PARSE);
+        assert!(json.is_null());
+        assert!(take(err)
+            .unwrap()
+            .contains("missing.automaticDestinations-ms"));
+        let rc = jumplist_parse_path(ptr::null(), &mut json, &mut err);
+        assert_eq!(rc, JUMPLIST_ERR_ARGUMENT);
+        assert!(take(err).is_some());
+        let rc = jumplist_parse_path(c_path.as_ptr(), ptr::null_mut(), ptr::null_mut());
+        assert_eq!(rc, JUMPLIST_ERR_ARGUMENT);
+        // Neither the name nor the content gives the type
+        let unknown = CString::new("notes.txt").unwrap();
+        let notes = b"not a Jumplist";
+        let rc = jumplist_parse_buffer(
+            notes.as_ptr(),
+            notes.len(),
+            unknown.as_ptr(),
+            &mut json,
+            &mut err,
+        );
+        assert_eq!(rc, JUMPLIST_ERR_ARGUMENT);
+        assert!(take(err).is_some());
+
+        jumplist_free_string(ptr::null_mut());
+    }
+}
@@ -0,0 +1,62 @@
+/*
+ * Parses a sample Jumplist through the C interface of the `ffi` feature.
+ *
+ *   cargo build --release --features ffi
+ *   cc -Iinclude tests/ffi/parse_sample.c target/release/libjumplist_parser.a \
+ *       -lpthread -ldl -lm -o parse_sample
+ *   ./parse_sample samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "jumplist_parser.h"
+
+static int check(const char *what, int32_t rc, char *json, char *err) {
+    int ok = rc == JUMPLIST_OK && json != NULL && strstr(json, "\"app_id\"") != NULL;
+    if (ok) {
+        printf("%s: %zu bytes of JSON\n", what, strlen(json));
+    } else {
+        fprintf(stderr, "%s failed (%d): %s\n", what, rc, err != NULL ? err : "");
+    }
+    jumplist_free_string(json);
+    jumplist_free_string(err);
+    return ok;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "Usage: %s <JUMPLIST>\n", argv[0]);
+        return 2;
+    }
+    const char *path = argv[1];
+    char *json = NULL;
+    char *err = NULL;
+
+    int32_t rc = jumplist_parse_path(path, &json, &err);
+    if (!check("jumplist_parse_path", rc, json, err)) {
+        return 1;
+    }
+
+    FILE *f = fopen(path, "rb");
+    if (f == NULL) {
+        perror(path);
+        return 1;
+    }
+    static uint8_t data[1 << 20];
+    size_t len = fread(data, 1, sizeof(data), f);
+    fclose(f);
+    const char *name = strrchr(path, '/') != NULL ? strrchr(path, '/') + 1 : path;
+    rc = jumplist_parse_buffer(data, len, name, &json, &err);
+    if (!check("jumplist_parse_buffer", rc, json, err)) {
+        return 1;
+    }
+
+    /* Errors are reported through `out_err` */
+    rc = jumplist_parse_path("missing.automaticDestinations-ms", &json, &err);
+    if (rc != JUMPLIST_ERR_PARSE || json != NULL || err == NULL) {
+        fprintf(stderr, "Expected a parse error, got %d\n", rc);
+        return 1;
+    }
+    jumplist_free_string(err);
+    return 0;
+}
@@ -1,3 +1,6 @@
+// The samples are read from disk, see `tests/wasm.rs` for WebAssembly
+#![cfg(not(target_arch = "wasm32"))]
+
 use glob::glob;
 use jumplist_parser::JumplistParser;
 
@@ -58,3 +61,5827 @@ fn win10_custom_destinations() {
 fn win10_automatic_destinations() {
     parse_and_print_glob("samples/win10/AutomaticDestinations/*");
 }
+
+#[cfg(test)]
+#[test]
+fn destlist_version_1() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::{DestListEntry, DestListVersion};
+
+    // No real Windows 7 Jumplist is checked in: the version 1 layout is built from the same
+    // entries as a version 4 one
+    let entries = [
+        AutomaticEntry {
+            pin_position: Some(0),
+            access_count: 3,
+            hostname: "win7-pc".to_string(),
+            ..AutomaticEntry::new(2, r"C:\Users\user\Desktop\Jumplists")
+        },
+        AutomaticEntry::new(1, r"C:\Users\user\Documents\report.docx"),
+    ];
+    let build = |version: DestListVersion| {
+        let data = entries
+            .iter()
+            .fold(AutomaticJumplistBuilder::new(version), |builder, entry| {
+                builder.entry(entry.clone())
+            })
+            .to_bytes()
+            .unwrap();
+        JumplistParser::from_bytes(&data, Some("0123456789abcdef.automaticDestinations-ms"))
+            .unwrap()
+    };
+    let win7 = build(DestListVersion::V1Win7);
+    let newer = build(DestListVersion::V4Win10_1703Plus);
+
+    let destlist = win7.destlist().unwrap();
+    assert_eq!(destlist.header.version, DestListVersion::V1Win7);
+    assert!(!destlist.header.version.has_extended_entries());
+    assert!(win7.is_complete());
+    let expected = &newer.destlist().unwrap().entries;
+    assert_eq!(destlist.entries.len(), expected.len());
+    for (entry, expected) in destlist.entries.iter().zip(expected) {
+        assert_eq!(entry.entry_number, expected.entry_number);
+        assert_eq!(entry.path, expected.path);
+        assert_eq!(entry.mtime, expected.mtime);
+        assert_eq!(entry.pinned, expected.pinned);
+        assert_eq!(entry.hostname, expected.hostname);
+        assert_eq!(entry.access_count, expected.access_count);
+        assert_eq!(entry.trailing_value, None);
+        assert!(entry.lnk.is_some());
+    }
+    assert_eq!(destlist.entries.iter().filter(|e| e.pinned).count(), 1);
+    let json = serde_json::to_value(&win7).unwrap();
+    assert_eq!(json["data"]["header"]["version"], 1);
+
+    // A version 1 entry written byte by byte from the documented layout, not by the builder
+    let mut raw = vec![0u8; 0x72];
+    raw[0x48..0x4F].copy_from_slice(b"win7-pc");
+    raw[0x58..0x5C].copy_from_slice(&0x1Fu32.to_le_bytes());
+    // Floating point access count
+    raw[0x60..0x64].copy_from_slice(&2.6f32.to_le_bytes());
+    raw[0x64..0x6C].copy_from_slice(&129_000_000_000_000_000u64.to_le_bytes());
+    // Signed pin position
+    raw[0x6C..0x70].copy_from_slice(&(-2i32).to_le_bytes());
+    let path: Vec<u16> = r"C:\notes.txt".encode_utf16().collect();
+    raw[0x70..0x72].copy_from_slice(&(path.len() as u16).to_le_bytes());
+    path.iter()
+        .for_each(|unit| raw.extend_from_slice(&unit.to_le_bytes()));
+    let entry = DestListEntry::from_buffer(&raw, DestListVersion::V1Win7).unwrap();
+    assert_eq!(entry.entry_number, 0x1F);
+    assert_eq!(entry.hostname.as_deref(), Some("win7-pc"));
+    assert_eq!(entry.access_count, Some(3));
+    assert_eq!(entry.mtime.filetime, 129_000_000_000_000_000);
+    assert!(!entry.pinned);
+    assert_eq!(entry.path, r"C:\notes.txt");
+    assert_eq!(entry.entry_size, raw.len() as u64);
+    assert_eq!(entry.trailing_value, None);
+    raw[0x6C..0x70].copy_from_slice(&1i32.to_le_bytes());
+    let entry = DestListEntry::from_buffer(&raw, DestListVersion::V1Win7).unwrap();
+    assert!(entry.pinned);
+
+    assert_eq!(DestListVersion::from(4), DestListVersion::V4Win10_1703Plus);
+    assert_eq!(DestListVersion::from(2), DestListVersion::Unknown(2));
+    assert!(DestListVersion::Unknown(2).has_extended_entries());
+    assert_eq!(u32::from(DestListVersion::V3Win10), 3);
+}
+
+#[cfg(test)]
+#[test]
+fn export_lnks() {
+    use jumplist_parser::ParseOptions;
+
+    let options = ParseOptions {
+        keep_raw_lnk: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+        &options,
+    )
+    .unwrap();
+    let dir = std::env::temp_dir().join("jumplist_parser_export_lnks");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let first = parsed.export_lnks(&dir).unwrap();
+    assert!(!first.is_empty());
+    assert!(first[0]
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("4cb9c5750d51c07f_"));
+
+    // Exporting again must not overwrite the previous files
+    let second = parsed.export_lnks(&dir).unwrap();
+    assert_eq!(first.len(), second.len());
+    assert!(second.iter().all(|p| !first.contains(p)));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(test)]
+#[test]
+fn export_lnks_requires_raw_lnk() {
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let dir = std::env::temp_dir().join("jumplist_parser_export_lnks_no_raw");
+    assert!(parsed.export_lnks(&dir).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn tsv_path_with_tab() {
+    use jumplist_parser::output::{csv_header, encode_rows, CsvOptions};
+
+    let options = CsvOptions::tsv();
+    assert!(
+        csv_header(&options).starts_with(b"jumplist_app_id\tjumplist_app_name\tjumplist_type\t")
+    );
+
+    let row = vec![
+        "C:\\Users\\u0041\\a\tb.txt".to_string(),
+        "/flag".to_string(),
+    ];
+    let encoded = encode_rows(std::slice::from_ref(&row), &options);
+    assert_eq!(encoded, b"\"C:\\Users\\u0041\\a\tb.txt\"\t/flag\n");
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .from_reader(encoded.as_slice());
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(record.iter().collect::<Vec<&str>>(), row);
+}
+
+#[cfg(test)]
+#[test]
+fn safe_csv() {
+    use jumplist_parser::output::{encode_rows, sanitize_field, CsvOptions};
+
+    for dangerous in ["=1+1", "+1", "-2", "@SUM(A1)", "\t=1", "\r=1", "\n=1"] {
+        assert_eq!(sanitize_field(dangerous), format!("'{}", dangerous));
+    }
+    assert_eq!(sanitize_field("C:\\a=b.txt"), "C:\\a=b.txt");
+    assert_eq!(sanitize_field(""), "");
+
+    let row = vec!["=cmd|' /C calc'!A0".to_string()];
+    let safe = encode_rows(std::slice::from_ref(&row), &CsvOptions::default());
+    assert_eq!(safe, b"\"'=cmd|' /C calc'!A0\"\n");
+
+    let unsafe_options = CsvOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let raw = encode_rows(std::slice::from_ref(&row), &unsafe_options);
+    assert_eq!(raw, b"\"=cmd|' /C calc'!A0\"\n");
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_header_counters() {
+    use jumplist_parser::JumplistData;
+
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    match parsed.data {
+        JumplistData::DestList(destlist) => {
+            assert_eq!(destlist.header.number_of_entries, 24);
+            assert_eq!(destlist.header.last_entry_number, 25);
+            assert_eq!(destlist.header.last_revision_number, 49);
+            assert!(destlist.header.aging_counter > 0.0);
+            assert_eq!(destlist.header.deleted_entry_estimate(), 1);
+        }
+        _ => panic!("expected a DestList"),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn truncated_destlist_is_incomplete() {
+    use jumplist_parser::JumplistType;
+    use std::io::{Cursor, Read, Write};
+
+    let sample = std::fs::read(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let mut original = cfb::CompoundFile::open(Cursor::new(sample)).unwrap();
+    let mut truncated = cfb::CompoundFile::create(Cursor::new(vec![])).unwrap();
+    let streams: Vec<String> = original
+        .walk()
+        .filter(|e| e.is_stream())
+        .map(|e| e.name().to_string())
+        .collect();
+    for name in streams {
+        let mut data = vec![];
+        original
+            .open_stream(&name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        if name == "DestList" {
+            // Cut the last entry in half
+            data.truncate(data.len() - 100);
+        }
+        truncated
+            .create_stream(&name)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+    }
+    truncated.flush().unwrap();
+    let mut cursor = Cursor::new(truncated.into_inner().into_inner());
+
+    let parsed = JumplistParser::from_reader(&mut cursor, JumplistType::Automatic).unwrap();
+    assert!(!parsed.is_complete());
+    assert_eq!(parsed.warnings().len(), 1);
+    assert!(parsed.warnings()[0].contains("declares 5 entries but 4 were parsed"));
+
+    let complete = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert!(complete.is_complete());
+    assert!(complete.warnings().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn pathological_sizes() {
+    use jumplist_parser::{
+        custom_destinations::CustomDestinations, destlist::DestListHeader,
+        errors::JumplistParserError,
+    };
+    use std::io::Cursor;
+
+    fn le(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+    // Custom Jumplists keep what was parsed before the bad value
+    fn assert_field(result: Result<CustomDestinations, JumplistParserError>, field: &str) {
+        let parsed = result.unwrap();
+        assert!(parsed.truncated);
+        assert!(parsed
+            .entries
+            .iter()
+            .flat_map(|c| c.entries.iter().flatten())
+            .next()
+            .is_none());
+        assert!(
+            parsed.warnings.iter().any(|w| w.contains(field)),
+            "{:?}",
+            parsed.warnings
+        );
+    }
+
+    // 4 billion categories
+    let data = le(&[2, u32::MAX, 0]);
+    assert_field(
+        CustomDestinations::from_reader(&mut Cursor::new(data)),
+        "category at offset 12",
+    );
+
+    // Task category with 4 billion entries
+    let data = le(&[2, 1, 0, 2, u32::MAX, 0xBABFFBAB]);
+    assert_field(
+        CustomDestinations::from_reader(&mut Cursor::new(data)),
+        "Task entry at offset 20",
+    );
+
+    // Custom category with a 65535 characters name
+    let mut data = le(&[2, 1, 0, 0]);
+    data.extend_from_slice(&u16::MAX.to_le_bytes());
+    data.extend_from_slice(&[0x41, 0x00, 0x42, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_field(
+        CustomDestinations::from_reader(&mut Cursor::new(data)),
+        "name_len",
+    );
+
+    // DestList declaring 4 billion entries
+    let data = le(&[4, u32::MAX, 0, 0, 0, 0, 0, 0]);
+    assert!(DestListHeader::from_buffer(&data).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn resolve_known_folders() {
+    use jumplist_parser::known_folders::resolve_known_folder_path;
+    use jumplist_parser::JumplistData;
+
+    assert_eq!(
+        resolve_known_folder_path("::{679f85cb-0220-4080-b29b-5540cc05aab6}"),
+        "[Quick Access]"
+    );
+    assert_eq!(
+        resolve_known_folder_path(
+            "::{26EE0668-A00A-44D7-9371-BEB064C98683}\\5\\::{BB06C0E4-D293-4F75-8A90-CB05B6477EEE}"
+        ),
+        "[Control Panel]\\5\\[System]"
+    );
+    assert_eq!(
+        resolve_known_folder_path("::{00000000-0000-0000-0000-000000000000}\\x"),
+        "::{00000000-0000-0000-0000-000000000000}\\x"
+    );
+    assert_eq!(resolve_known_folder_path("C:\\Users"), "C:\\Users");
+
+    // Explorer's jumplist references the pinned user folders by GUID
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    match parsed.data {
+        JumplistData::DestList(destlist) => {
+            let downloads = destlist
+                .entries
+                .iter()
+                .find(|e| e.entry_number == 2)
+                .unwrap();
+            assert_eq!(
+                downloads.path,
+                "knownfolder:{374DE290-123F-4565-9164-39C4925E467B}"
+            );
+            assert_eq!(downloads.path_resolved.as_deref(), Some("[Downloads]"));
+            assert!(destlist
+                .entries
+                .iter()
+                .filter(|e| !e.path.contains('{'))
+                .all(|e| e.path_resolved.is_none()));
+        }
+        _ => panic!("expected a DestList"),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn target_type() {
+    use jumplist_parser::target::{TargetInfo, TargetType};
+    use jumplist_parser::JumplistData;
+
+    let classify = |path: &str| TargetInfo::from_path(path, None);
+    assert_eq!(
+        classify("https://example.com/").target_type,
+        TargetType::Url
+    );
+    assert_eq!(classify("ms-photos:viewer").target_type, TargetType::Url);
+    assert_eq!(
+        classify("::{20D04FE0-3AEA-1069-A2D8-08002B30309D}").target_type,
+        TargetType::Shell
+    );
+    assert_eq!(
+        classify("knownfolder:{374DE290-123F-4565-9164-39C4925E467B}").target_type,
+        TargetType::Shell
+    );
+    let unc = classify(r"\\?\UNC\srv\docs\a.docx");
+    assert_eq!(unc.target_type, TargetType::Unc);
+    assert_eq!(unc.target_share.as_deref(), Some(r"\\srv\docs"));
+    let file = classify(r"d:\autorun.inf");
+    assert_eq!(file.target_type, TargetType::File);
+    assert_eq!(file.target_drive_letter.as_deref(), Some("D"));
+    let url = classify("file://srv/docs/a%20b.docx");
+    assert_eq!(url.target_type, TargetType::Unc);
+    assert_eq!(
+        url.target_local_path.as_deref(),
+        Some(r"\\srv\docs\a b.docx")
+    );
+    assert_eq!(
+        TargetInfo::from_path(r"C:\Windows", Some(0x10)).target_type,
+        TargetType::Directory
+    );
+
+    // Explorer's recent folders are directories
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    match parsed.data {
+        JumplistData::DestList(destlist) => {
+            let entry = destlist
+                .entries
+                .iter()
+                .find(|e| e.entry_number == 25)
+                .unwrap();
+            assert_eq!(entry.target.target_type, TargetType::Directory);
+            assert_eq!(entry.target.target_drive_letter.as_deref(), Some("C"));
+        }
+        _ => panic!("expected a DestList"),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn hostname_cleanup() {
+    use jumplist_parser::{Flatten, JumplistData};
+
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let normalized = parsed.flatten();
+    match parsed.data {
+        JumplistData::DestList(destlist) => {
+            for entry in &destlist.entries {
+                assert_eq!(entry.hostname.as_deref(), Some("desktop-u6f04qv"));
+                assert!(!entry.hostname_is_ip);
+            }
+        }
+        _ => panic!("expected a DestList"),
+    }
+    assert!(normalized
+        .iter()
+        .all(|e| e.get("target_hostname").map(|h| h.as_str()) == Some("desktop-u6f04qv")));
+
+    // Custom jumplists always have the column, even without tracker data
+    let parsed = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    assert!(parsed
+        .flatten()
+        .iter()
+        .all(|e| e.contains_key("target_hostname")));
+}
+
+#[cfg(test)]
+#[test]
+fn timestamp_format() {
+    use jumplist_parser::timestamp::{Timestamp, TimestampFormat};
+    use jumplist_parser::{Flatten, JumplistData, ParseOptions};
+
+    let ts = |format| Timestamp::new(133_966_062_971_234_567, format);
+    assert_eq!(ts(TimestampFormat::Iso).to_string(), "2025-07-10T07:31:37Z");
+    assert_eq!(
+        ts(TimestampFormat::IsoSubsecond).to_string(),
+        "2025-07-10T07:31:37.1234567Z"
+    );
+    assert_eq!(ts(TimestampFormat::Epoch).to_string(), "1752132697");
+    assert_eq!(
+        serde_json::to_string(&ts(TimestampFormat::FileTime)).unwrap(),
+        "133966062971234567"
+    );
+    // Zero FILETIMEs are unset, not 1601-01-01
+    let zero = Timestamp::new(0, TimestampFormat::Iso);
+    assert_eq!(zero.to_string(), "");
+    assert_eq!(serde_json::to_string(&zero).unwrap(), "null");
+
+    let options = ParseOptions {
+        ts_format: TimestampFormat::Epoch,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        &options,
+    )
+    .unwrap();
+    for entry in parsed.flatten() {
+        let mtime = entry.get("target_modification_time").unwrap();
+        assert!(mtime.is_empty() || mtime.parse::<i64>().is_ok());
+    }
+    match parsed.data {
+        JumplistData::DestList(destlist) => {
+            let json = serde_json::to_value(&destlist.entries[0]).unwrap();
+            assert!(json["mtime"].is_i64());
+        }
+        _ => panic!("expected a DestList"),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn deserialize_round_trip() {
+    use serde_json::Value;
+
+    // The LNK structures come from `lnk_parser` and are dropped when deserializing
+    fn strip_lnk(value: &mut Value) {
+        const KEPT_KEYS: &[&str] = &[
+            "file_offset",
+            "entry_size",
+            "target_type",
+            "target_drive_letter",
+            "target_share",
+            "target_local_path",
+            "target_mft_entry",
+            "target_mft_sequence",
+            "entry_fingerprint",
+        ];
+        let data = &mut value["data"];
+        if let Some(entries) = data["entries"].as_array_mut() {
+            for entry in entries {
+                let entry = entry.as_object_mut().unwrap();
+                entry.remove("lnk");
+                if let Some(lnks) = entry.get_mut("entries").and_then(|e| e.as_array_mut()) {
+                    for lnk in lnks {
+                        lnk.as_object_mut()
+                            .unwrap()
+                            .retain(|k, _| KEPT_KEYS.contains(&k.as_str()));
+                    }
+                }
+            }
+        }
+    }
+
+    for path in glob("samples/win1*/*/*").unwrap() {
+        let path = path.unwrap();
+        let parsed = JumplistParser::from_path(path.to_str().unwrap()).unwrap();
+        let mut original = serde_json::to_value(&parsed).unwrap();
+        let reloaded: JumplistParser = serde_json::from_value(original.clone()).unwrap();
+        let round_trip = serde_json::to_value(&reloaded).unwrap();
+        strip_lnk(&mut original);
+        assert_eq!(original, round_trip, "{}", path.display());
+    }
+}
+
+/// Compare the output schema with the checked-in snapshot.
+///
+/// Run with `UPDATE_SNAPSHOTS=1` to accept an intended output change.
+#[cfg(feature = "schema")]
+#[test]
+fn json_schema_snapshot() {
+    let snapshot_path = "tests/snapshots/schema.json";
+    let schema = format!("{}\n", jumplist_parser::schema::json_schema());
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(snapshot_path, &schema).unwrap();
+    }
+    let snapshot = std::fs::read_to_string(snapshot_path).unwrap();
+    assert!(
+        schema == snapshot,
+        "The output schema changed, run the tests with UPDATE_SNAPSHOTS=1 to update '{}'",
+        snapshot_path
+    );
+}
+
+/// The normalized entries of an automatic and a custom Jumplist, keys sorted. Run with
+/// `UPDATE_SNAPSHOTS=1` to accept an intended output change.
+#[cfg(test)]
+#[test]
+fn normalized_output_snapshots() {
+    use jumplist_parser::{
+        output::{with_legacy_keys, FIELDS},
+        Flatten, ParseOptions,
+    };
+    use std::collections::BTreeMap;
+
+    for (sample, snapshot_path) in [
+        (
+            "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+            "tests/snapshots/normalized_automatic.json",
+        ),
+        (
+            "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+            "tests/snapshots/normalized_custom.json",
+        ),
+    ] {
+        let parsed = JumplistParser::from_path(sample).unwrap();
+        let entries: Vec<BTreeMap<String, String>> = parsed
+            .flatten()
+            .into_iter()
+            .map(|e| e.into_iter().collect())
+            .collect();
+        // Every key can be selected with `--fields`
+        for key in entries.iter().flat_map(|e| e.keys()) {
+            assert!(FIELDS.iter().any(|f| f.name == key), "{}", key);
+        }
+        let normalized = format!("{}\n", serde_json::to_string_pretty(&entries).unwrap());
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(snapshot_path, &normalized).unwrap();
+        }
+        let snapshot = std::fs::read_to_string(snapshot_path).unwrap();
+        assert!(
+            normalized == snapshot,
+            "The normalized output changed, run the tests with UPDATE_SNAPSHOTS=1 to update '{}'",
+            snapshot_path
+        );
+
+        let options = ParseOptions {
+            legacy_keys: true,
+            ..Default::default()
+        };
+        let legacy = JumplistParser::from_path_with_options(sample, &options).unwrap();
+        let expected: Vec<_> = parsed.flatten().into_iter().map(with_legacy_keys).collect();
+        assert_eq!(legacy.flatten(), expected);
+        for entry in legacy.flatten() {
+            assert!(entry.contains_key("name_string"));
+            assert!(entry.contains_key("command_line_arguments"));
+            assert!(!entry.contains_key("lnk_description"));
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn unified_entries() {
+    use jumplist_parser::Flatten;
+
+    let automatic = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert_eq!(automatic.entry_count(), 24);
+    assert!(!automatic.is_empty());
+    assert_eq!(
+        automatic
+            .entries()
+            .filter(|e| e.pinned() == Some(true))
+            .count(),
+        4
+    );
+    let first = automatic.entries().next().unwrap();
+    assert_eq!(first.entry_number(), Some(25));
+    assert!(first.interaction_time().is_some());
+    assert_eq!(first.category(), None);
+
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    assert_eq!(custom.entry_count(), custom.flatten().len());
+    for entry in custom.entries() {
+        assert!(entry.category().is_some());
+        assert!(entry.lnk().is_some());
+        assert_eq!(
+            entry.pinned(),
+            Some(matches!(entry.category(), Some("tasks" | "pinned")))
+        );
+        assert_eq!(entry.entry_number(), None);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn data_accessors() {
+    for path in glob("samples/win1*/*/*").unwrap() {
+        let path = path.unwrap();
+        let mut parsed = JumplistParser::from_path(path.to_str().unwrap()).unwrap();
+        let automatic = path.to_str().unwrap().contains("AutomaticDestinations");
+        assert_eq!(parsed.is_automatic(), automatic);
+        assert_eq!(parsed.is_custom(), !automatic);
+        assert_eq!(parsed.destlist().is_some(), automatic);
+        assert_eq!(parsed.custom_destinations().is_some(), !automatic);
+        assert_eq!(parsed.data.as_destlist_mut().is_some(), automatic);
+        assert_eq!(
+            parsed.data.as_custom_destinations_mut().is_some(),
+            !automatic
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn clone_and_compare() {
+    for path in glob("samples/win1*/*/*").unwrap() {
+        let parsed = JumplistParser::from_path(path.unwrap().to_str().unwrap()).unwrap();
+        let mut cloned = parsed.clone();
+        assert_eq!(parsed, cloned);
+        cloned.app_id = Some("modified".to_string());
+        assert_ne!(parsed, cloned);
+    }
+
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let entries = &parsed.destlist().unwrap().entries;
+    let mut entry = entries[0].clone();
+    assert_eq!(entry, entries[0]);
+    entry.pinned = !entry.pinned;
+    assert_ne!(entry, entries[0]);
+    assert_ne!(entries[0], entries[1]);
+}
+
+#[cfg(test)]
+#[test]
+fn diff_snapshots() {
+    use jumplist_parser::diff::diff;
+
+    let old = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert!(diff(&old, &old).is_empty());
+    assert!(old
+        .destlist()
+        .unwrap()
+        .entries
+        .iter()
+        .all(|e| e.access_count.unwrap_or_default() > 0));
+
+    let mut new = old.clone();
+    let entries = &mut new.data.as_destlist_mut().unwrap().entries;
+    // The entry number of a deleted entry reused for another path
+    let reused = entries[1].entry_number;
+    entries[1].path = r"C:\Users\u0041\Desktop\other.txt".to_string();
+    entries[2].pinned = !entries[2].pinned;
+    entries[2].access_count = entries[2].access_count.map(|c| c + 1);
+    entries[3].mtime.filetime += 1;
+    let removed = entries.pop().unwrap();
+
+    let changes = diff(&old, &new);
+    assert_eq!(changes.added.len(), 1);
+    assert_eq!(changes.added[0].entry_number, Some(reused));
+    assert_eq!(changes.removed.len(), 2);
+    assert_eq!(changes.removed[0].entry_number, Some(reused));
+    assert_eq!(changes.removed[1].path.as_ref(), Some(&removed.path));
+    assert_eq!(changes.modified.len(), 2);
+    let fields: Vec<&str> = changes.modified[0]
+        .changes
+        .iter()
+        .map(|c| c.field.as_str())
+        .collect();
+    assert_eq!(fields, ["pinned", "access_count"]);
+    assert_eq!(changes.modified[1].changes[0].field, "mtime");
+
+    let json = serde_json::to_value(&changes).unwrap();
+    assert_eq!(json["modified"][0]["changes"][0]["old"], false);
+
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    assert!(diff(&custom, &custom).is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn aggregate_targets() {
+    use jumplist_parser::aggregate::{AggregateOptions, TargetActivity};
+    use jumplist_parser::target::canonicalize_path;
+
+    let parsed: Vec<JumplistParser> = glob("samples/win1*/*/*")
+        .unwrap()
+        .map(|p| JumplistParser::from_path(p.unwrap().to_str().unwrap()).unwrap())
+        .collect();
+    let activities = TargetActivity::from_parsers(&parsed);
+    let total: usize = parsed.iter().map(|p| p.entry_count()).sum();
+    assert!(activities.len() < total);
+
+    let keys: std::collections::HashSet<String> = activities
+        .iter()
+        .map(|a| a.target_path.to_lowercase())
+        .collect();
+    assert_eq!(keys.len(), activities.len());
+
+    let shared = activities
+        .iter()
+        .find(|a| a.target_path.ends_with(r"mkv\sample_640x360.mkv"))
+        .unwrap();
+    assert!(shared.apps.len() > 1);
+    assert!(shared.first_seen.unwrap().filetime < shared.last_seen.unwrap().filetime);
+    assert!(shared.access_count.unwrap() as usize >= shared.entry_count);
+    assert!(activities.iter().any(|a| a.pinned));
+
+    // The same target recorded with a different drive letter case and a trailing slash
+    let mut modified = parsed[0].clone();
+    if let Some(destlist) = modified.data.as_destlist_mut() {
+        for entry in destlist.entries.iter_mut() {
+            if let Some(rest) = entry.path.strip_prefix("C:") {
+                entry.path = format!("c:{}\\", rest);
+            }
+        }
+    }
+    let both = vec![parsed[0].clone(), modified];
+    let options = AggregateOptions {
+        canonicalize_paths: true,
+    };
+    let grouped = TargetActivity::from_parsers_with_options(&both, &options);
+    assert_eq!(
+        grouped.len(),
+        TargetActivity::from_parsers(&both[..1]).len()
+    );
+
+    assert_eq!(
+        canonicalize_path("//server/share/dir/"),
+        r"\\server\share\dir"
+    );
+    assert_eq!(
+        canonicalize_path("https://example.com/"),
+        "https://example.com/"
+    );
+    assert_eq!(
+        canonicalize_path("::{20D04FE0-3AEA-1069-A2D8-08002B30309D}"),
+        "::{20D04FE0-3AEA-1069-A2D8-08002B30309D}"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn entry_offsets() {
+    use jumplist_parser::output::{csv_header, CsvOptions};
+    use jumplist_parser::Flatten;
+
+    for path in glob("samples/win*/AutomaticDestinations/*").unwrap() {
+        let parsed = JumplistParser::from_path(path.unwrap().to_str().unwrap()).unwrap();
+        let mut entries = parsed.destlist().unwrap().entries.clone();
+        entries.sort_by_key(|e| e.stream_offset);
+        // Entries follow the 32 bytes header without gaps
+        let mut offset = 32;
+        for entry in &entries {
+            assert_eq!(entry.stream_offset, offset);
+            offset += entry.entry_size;
+        }
+    }
+
+    let path = "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let categories = &parsed.custom_destinations().unwrap().entries;
+    assert_eq!(categories[0].file_offset, 12);
+    for category in categories {
+        let offset = category.file_offset as usize;
+        assert_eq!(data[offset] as u32, category.r#type.clone() as u32);
+        for entry in category.entries.iter().flatten() {
+            // The entries start with the LNK CLSID
+            let start = entry.file_offset as usize;
+            assert_eq!(data[start..start + 4], [0x01, 0x14, 0x02, 0x00]);
+            assert!(start + entry.entry_size as usize <= data.len());
+        }
+    }
+    for normalized in parsed.flatten() {
+        assert!(normalized["entry_offset"].parse::<u64>().unwrap() > 12);
+    }
+
+    let options = CsvOptions {
+        offset_columns: true,
+        ..Default::default()
+    };
+    let header = String::from_utf8(csv_header(&options)).unwrap();
+    assert!(header
+        .trim_end()
+        .ends_with(r#""entry_offset","entry_size""#));
+}
+
+#[cfg(test)]
+#[test]
+fn capture_unknown_bytes() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::DestListVersion;
+    use jumplist_parser::ParseOptions;
+
+    let path = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let destlist = parsed.destlist().unwrap();
+    assert!(destlist.header.unknown_fields.is_none());
+    assert!(destlist.entries.iter().all(|e| e.unknown_fields.is_none()));
+    assert!(!serde_json::to_string(&parsed)
+        .unwrap()
+        .contains("unknown_fields"));
+
+    let options = ParseOptions {
+        capture_unknown_bytes: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(path, &options).unwrap();
+    let destlist = parsed.destlist().unwrap();
+    assert_eq!(
+        destlist.header.unknown_fields.as_ref().unwrap()["header_unknown_0x14_4"],
+        "00000000"
+    );
+    for entry in &destlist.entries {
+        let fields = entry.unknown_fields.as_ref().unwrap();
+        assert_eq!(fields.len(), 5);
+        assert_eq!(fields["entry_unknown_0x00_8"].len(), 16);
+        assert_eq!(fields["entry_unknown_post_path_4"].len(), 8);
+    }
+
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V1Win7)
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\notes.txt"))
+        .to_bytes()
+        .unwrap();
+    let win7 = JumplistParser::from_bytes_with_options(&data, None, &options).unwrap();
+    let fields = win7.destlist().unwrap().entries[0]
+        .unknown_fields
+        .clone()
+        .unwrap();
+    let mut keys: Vec<&str> = fields.keys().map(|k| k.as_str()).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["entry_unknown_0x00_8", "entry_unknown_0x5c_4"]);
+
+    let custom = JumplistParser::from_path_with_options(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+        &options,
+    )
+    .unwrap();
+    let custom = custom.custom_destinations().unwrap();
+    assert!(custom.header.unknown_fields.is_some());
+    for category in &custom.entries {
+        assert_eq!(
+            category.unknown_fields.as_ref().unwrap()["category_footer_4"],
+            "abfbbfba"
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn os_hint() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::DestListVersion;
+    use jumplist_parser::os_hint::OsHint;
+    use jumplist_parser::output::{csv_rows, CsvOptions, CSV_COLUMNS};
+    use jumplist_parser::Flatten;
+
+    let expected = [
+        (
+            "samples/win1*/AutomaticDestinations/*",
+            Some(OsHint::Win10Creators),
+        ),
+        ("samples/other/*ms", Some(OsHint::Win11)),
+        ("samples/win1*/CustomDestinations/*", None),
+    ];
+    for (pattern, hint) in expected.iter() {
+        for path in glob(pattern).unwrap() {
+            let parsed = JumplistParser::from_path(path.unwrap().to_str().unwrap()).unwrap();
+            if parsed.is_empty() {
+                // Empty DestList streams have no version
+                assert_eq!(parsed.os_hint(), None);
+                continue;
+            }
+            assert_eq!(parsed.os_hint(), *hint, "{:?}", parsed.source_path);
+
+            let json = serde_json::to_value(&parsed).unwrap();
+            match hint {
+                Some(hint) => assert_eq!(json["os_hint"], hint.to_string()),
+                None => assert!(json.get("os_hint").is_none()),
+            }
+            let column = CSV_COLUMNS
+                .iter()
+                .position(|c| *c == "jumplist_os_hint")
+                .unwrap();
+            for (row, normalized) in csv_rows(&parsed, &CsvOptions::default())
+                .iter()
+                .zip(parsed.flatten())
+            {
+                assert_eq!(row[column], normalized["jumplist_os_hint"]);
+                assert_eq!(row[column], hint.map(|h| h.to_string()).unwrap_or_default());
+            }
+        }
+    }
+
+    // Version 1 DestList streams are written by Windows 7 and 8
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V1Win7)
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\notes.txt"))
+        .to_bytes()
+        .unwrap();
+    let win7 = JumplistParser::from_bytes(&data, None).unwrap();
+    assert_eq!(win7.os_hint(), Some(OsHint::Win7OrWin8));
+    assert_eq!(win7.flatten()[0]["jumplist_os_hint"], "win7_or_win8");
+}
+
+#[cfg(test)]
+#[test]
+fn tracker_data() {
+    use jumplist_parser::Flatten;
+    use winparsingtools::traits::Normalize;
+
+    let path = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let normalized = parsed.flatten();
+    assert_eq!(normalized[0]["lnk_tracker_machine_id"], "desktop-u6f04qv");
+    assert_eq!(
+        normalized[0]["lnk_tracker_mac_address"],
+        "bc:24:11:91:c2:01"
+    );
+    assert_eq!(
+        normalized[0]["lnk_tracker_created_time"],
+        "2025-07-10T07:15:52Z"
+    );
+    assert!(normalized
+        .iter()
+        .all(|n| n["destlist_hostname_mismatch"] == "false"));
+
+    let mut entry = parsed.destlist().unwrap().entries[0].clone();
+    let tracker = entry
+        .lnk_details
+        .as_ref()
+        .and_then(|d| d.tracker.clone())
+        .unwrap();
+    assert_eq!(tracker.droid_file.0[7] >> 4, 1);
+
+    // An LNK copied from another machine keeps its tracker data
+    entry.hostname = Some("other-host".to_string());
+    assert_eq!(entry.normalize()["destlist_hostname_mismatch"], "true");
+
+    let path = "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    for normalized in parsed.flatten() {
+        assert_eq!(normalized["lnk_tracker_machine_id"], "win11");
+        assert_eq!(normalized["lnk_tracker_mac_address"], "bc:24:11:14:e2:f4");
+        // Custom Jumplists have no hostname to compare with
+        assert_eq!(normalized["destlist_hostname_mismatch"], "");
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn link_info() {
+    use jumplist_parser::lnk_info::{DriveType, LnkDetails};
+    use jumplist_parser::output::{csv_columns, csv_rows, CsvOptions, EXTENDED_CSV_COLUMNS};
+    use jumplist_parser::timestamp::TimestampFormat;
+
+    let path = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let options = CsvOptions {
+        extended_columns: true,
+        ..Default::default()
+    };
+    let columns = csv_columns(&options);
+    let column = |name: &str| columns.iter().position(|c| *c == name).unwrap();
+    for row in csv_rows(&parsed, &options) {
+        assert_eq!(row[column("target_volume_serial")], "405B-FCF0");
+        assert_eq!(row[column("target_drive_type")], "fixed");
+        assert_eq!(row[column("target_on_removable_media")], "false");
+        assert_eq!(row[column("target_network_share")], "");
+    }
+    assert!(!csv_columns(&CsvOptions::default()).contains(&EXTENDED_CSV_COLUMNS[0]));
+
+    // A LNK to a file on a removable drive, shared on the network as `\\srv\share` (mapped to Z:)
+    let mut link_info = vec![];
+    link_info.extend_from_slice(&[0; 4]);
+    link_info.extend_from_slice(&0x1Cu32.to_le_bytes());
+    link_info.extend_from_slice(&3u32.to_le_bytes());
+    for offset in [0x1Cu32, 0x33, 0x3E, 0x61] {
+        link_info.extend_from_slice(&offset.to_le_bytes());
+    }
+    // VolumeID
+    for value in [0x17u32, 2, 0x1234_ABCD, 0x10] {
+        link_info.extend_from_slice(&value.to_le_bytes());
+    }
+    link_info.extend_from_slice(b"USBKEY\0E:\\doc.txt\0");
+    // CommonNetworkRelativeLink
+    for value in [0x23u32, 3, 0x14, 0x20, 0x0002_0000] {
+        link_info.extend_from_slice(&value.to_le_bytes());
+    }
+    link_info.extend_from_slice(b"\\\\srv\\share\0Z:\0\0");
+    let size = link_info.len() as u32;
+    link_info[..4].copy_from_slice(&size.to_le_bytes());
+    let mut lnk = vec![0; 0x4C];
+    lnk[0] = 0x4C;
+    lnk[0x14] = 0x02;
+    lnk.extend_from_slice(&link_info);
+    lnk.extend_from_slice(&[0; 4]);
+
+    let details = LnkDetails::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+    let link_info = details.link_info.unwrap();
+    assert_eq!(link_info.drive_type, Some(DriveType::Removable));
+    assert_eq!(link_info.volume_serial_number().unwrap(), "1234-ABCD");
+    assert_eq!(
+        link_info.network_provider_name().unwrap(),
+        "WNNC_NET_LANMAN"
+    );
+    assert_eq!(link_info.volume_label.unwrap(), "USBKEY");
+    assert_eq!(link_info.local_base_path.unwrap(), "E:\\doc.txt");
+    assert_eq!(link_info.network_share.unwrap(), "\\\\srv\\share");
+    assert_eq!(link_info.network_device_name.unwrap(), "Z:");
+}
+
+#[cfg(test)]
+#[test]
+fn target_mft_reference() {
+    use jumplist_parser::Flatten;
+
+    let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let entries = &parsed.destlist().unwrap().entries;
+    let mov = entries
+        .iter()
+        .find(|e| e.path.ends_with("sample_1920x1080.mov"))
+        .unwrap();
+    assert_eq!(mov.target_mft_entry, Some(326377));
+    assert_eq!(mov.target_mft_sequence, Some(1));
+    let video = entries
+        .iter()
+        .find(|e| e.path.ends_with("sample_640x360.3gp"))
+        .unwrap();
+    assert_eq!(video.target_mft_entry, Some(325631));
+    assert_eq!(video.target_mft_sequence, Some(3));
+    let normalized = parsed.flatten();
+    let mov = normalized
+        .iter()
+        .find(|n| n["target_full_path"].ends_with("sample_1920x1080.mov"))
+        .unwrap();
+    assert_eq!(mov["target_mft_entry"], "326377");
+    assert_eq!(mov["target_mft_sequence"], "1");
+
+    // URLs have no file reference, the fields are omitted
+    let path = "samples/win11/AutomaticDestinations/a52b0784bd667468.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let json = serde_json::to_value(&parsed).unwrap();
+    for (entry, normalized) in parsed
+        .destlist()
+        .unwrap()
+        .entries
+        .iter()
+        .zip(parsed.flatten())
+    {
+        let is_file = normalized["target_type"] == "file";
+        assert_eq!(entry.target_mft_entry.is_some(), is_file);
+        assert_eq!(normalized.contains_key("target_mft_entry"), is_file);
+    }
+    let entries = json["data"]["entries"].as_array().unwrap();
+    assert!(entries.iter().any(|e| e.get("target_mft_entry").is_none()));
+
+    let path = "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    for normalized in parsed.flatten() {
+        assert_eq!(normalized["target_mft_entry"], "99219");
+        assert_eq!(normalized["target_mft_sequence"], "2");
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn shell_item_path_fallback() {
+    use jumplist_parser::Flatten;
+    use winparsingtools::traits::Normalize;
+
+    // Control Panel entry, the LNK has no LinkInfo
+    let path = "samples/win10/AutomaticDestinations/7e4dca80246863e3.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let normalized = &parsed.flatten()[0];
+    assert_eq!(normalized["target_path_source"], "shell_items");
+    assert_eq!(
+        normalized["target_full_path"],
+        parsed.destlist().unwrap().entries[0].path
+    );
+    assert_eq!(
+        normalized["target_full_path_resolved"],
+        "[Control Panel]\\5\\[System]"
+    );
+
+    // Photos app URIs
+    let path = "samples/win11/AutomaticDestinations/a52b0784bd667468.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    for entry in &parsed.destlist().unwrap().entries {
+        let normalized = entry.normalize();
+        match entry.path.starts_with("ms-photos:") {
+            true => {
+                assert_eq!(normalized["target_path_source"], "shell_items");
+                assert_eq!(normalized["target_full_path"], entry.path);
+            }
+            false => assert_eq!(normalized["target_path_source"], "link_info"),
+        }
+    }
+
+    // The shell items describe the same target as the LinkInfo
+    for path in glob("samples/win1*/AutomaticDestinations/*").unwrap() {
+        let parsed = JumplistParser::from_path(path.unwrap().to_str().unwrap()).unwrap();
+        for entry in &parsed.destlist().unwrap().entries {
+            let normalized = entry.normalize();
+            let target_full_path = &normalized["target_full_path"];
+            let details = entry.lnk_details.as_ref().unwrap();
+            let shell_item_path = details.shell_item_path.as_deref().unwrap();
+            // Known folders (e.g. `::{This PC}\::{Desktop}`) have no drive path
+            if normalized["target_path_source"] == "link_info"
+                && target_full_path.is_ascii()
+                && !shell_item_path.contains("::{")
+            {
+                assert!(shell_item_path.eq_ignore_ascii_case(target_full_path));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn custom_pinned_items_block() {
+    use jumplist_parser::custom_destinations::{CategoryType, CustomDestinations};
+    use std::io::Cursor;
+
+    let path = "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let original = CustomDestinations::from_path(path).unwrap();
+    let entry = original
+        .entries
+        .iter()
+        .flat_map(|c| c.entries.iter().flatten())
+        .next()
+        .unwrap();
+    let start = entry.file_offset as usize;
+    let entry_bytes = &data[start..start + entry.entry_size as usize];
+
+    // A count of 2 would be read as a task category, the LNK CLSID tells them apart
+    for (count, footer) in [(1u32, true), (2, false)] {
+        let mut file = data[..12].to_vec();
+        file.extend_from_slice(&count.to_le_bytes());
+        for _ in 0..count {
+            file.extend_from_slice(entry_bytes);
+        }
+        if footer {
+            file.extend_from_slice(&0xBABF_FBABu32.to_le_bytes());
+        }
+        file.extend_from_slice(&data[12..]);
+
+        let parsed = CustomDestinations::from_reader(&mut Cursor::new(&file)).unwrap();
+        assert!(parsed.is_complete());
+        assert!(parsed.warnings.is_empty());
+        let pinned = &parsed.entries[0];
+        assert_eq!(pinned.r#type, CategoryType::Pinned);
+        assert_eq!(pinned.file_offset, 12);
+        assert_eq!(pinned.entries.as_ref().unwrap().len(), count as usize);
+        assert_eq!(parsed.entries.len(), original.entries.len() + 1);
+        for (category, expected) in parsed.entries[1..].iter().zip(&original.entries) {
+            assert_eq!(category.r#type, expected.r#type);
+            assert_eq!(category.num_of_entries, expected.num_of_entries);
+        }
+
+        let jumplist = JumplistParser::from_reader(
+            &mut Cursor::new(file),
+            jumplist_parser::JumplistType::Custom,
+        )
+        .unwrap();
+        let first = jumplist.entries().next().unwrap();
+        assert_eq!(first.category(), Some("pinned"));
+        assert_eq!(first.pinned(), Some(true));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn truncated_custom_destinations() {
+    use jumplist_parser::custom_destinations::CustomDestinations;
+    use std::io::Cursor;
+
+    let path = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let complete = CustomDestinations::from_path(path).unwrap();
+    assert!(!complete.truncated);
+    assert!(complete.is_complete());
+    let entry_count = |parsed: &CustomDestinations| {
+        parsed
+            .entries
+            .iter()
+            .flat_map(|c| c.entries.iter().flatten())
+            .count()
+    };
+
+    // Categories at 12, 3298 and 7650, 6 entries in total, the last footer at 11452
+    for (len, categories, entries) in [
+        (11, None, 0),
+        (12, Some(0), 0),
+        (30, Some(0), 0),
+        (50, Some(1), 0),
+        (2167, Some(1), 1),
+        (3000, Some(1), 1),
+        (3302, Some(1), 2),
+        (7650, Some(2), 4),
+        (11454, Some(3), 6),
+    ] {
+        let result = CustomDestinations::from_reader(&mut Cursor::new(&data[..len]));
+        let (parsed, categories) = match categories {
+            None => {
+                // Not even the header
+                assert!(result.is_err());
+                continue;
+            }
+            Some(categories) => (result.unwrap(), categories),
+        };
+        assert!(parsed.truncated, "{}", len);
+        assert!(!parsed.is_complete());
+        assert!(!parsed.warnings.is_empty());
+        assert_eq!(parsed.entries.len(), categories, "{}", len);
+        assert_eq!(entry_count(&parsed), entries, "{}", len);
+        for (category, expected) in parsed.entries.iter().zip(&complete.entries) {
+            assert_eq!(category.file_offset, expected.file_offset);
+        }
+    }
+
+    // A corrupt LNK is dropped, the following entries and categories are kept
+    let mut corrupt = data.clone();
+    // The LNK header of the first entry (GUID at 46)
+    corrupt[62..66].copy_from_slice(&[0xFF; 4]);
+    let parsed = CustomDestinations::from_reader(&mut Cursor::new(corrupt)).unwrap();
+    assert!(!parsed.truncated);
+    assert_eq!(parsed.entries.len(), 3);
+    assert_eq!(entry_count(&parsed), 5);
+    assert!(parsed.warnings[0].starts_with("Dropped the Custom entry at offset 46"));
+    assert!(!parsed.is_complete());
+}
+
+#[cfg(test)]
+#[test]
+fn standalone_destlist_stream() {
+    use jumplist_parser::{destlist::DestList, JumplistData};
+    use std::io::Read;
+
+    let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let mut compound = cfb::open(path).unwrap();
+    let mut stream = vec![];
+    compound
+        .open_stream("DestList")
+        .unwrap()
+        .read_to_end(&mut stream)
+        .unwrap();
+    let exported = std::env::temp_dir().join("jumplist_parser_standalone_destlist");
+    std::fs::write(&exported, &stream).unwrap();
+
+    let mut destlist = DestList::from_path(exported.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&exported);
+    let expected = match JumplistParser::from_path(path).unwrap().data {
+        JumplistData::DestList(destlist) => destlist,
+        _ => unreachable!(),
+    };
+    assert_eq!(destlist.header, expected.header);
+    assert_eq!(destlist.entries.len(), expected.entries.len());
+    assert!(destlist.entries.iter().all(|e| e.lnk.is_none()));
+
+    // Attaching the LNK streams gives the same result as parsing the whole Jumplist, the
+    // AppID of the fingerprints comes from the file name
+    destlist.update_fingerprints(Some("4cb9c5750d51c07f"));
+    destlist.attach_lnks(|entry_number| {
+        let mut lnk = vec![];
+        compound
+            .open_stream(format!("{:x}", entry_number))
+            .ok()?
+            .read_to_end(&mut lnk)
+            .ok()?;
+        Some(lnk)
+    });
+    assert_eq!(destlist, expected);
+
+    assert!(DestList::from_path("samples/does_not_exist").is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn cfb_info() {
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let info = parsed.cfb_info.as_ref().unwrap();
+    assert_eq!(info.root_clsid, "00000000-0000-0000-0000-000000000000");
+    assert_eq!(info.numbered_streams, 5);
+    assert_eq!(info.other_streams, 1);
+    assert_eq!(info.streams.len(), 6);
+    assert_eq!(info.unexpected_streams().count(), 0);
+    let destlist = info.streams.iter().find(|s| s.name == "DestList").unwrap();
+    assert!(!destlist.is_numbered());
+    assert!(destlist.size > 0);
+    assert!(info.streams.iter().all(|s| s.created.is_zero()));
+
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(json["cfb_info"]["streams"][0]["name"], "1");
+    assert!(json["cfb_info"]["streams"][0]["modified"].is_null());
+
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    assert!(custom.cfb_info.is_none());
+    assert!(serde_json::to_value(&custom)
+        .unwrap()
+        .get("cfb_info")
+        .is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn extra_streams() {
+    use jumplist_parser::{property_store::PropertyValue, JumplistType, ParseOptions};
+    use std::io::{Cursor, Read, Write};
+
+    // Known streams only
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert!(parsed.extra_streams.is_empty());
+
+    // An empty property store
+    let parsed =
+        JumplistParser::from_path("samples/other/5f7b5f1e01b83767.automaticDestinations-ms")
+            .unwrap();
+    assert_eq!(parsed.extra_streams.len(), 1);
+    assert_eq!(parsed.extra_streams[0].name, "DestListPropertyStore");
+    assert_eq!(parsed.extra_streams[0].size, 4);
+    assert!(parsed.extra_streams[0].preview.is_none());
+
+    // A copy of a Jumplist with a property store and an unknown stream added
+    let mut source =
+        cfb::open("samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms")
+            .unwrap();
+    let mut compound = cfb::CompoundFile::create(Cursor::new(vec![])).unwrap();
+    let names: Vec<String> = source
+        .walk()
+        .filter(|e| e.is_stream())
+        .map(|e| e.name().to_string())
+        .collect();
+    for name in names {
+        let mut data = vec![];
+        source
+            .open_stream(&name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        compound
+            .create_stream(&name)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+    }
+
+    let utf16 = |s: &str| -> Vec<u8> {
+        s.encode_utf16()
+            .chain([0])
+            .flat_map(|c| c.to_le_bytes())
+            .collect()
+    };
+    // A numeric property set with a VT_LPWSTR (4) and a VT_UI4 (5) value
+    let mut values = vec![];
+    let text = utf16("notepad");
+    values.extend((9 + 8 + text.len() as u32).to_le_bytes());
+    values.extend(4u32.to_le_bytes());
+    values.push(0);
+    values.extend([0x1F, 0, 0, 0]);
+    values.extend(((text.len() / 2) as u32).to_le_bytes());
+    values.extend(&text);
+    values.extend((9 + 8u32).to_le_bytes());
+    values.extend(5u32.to_le_bytes());
+    values.push(0);
+    values.extend([0x13, 0, 0, 0]);
+    values.extend(42u32.to_le_bytes());
+    values.extend(0u32.to_le_bytes());
+    let mut store = vec![];
+    store.extend((24 + values.len() as u32).to_le_bytes());
+    store.extend(b"1SPS");
+    // B725F130-47EF-101A-A5F1-02608C9EEBAC
+    store.extend([
+        0x30, 0xF1, 0x25, 0xB7, 0xEF, 0x47, 0x1A, 0x10, 0xA5, 0xF1, 0x02, 0x60, 0x8C, 0x9E, 0xEB,
+        0xAC,
+    ]);
+    store.extend(&values);
+    store.extend(0u32.to_le_bytes());
+    compound
+        .create_stream("DestListPropertyStore")
+        .unwrap()
+        .write_all(&store)
+        .unwrap();
+    compound
+        .create_stream("Stash")
+        .unwrap()
+        .write_all(b"MZ\x90\x00hidden")
+        .unwrap();
+    compound.flush().unwrap();
+    let mut data = compound.into_inner();
+    data.set_position(0);
+
+    let options = ParseOptions {
+        extra_stream_preview: 4,
+        ..Default::default()
+    };
+    let parsed =
+        JumplistParser::from_reader_with_options(&mut data, JumplistType::Automatic, &options)
+            .unwrap();
+    assert_eq!(parsed.entries().count(), 5);
+    let mut extra = parsed.extra_streams.clone();
+    extra.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(extra.len(), 2);
+
+    let store = extra[0].property_store.as_ref().unwrap();
+    assert_eq!(store.storages.len(), 1);
+    let storage = &store.storages[0];
+    assert_eq!(
+        storage.format_id.to_string(),
+        "B725F130-47EF-101A-A5F1-02608C9EEBAC"
+    );
+    assert_eq!(storage.properties.len(), 2);
+    assert_eq!(storage.properties[0].id, Some(4));
+    assert_eq!(
+        storage.properties[0].value,
+        PropertyValue::String("notepad".to_string())
+    );
+    assert_eq!(storage.properties[1].value, PropertyValue::UInt(42));
+
+    assert_eq!(extra[1].name, "Stash");
+    assert_eq!(extra[1].size, 10);
+    assert_eq!(extra[1].preview.as_deref(), Some("4d5a9000"));
+    assert!(extra[1].property_store.is_none());
+    assert_eq!(
+        parsed
+            .cfb_info
+            .as_ref()
+            .unwrap()
+            .unexpected_streams()
+            .count(),
+        2
+    );
+
+    let json = serde_json::to_value(&parsed).unwrap();
+    let value = &json["extra_streams"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "DestListPropertyStore")
+        .unwrap()["property_store"]["storages"][0]["properties"][0]["value"];
+    assert_eq!(value["type"], "string");
+    assert_eq!(value["value"], "notepad");
+}
+
+#[cfg(test)]
+#[test]
+fn category_id() {
+    use jumplist_parser::{custom_destinations::CategoryId, Flatten};
+
+    for (raw, expected, text) in [
+        (1, CategoryId::Frequent, "frequent"),
+        (2, CategoryId::Recent, "recent"),
+        (-1, CategoryId::None, "none"),
+        (5, CategoryId::Unknown(5), "unknown(0x00000005)"),
+        (-2, CategoryId::Unknown(-2), "unknown(0xFFFFFFFE)"),
+    ] {
+        let id = CategoryId::from(raw);
+        assert_eq!(id, expected);
+        assert_eq!(id.to_string(), text);
+        assert_eq!(text.parse::<CategoryId>().unwrap(), id);
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", text));
+        assert_eq!(serde_json::from_str::<CategoryId>(&json).unwrap(), id);
+    }
+    assert_eq!("pinned".parse::<CategoryId>().unwrap(), CategoryId::Pinned);
+    // Written by older versions
+    assert_eq!(
+        "0005".parse::<CategoryId>().unwrap(),
+        CategoryId::Unknown(5)
+    );
+    assert!("unknown(0x5".parse::<CategoryId>().is_err());
+    assert!("sometimes".parse::<CategoryId>().is_err());
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    let flat = parsed.flatten();
+    assert!(flat.iter().all(|e| e.contains_key("entry_category_id")));
+    for (entry, normalized) in parsed.entries().zip(&flat) {
+        let expected = match entry.category() {
+            Some("frequent") => "frequent",
+            Some("recent") => "recent",
+            Some("pinned") => "pinned",
+            _ => "",
+        };
+        assert_eq!(normalized["entry_category_id"], expected);
+    }
+    let custom = parsed.custom_destinations().unwrap();
+    assert_eq!(
+        custom
+            .flatten()
+            .iter()
+            .map(|e| e["entry_category_id"].clone())
+            .collect::<Vec<_>>(),
+        flat.iter()
+            .map(|e| e["entry_category_id"].clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn custom_category_names_and_empty_categories() {
+    use jumplist_parser::custom_destinations::CustomDestinations;
+    use std::io::Cursor;
+
+    let custom_category = |name: &str| {
+        let units: Vec<u16> = name.encode_utf16().collect();
+        let mut data = vec![0, 0, 0, 0];
+        data.extend((units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|u| u.to_le_bytes()));
+        // No entries, then the footer
+        data.extend(0u32.to_le_bytes());
+        data.extend(0xBABF_FBABu32.to_le_bytes());
+        data
+    };
+    let mut data = vec![];
+    data.extend(2u32.to_le_bytes());
+    data.extend(3u32.to_le_bytes());
+    data.extend(0u32.to_le_bytes());
+    data.extend(custom_category("📌 Pinned 🎵"));
+    data.extend(custom_category(""));
+    data.extend(custom_category("Docs"));
+
+    let parsed = CustomDestinations::from_reader(&mut Cursor::new(data.clone())).unwrap();
+    assert!(parsed.is_complete(), "{:?}", parsed.warnings);
+    assert!(parsed.warnings.is_empty());
+    let names: Vec<_> = parsed
+        .entries
+        .iter()
+        .map(|c| c.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, ["📌 Pinned 🎵", "", "Docs"]);
+    assert!(parsed
+        .entries
+        .iter()
+        .all(|c| c.num_of_entries == Some(0) && c.entries.as_ref().unwrap().is_empty()));
+
+    // A name cut by the end of the file is an error, not a shorter name
+    let parsed = CustomDestinations::from_reader(&mut Cursor::new(&data[..20])).unwrap();
+    assert!(parsed.truncated);
+    assert!(parsed.entries.is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn carve_custom_destinations() {
+    use jumplist_parser::{
+        custom_destinations::CustomDestinations, errors::JumplistParserError, JumplistData,
+        JumplistType, ParseOptions,
+    };
+    use std::io::Cursor;
+
+    let path = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let structured = CustomDestinations::from_path(path).unwrap();
+    let carved = CustomDestinations::carve_from_reader(&mut Cursor::new(&data)).unwrap();
+    assert_eq!(carved.header, structured.header);
+    assert_eq!(carved.entries.len(), 1);
+    assert!(carved.entries[0].recovered);
+    assert!(!carved.is_complete());
+    // Carved entries start at the LNK, after the GUID of the structured entries
+    let offsets = |custom: &CustomDestinations, shift: u64| -> Vec<u64> {
+        custom
+            .entries
+            .iter()
+            .flat_map(|c| c.entries.iter().flatten())
+            .map(|e| e.file_offset + shift)
+            .collect()
+    };
+    let carved_offsets = offsets(&carved, 0);
+    assert_eq!(carved_offsets[..6], offsets(&structured, 16)[..]);
+    // The file has leftover LNKs after the end of its last category (at 11456)
+    assert_eq!(carved_offsets.len(), 8);
+    assert!(carved_offsets[6..].iter().all(|offset| *offset > 11456));
+
+    // An unknown category type stops the structured parser at the first category
+    let mut corrupt = data.clone();
+    corrupt[12..16].copy_from_slice(&7u32.to_le_bytes());
+    let parsed =
+        JumplistParser::from_reader(&mut Cursor::new(corrupt.clone()), JumplistType::Custom)
+            .unwrap();
+    let custom = match &parsed.data {
+        JumplistData::CustomDestinations(custom) => custom,
+        _ => unreachable!(),
+    };
+    assert!(custom.entries[0].recovered);
+    assert_eq!(parsed.entries().count(), 8);
+    assert!(parsed.warnings().len() >= 2);
+    assert!(!parsed.is_complete());
+
+    let options = ParseOptions {
+        disable_carving: true,
+        ..Default::default()
+    };
+    let strict = JumplistParser::from_reader_with_options(
+        &mut Cursor::new(corrupt),
+        JumplistType::Custom,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(strict.entries().count(), 0);
+    assert!(strict.custom_destinations().unwrap().truncated);
+
+    // Not even a header
+    assert!(JumplistParser::from_reader_with_options(
+        &mut Cursor::new(data[..8].to_vec()),
+        JumplistType::Custom,
+        &options
+    )
+    .is_err());
+    assert!(matches!(
+        JumplistParser::from_reader(&mut Cursor::new(data[..8].to_vec()), JumplistType::Custom),
+        Err(JumplistParserError::EmptyOrTruncatedFile { size: 8, .. })
+    ));
+
+    // Complete files are never carved
+    let parsed = JumplistParser::from_path(path).unwrap();
+    assert!(parsed
+        .custom_destinations()
+        .unwrap()
+        .entries
+        .iter()
+        .all(|c| !c.recovered));
+}
+
+#[cfg(test)]
+#[test]
+fn parse_mode() {
+    use jumplist_parser::{destlist::DestList, JumplistType, ParseMode, ParseOptions};
+    use std::{convert::TryInto, io::Cursor};
+
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    // Clean files parse the same in both modes
+    for path in glob::glob("samples/win1*/*/*").unwrap().flatten() {
+        let path = path.to_str().unwrap();
+        let lenient = JumplistParser::from_path(path).unwrap();
+        assert!(lenient.warnings().is_empty(), "{}", path);
+        let parsed = JumplistParser::from_path_with_options(path, &strict).unwrap();
+        assert_eq!(parsed.entries().count(), lenient.entries().count());
+    }
+
+    // A category footer that isn't 0xBABFFBAB
+    let path = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let mut corrupt = data.clone();
+    corrupt[3294..3298].copy_from_slice(&[0; 4]);
+    let lenient =
+        JumplistParser::from_reader(&mut Cursor::new(corrupt.clone()), JumplistType::Custom)
+            .unwrap();
+    assert_eq!(lenient.entries().count(), 6);
+    assert!(lenient.warnings()[0].contains("footer"));
+    let err = JumplistParser::from_reader_with_options(
+        &mut Cursor::new(corrupt),
+        JumplistType::Custom,
+        &strict,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Strict mode"));
+
+    // Truncated files are neither recovered nor carved
+    assert!(JumplistParser::from_reader_with_options(
+        &mut Cursor::new(data[..3000].to_vec()),
+        JumplistType::Custom,
+        &strict
+    )
+    .is_err());
+
+    // A DestList header that declares more entries than the stream holds
+    let mut destlist = std::fs::read("samples/other/DestList").unwrap();
+    let declared = u32::from_le_bytes(destlist[4..8].try_into().unwrap());
+    destlist[4..8].copy_from_slice(&(declared + 1).to_le_bytes());
+    let lenient = DestList::parse_stream(&mut Cursor::new(&destlist)).unwrap();
+    assert_eq!(lenient.warnings.len(), 1);
+    assert!(DestList::parse_stream_with_options(&mut Cursor::new(&destlist), &strict).is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parse_many_matches_sequential() {
+    use jumplist_parser::{errors::JumplistParserError, ParseOptions};
+    use std::sync::Mutex;
+
+    fn assert_send<T: Send>() {}
+    assert_send::<JumplistParser>();
+    assert_send::<JumplistParserError>();
+
+    let paths: Vec<_> = glob("samples/**/*")
+        .unwrap()
+        .flatten()
+        .filter(|p| p.is_file())
+        .collect();
+    let options = ParseOptions::default();
+    let to_json = |result: &Result<JumplistParser, JumplistParserError>| match result {
+        Ok(parsed) => serde_json::to_string(parsed).unwrap(),
+        Err(e) => e.to_string(),
+    };
+    let sequential: Vec<String> = paths
+        .iter()
+        .map(|p| {
+            to_json(&JumplistParser::from_path_with_options(
+                p.to_str().unwrap(),
+                &options,
+            ))
+        })
+        .collect();
+
+    let parallel = JumplistParser::parse_many(&paths, &options);
+    assert_eq!(
+        parallel.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        paths.iter().collect::<Vec<_>>()
+    );
+    let parallel: Vec<String> = parallel.iter().map(|(_, result)| to_json(result)).collect();
+    assert_eq!(parallel, sequential);
+
+    let streamed = Mutex::new(vec![]);
+    JumplistParser::parse_many_with(&paths, &options, |path, result| {
+        streamed
+            .lock()
+            .unwrap()
+            .push((path.to_path_buf(), to_json(&result)));
+    });
+    let mut streamed = streamed.into_inner().unwrap();
+    streamed.sort();
+    let mut expected: Vec<_> = paths.iter().cloned().zip(sequential).collect();
+    expected.sort();
+    assert_eq!(streamed, expected);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_matches_buffered_reads() {
+    use jumplist_parser::ParseOptions;
+
+    let options = ParseOptions::default();
+    for path in glob("samples/**/*")
+        .unwrap()
+        .flatten()
+        .filter(|p| p.is_file())
+    {
+        let path = path.to_str().unwrap();
+        let to_json = |result: Result<JumplistParser, _>| match result {
+            Ok(parsed) => serde_json::to_string(&parsed).unwrap(),
+            Err(e) => format!("{}", e),
+        };
+        assert_eq!(
+            to_json(JumplistParser::from_path_mmap(path, &options)),
+            to_json(JumplistParser::from_path_with_options(path, &options)),
+            "{}",
+            path
+        );
+    }
+    // Empty files can't be mapped and go through the regular path
+    let empty = std::env::temp_dir().join("jumplist_parser_mmap.customDestinations-ms");
+    std::fs::write(&empty, []).unwrap();
+    let result = JumplistParser::from_path_mmap(empty.to_str().unwrap(), &options);
+    let _ = std::fs::remove_file(&empty);
+    match result {
+        Err(jumplist_parser::errors::JumplistParserError::EmptyOrTruncatedFile { path, size }) => {
+            assert_eq!(path, empty.to_str().unwrap());
+            assert_eq!(size, 0);
+        }
+        other => panic!("{:?}", other.map(|p| p.entry_count())),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn search_entries() {
+    use jumplist_parser::Flatten;
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let hits = parsed.search("SAMPLE_640X360", true);
+    assert!(!hits.is_empty());
+    assert!(parsed.search("SAMPLE_640X360", false).is_empty());
+    assert_eq!(parsed.search("sample_640x360", false), hits);
+    let rows = parsed.flatten();
+    for hit in &hits {
+        assert_eq!(rows[hit.index][&hit.field], hit.value);
+        assert!(hit.value.to_lowercase().contains("sample_640x360"));
+        assert!(hit.entry_number.is_some() && hit.category.is_none());
+    }
+    assert!(hits.iter().any(|hit| hit.field == "target_full_path"));
+    // One hit per matching field, by entry and then by field name
+    let keys: Vec<_> = hits
+        .iter()
+        .map(|hit| (hit.index, hit.field.clone()))
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(keys, sorted);
+    assert!(parsed.search_by(|_| false).is_empty());
+
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    )
+    .unwrap();
+    let everything = custom.search_by(|_| true);
+    assert_eq!(
+        everything.iter().map(|hit| hit.index).max(),
+        Some(custom.entry_count() - 1)
+    );
+    assert!(everything
+        .iter()
+        .all(|hit| hit.category.is_some() && hit.entry_number.is_none()));
+    assert!(everything
+        .iter()
+        .all(|hit| hit.field != "entry_offset" && !hit.value.is_empty()));
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_integrity() {
+    use jumplist_parser::{JumplistData, JumplistType, ParseMode, ParseOptions};
+    use std::io::{Cursor, Read, Write};
+
+    let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let destlist = match JumplistParser::from_path(path).unwrap().data {
+        JumplistData::DestList(destlist) => destlist,
+        _ => panic!("expected a DestList"),
+    };
+    assert!(destlist.duplicate_entry_numbers.is_empty());
+    assert!(destlist.missing_lnk_streams.is_empty());
+    assert!(destlist.unexpected_lnk_streams.is_empty());
+
+    // A copy where the second entry takes the number of the first one, the LNK stream of
+    // the third entry is deleted and a stream after the last entry number is added
+    let kept = &destlist.entries[0];
+    let renumbered = &destlist.entries[1];
+    let deleted = destlist.entries[2].entry_number;
+    let forged = destlist.header.last_entry_number + 1;
+    let mut source = cfb::open(path).unwrap();
+    let mut compound = cfb::CompoundFile::create(Cursor::new(vec![])).unwrap();
+    let names: Vec<String> = source
+        .walk()
+        .filter(|e| e.is_stream())
+        .map(|e| e.name().to_string())
+        .collect();
+    for name in names {
+        let mut data = vec![];
+        source
+            .open_stream(&name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        if name == format!("{:x}", deleted) {
+            continue;
+        }
+        if name == "DestList" {
+            // The entry number is 88 bytes into the entry
+            let offset = renumbered.stream_offset as usize + 88;
+            data[offset..offset + 4].copy_from_slice(&kept.entry_number.to_le_bytes());
+        }
+        if name == format!("{:x}", kept.entry_number) {
+            compound
+                .create_stream(format!("{:x}", forged))
+                .unwrap()
+                .write_all(&data)
+                .unwrap();
+        }
+        compound
+            .create_stream(&name)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+    }
+    compound.flush().unwrap();
+    let data = compound.into_inner().into_inner();
+
+    let parsed =
+        JumplistParser::from_reader(&mut Cursor::new(data.clone()), JumplistType::Automatic)
+            .unwrap();
+    let tampered = match &parsed.data {
+        JumplistData::DestList(destlist) => destlist,
+        _ => panic!("expected a DestList"),
+    };
+    assert_eq!(tampered.duplicate_entry_numbers, vec![kept.entry_number]);
+    assert_eq!(tampered.missing_lnk_streams, vec![deleted]);
+    assert_eq!(tampered.unexpected_lnk_streams, vec![forged]);
+    assert_eq!(parsed.warnings().len(), 3);
+    // Both entries sharing the number get the same LNK entry
+    let lnks: Vec<_> = tampered
+        .entries
+        .iter()
+        .filter(|e| e.entry_number == kept.entry_number)
+        .map(|e| e.lnk_header.clone())
+        .collect();
+    assert_eq!(lnks.len(), 2);
+    assert!(lnks[0].is_some() && lnks[0] == lnks[1]);
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(
+        json["data"]["duplicate_entry_numbers"],
+        serde_json::json!([kept.entry_number])
+    );
+
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    assert!(JumplistParser::from_reader_with_options(
+        &mut Cursor::new(data),
+        JumplistType::Automatic,
+        &strict
+    )
+    .is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn detect_anomalies() {
+    use jumplist_parser::{anomaly::AnomalyKind, ParseOptions};
+    use std::io::{Cursor, Read, Write};
+
+    let path = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    assert!(JumplistParser::from_path(path)
+        .unwrap()
+        .anomalies
+        .is_empty());
+    let options = ParseOptions {
+        detect_anomalies: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(path, &options).unwrap();
+    assert_eq!(parsed.anomalies, parsed.detect_anomalies(None));
+
+    // A copy with more deleted entries, an interaction time at the epoch, one in 2100 and a
+    // control character in a hostname
+    let destlist = parsed.destlist().unwrap();
+    let mut source = cfb::open(path).unwrap();
+    let mut compound = cfb::CompoundFile::create(Cursor::new(vec![])).unwrap();
+    let names: Vec<String> = source
+        .walk()
+        .filter(|e| e.is_stream())
+        .map(|e| e.name().to_string())
+        .collect();
+    for name in names {
+        let mut data = vec![];
+        source
+            .open_stream(&name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        if name == "DestList" {
+            let last_entry_number = destlist.header.last_entry_number + 200;
+            data[16..20].copy_from_slice(&last_entry_number.to_le_bytes());
+            // The hostname is 72 bytes into the entry and the interaction time 100 bytes
+            let entries = &destlist.entries;
+            let mtime = entries[0].stream_offset as usize + 100;
+            data[mtime..mtime + 8].copy_from_slice(&0u64.to_le_bytes());
+            let mtime = entries[1].stream_offset as usize + 100;
+            data[mtime..mtime + 8].copy_from_slice(&159_271_200_000_000_000u64.to_le_bytes());
+            data[entries[2].stream_offset as usize + 73] = 0x07;
+        }
+        compound
+            .create_stream(&name)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+    }
+    compound.flush().unwrap();
+    let tampered = std::env::temp_dir()
+        .join("jumplist_parser_anomalies.f01b4d95cf55d32a.automaticDestinations-ms");
+    std::fs::write(&tampered, compound.into_inner().into_inner()).unwrap();
+
+    let parsed =
+        JumplistParser::from_path_with_options(tampered.to_str().unwrap(), &options).unwrap();
+    let find = |kind: AnomalyKind| {
+        parsed
+            .anomalies
+            .iter()
+            .filter(|a| a.kind == kind)
+            .map(|a| (a.index, a.entry_number))
+            .collect::<Vec<_>>()
+    };
+    let entries = &destlist.entries;
+    assert_eq!(find(AnomalyKind::EntryNumberGap), vec![(None, None)]);
+    assert_eq!(
+        find(AnomalyKind::EpochTimestamp),
+        vec![(Some(0), Some(entries[0].entry_number))]
+    );
+    assert_eq!(
+        find(AnomalyKind::InteractionAfterFileModified),
+        vec![(Some(1), Some(entries[1].entry_number))]
+    );
+    assert_eq!(
+        find(AnomalyKind::NonPrintableHostname),
+        vec![(Some(2), Some(entries[2].entry_number))]
+    );
+    // The file modification time is only known when parsing from a path
+    let data = std::fs::read(&tampered).unwrap();
+    let from_bytes = JumplistParser::from_bytes_with_options(&data, None, &options).unwrap();
+    assert!(from_bytes
+        .anomalies
+        .iter()
+        .all(|a| a.kind != AnomalyKind::InteractionAfterFileModified));
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert!(json["anomalies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|a| a["kind"] == "epoch_timestamp" && a["description"].is_string()));
+    std::fs::remove_file(&tampered).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn user_column() {
+    use jumplist_parser::{
+        output::{csv_columns, csv_rows, CsvOptions},
+        user::user_from_path,
+        Flatten,
+    };
+
+    assert_eq!(
+        user_from_path(
+            r"E:\triage\C\Users\alice\AppData\Roaming\Microsoft\Windows\Recent\AutomaticDestinations\x.automaticDestinations-ms"
+        ),
+        Some("alice".to_string())
+    );
+    assert_eq!(
+        user_from_path(r"C:\Documents and Settings\bob\Recent\x.automaticDestinations-ms"),
+        Some("bob".to_string())
+    );
+    // The last profile directory wins, the file itself is never a user
+    assert_eq!(
+        user_from_path("/cases/users/case1/mnt/c/Users/carol/x.customDestinations-ms"),
+        Some("carol".to_string())
+    );
+    assert_eq!(user_from_path("/tmp/Users/x.customDestinations-ms"), None);
+
+    let sample = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    assert_eq!(JumplistParser::from_path(sample).unwrap().user, None);
+    let dir = std::env::temp_dir().join("jumplist_parser_user/Users/dave/Recent");
+    std::fs::create_dir_all(&dir).unwrap();
+    let copy = dir.join("5d696d521de238c3.customDestinations-ms");
+    std::fs::copy(sample, &copy).unwrap();
+    let parsed = JumplistParser::from_path(copy.to_str().unwrap()).unwrap();
+    assert_eq!(parsed.user.as_deref(), Some("dave"));
+    assert_eq!(serde_json::to_value(&parsed).unwrap()["user"], "dave");
+    assert!(parsed
+        .flatten()
+        .iter()
+        .all(|e| e["jumplist_user"] == "dave"));
+    let options = CsvOptions::default();
+    let column = csv_columns(&options)
+        .iter()
+        .position(|c| *c == "jumplist_user")
+        .unwrap();
+    assert!(csv_rows(&parsed, &options)
+        .iter()
+        .all(|row| row[column] == "dave"));
+    std::fs::remove_dir_all(std::env::temp_dir().join("jumplist_parser_user")).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn table_output() {
+    use jumplist_parser::table::{render_table, shorten_path, table_rows, TableOptions};
+
+    let path = r"C:\Users\u0041\Desktop\sim\files\video\mov\sample_1920x1080.mov";
+    let shortened = shorten_path(path, 30);
+    assert_eq!(shortened.chars().count(), 30);
+    assert!(shortened.ends_with(r"\sample_1920x1080.mov"));
+    assert!(shortened.starts_with(r"C:\Users"));
+    // A file name longer than the width keeps its end
+    assert_eq!(shorten_path("sample_1920x1080.mov", 10), "…x1080.mov");
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let rows = table_rows(&parsed, &TableOptions::default().columns);
+    assert_eq!(rows.len(), parsed.entry_count());
+    assert_eq!(rows[0][1], path);
+    assert_eq!(
+        rows[0][2],
+        parsed
+            .entries()
+            .next()
+            .unwrap()
+            .interaction_time()
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(rows[0][3], "false");
+
+    let wide = render_table(&parsed, &TableOptions::default());
+    let lines: Vec<&str> = wide.lines().collect();
+    assert!(lines[0].contains("4cb9c5750d51c07f") && lines[0].ends_with("5 entries"));
+    assert!(lines[1].starts_with("app_name"));
+    assert!(lines[2].contains(path));
+    // Every column starts at the same position on every line
+    let start = lines[1].find("target_full_path").unwrap();
+    assert!(lines[2..7]
+        .iter()
+        .all(|l| l[start..].starts_with(r"C:\Users")));
+    assert!(!wide.contains('\x1b'));
+
+    let options = TableOptions {
+        columns: vec!["target_full_path".to_string(), "pinned".to_string()],
+        width: Some(40),
+        color: true,
+    };
+    let narrow = render_table(&parsed, &options);
+    assert!(narrow.contains("\x1b[1m"));
+    for line in narrow.lines().skip(2).filter(|l| !l.is_empty()) {
+        assert!(line.chars().count() <= 40);
+        assert!(line.contains("…"));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn sort_entries() {
+    use jumplist_parser::sort::{SortField, SortKey};
+
+    for name in SortField::NAMES {
+        assert_eq!(name.parse::<SortField>().unwrap().to_string(), *name);
+    }
+    assert!("size".parse::<SortField>().is_err());
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/5f7b5f1e01b83767.automaticDestinations-ms",
+    )
+    .unwrap();
+    let numbers: Vec<u32> = parsed
+        .sorted_entries(SortField::EntryNumber, false)
+        .iter()
+        .map(|e| e.entry_number().unwrap())
+        .collect();
+    assert_eq!(numbers.len(), parsed.entry_count());
+    assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+    let paths: Vec<String> = parsed
+        .sorted_entries(SortField::TargetFullPath, true)
+        .iter()
+        .map(|e| e.target_path().unwrap().to_lowercase())
+        .collect();
+    assert!(paths.windows(2).all(|w| w[0] >= w[1]));
+
+    // Custom entries have no DestList time and come last, ties are ordered by path and number
+    let custom = JumplistParser::from_path(
+        "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+    )
+    .unwrap();
+    let key = |parsed: &JumplistParser, index: usize| {
+        let entry = parsed.entries().nth(index).unwrap();
+        SortKey::new(SortField::DestListMtime, parsed, index, &entry)
+    };
+    for descending in [false, true] {
+        assert!(key(&parsed, 0)
+            .compare(&key(&custom, 0), descending)
+            .is_lt());
+        assert!(key(&custom, 0)
+            .compare(&key(&custom, 1), descending)
+            .is_lt());
+    }
+    assert!(key(&parsed, 0).compare(&key(&parsed, 1), true).is_lt());
+}
+
+#[cfg(test)]
+#[test]
+fn timestamp_timezone() {
+    use jumplist_parser::timestamp::{Timestamp, TimestampFormat};
+    use jumplist_parser::{Flatten, ParseOptions};
+
+    let riyadh = "Asia/Riyadh".parse().unwrap();
+    let ts = |format: TimestampFormat| {
+        Timestamp::new(133_966_062_971_234_567, format.with_timezone(riyadh))
+    };
+    assert_eq!(
+        ts(TimestampFormat::Iso).to_string(),
+        "2025-07-10 10:31:37 +03:00"
+    );
+    assert_eq!(
+        ts(TimestampFormat::IsoSubsecond).to_string(),
+        "2025-07-10 10:31:37.1234567 +03:00"
+    );
+    // The numeric formats don't depend on the timezone
+    assert_eq!(ts(TimestampFormat::Epoch).to_string(), "1752132697");
+    // Serialized in UTC, and read back from both representations
+    assert_eq!(
+        serde_json::to_string(&ts(TimestampFormat::Iso)).unwrap(),
+        "\"2025-07-10T07:31:37Z\""
+    );
+    let back: Timestamp = serde_json::from_str("\"2025-07-10 10:31:37 +03:00\"").unwrap();
+    assert_eq!(back.filetime, 133_966_062_970_000_000);
+    assert_eq!(
+        Timestamp::new(0, TimestampFormat::Iso.with_timezone(riyadh)).to_string(),
+        ""
+    );
+
+    let path = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let utc = JumplistParser::from_path(path).unwrap();
+    let options = ParseOptions {
+        ts_format: TimestampFormat::Iso.with_timezone(riyadh),
+        ..Default::default()
+    };
+    let local = JumplistParser::from_path_with_options(path, &options).unwrap();
+    assert_eq!(
+        serde_json::to_value(&utc).unwrap(),
+        serde_json::to_value(&local).unwrap()
+    );
+    for (utc, local) in utc.flatten().iter().zip(local.flatten()) {
+        let mtime = &local["target_modification_time"];
+        assert!(mtime.is_empty() || mtime.ends_with(" +03:00"));
+        assert_eq!(utc["target_full_path"], local["target_full_path"]);
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn compressed_output() {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let path = std::env::temp_dir().join("jumplist_parser_compressed_output.jsonl.gz");
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args(["-p", sample, "--output-format", "jsonl", "--output"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let compressed = std::fs::read(&path).unwrap();
+    assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+    let mut jsonl = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut jsonl)
+        .unwrap();
+    let lines: Vec<&str> = jsonl.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let parsed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    let expected = serde_json::to_value(JumplistParser::from_path(sample).unwrap()).unwrap();
+    assert_eq!(parsed["app_id"], expected["app_id"]);
+    assert_eq!(parsed["data"], expected["data"]);
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn http_sink() {
+    use jumplist_parser::{
+        sink::{HttpSink, HttpSinkOptions, HttpSinkSummary},
+        Flatten,
+    };
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    // A server answering every request with the next status, and returning the requests
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/services/collector/event",
+        listener.local_addr().unwrap()
+    );
+    let statuses = [503, 200, 200, 400];
+    let server = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_lowercase());
+            }
+            let length: usize = headers
+                .iter()
+                .find_map(|h| h.strip_prefix("content-length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+            requests.push((headers, String::from_utf8(body).unwrap()));
+        }
+        requests
+    });
+
+    let spool_path = std::env::temp_dir().join("jumplist_parser_http_sink_spool.jsonl");
+    let _ = std::fs::remove_file(&spool_path);
+    let mut sink = HttpSink::new(HttpSinkOptions {
+        url,
+        auth: Some("token".to_string()),
+        batch_size: 2,
+        hec: true,
+        retries: 1,
+        spool_path: spool_path.clone(),
+        ..Default::default()
+    });
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    for entry in parsed.flatten() {
+        sink.push(entry);
+    }
+    assert_eq!(
+        sink.finish(),
+        HttpSinkSummary {
+            sent_batches: 2,
+            sent_entries: 4,
+            failed_batches: 1,
+            spooled_entries: 1,
+        }
+    );
+
+    let requests = server.join().unwrap();
+    // The first batch is sent again after the 503
+    assert_eq!(requests[0].1, requests[1].1);
+    for (headers, body) in &requests {
+        assert!(headers.contains(&"authorization: splunk token".to_string()));
+        for line in body.lines() {
+            let event: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(event["sourcetype"], "jumplist_parser");
+            assert!(event["event"]["target_full_path"].is_string());
+        }
+    }
+    assert_eq!(requests[1].1.lines().count(), 2);
+    // The entry of the rejected batch is kept in the spool file, without the envelope
+    let spooled = std::fs::read_to_string(&spool_path).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(spooled.trim()).unwrap();
+    assert_eq!(
+        entry["target_full_path"],
+        parsed.flatten()[4]["target_full_path"]
+    );
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_events() {
+    use jumplist_parser::{
+        timestamp::{Timestamp, TimestampFormat},
+        watch::{watch, Baseline, WatchEventKind},
+        ParseOptions,
+    };
+    use std::path::Path;
+    use std::sync::{atomic::AtomicBool, atomic::Ordering, mpsc, Arc};
+    use std::time::{Duration, Instant};
+
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(sample).unwrap();
+    let now = Timestamp::new(133_000_000_000_000_000, TimestampFormat::Iso);
+    let path = Path::new(sample);
+
+    // Only the entries missing from the baseline or with changed fields are reported
+    let mut old = parsed.clone();
+    let entries = &mut old.data.as_destlist_mut().unwrap().entries;
+    entries.remove(0);
+    entries[0].pinned = !entries[0].pinned;
+    let mut baseline = Baseline::default();
+    baseline.insert(path.to_path_buf(), old);
+    let events = baseline.update(path, parsed.clone(), now);
+    let kinds: Vec<WatchEventKind> = events.iter().map(|e| e.event).collect();
+    assert_eq!(
+        kinds,
+        [WatchEventKind::EntryAdded, WatchEventKind::EntryChanged]
+    );
+    assert_eq!(events[1].changes[0].field, "pinned");
+    let json = serde_json::to_value(&events[0]).unwrap();
+    assert_eq!(json["event"], "entry_added");
+    assert_eq!(json["event_time"], "2022-06-18T04:26:40Z");
+    assert!(baseline.update(path, parsed.clone(), now).is_empty());
+    let deleted = baseline.remove(path, now).unwrap();
+    assert_eq!(deleted.event, WatchEventKind::JumplistDeleted);
+    assert!(deleted.entry.is_none() && baseline.is_empty());
+
+    // A Jumplist copied to a watched directory has all its entries added
+    let dir = std::env::temp_dir().join("jumplist_parser_watch");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let watcher = {
+        let (dir, stop) = (dir.clone(), stop.clone());
+        std::thread::spawn(move || {
+            watch(&[dir], &ParseOptions::default(), &stop, |event| {
+                sender.send(event).unwrap();
+            })
+        })
+    };
+    std::thread::sleep(Duration::from_millis(500));
+    let copied = dir.join("4cb9c5750d51c07f.automaticDestinations-ms");
+    std::fs::copy(sample, &copied).unwrap();
+    let mut received = vec![];
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while received.len() < parsed.entry_count() && Instant::now() < deadline {
+        if let Ok(event) = receiver.recv_timeout(Duration::from_millis(100)) {
+            received.push(event);
+        }
+    }
+    assert_eq!(received.len(), parsed.entry_count());
+    assert!(received
+        .iter()
+        .all(|e| e.event == WatchEventKind::EntryAdded && e.app_id == parsed.app_id));
+
+    std::fs::remove_file(&copied).unwrap();
+    let deleted = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(deleted.event, WatchEventKind::JumplistDeleted);
+    stop.store(true, Ordering::SeqCst);
+    watcher.join().unwrap().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn verify_files() {
+    use jumplist_parser::{
+        verify::{verify_path, VerifyStatus},
+        JumplistType, ParseOptions,
+    };
+
+    let automatic = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let result = verify_path(automatic, false);
+    assert_eq!(result.status, VerifyStatus::Ok);
+    assert_eq!(result.r#type, Some(JumplistType::Automatic));
+    assert_eq!((result.entries, result.declared_entries), (24, Some(24)));
+    assert_eq!(verify_path(automatic, true), result);
+
+    // The DestList is parsed without the LNK streams
+    let options = ParseOptions {
+        skip_lnks: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(automatic, &options).unwrap();
+    assert_eq!(parsed.entry_count(), 24);
+    assert!(parsed.entries().all(|e| e.lnk_header().is_none()));
+
+    let custom = verify_path(
+        "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+        false,
+    );
+    assert_eq!(custom.status, VerifyStatus::Warn);
+    assert!(custom.findings[0].contains("created at"));
+
+    let truncated = std::env::temp_dir().join("jumplist_parser_verify.customDestinations-ms");
+    let data =
+        std::fs::read("samples/win11/CustomDestinations/ccba5a5986c77e43.customDestinations-ms")
+            .unwrap();
+    std::fs::write(&truncated, &data[..data.len() / 2]).unwrap();
+    let result = verify_path(truncated.to_str().unwrap(), false);
+    std::fs::remove_file(&truncated).unwrap();
+    assert_eq!(result.status, VerifyStatus::Fail);
+    assert!(!result.findings.is_empty());
+
+    let missing = verify_path("missing.automaticDestinations-ms", false);
+    assert_eq!(missing.status, VerifyStatus::Fail);
+    let json = serde_json::to_value(&missing).unwrap();
+    assert_eq!(json["status"], "fail");
+    assert_eq!(json["type"], "automatic");
+}
+
+#[cfg(test)]
+#[test]
+fn canonical_target_paths() {
+    use jumplist_parser::{
+        output::{csv_columns, CsvOptions},
+        target::canonicalize_target_path,
+        Flatten, ParseOptions,
+    };
+
+    let user = Some("bob");
+    let expected = r"C:\Users\bob\Documents\x.docx";
+    for path in [
+        r"C:\Users\bob\Documents\x.docx",
+        r"c:/Users/bob/Documents/x.docx",
+        r"\\?\C:\Users\bob\Documents\x.docx",
+        r"%USERPROFILE%\Documents\x.docx",
+        r"%userprofile%\Documents\x.docx",
+    ] {
+        assert_eq!(canonicalize_target_path(path, user), expected, "{}", path);
+    }
+    assert_eq!(
+        canonicalize_target_path(r"%APPDATA%\Microsoft\Windows\Recent\", user),
+        r"C:\Users\bob\AppData\Roaming\Microsoft\Windows\Recent"
+    );
+    assert_eq!(
+        canonicalize_target_path(r"%ProgramFiles(x86)%\App\app.exe", None),
+        r"C:\Program Files (x86)\App\app.exe"
+    );
+    // UNC paths, with and without the device prefix
+    assert_eq!(
+        canonicalize_target_path(r"\\?\UNC\server\share\dir\", None),
+        r"\\server\share\dir"
+    );
+    assert_eq!(
+        canonicalize_target_path(r"\\server/share/x.docx", None),
+        r"\\server\share\x.docx"
+    );
+    // The variables of an unknown user and unknown variables are kept
+    assert_eq!(
+        canonicalize_target_path(r"%USERPROFILE%\x.docx", None),
+        r"%USERPROFILE%\x.docx"
+    );
+    assert_eq!(
+        canonicalize_target_path(r"%MYVAR%\x.docx", user),
+        r"%MYVAR%\x.docx"
+    );
+    assert_eq!(
+        canonicalize_target_path("https://example.com/a%20b%20c", user),
+        "https://example.com/a%20b%20c"
+    );
+
+    let path = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let plain = JumplistParser::from_path(path).unwrap();
+    assert!(plain
+        .flatten()
+        .iter()
+        .all(|e| !e.contains_key("target_full_path_canonical")));
+    let options = ParseOptions {
+        canonicalize_paths: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(path, &options).unwrap();
+    for entry in parsed.flatten() {
+        assert_eq!(
+            entry["target_full_path_canonical"],
+            canonicalize_target_path(&entry["target_full_path"], None)
+        );
+    }
+
+    let columns = csv_columns(&CsvOptions {
+        canonical_paths: true,
+        ..Default::default()
+    });
+    let position = columns
+        .iter()
+        .position(|c| *c == "target_full_path")
+        .unwrap();
+    assert_eq!(columns[position + 1], "target_full_path_canonical");
+}
+
+#[cfg(test)]
+#[test]
+fn target_attributes() {
+    use jumplist_parser::{
+        output::{csv_rows, CsvOptions, CSV_COLUMNS},
+        target::file_attribute_names,
+        Flatten,
+    };
+
+    assert_eq!(file_attribute_names(0x27), "readonly,hidden,system,archive");
+    assert_eq!(file_attribute_names(0x2000_0020), "archive,0x20000000");
+
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let entry = parsed
+        .destlist()
+        .unwrap()
+        .get_entry_by_number(0x19)
+        .unwrap();
+    let header = entry.lnk_header.as_ref().unwrap();
+    assert_eq!(header.file_attributes & 0x10, 0x10);
+    let json = serde_json::to_value(header).unwrap();
+    assert_eq!(json["file_attribute_names"], header.file_attribute_names);
+    assert!(header
+        .file_attribute_names
+        .split(',')
+        .any(|n| n == "directory"));
+
+    let normalized = parsed.flatten();
+    for entry in &normalized {
+        let is_directory = entry["target_attributes"]
+            .split(',')
+            .any(|n| n == "directory");
+        assert_eq!(entry["target_is_directory"], is_directory.to_string());
+        assert_eq!(entry["target_type"] == "directory", is_directory);
+        assert_eq!(entry["target_is_hidden"], "false");
+    }
+    let value: u32 = normalized[0]["target_attributes_value"].parse().unwrap();
+    assert_eq!(
+        file_attribute_names(value),
+        normalized[0]["target_attributes"]
+    );
+
+    // Custom Jumplist LNKs are decoded too
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    )
+    .unwrap();
+    let rows = csv_rows(&custom, &CsvOptions::default());
+    let column = CSV_COLUMNS
+        .iter()
+        .position(|c| *c == "target_attributes")
+        .unwrap();
+    for (row, entry) in rows.iter().zip(custom.flatten()) {
+        assert_eq!(row[column], entry["target_attributes"]);
+        assert!(!row[column].is_empty());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn lnk_execution_fields() {
+    use jumplist_parser::lnk_info::{LnkDetails, LnkHeader};
+    use jumplist_parser::output::{csv_columns, csv_rows, CsvOptions};
+    use jumplist_parser::timestamp::TimestampFormat;
+    use jumplist_parser::Flatten;
+
+    // A Unicode LNK with a relative path, a working directory and an icon location, opened
+    // minimized with the Ctrl+Shift+F5 hotkey
+    let mut lnk = vec![0; 0x4C];
+    lnk[0] = 0x4C;
+    lnk[0x14..0x18].copy_from_slice(&(0x08u32 | 0x10 | 0x40 | 0x80).to_le_bytes());
+    lnk[0x3C..0x40].copy_from_slice(&7u32.to_le_bytes());
+    lnk[0x40..0x42].copy_from_slice(&[0x74, 0x03]);
+    for value in [
+        r"..\tools\run.exe",
+        r"C:\tools",
+        r"C:\Windows\System32\cmd.exe,0",
+    ] {
+        let units: Vec<u16> = value.encode_utf16().collect();
+        lnk.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        units
+            .iter()
+            .for_each(|u| lnk.extend_from_slice(&u.to_le_bytes()));
+    }
+    lnk.extend_from_slice(&[0; 4]);
+
+    let header = LnkHeader::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+    assert_eq!(header.show_command_name(), "minimized_no_activate");
+    assert_eq!(header.hotkey_name().as_deref(), Some("Ctrl+Shift+F5"));
+    let details = LnkDetails::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+    assert_eq!(details.relative_path.as_deref(), Some(r"..\tools\run.exe"));
+    assert_eq!(details.working_directory.as_deref(), Some(r"C:\tools"));
+    assert_eq!(
+        details.icon_location.as_deref(),
+        Some(r"C:\Windows\System32\cmd.exe,0")
+    );
+
+    // The keys are always present, empty when the LNK doesn't have the field
+    let parsed = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/590aee7bdd69b59b.customDestinations-ms",
+    )
+    .unwrap();
+    let normalized = parsed.flatten();
+    for entry in &normalized {
+        for key in ["lnk_working_directory", "lnk_relative_path", "lnk_hotkey"] {
+            assert_eq!(entry[key], "", "{}", key);
+        }
+        assert_eq!(entry["lnk_show_command"], "normal");
+    }
+    assert!(normalized
+        .iter()
+        .any(|e| e["lnk_icon_location"].ends_with(r"\powershell.exe")));
+
+    let options = CsvOptions {
+        extended_columns: true,
+        ..Default::default()
+    };
+    let columns = csv_columns(&options);
+    let icon = columns
+        .iter()
+        .position(|c| *c == "lnk_icon_location")
+        .unwrap();
+    let rows = csv_rows(&parsed, &options);
+    assert!(rows.iter().all(|row| row.len() == columns.len()));
+    for (row, entry) in rows.iter().zip(&normalized) {
+        assert_eq!(row[icon], entry["lnk_icon_location"]);
+    }
+    assert!(!csv_columns(&CsvOptions::default()).contains(&"lnk_icon_location"));
+}
+
+#[cfg(test)]
+#[test]
+fn entry_fingerprints() {
+    use jumplist_parser::output::{csv_columns, csv_rows, CsvOptions};
+    use jumplist_parser::Flatten;
+    use sha1::{Digest, Sha1};
+    use std::collections::HashSet;
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let entry = &parsed.destlist().unwrap().entries[0];
+
+    // The documented recipe
+    let input = format!(
+        "4cb9c5750d51c07f|{}|{}|{}|{}",
+        entry.entry_number,
+        entry.path.to_lowercase(),
+        entry.mtime.filetime,
+        entry.file_droid
+    );
+    let digest: String = Sha1::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(entry.entry_fingerprint, format!("v1:{}", digest));
+
+    // Stable across runs and parse functions, unique within a file
+    let data = std::fs::read(parsed.source_path.as_ref().unwrap()).unwrap();
+    let from_bytes =
+        JumplistParser::from_bytes(&data, Some("4cb9c5750d51c07f.automaticDestinations-ms"))
+            .unwrap();
+    let fingerprints: Vec<&str> = parsed.entries().map(|e| e.fingerprint()).collect();
+    assert!(fingerprints
+        .iter()
+        .copied()
+        .eq(from_bytes.entries().map(|e| e.fingerprint())));
+    assert_eq!(
+        fingerprints.iter().collect::<HashSet<_>>().len(),
+        fingerprints.len()
+    );
+
+    // Without a name the AppID is unknown, which changes the fingerprints
+    let mut anonymous = JumplistParser::from_bytes(&data, None).unwrap();
+    assert_ne!(
+        anonymous.entries().next().unwrap().fingerprint(),
+        fingerprints[0]
+    );
+    anonymous.app_id = parsed.app_id.clone();
+    anonymous.update_fingerprints();
+    assert_eq!(
+        anonymous.entries().next().unwrap().fingerprint(),
+        fingerprints[0]
+    );
+
+    // Custom entries are set too, and the fingerprint is in every output format
+    let custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/590aee7bdd69b59b.customDestinations-ms",
+    )
+    .unwrap();
+    assert!(custom
+        .entries()
+        .all(|e| e.fingerprint().starts_with("v1:") && e.fingerprint().len() == 43));
+    let json = serde_json::to_value(&custom).unwrap();
+    assert_eq!(
+        json["data"]["entries"][0]["entries"][0]["entry_fingerprint"],
+        custom.entries().next().unwrap().fingerprint()
+    );
+    let normalized = custom.flatten();
+    let options = CsvOptions::default();
+    let column = csv_columns(&options)
+        .iter()
+        .position(|c| *c == "entry_fingerprint")
+        .unwrap();
+    let rows = csv_rows(&custom, &options);
+    for ((row, entry), fingerprint) in rows.iter().zip(&normalized).zip(custom.entries()) {
+        assert_eq!(row[column], entry["entry_fingerprint"]);
+        assert_eq!(row[column], fingerprint.fingerprint());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn builder_fixtures() {
+    use jumplist_parser::builder::{
+        lnk_bytes, AutomaticEntry, AutomaticJumplistBuilder, CustomCategory, CustomJumplistBuilder,
+    };
+    use jumplist_parser::custom_destinations::{CategoryId, CategoryType};
+    use jumplist_parser::destlist::DestListVersion;
+    use jumplist_parser::guid::Guid;
+    use jumplist_parser::{JumplistType, ParseMode, ParseOptions};
+    use std::io::Cursor;
+
+    let automatic = |data: &[u8]| {
+        JumplistParser::from_bytes(data, Some("0123456789abcdef.automaticDestinations-ms"))
+    };
+
+    // Every field round trips, in both entry layouts
+    for version in [DestListVersion::V1Win7, DestListVersion::V4Win10_1703Plus] {
+        let data = AutomaticJumplistBuilder::new(version)
+            .entry(AutomaticEntry {
+                mtime: 133_000_000_000_000_000,
+                pin_position: Some(0),
+                access_count: 7,
+                hostname: "desktop-01".to_string(),
+                file_droid: "01234567-89AB-CDEF-0123-456789ABCDEF".parse().unwrap(),
+                ..AutomaticEntry::new(0x1A, r"C:\Users\user\Documents\report.docx")
+            })
+            .to_bytes()
+            .unwrap();
+        let parsed = automatic(&data).unwrap();
+        assert!(parsed.warnings().is_empty());
+        let destlist = parsed.destlist().unwrap();
+        assert_eq!(destlist.header.version, version);
+        assert_eq!(destlist.header.number_of_pinned_entries, 1);
+        assert_eq!(destlist.header.last_entry_number, 0x1A);
+        let entry = &destlist.entries[0];
+        assert_eq!(entry.entry_number, 0x1A);
+        assert_eq!(entry.path, r"C:\Users\user\Documents\report.docx");
+        assert_eq!(entry.mtime.filetime, 133_000_000_000_000_000);
+        assert!(entry.pinned);
+        assert_eq!(entry.hostname.as_deref(), Some("desktop-01"));
+        assert_ne!(entry.file_droid, Guid::default());
+        assert!(entry.lnk.is_some());
+        assert_eq!(entry.access_count, Some(7));
+    }
+
+    // An empty DestList
+    let empty = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus);
+    let parsed = automatic(&empty.to_bytes().unwrap()).unwrap();
+    assert_eq!(parsed.entry_count(), 0);
+    assert!(parsed.warnings().is_empty());
+    let no_destlist = AutomaticJumplistBuilder {
+        omit_destlist: true,
+        ..empty
+    };
+    let parsed = automatic(&no_destlist.to_bytes().unwrap()).unwrap();
+    assert_eq!(parsed.entry_count(), 0);
+    assert_eq!(
+        parsed.destlist().unwrap().header.version,
+        DestListVersion::Unknown(0)
+    );
+    // A DestList that can't be parsed, even leniently, is reported with its error
+    let truncated = AutomaticJumplistBuilder {
+        omit_destlist: true,
+        ..AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+    }
+    .stream("DestList", vec![4, 0, 0, 0, 1, 0])
+    .to_bytes()
+    .unwrap();
+    let err = automatic(&truncated).unwrap_err();
+    assert_eq!(err.kind(), "no_destlist");
+    assert!(err
+        .to_string()
+        .contains("Unable to parse the 'DestList' stream"));
+
+    // Duplicate entry numbers, a missing LNK stream, an unexpected one and a wrong count
+    let data = AutomaticJumplistBuilder {
+        declared_entries: Some(2),
+        ..AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+    }
+    .entry(AutomaticEntry::new(1, r"C:\a.txt"))
+    .entry(AutomaticEntry::new(1, r"C:\b.txt"))
+    .entry(AutomaticEntry {
+        lnk: None,
+        ..AutomaticEntry::new(2, r"C:\c.txt")
+    })
+    .stream("9", lnk_bytes(r"C:\d.txt"))
+    .to_bytes()
+    .unwrap();
+    let parsed = automatic(&data).unwrap();
+    let destlist = parsed.destlist().unwrap();
+    assert_eq!(destlist.duplicate_entry_numbers, vec![1]);
+    assert_eq!(destlist.missing_lnk_streams, vec![2]);
+    assert_eq!(destlist.unexpected_lnk_streams, vec![9]);
+    assert!(parsed.warnings()[0].starts_with("The DestList header declares 2 entries"));
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    assert!(JumplistParser::from_bytes_with_options(&data, None, &strict).is_err());
+
+    // A custom Jumplist with every category type
+    let builder = CustomJumplistBuilder::new()
+        .pinned(vec![lnk_bytes(r"C:\pinned.txt")])
+        .category(CustomCategory::Custom {
+            name: "Projects".to_string(),
+            lnks: vec![lnk_bytes(r"C:\p1.txt"), lnk_bytes(r"C:\p2.txt")],
+        })
+        .category(CustomCategory::Known(CategoryId::Frequent))
+        .category(CustomCategory::Task(vec![lnk_bytes(r"C:\task.exe")]));
+    let data = builder.to_bytes();
+    let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+    assert!(parsed.warnings().is_empty());
+    let custom = parsed.custom_destinations().unwrap();
+    let types: Vec<&CategoryType> = custom.entries.iter().map(|c| &c.r#type).collect();
+    assert_eq!(
+        types,
+        [
+            &CategoryType::Pinned,
+            &CategoryType::Custom,
+            &CategoryType::Known,
+            &CategoryType::Task
+        ]
+    );
+    let categories: Vec<Option<&str>> = parsed.entries().map(|e| e.category()).collect();
+    assert_eq!(
+        categories,
+        [
+            Some("pinned"),
+            Some("Projects"),
+            Some("Projects"),
+            Some("tasks")
+        ]
+    );
+    assert_eq!(custom.entries[2].id, Some(CategoryId::Frequent));
+
+    // Truncated in the last category, and more categories declared than written
+    let truncated = &data[..data.len() - 10];
+    let options = ParseOptions {
+        disable_carving: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_reader_with_options(
+        &mut Cursor::new(truncated),
+        JumplistType::Custom,
+        &options,
+    )
+    .unwrap();
+    assert!(parsed.custom_destinations().unwrap().truncated);
+    assert_eq!(parsed.entry_count(), 3);
+    let overdeclared = CustomJumplistBuilder {
+        declared_categories: Some(5),
+        ..builder
+    };
+    let parsed = JumplistParser::from_bytes(&overdeclared.to_bytes(), None).unwrap();
+    assert!(!parsed.warnings().is_empty());
+
+    // Fixtures can be written to disk for other pipelines
+    let path = std::env::temp_dir().join("0123456789abcdef.customDestinations-ms");
+    overdeclared.write_to(path.to_str().unwrap()).unwrap();
+    let parsed = JumplistParser::from_path(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed.entry_count(), 4);
+}
+
+#[cfg(test)]
+#[test]
+fn parse_limits() {
+    use jumplist_parser::builder::{
+        lnk_bytes, AutomaticEntry, AutomaticJumplistBuilder, CustomCategory, CustomJumplistBuilder,
+    };
+    use jumplist_parser::destlist::DestListVersion;
+    use jumplist_parser::errors::JumplistParserError;
+    use jumplist_parser::{ParseLimits, ParseOptions};
+
+    const AUTOMATIC: &str = "0123456789abcdef.automaticDestinations-ms";
+    const CUSTOM: &str = "0123456789abcdef.customDestinations-ms";
+    let with_limits = |limits: ParseLimits| ParseOptions {
+        limits,
+        ..Default::default()
+    };
+    let is_limit = |r: Result<JumplistParser, JumplistParserError>| {
+        matches!(r, Err(JumplistParserError::LimitExceeded(..)))
+    };
+
+    // The defaults never trigger on the samples
+    for path in glob("samples/**/*Destinations-ms").unwrap().flatten() {
+        let parsed = JumplistParser::from_path(path.to_str().unwrap());
+        assert!(!is_limit(parsed), "{}", path.display());
+    }
+
+    let automatic = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\Documents\a.txt"))
+        .entry(AutomaticEntry::new(2, r"C:\Users\user\Documents\b.txt"))
+        .to_bytes()
+        .unwrap();
+    let custom = CustomJumplistBuilder::new()
+        .category(CustomCategory::Custom {
+            name: "Recent".to_string(),
+            lnks: vec![lnk_bytes(r"C:\a.txt"), lnk_bytes(r"C:\b.txt")],
+        })
+        .category(CustomCategory::Task(vec![lnk_bytes(r"C:\c.exe")]))
+        .to_bytes();
+    let parse = |data: &[u8], name: &str, limits: ParseLimits| {
+        JumplistParser::from_bytes_with_options(data, Some(name), &with_limits(limits))
+    };
+    let defaults = ParseLimits::default();
+    for (data, name) in [(&automatic, AUTOMATIC), (&custom, CUSTOM)] {
+        assert!(parse(data, name, defaults).is_ok());
+        let max_entries = ParseLimits {
+            max_entries: 1,
+            ..defaults
+        };
+        assert!(is_limit(parse(data, name, max_entries)));
+        let max_string_length = ParseLimits {
+            max_string_length: 4,
+            ..defaults
+        };
+        assert!(is_limit(parse(data, name, max_string_length)));
+        let max_lnk_bytes = ParseLimits {
+            max_lnk_bytes: 100,
+            ..defaults
+        };
+        assert!(is_limit(parse(data, name, max_lnk_bytes)));
+    }
+    let max_categories = ParseLimits {
+        max_categories: 1,
+        ..defaults
+    };
+    assert!(is_limit(parse(&custom, CUSTOM, max_categories)));
+
+    // Arbitrary input ends quickly, whatever the outcome
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    for _ in 0..4 {
+        let data: Vec<u8> = (0..1 << 20)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+        for name in [AUTOMATIC, CUSTOM] {
+            let _ = JumplistParser::from_bytes(&data, Some(name));
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_path_decoding() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::{DestList, DestListVersion};
+    use std::io::Cursor;
+
+    let entry = |number: u32, path: &str| AutomaticEntry {
+        mtime: 133_500_000_000_000_000 + number as u64,
+        hostname: "desktop-7".to_string(),
+        file_droid: format!("{:08X}-89AB-CDEF-0123-456789ABCDEF", number)
+            .parse()
+            .unwrap(),
+        lnk: None,
+        ..AutomaticEntry::new(number, path)
+    };
+    let byte_counted = |number: u32, path: &str| AutomaticEntry {
+        path_size: Some(path.encode_utf16().count() as u16 * 2),
+        ..entry(number, path)
+    };
+    let parse = |builder: AutomaticJumplistBuilder| {
+        let mut destlist =
+            DestList::parse_stream(&mut Cursor::new(builder.destlist_bytes())).unwrap();
+        destlist.entries.sort_by_key(|e| e.entry_number);
+        destlist
+    };
+    let path = "C:\\Users\\user\\\u{1F4C1}\\r\u{e9}sum\u{e9}.pdf";
+
+    for version in [DestListVersion::V1Win7, DestListVersion::V4Win10_1703Plus] {
+        // Embedded NULs are dropped, unpaired surrogates replaced
+        let destlist = parse(
+            AutomaticJumplistBuilder::new(version)
+                .entry(entry(1, path))
+                .entry(entry(2, "C:\\app\0\0\\data.bin"))
+                .entry(entry(3, "C:\\last.txt")),
+        );
+        assert!(destlist.warnings.is_empty());
+        let paths: Vec<&str> = destlist.paths().collect();
+        assert_eq!(paths, [path, "C:\\app\\data.bin", "C:\\last.txt"]);
+        assert_eq!(destlist.entries[1].path_raw_len, 34);
+
+        // A `path_size` counting bytes, in the middle and at the end of the stream
+        let destlist = parse(
+            AutomaticJumplistBuilder::new(version)
+                .entry(entry(1, "C:\\first.txt"))
+                .entry(byte_counted(2, "C:\\Program Files\\Vendor\\tool.exe"))
+                .entry(entry(3, path))
+                .entry(byte_counted(4, "D:\\last.iso")),
+        );
+        assert!(
+            destlist
+                .warnings
+                .iter()
+                .all(|w| w == "Unexpected stream '/Extension/Settings/State' in the compound file"),
+            "{:?}",
+            destlist.warnings
+        );
+        let paths: Vec<&str> = destlist.paths().collect();
+        assert_eq!(
+            paths,
+            [
+                "C:\\first.txt",
+                "C:\\Program Files\\Vendor\\tool.exe",
+                path,
+                "D:\\last.iso"
+            ]
+        );
+        assert_eq!(destlist.entries[1].path_raw_len, 64);
+        assert_eq!(destlist.entries[1].file_droid, entry(2, "").file_droid);
+    }
+
+    let mut data = AutomaticJumplistBuilder::new(DestListVersion::V1Win7)
+        .entry(entry(1, "C:\\r\u{e9}sum\u{e9}.txt"))
+        .destlist_bytes();
+    let units: Vec<u8> = "r\u{e9}"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let at = data
+        .windows(units.len())
+        .position(|w| w == &units[..])
+        .unwrap();
+    data[at..at + 2].copy_from_slice(&0xD800u16.to_le_bytes());
+    let destlist = DestList::parse_stream(&mut Cursor::new(&data)).unwrap();
+    assert_eq!(destlist.entries[0].path, "C:\\\u{FFFD}\u{e9}sum\u{e9}.txt");
+}
+
+#[cfg(test)]
+#[test]
+fn lnk_status() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::{DestListVersion, LnkStatus};
+    use jumplist_parser::{Flatten, ParseOptions};
+
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry::new(1, r"C:\parsed.txt"))
+        .entry(AutomaticEntry {
+            lnk: None,
+            ..AutomaticEntry::new(2, r"C:\missing.txt")
+        })
+        .entry(AutomaticEntry {
+            // A wiped stream
+            lnk: Some(vec![0; 64]),
+            ..AutomaticEntry::new(3, r"C:\corrupt.txt")
+        })
+        .to_bytes()
+        .unwrap();
+    let name = Some("0123456789abcdef.automaticDestinations-ms");
+    let parsed = JumplistParser::from_bytes(&data, name).unwrap();
+    let destlist = parsed.destlist().unwrap();
+    let status = |number: u32| &destlist.get_entry_by_number(number).unwrap().lnk_status;
+    assert_eq!(status(1), &LnkStatus::Parsed);
+    assert_eq!(status(2), &LnkStatus::Missing);
+    assert!(matches!(status(3), LnkStatus::ParseFailed(e) if !e.is_empty()));
+    let counts: Vec<_> = destlist.lnk_status_counts().into_iter().collect();
+    assert_eq!(counts, [("missing", 1), ("parse_failed", 1), ("parsed", 1)]);
+
+    let json = serde_json::to_value(status(3)).unwrap();
+    assert_eq!(json["status"], "parse_failed");
+    let normalized = parsed.flatten();
+    let mut statuses: Vec<&str> = normalized
+        .iter()
+        .map(|e| e["lnk_status"].as_str())
+        .collect();
+    statuses.sort_unstable();
+    assert_eq!(statuses, ["missing", "parse_failed", "parsed"]);
+
+    let skip_lnks = ParseOptions {
+        skip_lnks: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_bytes_with_options(&data, name, &skip_lnks).unwrap();
+    let counts: Vec<_> = parsed
+        .destlist()
+        .unwrap()
+        .lnk_status_counts()
+        .into_iter()
+        .collect();
+    assert_eq!(counts, [("skipped", 3)]);
+}
+
+#[cfg(test)]
+#[test]
+fn app_id_from_renamed_files() {
+    let path = "samples/win10/AutomaticDestinations/9b9cdc69c1c24e2b.automaticDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let original = JumplistParser::from_path(path).unwrap();
+    assert_eq!(original.app_id.as_deref(), Some("9b9cdc69c1c24e2b"));
+    assert!(original.app_id_valid);
+    assert_eq!(original.app_name.as_deref(), Some("Notepad 64-bit"));
+    let fingerprints: Vec<&str> = original.entries().map(|e| e.fingerprint()).collect();
+
+    for name in [
+        // Prefixed by a collector
+        "PC01_john_9b9cdc69c1c24e2b.automaticDestinations-ms",
+        // Suffixed
+        "9b9cdc69c1c24e2b-backup.automaticDestinations-ms",
+        // Copied in Explorer
+        "9b9cdc69c1c24e2b (1).automaticDestinations-ms",
+        "9B9CDC69C1C24E2B.automaticDestinations-ms",
+        "Jöhn_Døe_9b9cdc69C1C24E2B.automaticDestinations-ms",
+    ] {
+        let parsed = JumplistParser::from_bytes(&data, Some(name)).unwrap();
+        assert_eq!(
+            parsed.app_id.as_deref(),
+            Some("9b9cdc69c1c24e2b"),
+            "{}",
+            name
+        );
+        assert!(parsed.app_id_valid, "{}", name);
+        assert_eq!(parsed.app_name, original.app_name, "{}", name);
+        assert!(
+            parsed
+                .entries()
+                .map(|e| e.fingerprint())
+                .eq(fingerprints.iter().copied()),
+            "{}",
+            name
+        );
+    }
+
+    // No 16 hex digits token: the stem as is
+    for (name, stem) in [
+        ("notepad.automaticDestinations-ms", "notepad"),
+        // 17 hex digits
+        (
+            "9b9cdc69c1c24e2b0.automaticDestinations-ms",
+            "9b9cdc69c1c24e2b0",
+        ),
+        ("Jöhn.Døe.automaticDestinations-ms", "Jöhn.Døe"),
+    ] {
+        let parsed = JumplistParser::from_bytes(&data, Some(name)).unwrap();
+        assert_eq!(parsed.app_id.as_deref(), Some(stem));
+        assert!(!parsed.app_id_valid);
+        assert_eq!(parsed.app_name.as_deref(), Some(""));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn file_name_variants() {
+    use jumplist_parser::errors::JumplistParserError;
+    use jumplist_parser::{FileNameSuffixes, JumplistType, ParseOptions};
+
+    let automatic = "samples/win10/AutomaticDestinations/9b9cdc69c1c24e2b.automaticDestinations-ms";
+    let custom = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let dir = std::env::temp_dir().join("jumplist_parser_file_name_variants");
+    std::fs::create_dir_all(&dir).unwrap();
+    let copy = |sample: &str, name: &str| {
+        let path = dir.join(name);
+        std::fs::copy(sample, &path).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+
+    for (sample, name, jumplist_type, app_id) in [
+        (
+            automatic,
+            "9B9CDC69C1C24E2B.AUTOMATICDESTINATIONS-MS",
+            JumplistType::Automatic,
+            "9b9cdc69c1c24e2b",
+        ),
+        (
+            automatic,
+            "9b9cdc69c1c24e2b.automaticDestinations-ms.bak",
+            JumplistType::Automatic,
+            "9b9cdc69c1c24e2b",
+        ),
+        (
+            automatic,
+            "9b9cdc69c1c24e2b.automaticDestinations-ms_1",
+            JumplistType::Automatic,
+            "9b9cdc69c1c24e2b",
+        ),
+        // 8.3 names only have the content
+        (
+            automatic,
+            "9B9CDC~1.AUT",
+            JumplistType::Automatic,
+            "9B9CDC~1",
+        ),
+        (
+            custom,
+            "5d696d521de238c3.CustomDestinations-MS.copy",
+            JumplistType::Custom,
+            "5d696d521de238c3",
+        ),
+        (custom, "5D696D~1.CUS", JumplistType::Custom, "5D696D~1"),
+        // A suffix that isn't allowed falls back to the content as well
+        (
+            custom,
+            "5d696d521de238c3.customDestinations-ms.txt",
+            JumplistType::Custom,
+            "5d696d521de238c3",
+        ),
+    ] {
+        let path = copy(sample, name);
+        let parsed = JumplistParser::from_path(&path).unwrap();
+        assert_eq!(parsed.r#type, jumplist_type, "{}", name);
+        assert_eq!(parsed.app_id.as_deref(), Some(app_id), "{}", name);
+        assert!(parsed.entry_count() > 0, "{}", name);
+        let data = std::fs::read(&path).unwrap();
+        let from_bytes = JumplistParser::from_bytes(&data, Some(name)).unwrap();
+        assert_eq!(from_bytes.r#type, jumplist_type, "{}", name);
+    }
+
+    // The allowlist is configurable
+    let suffixes = FileNameSuffixes(vec!["-evidence".to_string()]);
+    let name = "9b9cdc69c1c24e2b.automaticDestinations-ms-EVIDENCE";
+    assert_eq!(
+        JumplistParser::type_from_name(name, &suffixes),
+        Some(JumplistType::Automatic)
+    );
+    assert_eq!(
+        JumplistParser::type_from_name(name, &FileNameSuffixes::default()),
+        None
+    );
+    let options = ParseOptions {
+        file_name_suffixes: suffixes,
+        ..Default::default()
+    };
+    let path = copy(automatic, name);
+    assert!(JumplistParser::from_path_with_options(&path, &options).is_ok());
+
+    // Neither the name nor the content gives the type
+    let path = dir.join("notes.txt");
+    std::fs::write(&path, "not a Jumplist").unwrap();
+    assert!(matches!(
+        JumplistParser::from_path(path.to_str().unwrap()),
+        Err(JumplistParserError::FileType(..))
+    ));
+    assert!(matches!(
+        JumplistParser::from_bytes(b"not a Jumplist", Some("notes.txt")),
+        Err(JumplistParserError::FileType(..))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn field_selection() {
+    use jumplist_parser::output::{
+        csv_columns, csv_rows, find_field, select_fields, CsvOptions, CSV_COLUMNS,
+        EXTENDED_CSV_COLUMNS, FIELDS, OFFSET_CSV_COLUMNS, VERBOSE_CSV_COLUMNS,
+    };
+    use jumplist_parser::Flatten;
+    use std::collections::HashSet;
+
+    // Every column of the default and optional layouts is registered, once
+    let names: HashSet<&str> = FIELDS.iter().map(|f| f.name).collect();
+    assert_eq!(names.len(), FIELDS.len());
+    for column in CSV_COLUMNS
+        .iter()
+        .chain(VERBOSE_CSV_COLUMNS)
+        .chain(EXTENDED_CSV_COLUMNS)
+        .chain(OFFSET_CSV_COLUMNS)
+        .chain(&["target_full_path_canonical"])
+    {
+        assert!(names.contains(column), "{}", column);
+    }
+    assert_eq!(csv_columns(&CsvOptions::default()), CSV_COLUMNS);
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    let default_rows = csv_rows(&parsed, &CsvOptions::default());
+    let options = CsvOptions {
+        fields: Some(vec![
+            "entry_fingerprint",
+            "jumplist_type",
+            "target_full_path",
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(
+        csv_columns(&options),
+        ["entry_fingerprint", "jumplist_type", "target_full_path"]
+    );
+    let column = |name: &str| CSV_COLUMNS.iter().position(|c| *c == name).unwrap();
+    for (row, default_row) in csv_rows(&parsed, &options).iter().zip(&default_rows) {
+        assert_eq!(row[0], default_row[column("entry_fingerprint")]);
+        assert_eq!(row[1], "automatic");
+        assert_eq!(row[2], default_row[column("target_full_path")]);
+    }
+
+    let entry = parsed.flatten().remove(0);
+    let selected = select_fields(&parsed, entry.clone(), &["jumplist_type", "lnk_status"]);
+    assert_eq!(selected.len(), 2);
+    assert_eq!(selected["jumplist_type"], "automatic");
+    assert_eq!(selected["lnk_status"], entry["lnk_status"]);
+
+    let error = find_field("entry_fingerprnt").unwrap_err();
+    assert!(
+        error.contains("did you mean 'entry_fingerprint'"),
+        "{}",
+        error
+    );
+    assert!(!find_field("nothing_like_it")
+        .unwrap_err()
+        .contains("did you mean"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn fields_argument() {
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args(["-p", sample])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let csv = run(&["--fields", "jumplist_type,jumplist_app_id"]);
+    assert!(csv.status.success());
+    let csv = String::from_utf8(csv.stdout).unwrap();
+    assert!(csv.starts_with(
+        "\"jumplist_type\",\"jumplist_app_id\"\n\"automatic\",\"4cb9c5750d51c07f\"\n"
+    ));
+
+    let jsonl = run(&[
+        "--output-format",
+        "jsonl",
+        "--normalize",
+        "--fields",
+        "jumplist_app_id,lnk_status",
+    ]);
+    let entries: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_slice(&jsonl.stdout).unwrap();
+    assert!(!entries.is_empty());
+    for entry in entries {
+        let mut keys: Vec<&String> = entry.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["jumplist_app_id", "lnk_status"]);
+    }
+
+    let unknown = run(&["--fields", "app_id,target_sise"]);
+    assert_eq!(unknown.status.code(), Some(2));
+    let stderr = String::from_utf8(unknown.stderr).unwrap();
+    assert!(stderr.contains("did you mean 'target_size'"), "{}", stderr);
+
+    let listed = run(&["--list-fields"]);
+    let listed = String::from_utf8(listed.stdout).unwrap();
+    assert!(listed.lines().any(|l| l.starts_with("entry_fingerprint ")));
+}
+
+#[cfg(test)]
+#[test]
+fn tln_output() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::DestListVersion;
+    use jumplist_parser::tln::{tln_records, TlnRecord};
+
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry {
+            // 2024-01-01T00:00:00Z
+            mtime: 133_485_408_000_000_000,
+            hostname: "PC|01".to_string(),
+            ..AutomaticEntry::new(1, r"C:\notes|draft.txt")
+        })
+        // No interaction time and no LNK times
+        .entry(AutomaticEntry::new(2, r"C:\undated.txt"))
+        .to_bytes()
+        .unwrap();
+    let mut parsed =
+        JumplistParser::from_bytes(&data, Some("9b9cdc69c1c24e2b.automaticDestinations-ms"))
+            .unwrap();
+    parsed.user = Some("dave".to_string());
+
+    let records = tln_records(&parsed);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records.iter().filter(|r| r.is_none()).count(), 1);
+    let record = records.into_iter().flatten().next().unwrap();
+    assert_eq!(
+        record,
+        TlnRecord {
+            time: 1_704_067_200,
+            source: "JUMPLIST",
+            host: "PC|01".to_string(),
+            user: "dave".to_string(),
+            description: r"Notepad 64-bit - C:\notes|draft.txt".to_string(),
+        }
+    );
+    assert_eq!(
+        record.to_string(),
+        r"1704067200|JUMPLIST|PC\|01|dave|Notepad 64-bit - C:\notes\|draft.txt"
+    );
+
+    // Custom entries have no interaction time, the LNK modification time is used
+    let parsed = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    )
+    .unwrap();
+    for (record, entry) in tln_records(&parsed).iter().zip(parsed.entries()) {
+        let write_time = entry.lnk_header().unwrap().write_time;
+        assert_eq!(record.as_ref().map(|r| r.time), write_time.to_epoch());
+        if let Some(record) = record {
+            assert_eq!(record.host, "");
+            assert_eq!(record.to_string().matches('|').count(), 4);
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn error_report_argument() {
+    let dir = std::env::temp_dir().join(format!("jumplist_error_report_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+        dir.join("4cb9c5750d51c07f.automaticDestinations-ms"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("0123456789abcdef.automaticDestinations-ms"),
+        b"not a Jumplist".repeat(40),
+    )
+    .unwrap();
+    std::fs::write(dir.join("fedcba9876543210.customDestinations-ms"), b"").unwrap();
+    let report_path = std::env::temp_dir().join(format!(
+        "jumplist_error_report_{}.jsonl",
+        std::process::id()
+    ));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .arg("-p")
+        .arg(dir.join("*"))
+        .arg("--error-report")
+        .arg(&report_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    let records: Vec<serde_json::Value> = report
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_file(&report_path).unwrap();
+
+    assert_eq!(records.len(), 2, "{}", report);
+    let record = &records[0];
+    assert!(record["source_path"]
+        .as_str()
+        .unwrap()
+        .ends_with("0123456789abcdef.automaticDestinations-ms"));
+    assert_eq!(record["status"], "failed");
+    assert_eq!(record["size"], 560);
+    assert_eq!(record["type"], "automatic");
+    assert_eq!(record["kind"], "file_structure");
+    assert!(record["message"].is_string());
+    let record = &records[1];
+    assert_eq!(record["status"], "empty");
+    assert_eq!(record["size"], 0);
+    assert_eq!(record["type"], "custom");
+    assert_eq!(record["kind"], "empty_or_truncated_file");
+}
+
+#[cfg(test)]
+#[test]
+fn csv_crlf_line_endings() {
+    use jumplist_parser::output::{encode_rows, CsvOptions};
+
+    let rows = vec![
+        vec!["a".to_string(), "b\nc".to_string()],
+        vec!["d".to_string(), "e".to_string()],
+    ];
+    let options = CsvOptions {
+        crlf: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        encode_rows(&rows, &options),
+        b"\"a\",\"b\nc\"\r\n\"d\",\"e\"\r\n"
+    );
+    assert_eq!(
+        encode_rows(&rows, &CsvOptions::default()),
+        b"\"a\",\"b\nc\"\n\"d\",\"e\"\n"
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn excel_csv_arguments() {
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args(["-p", sample])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+
+    let bom = run(&["--bom"]);
+    assert!(bom.starts_with(b"\xEF\xBB\xBF\"jumplist_app_id\","));
+    assert!(!bom.windows(2).any(|w| w == b"\r\n"));
+
+    let crlf = run(&["--crlf", "--output-format", "tsv"]);
+    assert!(crlf.starts_with(b"jumplist_app_id\t"));
+    let lines = crlf
+        .split(|b| *b == b'\n')
+        .filter(|l| !l.is_empty())
+        .count();
+    assert_eq!(lines, crlf.windows(2).filter(|w| *w == b"\r\n").count());
+
+    let excel = run(&["--excel", "--no-headers"]);
+    assert!(excel.starts_with(b"\xEF\xBB\xBF\""));
+    assert!(excel.ends_with(b"\"\r\n"));
+
+    let json = run(&["--excel", "--output-format", "jsonl"]);
+    assert!(json.starts_with(b"{"));
+    assert!(!json.windows(2).any(|w| w == b"\r\n"));
+}
+
+#[cfg(test)]
+#[test]
+fn pinned_entries() {
+    use jumplist_parser::Flatten;
+
+    let mut automatic = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let pinned = automatic
+        .flatten()
+        .iter()
+        .filter(|e| e["entry_pinned"] == "true")
+        .count();
+    assert_eq!(pinned, 4);
+    automatic.retain_entries(|e| e.pinned() != Some(true));
+    assert_eq!(automatic.entry_count(), 20);
+    assert_eq!(automatic.pinned_entries().count(), 0);
+    assert!(automatic
+        .flatten()
+        .iter()
+        .all(|e| e["entry_pinned"] == "false"));
+
+    let mut custom = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    )
+    .unwrap();
+    assert_eq!(custom.entry_count(), 6);
+    for entry in custom.pinned_entries() {
+        assert_eq!(entry.category(), Some("tasks"));
+    }
+    custom.retain_entries(|e| e.pinned() == Some(true));
+    assert_eq!(custom.entry_count(), 2);
+    assert!(custom.flatten().iter().all(|e| e["entry_pinned"] == "true"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn pinned_arguments() {
+    let sample = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args(["-p", sample, "--output-format", "jsonl", "--normalize"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let pinned = |output: &std::process::Output| -> Vec<String> {
+        let entries: Vec<serde_json::Map<String, serde_json::Value>> =
+            serde_json::from_slice(&output.stdout).unwrap();
+        entries
+            .iter()
+            .map(|e| e["entry_pinned"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let only = pinned(&run(&["--pinned-only"]));
+    assert_eq!(only, ["true"; 4]);
+    let excluded = pinned(&run(&["--exclude-pinned"]));
+    assert_eq!(excluded.len(), 20);
+    assert!(excluded.iter().all(|p| p == "false"));
+
+    let verbose = run(&["--pinned-only", "--verbose"]);
+    let stderr = String::from_utf8(verbose.stderr).unwrap();
+    assert!(stderr.contains(": 4 pinned of 24 entries"), "{}", stderr);
+
+    let both = run(&["--pinned-only", "--exclude-pinned"]);
+    assert_eq!(both.status.code(), Some(2));
+}
+
+#[cfg(test)]
+#[test]
+#[allow(deprecated)]
+fn deprecated_flaten() {
+    use jumplist_parser::{Flaten, Flatten};
+
+    let parsed = JumplistParser::from_path(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    )
+    .unwrap();
+    assert_eq!(parsed.flaten(), parsed.flatten());
+    let custom = parsed.custom_destinations().unwrap();
+    assert_eq!(custom.flaten(), custom.flatten());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn parse_to_records_matches_cli() {
+    use jumplist_parser::{parse_to_records, NormalizedEntry, ParseOptions};
+
+    for sample in [
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+    ] {
+        let records = parse_to_records(sample, &ParseOptions::default()).unwrap();
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                sample,
+                "--output-format",
+                "jsonl",
+                "--normalize",
+                "--offsets",
+            ])
+            .output()
+            .unwrap();
+        let emitted: Vec<NormalizedEntry> = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(records, emitted);
+        for record in &records {
+            assert_eq!(record["jumplist_file_path"], sample);
+            assert_eq!(
+                record["jumplist_file_size"],
+                std::fs::metadata(sample).unwrap().len().to_string()
+            );
+            assert!(!record["jumplist_file_modified"].is_empty());
+            assert!(!record["jumplist_app_name"].is_empty());
+        }
+    }
+    assert!(parse_to_records("samples/missing", &ParseOptions::default()).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn deterministic_entry_order() {
+    use jumplist_parser::{
+        builder::{AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+        sort::SortField,
+        JumplistType,
+    };
+    use std::io::Cursor;
+
+    let sample = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let first = serde_json::to_string(&JumplistParser::from_path(sample).unwrap()).unwrap();
+    for _ in 0..50 {
+        let parsed = JumplistParser::from_path(sample).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), first);
+    }
+
+    // Three entries sharing number 2, written out of order in the stream
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry::new(2, r"C:\first.txt"))
+        .entry(AutomaticEntry::new(1, r"C:\one.txt"))
+        .entry(AutomaticEntry::new(2, r"C:\second.txt"))
+        .entry(AutomaticEntry::new(3, r"C:\three.txt"))
+        .entry(AutomaticEntry::new(2, r"C:\third.txt"))
+        .to_bytes()
+        .unwrap();
+    let parse = || {
+        JumplistParser::from_reader(&mut Cursor::new(data.clone()), JumplistType::Automatic)
+            .unwrap()
+    };
+    let parsed = parse();
+    let destlist = parsed.destlist().unwrap();
+    assert_eq!(destlist.duplicate_entry_numbers, vec![2]);
+    let paths: Vec<&str> = destlist.entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            r"C:\three.txt",
+            r"C:\first.txt",
+            r"C:\second.txt",
+            r"C:\third.txt",
+            r"C:\one.txt"
+        ]
+    );
+    assert!(destlist
+        .entries
+        .windows(2)
+        .filter(|w| w[0].entry_number == w[1].entry_number)
+        .all(|w| w[0].stream_offset < w[1].stream_offset));
+
+    let serialized = serde_json::to_string(&parsed).unwrap();
+    for field in [
+        SortField::DestListMtime,
+        SortField::TargetModificationTime,
+        SortField::TargetFullPath,
+        SortField::AppName,
+        SortField::EntryNumber,
+    ] {
+        for descending in [false, true] {
+            let sorted: Vec<String> = parsed
+                .sorted_entries(field, descending)
+                .iter()
+                .map(|e| e.target_path().unwrap_or_default())
+                .collect();
+            for _ in 0..50 {
+                let again = parse();
+                assert_eq!(serde_json::to_string(&again).unwrap(), serialized);
+                let paths: Vec<String> = again
+                    .sorted_entries(field, descending)
+                    .iter()
+                    .map(|e| e.target_path().unwrap_or_default())
+                    .collect();
+                assert_eq!(paths, sorted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn volume_summary() {
+    use jumplist_parser::{
+        aggregate::{Summary, VolumeCounts},
+        lnk_info::{DriveType, LinkInfo},
+    };
+
+    let link_info = |drive_type, network_share: Option<&str>| LinkInfo {
+        drive_type,
+        volume_serial: None,
+        volume_label: None,
+        local_base_path: None,
+        network_share: network_share.map(|s| s.to_string()),
+        common_path_suffix: None,
+        network_device_name: None,
+        network_provider: None,
+    };
+    let mut counts = VolumeCounts::default();
+    counts.add(Some(&link_info(Some(DriveType::Removable), None)));
+    counts.add(Some(&link_info(Some(DriveType::Fixed), None)));
+    counts.add(Some(&link_info(None, Some(r"\\FILESRV\Share"))));
+    counts.add(Some(&link_info(Some(DriveType::Cdrom), None)));
+    counts.add(Some(&link_info(Some(DriveType::Ramdisk), None)));
+    counts.add(Some(&link_info(None, None)));
+    counts.add(None);
+    assert_eq!(
+        counts,
+        VolumeCounts {
+            fixed: 1,
+            removable: 1,
+            remote: 1,
+            cdrom: 1,
+            other: 1,
+            unknown: 2,
+        }
+    );
+
+    let paths: Vec<String> = glob("samples/win10/AutomaticDestinations/*")
+        .unwrap()
+        .map(|p| p.unwrap().to_string_lossy().to_string())
+        .collect();
+    let parsed: Vec<JumplistParser> = paths
+        .iter()
+        .map(|p| JumplistParser::from_path(p).unwrap())
+        .collect();
+    let summary = Summary::from_parsers(&parsed);
+    assert!(summary.apps.windows(2).all(|w| w[0].app_id < w[1].app_id));
+    assert_eq!(
+        summary.apps.iter().map(|a| a.files).sum::<usize>(),
+        parsed.len()
+    );
+    for app in &summary.apps {
+        let volumes = &app.volumes;
+        assert_eq!(
+            volumes.fixed
+                + volumes.removable
+                + volumes.remote
+                + volumes.cdrom
+                + volumes.other
+                + volumes.unknown,
+            app.entries
+        );
+        assert!(app.volume_serials.windows(2).all(|w| w[0] < w[1]));
+    }
+    let explorer = summary
+        .apps
+        .iter()
+        .find(|a| a.app_id.as_deref() == Some("f01b4d95cf55d32a"))
+        .unwrap();
+    assert_eq!(explorer.entries, 24);
+    assert_eq!(explorer.volumes.fixed, 24);
+    assert_eq!(explorer.volume_serials, ["405B-FCF0"]);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn summary_argument() {
+    let run = |format: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                "samples/win10/AutomaticDestinations/*",
+                "--summary",
+                "--output-format",
+                format,
+            ])
+            .output()
+            .unwrap()
+    };
+    let csv = String::from_utf8(run("csv").stdout)
+        .unwrap()
+        .replace('"', "");
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("app_id,app_name,files,entries,fixed,removable,remote,cdrom,other,unknown,volume_serials,unc_servers,format_versions,categories")
+    );
+    let explorer = lines.find(|l| l.starts_with("f01b4d95cf55d32a,")).unwrap();
+    assert!(
+        explorer.ends_with(",1,24,24,0,0,0,0,0,1,0,automatic:4,other:24"),
+        "{}",
+        explorer
+    );
+
+    let json: Vec<serde_json::Value> = serde_json::from_slice(&run("json").stdout).unwrap();
+    let explorer = json
+        .iter()
+        .find(|a| a["app_id"] == "f01b4d95cf55d32a")
+        .unwrap();
+    assert_eq!(explorer["volumes"]["fixed"], 24);
+    assert_eq!(explorer["volume_serials"], serde_json::json!(["405B-FCF0"]));
+    assert_eq!(explorer["unc_servers"], serde_json::json!([]));
+    assert_eq!(explorer["categories"], serde_json::json!({"other": 24}));
+
+    assert_eq!(run("tln").status.code(), Some(1));
+}
+
+#[cfg(test)]
+#[test]
+fn forced_jumplist_type() {
+    use jumplist_parser::JumplistType;
+
+    let dir = std::env::temp_dir().join(format!("jumplist_forced_type_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let custom = "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms";
+    let automatic = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let renamed = dir.join("1ced32d74a95c7bc.automaticDestinations-ms");
+    std::fs::copy(custom, &renamed).unwrap();
+    let carved = dir.join("carved.bin");
+    std::fs::copy(automatic, &carved).unwrap();
+    let renamed = renamed.to_str().unwrap();
+    let carved = carved.to_str().unwrap();
+
+    assert!(JumplistParser::from_path(renamed).is_err());
+    let parsed = JumplistParser::from_path_with_type(renamed, JumplistType::Custom).unwrap();
+    assert_eq!(parsed.r#type, JumplistType::Custom);
+    assert_eq!(parsed.app_id.as_deref(), Some("1ced32d74a95c7bc"));
+    assert!(parsed.app_id_valid);
+    assert_eq!(
+        parsed.entry_count(),
+        JumplistParser::from_path(custom).unwrap().entry_count()
+    );
+
+    let parsed = JumplistParser::from_path_with_type(carved, JumplistType::Automatic).unwrap();
+    assert_eq!(parsed.entry_count(), 24);
+    assert!(!parsed.app_id_valid);
+
+    // Misforced types fail like corrupt files, without panicking
+    assert!(JumplistParser::from_path_with_type(custom, JumplistType::Automatic).is_err());
+    let _ = JumplistParser::from_path_with_type(automatic, JumplistType::Custom);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn force_type_arguments() {
+    let dir = std::env::temp_dir().join(format!("jumplist_force_type_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let custom = dir.join("1ced32d74a95c7bc.automaticDestinations-ms");
+    std::fs::copy(
+        "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+        &custom,
+    )
+    .unwrap();
+    let automatic = dir.join("carved.bin");
+    std::fs::copy(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        &automatic,
+    )
+    .unwrap();
+    let custom = custom.to_str().unwrap();
+    let automatic = automatic.to_str().unwrap();
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args(["--output-format", "jsonl"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let types = |output: &std::process::Output| -> Vec<String> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| {
+                let json: serde_json::Value = serde_json::from_str(line).unwrap();
+                json["type"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    let output = run(&["-p", custom, "--force-type", "custom"]);
+    assert_eq!(types(&output), ["custom"]);
+    let output = run(&[
+        "-p",
+        &format!("{}:custom", custom),
+        "-p",
+        &format!("{}:automatic", automatic),
+    ]);
+    assert_eq!(types(&output), ["custom", "automatic"]);
+    // The suffix of a path wins over --force-type
+    let output = run(&[
+        "-p",
+        &format!("{}:automatic", automatic),
+        "-p",
+        custom,
+        "--force-type",
+        "custom",
+    ]);
+    assert_eq!(types(&output), ["automatic", "custom"]);
+
+    let misforced = run(&["-p", custom, "--force-type", "automatic"]);
+    assert!(misforced.status.success());
+    assert!(misforced.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&misforced.stderr);
+    assert!(stderr.contains("Did not parse"), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn empty_or_truncated_files() {
+    use jumplist_parser::{errors::JumplistParserError, JumplistType};
+
+    let dir = std::env::temp_dir().join(format!("jumplist_empty_files_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let empty = dir.join("1ced32d74a95c7bc.customDestinations-ms");
+    std::fs::write(&empty, b"").unwrap();
+    let fragment = dir.join("f01b4d95cf55d32a.automaticDestinations-ms");
+    let sample = std::fs::read(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    std::fs::write(&fragment, &sample[..100]).unwrap();
+    let unnamed = dir.join("carved.bin");
+    std::fs::write(&unnamed, [0; 4]).unwrap();
+
+    for (path, expected_size) in [(&empty, 0), (&fragment, 100), (&unnamed, 4)] {
+        let path = path.to_str().unwrap();
+        match JumplistParser::from_path(path) {
+            Err(e @ JumplistParserError::EmptyOrTruncatedFile { .. }) => {
+                assert_eq!(e.kind(), "empty_or_truncated_file");
+                assert_eq!(e.message(), path);
+                match e {
+                    JumplistParserError::EmptyOrTruncatedFile { size, .. } => {
+                        assert_eq!(size, expected_size)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            other => panic!("{}: {:?}", path, other.map(|p| p.entry_count())),
+        }
+    }
+    assert!(matches!(
+        JumplistParser::from_bytes(
+            &sample[..511],
+            Some("f01b4d95cf55d32a.automaticDestinations-ms")
+        ),
+        Err(JumplistParserError::EmptyOrTruncatedFile { size: 511, .. })
+    ));
+    assert!(matches!(
+        JumplistParser::from_bytes(b"", None),
+        Err(JumplistParserError::EmptyOrTruncatedFile { size: 0, .. })
+    ));
+    assert_eq!(JumplistType::Custom.min_file_size(), 12);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn empty_files_summary() {
+    let dir = std::env::temp_dir().join(format!("jumplist_empty_summary_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["1ced32d74a95c7bc", "5d696d521de238c3", "9b9cdc69c1c24e2b"] {
+        std::fs::write(dir.join(format!("{}.customDestinations-ms", name)), b"").unwrap();
+    }
+    std::fs::copy(
+        "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+        dir.join("7e4dca80246863e3.customDestinations-ms"),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .arg("-p")
+        .arg(dir.join("*"))
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Skipped 3 empty jumplist files"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("Did not parse"), "{}", stderr);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("7e4dca80246863e3"));
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_slack() {
+    use jumplist_parser::{
+        builder::{AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::{DestList, DestListVersion},
+        verify::{verify_path, VerifyStatus},
+    };
+    use std::io::Cursor;
+
+    // The committed samples have no slack
+    for (sample, size) in [
+        (
+            "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+            5348,
+        ),
+        (
+            "samples/win11/AutomaticDestinations/5f7b5f1e01b83767.automaticDestinations-ms",
+            8188,
+        ),
+    ] {
+        let parsed = JumplistParser::from_path(sample).unwrap();
+        let destlist = parsed.destlist().unwrap();
+        assert_eq!(destlist.stream_size, size);
+        assert_eq!(destlist.bytes_consumed, size);
+        assert_eq!(destlist.slack_bytes(), 0);
+        assert_eq!(destlist.slack_ratio(), 0.0);
+        assert_eq!(destlist.slack_note(), None);
+    }
+    let empty = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/d06c94537ecaee12.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert_eq!(empty.destlist().unwrap().stream_size, 0);
+    assert_eq!(empty.destlist().unwrap().slack_ratio(), 0.0);
+
+    // Deleted entries leave bytes after the last entry
+    let builder = AutomaticJumplistBuilder {
+        omit_destlist: true,
+        ..AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+    }
+    .entry(AutomaticEntry {
+        mtime: 133_000_000_000_000_000,
+        ..AutomaticEntry::new(1, r"C:\a.txt")
+    });
+    let mut stream = builder.destlist_bytes();
+    let entries_end = stream.len() as u64;
+    stream.extend_from_slice(&[b'A'; 300]);
+    let destlist = DestList::parse_stream(&mut Cursor::new(&stream)).unwrap();
+    assert_eq!(destlist.entries.len(), 1);
+    assert_eq!(destlist.stream_size, entries_end + 300);
+    assert_eq!(destlist.bytes_consumed, entries_end);
+    assert_eq!(destlist.slack_bytes(), 300);
+    let note = destlist.slack_note().unwrap();
+    assert!(note.starts_with("300 of the "), "{}", note);
+    let json = serde_json::to_value(&destlist).unwrap();
+    assert_eq!(json["stream_size"], entries_end + 300);
+    assert_eq!(json["bytes_consumed"], entries_end);
+
+    let path = std::env::temp_dir().join(format!(
+        "jumplist_slack_{}.automaticDestinations-ms",
+        std::process::id()
+    ));
+    builder
+        .stream("DestList", stream)
+        .write_to(path.to_str().unwrap())
+        .unwrap();
+    let result = verify_path(path.to_str().unwrap(), false);
+    #[cfg(feature = "cli")]
+    let summary = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args(["-p", path.to_str().unwrap(), "--summary"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(result.status, VerifyStatus::Ok);
+    assert_eq!(result.notes, std::slice::from_ref(&note));
+    #[cfg(feature = "cli")]
+    assert!(String::from_utf8_lossy(&summary.stderr).contains(&note));
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_stream_lookup() {
+    use jumplist_parser::{
+        builder::{lnk_bytes, AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+    };
+
+    let builder =
+        AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus).entry(AutomaticEntry {
+            lnk: Some(lnk_bytes(r"C:\Users\user\report.docx")),
+            ..AutomaticEntry::new(1, r"C:\Users\user\report.docx")
+        });
+    let expected = JumplistParser::from_bytes(&builder.to_bytes().unwrap(), None).unwrap();
+    let destlist = builder.destlist_bytes();
+    let renamed = |name: &str| AutomaticJumplistBuilder {
+        omit_destlist: true,
+        ..builder.clone().stream(name, destlist.clone())
+    };
+
+    for builder in [
+        renamed("destlist"),
+        renamed("DESTLIST"),
+        renamed("Storage/DestList"),
+        // Streams of unknown storages are only reported
+        renamed("destlist").stream("Extension/Settings/State", vec![1, 2, 3]),
+    ] {
+        let bytes = builder.to_bytes().unwrap();
+        let parsed = JumplistParser::from_bytes(&bytes, None).unwrap();
+        let destlist = parsed.destlist().unwrap();
+        assert_eq!(destlist.entries, expected.destlist().unwrap().entries);
+        assert!(
+            destlist
+                .warnings
+                .iter()
+                .all(|w| w == "Unexpected stream '/Extension/Settings/State' in the compound file"),
+            "{:?}",
+            destlist.warnings
+        );
+        assert_eq!(
+            parsed.entries().next().unwrap().target_path().as_deref(),
+            Some(r"C:\Users\user\report.docx")
+        );
+        assert!(parsed
+            .extra_streams
+            .iter()
+            .all(|s| !s.name.eq_ignore_ascii_case("DestList")));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_path_mismatch() {
+    use jumplist_parser::{
+        builder::{lnk_bytes, AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+        output::{csv_columns, csv_rows, CsvOptions},
+    };
+
+    // The first file was renamed after it was last opened, the second path only differs in
+    // case and separators
+    let bytes = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry {
+            lnk: Some(lnk_bytes(r"C:\Users\user\report-final.docx")),
+            ..AutomaticEntry::new(2, r"C:\Users\user\report.docx")
+        })
+        .entry(AutomaticEntry {
+            lnk: Some(lnk_bytes(r"C:\Users\user\notes.txt")),
+            ..AutomaticEntry::new(1, "c:/users/USER/Notes.txt")
+        })
+        .to_bytes()
+        .unwrap();
+    let parsed = JumplistParser::from_bytes(&bytes, None).unwrap();
+    let entries = &parsed.destlist().unwrap().entries;
+    assert!(entries[0].path_mismatch);
+    assert!(!entries[1].path_mismatch);
+    let json = serde_json::to_value(&entries[0]).unwrap();
+    assert_eq!(json["path_mismatch"], true);
+
+    let records = parsed.records();
+    assert_eq!(records[0]["destlist_path"], r"C:\Users\user\report.docx");
+    assert_eq!(
+        records[0]["target_full_path"],
+        r"C:\Users\user\report-final.docx"
+    );
+    assert_eq!(records[0]["path_mismatch"], "true");
+    assert_eq!(records[1]["destlist_path"], "c:/users/USER/Notes.txt");
+    assert_eq!(records[1]["path_mismatch"], "false");
+
+    let options = CsvOptions {
+        extended_columns: true,
+        ..Default::default()
+    };
+    let columns = csv_columns(&options);
+    let column = |name: &str| columns.iter().position(|c| *c == name).unwrap();
+    let rows = csv_rows(&parsed, &options);
+    assert_eq!(
+        rows[0][column("destlist_path")],
+        r"C:\Users\user\report.docx"
+    );
+    assert_eq!(rows[0][column("path_mismatch")], "true");
+    assert_eq!(rows[1][column("path_mismatch")], "false");
+    assert!(!csv_columns(&CsvOptions::default()).contains(&"path_mismatch"));
+
+    // Known folders of the DestList name no file path, they aren't compared
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let records = parsed.records();
+    assert!(records
+        .iter()
+        .any(|r| r["destlist_path"].starts_with("knownfolder:")));
+    assert!(records.iter().all(|r| r["path_mismatch"] == "false"));
+
+    // Without an LNK stream the DestList fields are still there
+    let bytes = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry {
+            lnk: None,
+            mtime: 133_000_000_000_000_000,
+            ..AutomaticEntry::new(7, r"C:\Users\user\missing.docx")
+        })
+        .to_bytes()
+        .unwrap();
+    let parsed = JumplistParser::from_bytes(&bytes, None).unwrap();
+    let records = parsed.records();
+    assert_eq!(records[0]["lnk_status"], "missing");
+    assert_eq!(records[0]["destlist_entry_number"], "7");
+    assert_eq!(
+        records[0]["destlist_interaction_time"],
+        "2022-06-18T04:26:40Z"
+    );
+    assert_eq!(records[0]["destlist_path"], r"C:\Users\user\missing.docx");
+    assert_eq!(
+        records[0]["target_full_path"],
+        r"C:\Users\user\missing.docx"
+    );
+    assert_eq!(records[0]["path_mismatch"], "false");
+    assert_eq!(
+        csv_rows(&parsed, &options)[0][column("destlist_path")],
+        r"C:\Users\user\missing.docx"
+    );
+}
+
+#[cfg(test)]
+#[test]
+#[allow(deprecated)]
+fn category_names() {
+    use jumplist_parser::custom_destinations::{
+        Catagory, CatagoryType, Category, CategoryID, CategoryId, CategoryType, CustomDestinations,
+        CustomDestinationsHeader,
+    };
+
+    let sample = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let custom = CustomDestinations::from_path(sample).unwrap();
+    assert_eq!(custom.header.reserved, 2);
+    let json = serde_json::to_value(&custom.header).unwrap();
+    assert_eq!(json["reserved"], 2);
+    let header: CustomDestinationsHeader =
+        serde_json::from_str(r#"{"version": 2, "num_of_cat": 1, "unkonwn": 7}"#).unwrap();
+    assert_eq!(header.reserved, 7);
+    let header: CustomDestinationsHeader =
+        serde_json::from_str(r#"{"version": 2, "num_of_cat": 1}"#).unwrap();
+    assert_eq!(header.reserved, 0);
+
+    // The former names still compile
+    let category: &Category = &custom.entries[0];
+    let old: &Catagory = category;
+    let old_type: CatagoryType = CategoryType::Custom;
+    assert_eq!(old.r#type, old_type);
+    let id: CategoryID = CategoryId::Recent;
+    assert_eq!(id.to_string(), "recent");
+
+    #[cfg(feature = "cli")]
+    {
+        let header = |legacy: bool| {
+            let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"));
+            command.args(["-p", sample, "--output-format", "jsonl"]);
+            if legacy {
+                command.arg("--legacy-keys");
+            }
+            let output = command.output().unwrap();
+            let record: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            record["data"]["header"].clone()
+        };
+        assert_eq!(header(false)["reserved"], 2);
+        assert_eq!(header(true).get("reserved"), None);
+        assert_eq!(header(true)["num_of_cat"], 3);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn progress_events() {
+    use jumplist_parser::{
+        builder::{AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+        ParseOptions, Progress, ProgressEvent,
+    };
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(vec![]));
+    let recorded = events.clone();
+    let options = ParseOptions {
+        progress: Some(Progress::new(move |event| {
+            recorded.lock().unwrap().push(event)
+        })),
+        ..Default::default()
+    };
+
+    let builder = (1..=250).fold(
+        AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus),
+        |builder, number| {
+            builder.entry(AutomaticEntry::new(number, &format!(r"C:\{}.txt", number)))
+        },
+    );
+    let bytes = builder.to_bytes().unwrap();
+    // The options are cloned with their callback
+    JumplistParser::from_bytes_with_options(&bytes, None, &options.clone()).unwrap();
+    assert_eq!(
+        std::mem::take(&mut *events.lock().unwrap()),
+        [
+            ProgressEvent::HeaderParsed { declared: 250 },
+            ProgressEvent::EntriesParsed { entries: 100 },
+            ProgressEvent::EntriesParsed { entries: 200 },
+            ProgressEvent::LnksAttached {
+                attached: 100,
+                total: 250
+            },
+            ProgressEvent::LnksAttached {
+                attached: 200,
+                total: 250
+            },
+            ProgressEvent::LnksAttached {
+                attached: 250,
+                total: 250
+            },
+        ]
+    );
+
+    JumplistParser::from_path_with_options(
+        "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        std::mem::take(&mut *events.lock().unwrap()),
+        [ProgressEvent::HeaderParsed { declared: 3 }]
+    );
+
+    // The progress bar is only drawn on a terminal
+    #[cfg(feature = "cli")]
+    {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                "samples/win10/AutomaticDestinations/*",
+                "--output-format",
+                "jsonl",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(
+            output.stderr.is_empty(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn best_target_path_sources() {
+    use jumplist_parser::{
+        builder::{lnk_bytes, AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+        filetype::FileCategory,
+        lnk_info::{LnkDetails, TargetPathSource},
+        timestamp::TimestampFormat,
+        Flatten,
+    };
+
+    for (path, index, source, target) in [
+        (
+            "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+            1,
+            TargetPathSource::LinkInfo,
+            r"C:\Users\u0041\Desktop\sim\files\code\yaml",
+        ),
+        (
+            "samples/win10/AutomaticDestinations/7e4dca80246863e3.automaticDestinations-ms",
+            0,
+            TargetPathSource::ShellItems,
+            r"::{26EE0668-A00A-44D7-9371-BEB064C98683}\5\::{BB06C0E4-D293-4F75-8A90-CB05B6477EEE}",
+        ),
+        (
+            "samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms",
+            0,
+            TargetPathSource::LinkInfo,
+            r"C:\Program Files\Microsoft VS Code\Code.exe",
+        ),
+        (
+            "samples/win11/AutomaticDestinations/a52b0784bd667468.automaticDestinations-ms",
+            3,
+            TargetPathSource::ShellItems,
+            "ms-photos:spareprocess-viewer",
+        ),
+        (
+            "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms",
+            2,
+            TargetPathSource::LinkInfo,
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        ),
+    ] {
+        let parsed = JumplistParser::from_path(path).unwrap();
+        let entry = parsed.entries().nth(index).unwrap();
+        assert_eq!(
+            entry.target_path_source(),
+            Some(source),
+            "{} {}",
+            path,
+            index
+        );
+        assert_eq!(entry.best_target_path(), Some(target), "{} {}", path, index);
+        let normalized = &parsed.flatten()[index];
+        assert_eq!(normalized["target_path_source"], source.to_string());
+        assert_eq!(normalized["target_full_path"], target);
+    }
+
+    // The LNK has no path, the DestList path is the last resort
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry {
+            lnk: Some(lnk_bytes("")),
+            ..AutomaticEntry::new(1, r"C:\Users\user\Desktop\notes.txt")
+        })
+        .entry(AutomaticEntry {
+            path: String::new(),
+            lnk: Some(lnk_bytes("")),
+            ..AutomaticEntry::new(2, "")
+        })
+        .to_bytes()
+        .unwrap();
+    let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+    // Most recently used first
+    let entries: Vec<_> = parsed.entries().collect();
+    assert_eq!(entries[0].target_path_source(), None);
+    assert_eq!(entries[0].best_target_path(), None);
+    assert_eq!(
+        entries[1].target_path_source(),
+        Some(TargetPathSource::Destlist)
+    );
+    assert_eq!(
+        entries[1].best_target_path(),
+        Some(r"C:\Users\user\Desktop\notes.txt")
+    );
+    let normalized = parsed.flatten();
+    assert_eq!(normalized[0]["target_path_source"], "");
+    assert_eq!(normalized[0]["target_full_path"], "");
+    assert_eq!(normalized[1]["target_path_source"], "destlist");
+    assert_eq!(
+        normalized[1]["target_full_path"],
+        r"C:\Users\user\Desktop\notes.txt"
+    );
+
+    // No LNK stream at all, the DestList path is classified like an LNK target
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry {
+            lnk: None,
+            ..AutomaticEntry::new(1, r"C:\Tools\PsExec64.exe")
+        })
+        .to_bytes()
+        .unwrap();
+    let parsed = JumplistParser::from_bytes(&data, None).unwrap();
+    let entry = parsed.entries().next().unwrap();
+    assert!(entry.lnk().is_none());
+    assert_eq!(entry.target_path_source(), Some(TargetPathSource::Destlist));
+    assert_eq!(entry.best_target_path(), Some(r"C:\Tools\PsExec64.exe"));
+    let normalized = &parsed.flatten()[0];
+    assert_eq!(normalized["target_path_source"], "destlist");
+    assert_eq!(normalized["target_full_path"], r"C:\Tools\PsExec64.exe");
+    assert_eq!(normalized["target_type"], "file");
+    assert_eq!(normalized["target_category"], "executable");
+    assert_eq!(parsed.target_categories(), [FileCategory::Executable]);
+
+    // Network target: the share and the common path suffix
+    let mut link_info = vec![];
+    for value in [0u32, 0x1C, 2, 0, 0, 0x1C, 0x3E] {
+        link_info.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [0x21u32, 3, 0x14, 0, 0x0002_0000] {
+        link_info.extend_from_slice(&value.to_le_bytes());
+    }
+    link_info.extend_from_slice(b"\\\\srv\\share\0\0\0Reports\\q3.xlsx\0");
+    let size = link_info.len() as u32;
+    link_info[..4].copy_from_slice(&size.to_le_bytes());
+    let mut lnk = vec![0; 0x4C];
+    lnk[0] = 0x4C;
+    lnk[0x14] = 0x02;
+    lnk.extend_from_slice(&link_info);
+    lnk.extend_from_slice(&[0; 4]);
+    let details = LnkDetails::from_buffer(&lnk, TimestampFormat::default()).unwrap();
+    assert_eq!(
+        details.target_path_source,
+        Some(TargetPathSource::NetworkShare)
+    );
+    assert_eq!(
+        details.target_path.as_deref(),
+        Some(r"\\srv\share\Reports\q3.xlsx")
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn droid_guid_format() {
+    use jumplist_parser::{
+        builder::{AutomaticEntry, AutomaticJumplistBuilder},
+        destlist::DestListVersion,
+        guid::{Droid, GuidFormat, GuidStyle, ZeroGuid},
+        Flatten, ParseOptions,
+    };
+
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let parse = |guid_format: GuidFormat| {
+        let options = ParseOptions {
+            guid_format,
+            ..Default::default()
+        };
+        JumplistParser::from_path_with_options(sample, &options).unwrap()
+    };
+    let default = parse(GuidFormat::default());
+    for (format, file_droid, volume_droid) in [
+        (
+            "upper",
+            "663732CB-5C0D-11F0-8152-BC241114E2F4",
+            "E193D864-C7E0-4660-896E-E4E81879E74C",
+        ),
+        (
+            "lower",
+            "663732cb-5c0d-11f0-8152-bc241114e2f4",
+            "e193d864-c7e0-4660-896e-e4e81879e74c",
+        ),
+        (
+            "braced",
+            "{663732CB-5C0D-11F0-8152-BC241114E2F4}",
+            "{E193D864-C7E0-4660-896E-E4E81879E74C}",
+        ),
+        (
+            "braced-lower",
+            "{663732cb-5c0d-11f0-8152-bc241114e2f4}",
+            "{e193d864-c7e0-4660-896e-e4e81879e74c}",
+        ),
+        (
+            "urn",
+            "urn:uuid:663732cb-5c0d-11f0-8152-bc241114e2f4",
+            "urn:uuid:e193d864-c7e0-4660-896e-e4e81879e74c",
+        ),
+    ] {
+        let parsed = parse(format.parse().unwrap());
+        let entry = &parsed.destlist().unwrap().entries[0];
+        let json = serde_json::to_value(entry).unwrap();
+        assert_eq!(json["file_droid"], file_droid, "{}", format);
+        assert_eq!(json["file_birth_droid"], file_droid, "{}", format);
+        assert_eq!(json["volume_droid"], volume_droid, "{}", format);
+        assert_eq!(json["volume_birth_droid"], volume_droid, "{}", format);
+        let normalized = &parsed.flatten()[0];
+        assert_eq!(normalized["destlist_file_droid"], file_droid, "{}", format);
+        assert_eq!(normalized["destlist_volume_droid"], volume_droid);
+        // The fingerprints don't depend on the format
+        assert_eq!(
+            normalized["entry_fingerprint"],
+            default.flatten()[0]["entry_fingerprint"]
+        );
+        // Any format reads back
+        let droid: Droid = serde_json::from_value(json["file_droid"].clone()).unwrap();
+        assert_eq!(droid, entry.file_droid.guid);
+    }
+
+    // Zero droids
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V4Win10_1703Plus)
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\Desktop\notes.txt"))
+        .to_bytes()
+        .unwrap();
+    let droids = [
+        "volume_droid",
+        "file_droid",
+        "volume_birth_droid",
+        "file_birth_droid",
+    ];
+    for (zero, expected) in [
+        (
+            ZeroGuid::Keep,
+            Some(serde_json::json!("{00000000-0000-0000-0000-000000000000}")),
+        ),
+        (ZeroGuid::Null, Some(serde_json::Value::Null)),
+        (ZeroGuid::Omit, None),
+    ] {
+        let options = ParseOptions {
+            guid_format: GuidFormat {
+                style: GuidStyle::Braced,
+                zero,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parsed = JumplistParser::from_bytes_with_options(&data, None, &options).unwrap();
+        let entry = &parsed.destlist().unwrap().entries[0];
+        let json = serde_json::to_value(entry).unwrap();
+        let normalized = &parsed.flatten()[0];
+        for droid in droids {
+            assert_eq!(json.get(droid), expected.as_ref(), "{:?} {}", zero, droid);
+            let value = normalized[&format!("destlist_{}", droid)].as_str();
+            match zero {
+                ZeroGuid::Keep => assert_eq!(value, "{00000000-0000-0000-0000-000000000000}"),
+                _ => assert_eq!(value, ""),
+            }
+        }
+        // Omitted and null droids read back as zero
+        let back: jumplist_parser::destlist::DestListEntry = serde_json::from_value(json).unwrap();
+        assert!(back.file_droid.is_zero());
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn guid_format_argument() {
+    let sample = "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms";
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args([
+            "-p",
+            sample,
+            "--output-format",
+            "jsonl",
+            "--guid-format",
+            "urn",
+        ])
+        .args(["--normalize", "--fields", "destlist_file_droid"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // One array of normalized entries per file
+    let rows: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap()).unwrap();
+    assert_eq!(
+        rows[0]["destlist_file_droid"],
+        "urn:uuid:663732cb-5c0d-11f0-8152-bc241114e2f4"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn recent_lnk_files() {
+    use jumplist_parser::{
+        builder::lnk_bytes, errors::JumplistParserError, parse_lnk_file, Flatten, JumplistType,
+        ParseOptions,
+    };
+
+    let dir = std::env::temp_dir().join("jumplist_parser_recent_lnk");
+    std::fs::create_dir_all(&dir).unwrap();
+    let lnk = dir.join("notes.txt.lnk");
+    std::fs::write(&lnk, lnk_bytes(r"C:\Users\user\Desktop\notes.txt")).unwrap();
+
+    let mut parsed = parse_lnk_file(lnk.to_str().unwrap(), &ParseOptions::default()).unwrap();
+    assert_eq!(parsed.r#type, JumplistType::RecentLnk);
+    assert!(parsed.is_recent_lnk() && parsed.is_complete());
+    assert_eq!(parsed.app_id.as_deref().unwrap_or_default(), "");
+    let rows = parsed.records();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["jumplist_type"], "recent_lnk");
+    assert_eq!(rows[0]["jumplist_app_id"], "");
+    assert_eq!(rows[0]["jumplist_app_name"], "");
+    assert_eq!(
+        rows[0]["target_full_path"],
+        r"C:\Users\user\Desktop\notes.txt"
+    );
+    assert_eq!(rows[0]["entry_pinned"], "false");
+    assert!(rows[0]["entry_fingerprint"].starts_with("v1:"));
+    assert!(!rows[0].contains_key("destlist_entry_number"));
+    let entry = parsed.entries().next().unwrap();
+    assert_eq!(
+        entry.target_path().as_deref(),
+        Some(r"C:\Users\user\Desktop\notes.txt")
+    );
+
+    parsed.retain_entries(|_| false);
+    assert_eq!(parsed.entries().count(), 0);
+    assert!(parsed.flatten().is_empty());
+
+    // Not a LNK file
+    let garbage = dir.join("garbage.lnk");
+    std::fs::write(&garbage, [0u8; 0x100]).unwrap();
+    assert!(matches!(
+        parse_lnk_file(garbage.to_str().unwrap(), &ParseOptions::default()),
+        Err(JumplistParserError::LnkEntry(..))
+    ));
+    // The right header size but another CLSID
+    let mut data = [0u8; 0x100];
+    data[0] = 0x4C;
+    std::fs::write(&garbage, data).unwrap();
+    assert!(matches!(
+        parse_lnk_file(garbage.to_str().unwrap(), &ParseOptions::default()),
+        Err(JumplistParserError::LnkEntry(..))
+    ));
+    std::fs::remove_file(&garbage).unwrap();
+}
+
+#[cfg(test)]
+#[cfg(feature = "cli")]
+#[test]
+fn include_recent_lnks_argument() {
+    let dir = std::env::temp_dir().join("jumplist_parser_include_recent_lnks");
+    std::fs::create_dir_all(&dir).unwrap();
+    let lnk = dir.join("report.docx.lnk");
+    std::fs::write(
+        &lnk,
+        jumplist_parser::builder::lnk_bytes(r"C:\Users\user\Documents\report.docx"),
+    )
+    .unwrap();
+    let pattern = format!("{}/*.lnk", dir.display());
+    let run = |include: bool| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"));
+        command.args(["-p", &pattern, "--output-format", "jsonl", "--normalize"]);
+        if include {
+            command.arg("--include-recent-lnks");
+        }
+        command.output().unwrap()
+    };
+
+    let output = run(true);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap()).unwrap();
+    assert_eq!(rows[0]["jumplist_type"], "recent_lnk");
+    assert_eq!(rows[0]["jumplist_app_id"], "");
+    assert_eq!(
+        rows[0]["target_full_path"],
+        r"C:\Users\user\Documents\report.docx"
+    );
+
+    // Without the flag the file name gives no Jumplist type
+    let output = run(false);
+    assert!(String::from_utf8(output.stdout).unwrap().trim().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn unknown_destlist_version() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::{DestList, DestListVersion};
+    use jumplist_parser::verify::{verify_path, VerifyStatus};
+    use jumplist_parser::{ParseMode, ParseOptions};
+
+    let data = AutomaticJumplistBuilder::new(5)
+        .entry(AutomaticEntry::new(
+            2,
+            r"C:\Users\user\Documents\report.docx",
+        ))
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\Desktop\notes.txt"))
+        .to_bytes()
+        .unwrap();
+    let name = "0123456789abcdef.automaticDestinations-ms";
+
+    // Parsed with the newest known layout, with a warning
+    let parsed = JumplistParser::from_bytes(&data, Some(name)).unwrap();
+    let destlist = parsed.destlist().unwrap();
+    assert_eq!(destlist.header.version, DestListVersion::Unknown(5));
+    assert!(!destlist.header.version_supported);
+    assert_eq!(destlist.entries.len(), 2);
+    assert_eq!(
+        destlist.entries[0].path,
+        r"C:\Users\user\Documents\report.docx"
+    );
+    assert!(parsed.warnings()[0].starts_with("Unknown DestList version 5"));
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(json["data"]["header"]["version_supported"], false);
+
+    // The known versions are supported, version 6 included
+    let stream = std::fs::read("samples/other/DestList").unwrap();
+    let destlist = DestList::parse_stream(&mut std::io::Cursor::new(stream)).unwrap();
+    assert_eq!(destlist.header.version, DestListVersion::V6Win11);
+    assert!(destlist.header.version_supported && destlist.warnings.is_empty());
+    let parsed = JumplistParser::from_path(
+        "samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms",
+    )
+    .unwrap();
+    assert!(parsed.destlist().unwrap().header.version_supported);
+
+    // An error in strict mode
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    let err = JumplistParser::from_bytes_with_options(&data, Some(name), &strict).unwrap_err();
+    assert!(err.to_string().contains("Unknown DestList version 5"));
+
+    // A failure of `verify`
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, &data).unwrap();
+    let result = verify_path(path.to_str().unwrap(), false);
+    assert_eq!(result.status, VerifyStatus::Fail);
+    assert_eq!(result.version_supported, Some(false));
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["version_supported"], false);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn custom_destinations_categories() {
+    use jumplist_parser::custom_destinations::{CategoryType, CustomDestinations};
+    use jumplist_parser::Flatten;
+    use std::io::Cursor;
+
+    let path = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let custom = CustomDestinations::from_path(path).unwrap();
+    assert_eq!(custom.categories().len(), 3);
+    assert_eq!(custom.total_entries(), 6);
+    for category in custom.categories() {
+        if category.r#type == CategoryType::Custom {
+            assert_eq!(
+                category.declared_entry_count(),
+                Some(category.entry_count() as u32)
+            );
+        }
+    }
+    let entries: Vec<_> = custom.iter_entries().collect();
+    assert_eq!(entries.len(), custom.total_entries());
+    assert!(std::ptr::eq(entries[0].0, &custom.categories()[0]));
+    assert_eq!(custom.flatten().len(), custom.total_entries());
+    let parsed = JumplistParser::from_path(path).unwrap();
+    assert_eq!(parsed.entry_count(), custom.total_entries());
+
+    // A dropped entry makes the declared and parsed counts differ
+    let mut corrupt = std::fs::read(path).unwrap();
+    corrupt[62..66].copy_from_slice(&[0xFF; 4]);
+    let parsed = CustomDestinations::from_reader(&mut Cursor::new(corrupt)).unwrap();
+    let category = &parsed.categories()[0];
+    assert_eq!(
+        category.declared_entry_count(),
+        Some(category.entry_count() as u32 + 1)
+    );
+    assert_eq!(parsed.total_entries(), 5);
+    assert!(parsed.warnings[0].starts_with("Dropped the Custom entry at offset 46"));
+    assert_eq!(
+        parsed.warnings[1],
+        "Category 0 declares 2 entries but 1 were parsed"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn custom_destinations_outline() {
+    use jumplist_parser::custom_destinations::CustomDestinations;
+    use std::io::Cursor;
+
+    for path in glob("samples/*/CustomDestinations/*").unwrap() {
+        let path = path.unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let parsed = CustomDestinations::from_buffer(&data).unwrap();
+        assert_eq!(
+            parsed,
+            CustomDestinations::from_path(path.to_str().unwrap()).unwrap()
+        );
+        let outline = CustomDestinations::scan_structure(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(outline.header, parsed.header, "{:?}", path);
+        assert_eq!(outline.warnings, parsed.warnings, "{:?}", path);
+        assert_eq!(outline.total_entries(), parsed.total_entries());
+        assert_eq!(outline.categories.len(), parsed.categories().len());
+        for (outline, category) in outline.categories.iter().zip(parsed.categories()) {
+            assert_eq!(outline.file_offset, category.file_offset);
+            assert_eq!(outline.r#type, category.r#type);
+            assert_eq!(outline.name, category.name);
+            assert_eq!(outline.id, category.id);
+            assert_eq!(
+                outline.declared_entry_count,
+                category.declared_entry_count()
+            );
+            assert_eq!(outline.entry_count, category.entry_count());
+            // The entries are within the byte range of their category
+            let end = outline.file_offset + outline.size;
+            for entry in category.iter_entries() {
+                assert!(entry.file_offset > outline.file_offset);
+                assert!(entry.file_offset + entry.entry_size <= end);
+            }
+        }
+        let last = outline.categories.last().unwrap();
+        assert!(last.file_offset + last.size <= data.len() as u64);
+    }
+
+    // The same findings as the full parse on a damaged file
+    let path = "samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms";
+    let data = std::fs::read(path).unwrap();
+    let mut corrupt = data.clone();
+    corrupt[62..66].copy_from_slice(&[0xFF; 4]);
+    let outline = CustomDestinations::scan_structure(&mut Cursor::new(&corrupt)).unwrap();
+    let parsed = CustomDestinations::from_buffer(&corrupt).unwrap();
+    // Both reject the LNK header before reading the rest of the LNK
+    assert_eq!(outline.warnings, parsed.warnings);
+    assert!(outline.warnings[0].starts_with("Dropped the Custom entry at offset 46"));
+    assert_eq!(outline.total_entries(), 5);
+    // Cut in the LNK of a task
+    let truncated = &data[..10000];
+    let outline = CustomDestinations::scan_structure(&mut Cursor::new(truncated)).unwrap();
+    assert!(outline.truncated);
+    assert_eq!(
+        outline.total_entries(),
+        CustomDestinations::from_buffer(truncated)
+            .unwrap()
+            .total_entries()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn custom_destinations_version() {
+    use jumplist_parser::aggregate::Summary;
+    use jumplist_parser::builder::{lnk_bytes, CustomCategory, CustomJumplistBuilder};
+    use jumplist_parser::custom_destinations::{CustomDestinations, CustomDestinationsHeader};
+    use jumplist_parser::{JumplistType, ParseMode, ParseOptions};
+
+    let builder = CustomJumplistBuilder::new().category(CustomCategory::Task(vec![lnk_bytes(
+        r"C:\Windows\notepad.exe",
+    )]));
+    let name = "0123456789abcdef.customDestinations-ms";
+
+    // Another small version is parsed with the layout of version 2, with a warning
+    let other = CustomJumplistBuilder {
+        version: 3,
+        ..builder.clone()
+    }
+    .to_bytes();
+    let parsed = JumplistParser::from_bytes(&other, Some(name)).unwrap();
+    assert_eq!(parsed.entry_count(), 1);
+    assert_eq!(parsed.format_version(), Some(3));
+    assert!(parsed.warnings()[0].starts_with("Unknown format version 3"));
+    assert_eq!(parsed.records()[0]["jumplist_format_version"], "3");
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    assert!(JumplistParser::from_bytes_with_options(&other, Some(name), &strict).is_err());
+    let known = JumplistParser::from_bytes(&builder.to_bytes(), Some(name)).unwrap();
+    assert!(known.warnings().is_empty());
+    assert_eq!(known.records()[0]["jumplist_format_version"], "2");
+
+    // An impossible version is an error, without carving
+    let impossible = CustomJumplistBuilder {
+        version: 1001,
+        ..builder
+    }
+    .to_bytes();
+    let err = CustomDestinations::from_buffer(&impossible).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("format version 1001 is not plausible"));
+    let err = JumplistParser::from_bytes(&impossible, Some(name)).unwrap_err();
+    assert!(err.to_string().contains("not plausible"));
+    assert!(JumplistParser::from_bytes(&impossible, None).is_err());
+    assert!(!CustomDestinationsHeader::is_plausible_version(0));
+
+    // The content detection checks the version too
+    let footer = 0xBABF_FBABu32.to_le_bytes();
+    assert_eq!(
+        JumplistParser::type_from_content(&3u32.to_le_bytes(), &footer),
+        Some(JumplistType::Custom)
+    );
+    assert_eq!(
+        JumplistParser::type_from_content(&3u32.to_le_bytes(), &[0; 4]),
+        None
+    );
+    assert_eq!(
+        JumplistParser::type_from_content(&1001u32.to_le_bytes(), &footer),
+        None
+    );
+    assert_eq!(
+        JumplistParser::type_from_content(&impossible, &impossible),
+        None
+    );
+    let mut reader = std::io::Cursor::new(
+        std::fs::read("samples/win10/CustomDestinations/1ced32d74a95c7bc.customDestinations-ms")
+            .unwrap(),
+    );
+    assert_eq!(
+        JumplistParser::type_from_reader(&mut reader),
+        Some(JumplistType::Custom)
+    );
+    assert_eq!(reader.position(), 0);
+    assert_eq!(
+        JumplistParser::type_from_reader(&mut std::io::Cursor::new(impossible)),
+        None
+    );
+
+    // The versions seen per application
+    let automatic = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let summary = Summary::from_parsers(&[automatic]);
+    assert_eq!(summary.apps[0].format_versions, ["automatic:4"]);
+}
+
+#[cfg(test)]
+#[test]
+fn prelude_reexports() {
+    use jumplist_parser::prelude::*;
+    use jumplist_parser::{FileTime, Guid};
+
+    let parsed = JumplistParser::from_path_with_options(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        &ParseOptions {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(parsed.r#type, JumplistType::Automatic);
+    assert!(matches!(parsed.data, JumplistData::DestList(_)));
+    let lnks: Vec<&LNKParser> = parsed.entries().filter_map(|e| e.lnk()).collect();
+    assert!(!lnks.is_empty());
+    let normalized = lnks[0].normalize();
+    assert!(normalized.contains_key("target_full_path"));
+    assert_eq!(parsed.flatten().len(), 24);
+
+    let error: JumplistParserError = JumplistParser::from_bytes(&[0; 8], None).unwrap_err();
+    assert!(!error.to_string().is_empty());
+    let _ = FileTime::new(0);
+    assert_eq!(
+        Guid::from_buffer(&[0; 16]).unwrap().to_string(),
+        "00000000-0000-0000-0000-000000000000"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn parse_stats() {
+    use jumplist_parser::{stats::ParseStats, ParseOptions};
+
+    let automatic = "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms";
+    let custom = "samples/win10/CustomDestinations/7e4dca80246863e3.customDestinations-ms";
+    // Nothing is collected by default
+    assert!(JumplistParser::from_path(automatic)
+        .unwrap()
+        .stats
+        .is_none());
+
+    let options = ParseOptions {
+        collect_stats: true,
+        ..Default::default()
+    };
+    let parsed = JumplistParser::from_path_with_options(automatic, &options).unwrap();
+    let stats = parsed.stats.clone().unwrap();
+    assert_eq!(stats.entries_parsed, 24);
+    assert_eq!(stats.lnks_parsed + stats.lnks_failed, 24);
+    // The DestList stream and one LNK stream per entry
+    assert_eq!(stats.streams_opened, 25);
+    assert!(stats.bytes_read > parsed.destlist().unwrap().stream_size);
+    assert!(stats.total_micros >= stats.cfb_open_micros + stats.lnk_parse_micros);
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(json["stats"]["entries_parsed"], 24);
+
+    let parsed = JumplistParser::from_path_with_options(custom, &options).unwrap();
+    let custom_stats = parsed.stats.clone().unwrap();
+    assert_eq!(custom_stats.streams_opened, 0);
+    assert_eq!(
+        custom_stats.bytes_read,
+        std::fs::metadata(custom).unwrap().len()
+    );
+    assert_eq!(custom_stats.entries_parsed, parsed.entry_count() as u64);
+    assert_eq!(custom_stats.cfb_open_micros, 0);
+
+    let mut total = ParseStats::default();
+    total.add(&stats);
+    total.add(&custom_stats);
+    assert_eq!(
+        total.entries_parsed,
+        stats.entries_parsed + custom_stats.entries_parsed
+    );
+    assert!(total.to_string().contains(" entries, "));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn verbose_parse_times() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args([
+            "-p",
+            "samples/win10/AutomaticDestinations/*",
+            "--verbose",
+            "-o",
+            "/dev/null",
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let files = stderr
+        .lines()
+        .filter(|l| l.starts_with("Parsed 'samples/win10/AutomaticDestinations/"))
+        .count();
+    assert!(files > 1, "{}", stderr);
+    assert!(
+        stderr.contains(&format!("Parsed {} files in ", files)),
+        "{}",
+        stderr
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn windows_path_patterns() {
+    use jumplist_parser::path_pattern::{expand_pattern, split_windows_pattern, PatternParts};
+
+    let parts = |root: &str, components: &[&str]| PatternParts {
+        root: root.to_string(),
+        components: components.iter().map(|c| c.to_string()).collect(),
+    };
+    assert_eq!(
+        split_windows_pattern(r"\\SERVER\C$\Users\*\AppData\Roaming\*ms"),
+        Some(parts(
+            r"\\SERVER\C$\",
+            &["Users", "*", "AppData", "Roaming", "*ms"]
+        ))
+    );
+    assert_eq!(
+        split_windows_pattern(r"\\?\C:\Users\*\AppData"),
+        Some(parts(r"\\?\C:\", &["Users", "*", "AppData"]))
+    );
+    assert_eq!(
+        split_windows_pattern(r"\\?\UNC\SERVER\C$\Users\*"),
+        Some(parts(r"\\?\UNC\SERVER\C$\", &["Users", "*"]))
+    );
+    assert_eq!(
+        split_windows_pattern(r"\\?\unc\SERVER\C$"),
+        Some(parts(r"\\?\unc\SERVER\C$\", &[]))
+    );
+    assert_eq!(
+        split_windows_pattern(r"\\.\C:\Users/*//AppData"),
+        Some(parts(r"\\.\C:\", &["Users", "*", "AppData"]))
+    );
+    // Not a UNC or extended-length pattern, or without a share or volume
+    assert_eq!(split_windows_pattern(r"C:\Users\*"), None);
+    assert_eq!(split_windows_pattern("/mnt/c/Users/*"), None);
+    assert_eq!(split_windows_pattern(r"\\SERVER"), None);
+    assert_eq!(split_windows_pattern(r"\\?\UNC\SERVER"), None);
+
+    // The expansion level by level, from a local root
+    let expanded: Vec<_> = parts("samples", &["win10", "*DESTINATIONS", "f01b*"])
+        .expand()
+        .unwrap()
+        .into_iter()
+        .map(|path| path.unwrap())
+        .collect();
+    let globbed: Vec<_> = expand_pattern("samples/win10/*Destinations/f01b*")
+        .unwrap()
+        .into_iter()
+        .map(|path| path.unwrap())
+        .collect();
+    assert_eq!(expanded.len(), 2);
+    assert_eq!(expanded, globbed);
+    // Missing directories match nothing, without errors
+    assert!(parts("samples", &["missing", "*", "*ms"])
+        .expand()
+        .unwrap()
+        .is_empty());
+    assert!(parts("samples", &["win10", "[", "*ms"]).expand().is_err());
+    assert!(
+        parts("samples", &["win10", "AutomaticDestinations", "missing"])
+            .expand()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn non_jumplist_files() {
+    use jumplist_parser::{is_probably_jumplist, sniff_jumplist};
+    use std::path::Path;
+
+    let dir = std::env::temp_dir().join("jumplist_parser_non_jumplist_files");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let automatic = dir.join("f01b4d95cf55d32a.automaticDestinations-ms");
+    std::fs::copy(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        &automatic,
+    )
+    .unwrap();
+    let stray = dir.join("desktop.ini.customDestinations-ms.lnk");
+    std::fs::write(&stray, b"[.ShellClassInfo]").unwrap();
+    let garbage = dir.join("0123456789abcdef.customDestinations-ms");
+    std::fs::write(&garbage, [0xFF; 64]).unwrap();
+    let empty = dir.join("1ced32d74a95c7bc.customDestinations-ms");
+    std::fs::write(&empty, []).unwrap();
+
+    assert!(is_probably_jumplist(&automatic));
+    assert!(!is_probably_jumplist(&stray));
+    assert!(is_probably_jumplist(&garbage));
+    assert!(sniff_jumplist(&automatic));
+    assert!(!sniff_jumplist(&garbage));
+    // Too short to tell, the parser reports it as empty
+    assert!(sniff_jumplist(&empty));
+    assert!(sniff_jumplist(Path::new(
+        "samples/missing.customDestinations-ms"
+    )));
+
+    #[cfg(feature = "cli")]
+    {
+        let run = |extra: &[&str]| {
+            let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+                .args(["-p", &format!("{}/*ms*", dir.display()), "-o", "/dev/null"])
+                .args(extra)
+                .output()
+                .unwrap();
+            String::from_utf8(output.stderr).unwrap()
+        };
+        let stderr = run(&[]);
+        assert!(
+            stderr.contains("Skipped 1 non-jumplist files"),
+            "{}",
+            stderr
+        );
+        assert!(!stderr.contains("desktop.ini"), "{}", stderr);
+        assert!(stderr.contains("0123456789abcdef"), "{}", stderr);
+        let stderr = run(&["--sniff"]);
+        assert!(
+            stderr.contains("Skipped 2 non-jumplist files"),
+            "{}",
+            stderr
+        );
+        assert!(!stderr.contains("0123456789abcdef"), "{}", stderr);
+        assert!(
+            stderr.contains("Skipped 1 empty jumplist files"),
+            "{}",
+            stderr
+        );
+        // A file given without wildcard is always parsed
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                &garbage.to_string_lossy(),
+                "--sniff",
+                "-o",
+                "/dev/null",
+            ])
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Did not parse"), "{}", stderr);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn deterministic_json_output() {
+    use jumplist_parser::{output::FIELDS, Flatten};
+
+    let run = || {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                "samples/win10/*/*",
+                "--normalize",
+                "--output-format",
+                "jsonl",
+            ])
+            .output()
+            .unwrap()
+            .stdout
+    };
+    let first = run();
+    assert!(!first.is_empty());
+    assert_eq!(first, run());
+
+    let parsed = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let canonical: Vec<&str> = FIELDS.iter().map(|f| f.name).collect();
+    for entry in parsed.flatten() {
+        let keys: Vec<&str> = entry
+            .keys()
+            .map(|k| k.as_str())
+            .filter(|k| canonical.contains(k))
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort_by_key(|k| canonical.iter().position(|c| c == k));
+        assert!(!keys.is_empty());
+        assert_eq!(keys, sorted);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn destlist_trailing_value() {
+    use jumplist_parser::builder::{AutomaticEntry, AutomaticJumplistBuilder};
+    use jumplist_parser::destlist::{DestList, DestListVersion};
+    use std::io::Cursor;
+
+    for path in [
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        "samples/win11/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    ] {
+        let parsed = JumplistParser::from_path(path).unwrap();
+        let destlist = parsed.destlist().unwrap();
+        assert!(destlist
+            .entries
+            .iter()
+            .all(|entry| entry.trailing_value == Some(0)));
+        assert!(destlist.warnings.is_empty());
+    }
+    let data = AutomaticJumplistBuilder::new(DestListVersion::V1Win7)
+        .entry(AutomaticEntry::new(1, r"C:\Users\user\notes.txt"))
+        .to_bytes()
+        .unwrap();
+    let win7 = JumplistParser::from_bytes(&data, None).unwrap();
+    assert!(win7
+        .destlist()
+        .unwrap()
+        .entries
+        .iter()
+        .all(|entry| entry.trailing_value.is_none()));
+
+    // The trailing value of an entry set to the number of another one
+    let mut stream = std::fs::read("samples/other/DestList").unwrap();
+    let destlist = DestList::parse_stream(&mut Cursor::new(&stream)).unwrap();
+    let (first, second) = (&destlist.entries[0], &destlist.entries[1]);
+    let end = (second.stream_offset + second.entry_size) as usize;
+    stream[end - 4..end].copy_from_slice(&first.entry_number.to_le_bytes());
+    let tampered = DestList::parse_stream(&mut Cursor::new(&stream)).unwrap();
+    assert_eq!(tampered.entries[1].trailing_value, Some(first.entry_number));
+    assert_eq!(
+        tampered.warnings,
+        [format!(
+            "DestList entries whose trailing value is another entry number: {:x} ({:x})",
+            second.entry_number, first.entry_number
+        )]
+    );
+    let json = serde_json::to_value(&tampered.entries[1]).unwrap();
+    assert_eq!(json["trailing_value"], first.entry_number);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn raw_csv_output() {
+    use jumplist_parser::output::{csv_columns, csv_rows, CsvOptions, RAW_FIELDS};
+
+    let options = CsvOptions {
+        raw: true,
+        ..Default::default()
+    };
+    let names: Vec<&str> = RAW_FIELDS.iter().map(|f| f.name).collect();
+    assert_eq!(csv_columns(&options), names);
+    let column = |name: &str| names.iter().position(|n| *n == name).unwrap();
+
+    let automatic = JumplistParser::from_path(
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+    )
+    .unwrap();
+    let rows = csv_rows(&automatic, &options);
+    assert_eq!(rows.len(), automatic.entry_count());
+    let destlist = automatic.destlist().unwrap();
+    for (row, entry) in rows.iter().zip(&destlist.entries) {
+        assert_eq!(row.len(), names.len());
+        assert_eq!(row[column("entry_number")], entry.entry_number.to_string());
+        assert_eq!(row[column("path")], entry.path);
+        assert_eq!(row[column("mtime")], entry.mtime.to_string());
+        assert_eq!(row[column("file_droid")], entry.file_droid.to_string());
+        assert_eq!(row[column("type")], "automatic");
+        assert_eq!(row[column("category_type")], "");
+    }
+
+    let custom = JumplistParser::from_path(
+        "samples/win10/CustomDestinations/7e4dca80246863e3.customDestinations-ms",
+    )
+    .unwrap();
+    let rows = csv_rows(&custom, &options);
+    assert_eq!(rows.len(), custom.entry_count());
+    assert!(rows
+        .iter()
+        .all(|row| row[column("entry_number")].is_empty()
+            && !row[column("category_type")].is_empty()));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args(["--list-fields", "--raw"])
+        .output()
+        .unwrap();
+    let listed: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| l.split_whitespace().next().unwrap().to_string())
+        .collect();
+    assert_eq!(listed, names);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args([
+            "-p",
+            "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+            "--raw-csv",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("\"source_path\",\"app_id\","));
+    assert_eq!(lines.count(), automatic.entry_count());
+}
+
+#[cfg(test)]
+#[test]
+fn default_locations() {
+    use jumplist_parser::locations::{
+        default_paths, default_paths_under, for_user, recent_directory, LocationOptions,
+    };
+    use std::path::Path;
+
+    let expected_root = match cfg!(windows) {
+        true => r"C:\Users\*\AppData",
+        false => "/mnt/c/Users/*/AppData",
+    };
+    let paths = default_paths();
+    assert_eq!(paths.len(), 2);
+    assert!(paths.iter().all(|p| p.starts_with(expected_root)));
+
+    assert_eq!(
+        default_paths_under(r"F:\"),
+        [
+            r"F:\Users\*\AppData\Roaming\Microsoft\Windows\Recent\AutomaticDestinations\*ms",
+            r"F:\Users\*\AppData\Roaming\Microsoft\Windows\Recent\CustomDestinations\*ms",
+        ]
+    );
+    let options = LocationOptions {
+        recent_lnks: true,
+        documents_and_settings: true,
+    };
+    assert_eq!(
+        options.paths_under("/mnt/image/C/"),
+        [
+            "/mnt/image/C/Users/*/AppData/Roaming/Microsoft/Windows/Recent/AutomaticDestinations/*ms",
+            "/mnt/image/C/Users/*/AppData/Roaming/Microsoft/Windows/Recent/CustomDestinations/*ms",
+            "/mnt/image/C/Users/*/AppData/Roaming/Microsoft/Windows/Recent/*.lnk",
+            "/mnt/image/C/Documents and Settings/*/Application Data/Microsoft/Windows/Recent/AutomaticDestinations/*ms",
+            "/mnt/image/C/Documents and Settings/*/Application Data/Microsoft/Windows/Recent/CustomDestinations/*ms",
+            "/mnt/image/C/Documents and Settings/*/Application Data/Microsoft/Windows/Recent/*.lnk",
+        ]
+    );
+
+    // The samples laid out as a profile
+    let dir = std::env::temp_dir().join(format!("jumplist_locations_{}", std::process::id()));
+    let profile = dir.join("Users").join("user");
+    for (destinations, sample) in for_user(&profile).iter().zip([
+        "samples/win10/AutomaticDestinations/f01b4d95cf55d32a.automaticDestinations-ms",
+        "samples/win10/CustomDestinations/7e4dca80246863e3.customDestinations-ms",
+    ]) {
+        assert!(destinations.starts_with(recent_directory(&profile)));
+        std::fs::create_dir_all(destinations).unwrap();
+        std::fs::copy(
+            sample,
+            destinations.join(Path::new(sample).file_name().unwrap()),
+        )
+        .unwrap();
+    }
+    let found: usize = default_paths_under(&dir.to_string_lossy())
+        .iter()
+        .map(|pattern| glob(pattern).unwrap().flatten().count())
+        .sum();
+    assert_eq!(found, 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn parse_outcome() {
+    use jumplist_parser::{ParseMode, ParseOptions};
+
+    // A custom Jumplist with a category footer that isn't 0xBABFFBAB
+    let dir = std::env::temp_dir().join(format!("jumplist_outcome_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("5d696d521de238c3.customDestinations-ms");
+    let mut data =
+        std::fs::read("samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms")
+            .unwrap();
+    data[3294..3298].copy_from_slice(&[0; 4]);
+    std::fs::write(&path, data).unwrap();
+    let path = path.to_str().unwrap();
+
+    let lenient = JumplistParser::try_from_path(path, &ParseOptions::default());
+    assert!(lenient.error.is_none() && !lenient.is_partial());
+    assert!(lenient.warnings[0].message.contains("footer"));
+    assert_eq!(lenient.parsed.as_ref().unwrap().entry_count(), 6);
+
+    let strict = ParseOptions {
+        mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    let outcome = JumplistParser::try_from_path(path, &strict);
+    assert!(outcome.is_partial());
+    assert!(outcome
+        .error
+        .as_ref()
+        .unwrap()
+        .to_string()
+        .contains("Strict mode"));
+    assert_eq!(outcome.warnings, lenient.warnings);
+    assert_eq!(outcome.into_result().unwrap().entry_count(), 6);
+    assert!(JumplistParser::from_path_with_options(path, &strict).is_err());
+
+    let missing = dir.join("missing.customDestinations-ms");
+    let outcome = JumplistParser::try_from_path(missing.to_str().unwrap(), &strict);
+    assert!(outcome.parsed.is_none() && outcome.warnings.is_empty());
+    assert!(outcome.into_result().is_err());
+
+    #[cfg(feature = "cli")]
+    {
+        let report_path = dir.join("report.jsonl");
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+            .args([
+                "-p",
+                path,
+                "--strict",
+                "--output-format",
+                "jsonl",
+                "--normalize",
+            ])
+            .arg("--error-report")
+            .arg(&report_path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(entries.len(), 6);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("Partially parsed '") && stderr.contains("', 6 entries recovered"),
+            "{}",
+            stderr
+        );
+        let report = std::fs::read_to_string(&report_path).unwrap();
+        let records: Vec<serde_json::Value> = report
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 1, "{}", report);
+        assert_eq!(records[0]["status"], "partial");
+        assert_eq!(records[0]["kind"], "file_structure");
+        assert_eq!(records[0]["entries"], 6);
+        assert!(records[0]["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("footer"));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn filetype_categories() {
+    use jumplist_parser::{
+        aggregate::Summary,
+        filetype::{target_extension, FileCategory, FiletypeMap},
+        output::{csv_columns, csv_rows, CsvOptions, FILETYPE_CSV_COLUMNS},
+        Flatten, ParseOptions,
+    };
+    use std::sync::Arc;
+
+    assert_eq!(target_extension(r"C:\Tools\PsExec64.EXE", "file"), "exe");
+    assert_eq!(
+        target_extension(r"C:\Users\user\Documents", "directory"),
+        ""
+    );
+    assert_eq!(target_extension("ms-settings:display", "url"), "");
+    assert_eq!(target_extension("", "file"), "");
+    let map = FiletypeMap::default();
+    assert_eq!(map.category("ps1"), FileCategory::Script);
+    assert_eq!(map.category("yaml"), FileCategory::Other);
+    assert_eq!(map.category(""), FileCategory::Other);
+    assert_eq!("Executable".parse(), Ok(FileCategory::Executable));
+    assert!("binary".parse::<FileCategory>().is_err());
+    assert!(FiletypeMap::from_json(r#"{"yaml": "config"}"#).is_err());
+    assert!(FiletypeMap::from_json("[]").is_err());
+
+    let path = "samples/win10/AutomaticDestinations/9b9cdc69c1c24e2b.automaticDestinations-ms";
+    let parsed = JumplistParser::from_path(path).unwrap();
+    let rows = parsed.flatten();
+    let yaml = rows
+        .iter()
+        .find(|e| e["target_extension"] == "yaml")
+        .unwrap();
+    assert_eq!(yaml["target_category"], "other");
+    assert!(rows
+        .iter()
+        .any(|e| e["target_extension"] == "csv" && e["target_category"] == "spreadsheet"));
+
+    // The user mapping takes precedence over the internal table
+    let options = ParseOptions {
+        filetype_map: Some(Arc::new(
+            FiletypeMap::from_json(r#"{".YAML": "document", "csv": "other"}"#).unwrap(),
+        )),
+        ..Default::default()
+    };
+    let mapped = JumplistParser::from_path_with_options(path, &options).unwrap();
+    let categories: Vec<String> = mapped
+        .flatten()
+        .iter()
+        .map(|e| e["target_category"].clone())
+        .collect();
+    assert_eq!(
+        categories,
+        mapped
+            .target_categories()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+    );
+    assert!(!categories.contains(&"spreadsheet".to_string()));
+    let map = mapped.filetype_map.clone().unwrap();
+    assert!(mapped
+        .entries()
+        .zip(mapped.target_categories())
+        .all(|(entry, category)| entry.target_category(&map) == category));
+    let summary = Summary::from_parsers(&[mapped]);
+    assert_eq!(summary.apps[0].categories[&FileCategory::Document], 2);
+    assert!(summary.apps[0]
+        .csv_row()
+        .last()
+        .unwrap()
+        .starts_with("document:2; other:"));
+
+    let options = CsvOptions {
+        filetype_columns: true,
+        ..Default::default()
+    };
+    let columns = csv_columns(&options);
+    let position = columns.iter().position(|c| *c == "target_type").unwrap();
+    assert_eq!(&columns[position + 1..position + 3], FILETYPE_CSV_COLUMNS);
+    let rows = csv_rows(&parsed, &options);
+    assert!(rows
+        .iter()
+        .all(|row| row[position + 2] != "spreadsheet" || row[position + 1] == "csv"));
+
+    let dir = std::env::temp_dir().join(format!("jumplist_filetype_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let filetype_map = dir.join("filetypes.json");
+    std::fs::write(&filetype_map, r#"{"yaml": "script"}"#).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args([
+            "-p",
+            "samples/win10/*/*",
+            "--normalize",
+            "--output-format",
+            "jsonl",
+            "--only-category",
+            "executable,script",
+            "--filetype-map",
+            filetype_map.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    let entries: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .flat_map(|line| serde_json::from_str::<Vec<serde_json::Value>>(line).unwrap())
+        .collect();
+    let mut extensions: Vec<&str> = entries
+        .iter()
+        .map(|e| e["target_extension"].as_str().unwrap())
+        .collect();
+    extensions.sort_unstable();
+    extensions.dedup();
+    assert_eq!(extensions, ["exe", "yaml"]);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jumplist_parser"))
+        .args(["-p", path, "--only-category", "binary"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,32 @@
+//! Tests of the WebAssembly bindings, run with
+//! `wasm-pack test --node -- --no-default-features --features wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use jumplist_parser::{wasm::parse, JumplistParser, JumplistType};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const AUTOMATIC: &[u8] = include_bytes!(
+    "../samples/win11/AutomaticDestinations/4cb9c5750d51c07f.automaticDestinations-ms"
+);
+const CUSTOM: &[u8] =
+    include_bytes!("../samples/win11/CustomDestinations/5d696d521de238c3.customDestinations-ms");
+
+#[wasm_bindgen_test]
+fn parse_embedded_samples() {
+    let automatic = JumplistParser::from_bytes(AUTOMATIC, None).unwrap();
+    assert_eq!(automatic.r#type, JumplistType::Automatic);
+    assert!(automatic.entry_count() > 0);
+    let custom =
+        JumplistParser::from_bytes(CUSTOM, Some("5d696d521de238c3.customDestinations-ms")).unwrap();
+    assert_eq!(custom.app_id.as_deref(), Some("5d696d521de238c3"));
+    assert_eq!(custom.entry_count(), 6);
+
+    // The JavaScript value has the layout of the JSON output
+    let name = "4cb9c5750d51c07f.automaticDestinations-ms";
+    let value = parse(AUTOMATIC, Some(name.to_string())).unwrap();
+    let json: serde_json::Value = serde_wasm_bindgen::from_value(value).unwrap();
+    assert_eq!(json["app_id"], "4cb9c5750d51c07f");
+    let expected = JumplistParser::from_bytes(AUTOMATIC, Some(name)).unwrap();
+    assert_eq!(json, serde_json::to_value(expected).unwrap());
+    assert!(parse(b"not a jumplist", Some("notes.txt".to_string())).is_err());
+}